@@ -0,0 +1,87 @@
+// 别名分析（基于内存空间）
+//
+// 指向不同内存空间的指针不可能指向同一地址；其余情况保守地视为可能别名
+
+use crate::ir::MemorySpace;
+use crate::ir::value::Value;
+
+/// 获取指针值所在的内存空间，非指针返回 None
+fn pointer_space(value: &Value) -> Option<MemorySpace> {
+    value.get_type().borrow().pointer_space()
+}
+
+/// 两次访存之间的别名关系
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AliasResult {
+    /// 一定访问不同的内存
+    NoAlias,
+    /// 一定访问同一地址
+    MustAlias,
+    /// 无法确定
+    MayAlias,
+}
+
+/// 按两个指针及其所在的内存空间（None 表示未知）判断别名关系
+///
+/// 同名指针必然别名，未命名的值（如常量）不能按名称判断；两者都位于确定且不同的
+/// 内存空间时可证明不相交。`generic` 空间或空间未知的指针与任何指针都可能别名。
+pub fn alias_in(
+    a: &Value,
+    space_a: Option<MemorySpace>,
+    b: &Value,
+    space_b: Option<MemorySpace>,
+) -> AliasResult {
+    if !a.get_name().is_empty() && a.get_name() == b.get_name() {
+        return AliasResult::MustAlias;
+    }
+    match (space_a, space_b) {
+        (Some(MemorySpace::Generic), _) | (_, Some(MemorySpace::Generic)) => AliasResult::MayAlias,
+        (Some(sa), Some(sb)) if sa != sb => AliasResult::NoAlias,
+        _ => AliasResult::MayAlias,
+    }
+}
+
+/// 按指针类型中的内存空间判断两个指针的别名关系，见 `alias_in`
+pub fn alias(a: &Value, b: &Value) -> AliasResult {
+    alias_in(a, pointer_space(a), b, pointer_space(b))
+}
+
+/// 判断两个指针是否可能指向同一内存
+pub fn may_alias(a: &Value, b: &Value) -> bool {
+    alias(a, b) != AliasResult::NoAlias
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::types::{Type, TypeKind};
+
+    fn pointer(name: &str, space: MemorySpace) -> Value {
+        let elem = Type::get_int_type(TypeKind::Int32);
+        Value::new(Type::get_pointer_type(elem, space), name.to_string())
+    }
+
+    #[test]
+    fn test_memory_space_alias() {
+        let a = pointer("%a", MemorySpace::SRAM);
+        let b = pointer("%b", MemorySpace::SRAM);
+        let v = pointer("%v", MemorySpace::VSPM);
+        let g = pointer("%g", MemorySpace::Generic);
+
+        assert!(may_alias(&a, &b));
+        assert!(!may_alias(&a, &v));
+        assert!(may_alias(&g, &v));
+        assert_eq!(alias(&a, &a), AliasResult::MustAlias);
+        assert_eq!(alias(&a, &b), AliasResult::MayAlias);
+    }
+
+    #[test]
+    fn test_unnamed_values_are_not_must_alias() {
+        let a = pointer("", MemorySpace::SRAM);
+        let b = pointer("", MemorySpace::SRAM);
+        let v = pointer("", MemorySpace::VSPM);
+
+        assert_eq!(alias(&a, &b), AliasResult::MayAlias);
+        assert_eq!(alias(&a, &v), AliasResult::NoAlias);
+    }
+}
@@ -0,0 +1,192 @@
+// 内存分配检查
+//
+// 沿控制流追踪 alloc/free，报告泄漏、重复释放以及释放非 alloc 值
+
+use crate::analysis::Analysis;
+use crate::analysis::cfg::FunctionCFG;
+use crate::ir::ModuleRef;
+use crate::ir::function::Function;
+use crate::ir::instruction::Opcode;
+use std::any::Any;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+/// 分配检查诊断种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AllocDiagnosticKind {
+    Leak,           // 存在从分配到函数返回却未释放的路径
+    DoubleFree,     // 可能已释放的分配再次被释放
+    FreeOfNonAlloc, // 释放的值不是 alloc 的结果
+}
+
+/// 分配检查诊断
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AllocDiagnostic {
+    pub kind: AllocDiagnosticKind,
+    pub function: String,
+    pub block: String,
+    pub value: String,
+}
+
+impl fmt::Display for AllocDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self.kind {
+            AllocDiagnosticKind::Leak => "分配的内存在某些路径上未被释放",
+            AllocDiagnosticKind::DoubleFree => "内存可能被重复释放",
+            AllocDiagnosticKind::FreeOfNonAlloc => "释放的值不是由 alloc 分配的",
+        };
+        write!(
+            f,
+            "{}::{}: {} ({})",
+            self.function, self.block, message, self.value
+        )
+    }
+}
+
+/// 基本块入口处的分配状态（按值名称追踪）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct AllocState {
+    live: BTreeSet<String>,  // 在某条路径上已分配且尚未释放
+    freed: BTreeSet<String>, // 在某条路径上已经释放
+}
+
+impl AllocState {
+    fn merge(&mut self, other: &AllocState) {
+        self.live.extend(other.live.iter().cloned());
+        self.freed.extend(other.freed.iter().cloned());
+    }
+}
+
+/// 分配检查器
+pub struct AllocChecker;
+
+impl AllocChecker {
+    /// 检查整个模块
+    pub fn check(module: &ModuleRef) -> Vec<AllocDiagnostic> {
+        module
+            .borrow()
+            .get_functions()
+            .iter()
+            .flat_map(|func| Self::check_function(&func.borrow()))
+            .collect()
+    }
+
+    /// 检查单个函数
+    ///
+    /// 在 CFG 上做前向数据流分析，合并点取并集，因此只要有一条路径
+    /// 出现泄漏或重复释放就会被报告。
+    pub fn check_function(func: &Function) -> Vec<AllocDiagnostic> {
+        let cfg = FunctionCFG::compute(func);
+        let blocks: HashMap<String, _> = func
+            .get_basic_blocks()
+            .iter()
+            .map(|bb| (bb.borrow().get_name().to_string(), bb.clone()))
+            .collect();
+        let allocs: HashSet<String> = func
+            .get_basic_blocks()
+            .iter()
+            .flat_map(|bb| bb.borrow().get_instructions().to_vec())
+            .filter(|instr| instr.borrow().get_opcode() == Opcode::Alloc)
+            .filter_map(|instr| instr.borrow().get_name())
+            .collect();
+
+        // 迭代到不动点
+        let mut entry_states: HashMap<String, AllocState> = HashMap::new();
+        let mut worklist: Vec<String> = cfg.get_entry().map(str::to_string).into_iter().collect();
+        if let Some(entry) = cfg.get_entry() {
+            entry_states.insert(entry.to_string(), AllocState::default());
+        }
+        let mut visited = HashSet::new();
+        while let Some(name) = worklist.pop() {
+            visited.insert(name.clone());
+            let mut state = entry_states[&name].clone();
+            Self::transfer(&blocks[&name].borrow(), &allocs, &mut state, &mut |_| {});
+            for succ in cfg.get_successors(&name) {
+                let succ_state = entry_states.entry(succ.clone()).or_default();
+                let before = succ_state.clone();
+                succ_state.merge(&state);
+                let changed = *succ_state != before;
+                if (changed || !visited.contains(succ)) && !worklist.contains(succ) {
+                    worklist.push(succ.clone());
+                }
+            }
+        }
+
+        // 基于稳定后的状态生成诊断
+        let mut diagnostics = Vec::new();
+        for name in cfg.get_blocks() {
+            let Some(state) = entry_states.get(name) else {
+                continue; // 不可达基本块
+            };
+            let mut state = state.clone();
+            let bb = blocks[name].borrow();
+            Self::transfer(&bb, &allocs, &mut state, &mut |(kind, value)| {
+                diagnostics.push(AllocDiagnostic {
+                    kind,
+                    function: func.get_name().to_string(),
+                    block: name.clone(),
+                    value,
+                });
+            });
+
+            let returns = bb
+                .get_terminator()
+                .is_some_and(|t| t.borrow().get_opcode() == Opcode::Ret);
+            if returns {
+                for value in &state.live {
+                    diagnostics.push(AllocDiagnostic {
+                        kind: AllocDiagnosticKind::Leak,
+                        function: func.get_name().to_string(),
+                        block: name.clone(),
+                        value: value.clone(),
+                    });
+                }
+            }
+        }
+        diagnostics
+    }
+
+    /// 基本块内的状态转移，`report` 接收 (诊断种类, 值名称)
+    fn transfer(
+        bb: &crate::ir::BasicBlock,
+        allocs: &HashSet<String>,
+        state: &mut AllocState,
+        report: &mut dyn FnMut((AllocDiagnosticKind, String)),
+    ) {
+        for instr in bb.get_instructions() {
+            let instr = instr.borrow();
+            match instr.get_opcode() {
+                Opcode::Alloc => {
+                    if let Some(name) = instr.get_name() {
+                        state.freed.remove(&name);
+                        state.live.insert(name);
+                    }
+                }
+                Opcode::Free if instr.get_operand_count() > 0 => {
+                    let name = instr.get_operand(0).borrow().get_name().to_string();
+                    if !allocs.contains(&name) {
+                        report((AllocDiagnosticKind::FreeOfNonAlloc, name));
+                        continue;
+                    }
+                    if state.freed.contains(&name) {
+                        report((AllocDiagnosticKind::DoubleFree, name.clone()));
+                    }
+                    state.live.remove(&name);
+                    state.freed.insert(name);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Analysis for AllocChecker {
+    fn name(&self) -> &'static str {
+        "analysis::AllocChecker"
+    }
+
+    /// 结果类型为 `Vec<AllocDiagnostic>`
+    fn compute(&self, module: &ModuleRef) -> Box<dyn Any> {
+        Box::new(Self::check(module))
+    }
+}
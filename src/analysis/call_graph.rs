@@ -0,0 +1,96 @@
+// 调用图分析
+//
+// 根据 `call @f` 以及其它 `@f` 函数引用构建函数之间的引用关系
+
+use crate::analysis::Analysis;
+use crate::ir::ModuleRef;
+use crate::ir::function::Function;
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+
+/// 模块的调用图（以函数名为节点）
+#[derive(Debug, Clone, Default)]
+pub struct CallGraph {
+    callees: HashMap<String, Vec<String>>,
+}
+
+impl CallGraph {
+    /// 计算模块的调用图
+    ///
+    /// 任何命名为 `@f` 且 `f` 为模块内函数的操作数都视为一条边，
+    /// 因此把函数作为值传递也会保持其可达。
+    pub fn compute(module: &ModuleRef) -> Self {
+        let module = module.borrow();
+        let functions: HashSet<String> = module
+            .get_functions()
+            .iter()
+            .map(|f| f.borrow().get_name().to_string())
+            .collect();
+
+        let callees = module
+            .get_functions()
+            .iter()
+            .map(|func| {
+                let func = func.borrow();
+                (
+                    func.get_name().to_string(),
+                    Self::referenced_functions(&func, &functions),
+                )
+            })
+            .collect();
+        CallGraph { callees }
+    }
+
+    /// 收集函数体中引用的模块内函数（去重，按出现顺序）
+    fn referenced_functions(func: &Function, functions: &HashSet<String>) -> Vec<String> {
+        let mut result: Vec<String> = Vec::new();
+        for bb in func.get_basic_blocks() {
+            for instr in bb.borrow().get_instructions() {
+                for op in instr.borrow().get_operands() {
+                    let op = op.borrow();
+                    let Some(name) = op.get_name().strip_prefix('@') else {
+                        continue;
+                    };
+                    if functions.contains(name) && !result.iter().any(|r| r == name) {
+                        result.push(name.to_string());
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// 获取函数直接引用的函数
+    pub fn get_callees(&self, name: &str) -> &[String] {
+        self.callees.get(name).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// 从给定根函数出发可达的所有函数（包括根本身）
+    pub fn reachable_from<'a>(&self, roots: impl IntoIterator<Item = &'a str>) -> HashSet<String> {
+        let mut reachable = HashSet::new();
+        let mut work: Vec<String> = roots
+            .into_iter()
+            .filter(|r| self.callees.contains_key(*r))
+            .map(str::to_string)
+            .collect();
+        while let Some(name) = work.pop() {
+            if reachable.insert(name.clone()) {
+                work.extend(self.get_callees(&name).iter().cloned());
+            }
+        }
+        reachable
+    }
+}
+
+/// 调用图分析，结果类型为 `CallGraph`
+pub struct CallGraphAnalysis;
+
+impl Analysis for CallGraphAnalysis {
+    fn name(&self) -> &'static str {
+        "analysis::CallGraph"
+    }
+
+    fn compute(&self, module: &ModuleRef) -> Box<dyn Any> {
+        Box::new(CallGraph::compute(module))
+    }
+}
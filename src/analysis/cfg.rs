@@ -0,0 +1,194 @@
+// 控制流图分析
+//
+// 根据基本块的终结指令 (br/condbr) 构建后继/前驱关系
+
+use crate::analysis::Analysis;
+use crate::ir::function::Function;
+use crate::ir::ModuleRef;
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+
+/// 单个函数的控制流图（以基本块名称为节点）
+#[derive(Debug, Clone, Default)]
+pub struct FunctionCFG {
+    blocks: Vec<String>,
+    successors: HashMap<String, Vec<String>>,
+    predecessors: HashMap<String, Vec<String>>,
+}
+
+impl FunctionCFG {
+    /// 根据函数当前的基本块与终结指令计算控制流图
+    pub fn compute(func: &Function) -> Self {
+        let mut cfg = FunctionCFG::default();
+
+        for bb in func.get_basic_blocks() {
+            let name = bb.borrow().get_name().to_string();
+            cfg.blocks.push(name.clone());
+            cfg.successors.insert(name.clone(), Vec::new());
+            cfg.predecessors.insert(name, Vec::new());
+        }
+
+        for bb in func.get_basic_blocks() {
+            let bb = bb.borrow();
+            let Some(terminator) = bb.get_terminator() else {
+                continue;
+            };
+            for target in terminator.borrow().branch_targets() {
+                // 忽略指向不存在基本块的跳转，由验证器负责报告
+                if !cfg.successors.contains_key(&target) {
+                    continue;
+                }
+                let succs = cfg.successors.get_mut(bb.get_name()).unwrap();
+                if succs.contains(&target) {
+                    continue;
+                }
+                succs.push(target.clone());
+                cfg.predecessors
+                    .get_mut(&target)
+                    .unwrap()
+                    .push(bb.get_name().to_string());
+            }
+        }
+
+        cfg
+    }
+
+    /// 获取所有基本块名称（按函数中的存储顺序）
+    pub fn get_blocks(&self) -> &[String] {
+        &self.blocks
+    }
+
+    /// 获取入口基本块名称
+    pub fn get_entry(&self) -> Option<&str> {
+        self.blocks.first().map(String::as_str)
+    }
+
+    /// 获取基本块的后继
+    pub fn get_successors(&self, block: &str) -> &[String] {
+        self.successors.get(block).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// 获取基本块的前驱
+    pub fn get_predecessors(&self, block: &str) -> &[String] {
+        self.predecessors.get(block).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// 从入口出发的逆后序（reverse postorder）基本块序列
+    ///
+    /// 不可达基本块不包含在结果中。
+    pub fn reverse_postorder(&self) -> Vec<String> {
+        let Some(entry) = self.get_entry() else {
+            return Vec::new();
+        };
+
+        // 迭代式 DFS：栈中保存 (基本块, 下一个待访问的后继下标)
+        let mut postorder = Vec::new();
+        let mut visited = HashSet::new();
+        let mut stack = vec![(entry, 0)];
+        visited.insert(entry);
+        while let Some((block, next)) = stack.last_mut() {
+            let succs = self.get_successors(block);
+            if let Some(succ) = succs.get(*next) {
+                *next += 1;
+                if visited.insert(succ.as_str()) {
+                    stack.push((succ.as_str(), 0));
+                }
+            } else {
+                postorder.push(block.to_string());
+                stack.pop();
+            }
+        }
+
+        postorder.reverse();
+        postorder
+    }
+}
+
+/// 整个模块的控制流图（按函数名索引）
+#[derive(Debug, Clone, Default)]
+pub struct ModuleCFG {
+    functions: HashMap<String, FunctionCFG>,
+}
+
+impl ModuleCFG {
+    /// 计算模块中所有函数的控制流图
+    pub fn compute(module: &ModuleRef) -> Self {
+        let functions = module
+            .borrow()
+            .get_functions()
+            .iter()
+            .map(|func| {
+                let func = func.borrow();
+                (func.get_name().to_string(), FunctionCFG::compute(&func))
+            })
+            .collect();
+        ModuleCFG { functions }
+    }
+
+    /// 获取指定函数的控制流图
+    pub fn get_function(&self, name: &str) -> Option<&FunctionCFG> {
+        self.functions.get(name)
+    }
+}
+
+/// 控制流图分析，结果类型为 `ModuleCFG`
+pub struct CFGAnalysis;
+
+impl Analysis for CFGAnalysis {
+    fn name(&self) -> &'static str {
+        "analysis::CFG"
+    }
+
+    fn compute(&self, module: &ModuleRef) -> Box<dyn Any> {
+        Box::new(ModuleCFG::compute(module))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::basic_block::BasicBlock;
+    use crate::ir::instruction::{Instruction, InstructionModifier, Opcode};
+    use crate::ir::types::Type;
+    use crate::ir::value::Value;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn label(name: &str) -> crate::ir::ValueRef {
+        Rc::new(RefCell::new(Value::new(Type::get_void_type(), name.to_string())))
+    }
+
+    fn add_block(func: &crate::ir::FunctionRef, name: &str, opcode: Opcode, operands: Vec<&str>) {
+        let bb = Rc::new(RefCell::new(BasicBlock::new(name.to_string(), Some(func.clone()))));
+        let instr = Instruction::new(
+            opcode,
+            None,
+            operands.into_iter().map(label).collect(),
+            InstructionModifier::None,
+        );
+        bb.borrow_mut()
+            .add_instruction(Rc::new(RefCell::new(instr)), bb.clone());
+        func.borrow_mut().add_basic_block(bb);
+    }
+
+    #[test]
+    fn test_diamond_cfg() {
+        let func = Rc::new(RefCell::new(Function::new(
+            "f".to_string(),
+            Type::get_void_type(),
+            vec![],
+        )));
+        add_block(&func, "entry", Opcode::CondBr, vec!["%c", "%then", "%else"]);
+        add_block(&func, "then", Opcode::Br, vec!["%merge"]);
+        add_block(&func, "else", Opcode::Br, vec!["merge"]);
+        add_block(&func, "merge", Opcode::Ret, vec![]);
+
+        let cfg = FunctionCFG::compute(&func.borrow());
+        assert_eq!(cfg.get_entry(), Some("entry"));
+        assert_eq!(cfg.get_successors("entry"), ["then", "else"]);
+        assert_eq!(cfg.get_predecessors("merge"), ["then", "else"]);
+        assert!(cfg.get_successors("merge").is_empty());
+        assert!(cfg.get_predecessors("entry").is_empty());
+        assert_eq!(cfg.reverse_postorder(), ["entry", "else", "then", "merge"]);
+    }
+}
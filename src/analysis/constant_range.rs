@@ -0,0 +1,186 @@
+// 常量值分析
+//
+// 在控制流图上沿逆后序迭代到不动点，把已知的整数常量经纯指令、mov 与 phi 传播，
+// 得到每个指令结果的格值（未知 / 常量 / 非常量）
+
+use crate::analysis::{Analysis, FunctionCFG};
+use crate::ir::ModuleRef;
+use crate::ir::basic_block::BasicBlockRef;
+use crate::ir::function::Function;
+use crate::ir::instruction::{Instruction, Opcode};
+use crate::ir::value::Value;
+use crate::optimizer::passes::const_fold::evaluate;
+use std::any::Any;
+use std::collections::HashMap;
+
+/// 常量传播的格值
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatticeValue {
+    /// 尚未确定（可能是任意常量）
+    Top,
+    /// 已知为该整数常量
+    Constant(i64),
+    /// 不是常量
+    Bottom,
+}
+
+impl LatticeValue {
+    /// 格的交汇：Top 为单位元，不同常量交汇为 Bottom
+    pub fn meet(self, other: LatticeValue) -> LatticeValue {
+        match (self, other) {
+            (LatticeValue::Top, x) | (x, LatticeValue::Top) => x,
+            (LatticeValue::Constant(a), LatticeValue::Constant(b)) if a == b => self,
+            _ => LatticeValue::Bottom,
+        }
+    }
+
+    /// 已知的常量值
+    pub fn as_constant(self) -> Option<i64> {
+        match self {
+            LatticeValue::Constant(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// 模块中各函数指令结果的常量值
+#[derive(Debug, Clone, Default)]
+pub struct ConstantRange {
+    /// 函数名 -> (结果名称 -> 格值)
+    functions: HashMap<String, HashMap<String, LatticeValue>>,
+}
+
+impl ConstantRange {
+    /// 计算模块中所有函数的常量值
+    pub fn compute(module: &ModuleRef) -> Self {
+        let functions = module
+            .borrow()
+            .get_functions()
+            .iter()
+            .map(|func| {
+                let func = func.borrow();
+                (func.get_name().to_string(), Self::compute_function(&func))
+            })
+            .collect();
+        ConstantRange { functions }
+    }
+
+    /// 计算单个函数中每个指令结果的格值
+    ///
+    /// 不可达基本块不参与迭代，其结果保持 Top。
+    pub fn compute_function(func: &Function) -> HashMap<String, LatticeValue> {
+        let mut values = HashMap::new();
+        for bb in func.get_basic_blocks() {
+            for instr in bb.borrow().get_instructions() {
+                if let Some(name) = instr.borrow().get_name() {
+                    values.insert(name, LatticeValue::Top);
+                }
+            }
+        }
+
+        let blocks: HashMap<String, &BasicBlockRef> = func
+            .get_basic_blocks()
+            .iter()
+            .map(|bb| (bb.borrow().get_name().to_string(), bb))
+            .collect();
+        let order = FunctionCFG::compute(func).reverse_postorder();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for name in &order {
+                let Some(bb) = blocks.get(name) else {
+                    continue;
+                };
+                for instr in bb.borrow().get_instructions() {
+                    let instr = instr.borrow();
+                    let Some(name) = instr.get_name() else {
+                        continue;
+                    };
+                    let value = Self::transfer(&instr, &values);
+                    if values.get(&name) != Some(&value) {
+                        values.insert(name, value);
+                        changed = true;
+                    }
+                }
+            }
+        }
+        values
+    }
+
+    /// 根据操作数的格值计算指令结果的格值
+    pub(crate) fn transfer(
+        instr: &Instruction,
+        values: &HashMap<String, LatticeValue>,
+    ) -> LatticeValue {
+        if let Some(value) = instr.get_result().and_then(|r| r.borrow().as_i64()) {
+            return LatticeValue::Constant(value);
+        }
+        let operands = instr.get_operands();
+        match instr.get_opcode() {
+            Opcode::Mov if operands.len() == 1 => Self::operand(&operands[0].borrow(), values),
+            // phi 操作数为 (值, 来源基本块) 对
+            Opcode::Phi => operands
+                .iter()
+                .step_by(2)
+                .fold(LatticeValue::Top, |acc, op| {
+                    acc.meet(Self::operand(&op.borrow(), values))
+                }),
+            _ if instr.is_pure() && !operands.is_empty() => {
+                let mut constants = Vec::new();
+                for op in operands {
+                    let op = op.borrow();
+                    match Self::operand(&op, values) {
+                        LatticeValue::Constant(value) => constants.push((value, op.get_type())),
+                        other => return other,
+                    }
+                }
+                match evaluate(instr.get_opcode(), &constants, &instr.get_type().borrow()) {
+                    Some(value) => LatticeValue::Constant(value),
+                    None => LatticeValue::Bottom,
+                }
+            }
+            _ => LatticeValue::Bottom,
+        }
+    }
+
+    /// 操作数的格值：常量、函数内指令结果，其它（参数、全局符号）视为非常量
+    pub(crate) fn operand(value: &Value, values: &HashMap<String, LatticeValue>) -> LatticeValue {
+        if let Some(constant) = value.as_i64() {
+            return LatticeValue::Constant(constant);
+        }
+        values
+            .get(value.get_name())
+            .copied()
+            .unwrap_or(LatticeValue::Bottom)
+    }
+
+    /// 查询函数中指定结果的格值
+    pub fn get_value(&self, function: &str, name: &str) -> LatticeValue {
+        self.functions
+            .get(function)
+            .and_then(|values| values.get(name))
+            .copied()
+            .unwrap_or(LatticeValue::Bottom)
+    }
+
+    /// 指令结果的已知常量值；指令不属于任何函数或结果不是常量时返回 None
+    pub fn known_value(&self, instr: &Instruction) -> Option<i64> {
+        let name = instr.get_name()?;
+        let func = instr.get_parent_bb()?.borrow().get_parent()?;
+        let function = func.borrow().get_name().to_string();
+        self.get_value(&function, &name).as_constant()
+    }
+}
+
+/// 常量值分析，结果类型为 `ConstantRange`
+pub struct ConstantRangeAnalysis;
+
+impl Analysis for ConstantRangeAnalysis {
+    fn name(&self) -> &'static str {
+        "analysis::ConstantRange"
+    }
+
+    fn compute(&self, module: &ModuleRef) -> Box<dyn Any> {
+        Box::new(ConstantRange::compute(module))
+    }
+}
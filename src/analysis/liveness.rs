@@ -0,0 +1,152 @@
+// 值的活跃性分析
+//
+// 在控制流图上做逆向数据流迭代到不动点，得到每个基本块入口 / 出口处活跃的值，
+// 再在基本块内逆序扫描得到每条指令之前活跃的值。phi 的来源值视为在对应前驱的
+// 出口处被使用，而不是在 phi 所在基本块的入口处。
+
+use crate::analysis::FunctionCFG;
+use crate::ir::function::Function;
+use crate::ir::instruction::{Instruction, Opcode};
+use crate::ir::operand::OperandKind;
+use std::collections::{HashMap, HashSet};
+
+/// 单个函数的活跃性分析结果（以值名称表示）
+#[derive(Debug, Clone, Default)]
+pub struct Liveness {
+    live_in: HashMap<String, HashSet<String>>,
+    live_out: HashMap<String, HashSet<String>>,
+    live_before: HashMap<u64, HashSet<String>>, // 指令 ID -> 该指令之前活跃的值
+}
+
+/// 指令中非 phi 的值使用（常量不计入）
+fn value_uses(instr: &Instruction) -> Vec<String> {
+    instr
+        .get_operand_refs()
+        .iter()
+        .filter_map(|operand| match operand.borrow().get_kind() {
+            OperandKind::Value(value) if !value.borrow().is_constant() => {
+                Some(value.borrow().get_name().to_string())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// phi 指令中来自 `pred` 的值
+fn phi_incoming(instr: &Instruction, pred: &str) -> Option<String> {
+    instr.get_operand_refs().chunks(2).find_map(|pair| {
+        let [value, block] = pair else {
+            return None;
+        };
+        let block = block.borrow().to_value();
+        if block.borrow().get_name().trim_start_matches('%') != pred {
+            return None;
+        }
+        match value.borrow().get_kind() {
+            OperandKind::Value(value) if !value.borrow().is_constant() => {
+                Some(value.borrow().get_name().to_string())
+            }
+            _ => None,
+        }
+    })
+}
+
+impl Liveness {
+    /// 计算函数的活跃性
+    pub fn compute(func: &Function) -> Self {
+        let cfg = FunctionCFG::compute(func);
+        let blocks: HashMap<String, _> = func
+            .get_basic_blocks()
+            .iter()
+            .map(|bb| (bb.borrow().get_name().to_string(), bb.clone()))
+            .collect();
+
+        // 基本块的向上暴露使用 (use) 与定义 (def)
+        let mut uses: HashMap<String, HashSet<String>> = HashMap::new();
+        let mut defs: HashMap<String, HashSet<String>> = HashMap::new();
+        for (name, bb) in &blocks {
+            let mut block_uses = HashSet::new();
+            let mut block_defs = HashSet::new();
+            for instr in bb.borrow().get_instructions() {
+                let instr = instr.borrow();
+                if instr.get_opcode() != Opcode::Phi {
+                    for used in value_uses(&instr) {
+                        if !block_defs.contains(&used) {
+                            block_uses.insert(used);
+                        }
+                    }
+                }
+                block_defs.extend(instr.get_name());
+            }
+            uses.insert(name.clone(), block_uses);
+            defs.insert(name.clone(), block_defs);
+        }
+
+        let mut liveness = Liveness::default();
+        for name in cfg.get_blocks() {
+            liveness.live_in.insert(name.clone(), HashSet::new());
+            liveness.live_out.insert(name.clone(), HashSet::new());
+        }
+
+        // 逆后序的逆序最接近逆向数据流的自然顺序；循环通过迭代到不动点处理
+        let mut order = cfg.reverse_postorder();
+        order.reverse();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for name in &order {
+                let mut out = HashSet::new();
+                for succ in cfg.get_successors(name) {
+                    out.extend(liveness.live_in[succ].iter().cloned());
+                    for instr in blocks[succ].borrow().get_instructions() {
+                        let instr = instr.borrow();
+                        if instr.get_opcode() == Opcode::Phi {
+                            out.extend(phi_incoming(&instr, name));
+                        }
+                    }
+                }
+                let mut live_in: HashSet<String> = out.difference(&defs[name]).cloned().collect();
+                live_in.extend(uses[name].iter().cloned());
+
+                if live_in != liveness.live_in[name] || out != liveness.live_out[name] {
+                    liveness.live_in.insert(name.clone(), live_in);
+                    liveness.live_out.insert(name.clone(), out);
+                    changed = true;
+                }
+            }
+        }
+
+        // 基本块内逆序扫描
+        for (name, bb) in &blocks {
+            let mut live = liveness.live_out.get(name).cloned().unwrap_or_default();
+            for instr in bb.borrow().get_instructions().iter().rev() {
+                let instr = instr.borrow();
+                if let Some(def) = instr.get_name() {
+                    live.remove(&def);
+                }
+                if instr.get_opcode() != Opcode::Phi {
+                    live.extend(value_uses(&instr));
+                }
+                liveness.live_before.insert(instr.get_id(), live.clone());
+            }
+        }
+        liveness
+    }
+
+    /// 基本块入口处活跃的值
+    pub fn get_live_in(&self, block: &str) -> Option<&HashSet<String>> {
+        self.live_in.get(block)
+    }
+
+    /// 基本块出口处活跃的值
+    pub fn get_live_out(&self, block: &str) -> Option<&HashSet<String>> {
+        self.live_out.get(block)
+    }
+
+    /// 值在指令执行之前是否活跃
+    pub fn is_live_at(&self, value: &str, instr: &Instruction) -> bool {
+        self.live_before
+            .get(&instr.get_id())
+            .is_some_and(|live| live.contains(value))
+    }
+}
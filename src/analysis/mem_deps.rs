@@ -0,0 +1,105 @@
+// 内存依赖分析
+//
+// 判断两条访存指令访问的地址是否可能重叠，供 CSE、LICM、指令调度等判断能否交换
+// 两条访存指令的顺序。别名规则见 `analysis::alias`，这里补充取地址操作数与
+// 指令上记录的内存空间。
+
+use crate::analysis::alias::{AliasResult, alias_in};
+use crate::ir::MemorySpace;
+use crate::ir::instruction::{Instruction, Opcode};
+use crate::ir::value::ValueRef;
+use std::rc::Rc;
+
+/// 访存指令之间的依赖查询
+pub struct MemDeps;
+
+impl MemDeps {
+    /// 获取访存指令的地址操作数，非访存指令返回 None
+    pub fn address(instr: &Instruction) -> Option<ValueRef> {
+        let index = match instr.get_opcode() {
+            Opcode::Load => 0,
+            Opcode::Store => 1,
+            _ => return None,
+        };
+        (index < instr.get_operand_count()).then(|| instr.get_operand(index))
+    }
+
+    /// 访存指令访问的内存空间：优先使用指令上记录的空间，其次取地址的指针类型
+    fn space(instr: &Instruction, address: &ValueRef) -> Option<MemorySpace> {
+        instr
+            .memory_space()
+            .or_else(|| address.borrow().get_type().borrow().pointer_space())
+    }
+
+    /// 判断两条访存指令之间的别名关系
+    ///
+    /// 任一指令不是 load / store 时返回 `MayAlias`。
+    pub fn query(a: &Instruction, b: &Instruction) -> AliasResult {
+        let (Some(addr_a), Some(addr_b)) = (Self::address(a), Self::address(b)) else {
+            return AliasResult::MayAlias;
+        };
+        if Rc::ptr_eq(&addr_a, &addr_b) {
+            return AliasResult::MustAlias;
+        }
+        let (space_a, space_b) = (Self::space(a, &addr_a), Self::space(b, &addr_b));
+        alias_in(&addr_a.borrow(), space_a, &addr_b.borrow(), space_b)
+    }
+
+    /// 判断两条访存指令能否交换顺序
+    ///
+    /// 两条非 volatile 的 load 总可以交换；至少一方写内存时，只有证明不相交才能交换。
+    pub fn can_reorder(a: &Instruction, b: &Instruction) -> bool {
+        let is_plain_load =
+            |instr: &Instruction| instr.get_opcode() == Opcode::Load && !instr.has_side_effects();
+        if is_plain_load(a) && is_plain_load(b) {
+            return true;
+        }
+        Self::query(a, b) == AliasResult::NoAlias
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::instruction::InstructionRef;
+
+    fn instructions(source: &str) -> Vec<InstructionRef> {
+        let module = crate::frontend::parse_vil(source, "mem_deps.vil").expect("应成功解析模块");
+        let func = module.borrow().get_function("f").unwrap();
+        func.borrow().instructions().collect()
+    }
+
+    #[test]
+    fn test_mem_deps_query() {
+        let instrs = instructions(
+            r#".module m
+.function f(.param %v i32, .param %x i32* sram, .param %y i32* sram) {
+    %p = alloc i32 [sram]
+    %q = alloc i32 [vspm]
+    %a = load %p
+    store %v, %q
+    store %v, %p
+    %b = load %x
+    %c = load %y
+    ret
+}
+"#,
+        );
+        let query = |i: usize, j: usize| MemDeps::query(&instrs[i].borrow(), &instrs[j].borrow());
+        let reorder =
+            |i: usize, j: usize| MemDeps::can_reorder(&instrs[i].borrow(), &instrs[j].borrow());
+
+        // 不同内存空间
+        assert_eq!(query(2, 3), AliasResult::NoAlias);
+        assert!(reorder(2, 3));
+        // 同一指针
+        assert_eq!(query(2, 4), AliasResult::MustAlias);
+        assert!(!reorder(2, 4));
+        // 同一空间中的两个参数指针无法区分
+        assert_eq!(query(4, 5), AliasResult::MayAlias);
+        assert_eq!(query(5, 6), AliasResult::MayAlias);
+        assert!(reorder(5, 6));
+        // 非访存指令
+        assert_eq!(query(0, 2), AliasResult::MayAlias);
+    }
+}
@@ -0,0 +1,35 @@
+// 分析模块入口
+//
+// 这个模块包含只读的 IR 分析（如控制流图、验证器），其结果可由 PassManager 缓存，
+// 并在修改 IR 的 Pass 运行后失效
+
+pub mod alias;
+pub mod alloc_checker;
+pub mod call_graph;
+pub mod cfg;
+pub mod constant_range;
+pub mod liveness;
+pub mod mem_deps;
+pub mod verifier;
+
+use crate::ir::ModuleRef;
+use std::any::Any;
+
+// 重新导出常用类型
+pub use alias::{AliasResult, may_alias};
+pub use alloc_checker::{AllocChecker, AllocDiagnostic, AllocDiagnosticKind};
+pub use call_graph::{CallGraph, CallGraphAnalysis};
+pub use cfg::{CFGAnalysis, FunctionCFG, ModuleCFG};
+pub use constant_range::{ConstantRange, ConstantRangeAnalysis, LatticeValue};
+pub use liveness::Liveness;
+pub use mem_deps::MemDeps;
+pub use verifier::{Verifier, VerifierError};
+
+/// 所有分析需实现的统一接口
+pub trait Analysis {
+    /// 分析唯一名称（建议使用 "analysis::Name" 格式），同时作为缓存键
+    fn name(&self) -> &'static str;
+
+    /// 计算分析结果
+    fn compute(&self, module: &ModuleRef) -> Box<dyn Any>;
+}
@@ -0,0 +1,1186 @@
+// IR 验证器
+//
+// 检查 IR 的结构与类型约束，收集所有违规而不是在第一个错误处停止
+
+use crate::analysis::Analysis;
+use crate::diagnostics::DiagnosticBag;
+use crate::ir::{MemorySpace, ModuleRef};
+use crate::ir::function::Function;
+use crate::ir::instruction::{Instruction, InstructionModifier, Opcode};
+use crate::ir::module::Module;
+use crate::ir::types::{Type, TypeKind, TypeRef};
+use std::any::Any;
+use std::fmt;
+
+/// 验证错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifierError {
+    pub function: String,
+    pub block: String,
+    /// 出错指令的文本形式（块级错误为 None）
+    pub instruction: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for VerifierError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}::{}: {}", self.function, self.block, self.message)?;
+        if let Some(instr) = &self.instruction {
+            write!(f, " (在指令 `{}`)", instr)?;
+        }
+        Ok(())
+    }
+}
+
+/// IR 验证器
+pub struct Verifier;
+
+impl Verifier {
+    /// 验证整个模块
+    pub fn verify_module(module: &Module) -> Vec<VerifierError> {
+        module
+            .get_functions()
+            .iter()
+            .flat_map(|func| Self::verify_function(&func.borrow()))
+            .collect()
+    }
+
+    /// 验证整个模块，将每个违规作为错误推入 `diagnostics`，返回错误数量
+    pub fn verify_module_with_diagnostics(
+        module: &Module,
+        diagnostics: &mut DiagnosticBag,
+    ) -> usize {
+        let errors = Self::verify_module(module);
+        for error in &errors {
+            diagnostics.error(&error.to_string(), None);
+        }
+        errors.len()
+    }
+
+    /// 验证单个函数
+    ///
+    /// 外部函数声明没有函数体，不做检查。
+    pub fn verify_function(func: &Function) -> Vec<VerifierError> {
+        if func.is_declaration() {
+            return Vec::new();
+        }
+        let mut errors: Vec<VerifierError> = func
+            .verify_terminators()
+            .into_iter()
+            .map(|(block, message)| VerifierError {
+                function: func.get_name().to_string(),
+                block,
+                instruction: None,
+                message,
+            })
+            .collect();
+        for bb in func.get_basic_blocks() {
+            let bb = bb.borrow();
+            for instr in bb.get_instructions() {
+                let instr = instr.borrow();
+                let mut messages = Self::verify_instruction(&instr);
+                if instr.get_opcode() == Opcode::Ret {
+                    Self::check_return(func, &instr, &mut messages);
+                }
+                for message in messages {
+                    errors.push(VerifierError {
+                        function: func.get_name().to_string(),
+                        block: bb.get_name().to_string(),
+                        instruction: Some(instr.to_string()),
+                        message,
+                    });
+                }
+            }
+        }
+        errors
+    }
+
+    /// `ret` 与函数声明的返回类型一致：带值时返回类型非 void 且与值类型相同，不带值时返回类型为 void
+    ///
+    /// 有结果参数的函数改为检查 `ret` 的值与结果参数逐个对应。立即数常量的类型不参与比较。
+    fn check_return(func: &Function, instr: &Instruction, errors: &mut Vec<String>) {
+        if !func.results().is_empty() {
+            Self::check_multi_return(func, instr, errors);
+            return;
+        }
+        let return_type = func.get_return_type();
+        let return_type = return_type.borrow();
+        let is_void = matches!(return_type.get_kind(), TypeKind::Void);
+        match instr.get_operands().as_slice() {
+            [] if !is_void => {
+                errors.push(format!(
+                    "返回类型为 {} 的函数中 ret 缺少返回值",
+                    return_type
+                ));
+            }
+            [] => {}
+            [_] if is_void => errors.push("void 函数的 ret 不能带返回值".to_string()),
+            [value] => {
+                let value = value.borrow();
+                if !value.is_constant() && *value.get_type().borrow() != *return_type {
+                    errors.push(format!(
+                        "ret 的值类型 {} 与函数返回类型 {} 不匹配",
+                        value.get_type().borrow(),
+                        return_type
+                    ));
+                }
+            }
+            operands => errors.push(format!(
+                "ret 最多只能有 1 个操作数，实际为 {}",
+                operands.len()
+            )),
+        }
+    }
+
+    /// 有结果参数的函数的 `ret`：值的个数与结果参数相同，类型逐个一致
+    ///
+    /// 结果参数全为指针（输出内存）时，不带值的 `ret` 返回这些指针本身。
+    fn check_multi_return(func: &Function, instr: &Instruction, errors: &mut Vec<String>) {
+        let results = func.results();
+        let values = instr.get_operands();
+        if values.is_empty()
+            && results
+                .iter()
+                .all(|result| result.borrow().get_type().borrow().pointer_space().is_some())
+        {
+            return;
+        }
+        if values.len() != results.len() {
+            errors.push(format!(
+                "ret 的返回值个数 {} 与结果参数个数 {} 不一致",
+                values.len(),
+                results.len()
+            ));
+            return;
+        }
+        for (i, (value, result)) in values.iter().zip(&results).enumerate() {
+            let value = value.borrow();
+            let result = result.borrow();
+            if !value.is_constant()
+                && *value.get_type().borrow() != *result.get_type().borrow()
+            {
+                errors.push(format!(
+                    "ret 的第 {} 个值类型 {} 与结果参数 {} 的类型 {} 不匹配",
+                    i + 1,
+                    value.get_type().borrow(),
+                    result.get_name(),
+                    result.get_type().borrow()
+                ));
+            }
+        }
+    }
+
+    /// 验证单条指令，返回所有错误信息
+    pub fn verify_instruction(instr: &Instruction) -> Vec<String> {
+        let mut errors = Vec::new();
+        if instr.get_opcode().is_comparison() {
+            Self::check_comparison(instr, &mut errors);
+        }
+        Self::check_modifier(instr, &mut errors);
+        match instr.get_opcode() {
+            Opcode::Load | Opcode::Store => Self::check_memory_space(instr, &mut errors),
+            Opcode::SAdd | Opcode::SMul => Self::check_vector_scalar(instr, &mut errors),
+            Opcode::Broadcast => Self::check_broadcast(instr, &mut errors),
+            Opcode::RedSum | Opcode::RedMax | Opcode::RedMin => {
+                Self::check_reduction(instr, &mut errors)
+            }
+            Opcode::Shuffle | Opcode::ShuffleClbmv => Self::check_shuffle(instr, &mut errors),
+            Opcode::Extract => Self::check_extract(instr, &mut errors),
+            Opcode::Bitcast => Self::check_bitcast(instr, &mut errors),
+            Opcode::SExt | Opcode::ZExt | Opcode::Trunc => {
+                Self::check_int_conversion(instr, &mut errors)
+            }
+            Opcode::Add
+            | Opcode::Sub
+            | Opcode::Mul
+            | Opcode::And
+            | Opcode::Or
+            | Opcode::Xor
+            | Opcode::Div
+            | Opcode::DivU
+            | Opcode::Rem
+            | Opcode::RemU => Self::check_binary_types(instr, &mut errors),
+            _ => {}
+        }
+        errors
+    }
+
+    /// 显式修饰符需与类型一致：`.v` 至少一个向量操作数，`.p` 结果为谓词（带掩码指令见
+    /// `check_mask`），`.s` 操作数全为标量
+    fn check_modifier(instr: &Instruction, errors: &mut Vec<String>) {
+        let operand_types: Vec<TypeRef> = instr
+            .get_data_operands()
+            .iter()
+            .map(|op| op.borrow().get_type())
+            .collect();
+        match instr.get_modifier() {
+            InstructionModifier::Vector => {
+                if !operand_types.iter().any(|t| t.borrow().is_vector()) {
+                    errors.push(".v 修饰符要求至少一个向量操作数".to_string());
+                }
+            }
+            InstructionModifier::Predicate if instr.is_masked() => {
+                Self::check_mask(instr, errors);
+            }
+            InstructionModifier::Predicate => {
+                let is_predicate = instr
+                    .get_result()
+                    .is_some_and(|r| r.borrow().get_type().borrow().is_predicate());
+                if !is_predicate {
+                    errors.push(".p 修饰符要求结果为谓词类型".to_string());
+                }
+            }
+            InstructionModifier::Scalar => {
+                if let Some(t) = operand_types.iter().find(|t| !t.borrow().is_scalar()) {
+                    errors.push(format!(
+                        ".s 修饰符要求操作数全为标量，实际包含 {}",
+                        t.borrow()
+                    ));
+                }
+            }
+            InstructionModifier::None => {}
+        }
+    }
+
+    /// 带掩码指令：最后一个操作数为 `<pred N>`，N 等于数据宽度（向量长度，标量为 1）
+    fn check_mask(instr: &Instruction, errors: &mut Vec<String>) {
+        let opcode = instr.get_opcode();
+        let Some(mask) = instr.get_mask() else {
+            errors.push(format!("{}.p 缺少谓词掩码操作数", opcode));
+            return;
+        };
+        let mask_type = mask.borrow().get_type();
+        let mask_len = match mask_type.borrow().get_kind() {
+            TypeKind::Predicate(length) => *length,
+            _ => {
+                errors.push(format!(
+                    "{}.p 的掩码操作数必须为谓词类型，实际为 {}",
+                    opcode,
+                    mask_type.borrow()
+                ));
+                return;
+            }
+        };
+        let data_type = match instr.get_result() {
+            Some(result) => result.borrow().get_type(),
+            None => match instr.get_data_operands().first() {
+                Some(operand) => operand.borrow().get_type(),
+                None => return,
+            },
+        };
+        let width = match data_type.borrow().get_kind() {
+            TypeKind::Vector(_, length) => *length,
+            _ => 1,
+        };
+        if mask_len != width {
+            errors.push(format!(
+                "{}.p 的掩码类型 {} 与数据类型 {} 的宽度 {} 不一致",
+                opcode,
+                mask_type.borrow(),
+                data_type.borrow(),
+                width
+            ));
+        }
+    }
+
+    /// 访存指令的地址操作数必须为指针类型，且指令标注的内存空间需与指针的内存空间一致
+    /// （`generic` 视为通配）
+    ///
+    /// `load` 的指针为第 0 个操作数，`store` 的指针为第 1 个操作数。
+    fn check_memory_space(instr: &Instruction, errors: &mut Vec<String>) {
+        let opcode = instr.get_opcode();
+        let index = if opcode == Opcode::Load { 0 } else { 1 };
+        if index >= instr.get_operand_count() {
+            return;
+        }
+        let pointer = instr.get_operand(index);
+        let pointer = pointer.borrow();
+        let Some(pointer_space) = pointer.get_type().borrow().pointer_space() else {
+            errors.push(format!(
+                "{} 的地址操作数 {} 不是指针类型，实际为 {}",
+                opcode,
+                pointer.get_name(),
+                pointer.get_type().borrow()
+            ));
+            return;
+        };
+        if let Some(space) = instr.memory_space()
+            && !MemorySpace::spaces_compatible(space, pointer_space)
+        {
+            errors.push(format!(
+                "{} 的内存空间 {} 与指针 {} 的内存空间 {} 不一致",
+                opcode,
+                space,
+                pointer.get_name(),
+                pointer_space
+            ));
+        }
+    }
+
+    /// 向量与标量运算 (sadd/smul)：需要一个向量操作数和一个标量操作数，且元素类型一致
+    fn check_vector_scalar(instr: &Instruction, errors: &mut Vec<String>) {
+        let operands = instr.get_data_operands();
+        if operands.len() != 2 {
+            errors.push(format!(
+                "{} 需要 2 个操作数，实际为 {}",
+                instr.get_opcode(),
+                operands.len()
+            ));
+            return;
+        }
+        let types: Vec<TypeRef> = operands
+            .iter()
+            .map(|op| op.borrow().get_type())
+            .collect();
+        let vector = types.iter().find(|t| t.borrow().is_vector());
+        let scalar = types.iter().find(|t| t.borrow().is_scalar());
+        let (Some(vector), Some(scalar)) = (vector, scalar) else {
+            errors.push(format!(
+                "{} 需要一个向量操作数和一个标量操作数，实际为 {} 与 {}",
+                instr.get_opcode(),
+                types[0].borrow(),
+                types[1].borrow()
+            ));
+            return;
+        };
+        if let TypeKind::Vector(elem, _) = vector.borrow().get_kind()
+            && *elem.borrow() != *scalar.borrow()
+        {
+            errors.push(format!(
+                "{} 的向量元素类型 {} 与标量类型 {} 不一致",
+                instr.get_opcode(),
+                elem.borrow(),
+                scalar.borrow()
+            ));
+        }
+    }
+
+    /// 二元运算：操作数类型可以统一（见 `Type::get_common_type`），结果为公共类型
+    ///
+    /// 立即数常量的类型随另一个操作数，不参与统一。
+    fn check_binary_types(instr: &Instruction, errors: &mut Vec<String>) {
+        let types: Vec<TypeRef> = instr
+            .get_data_operands()
+            .iter()
+            .filter(|op| !op.borrow().is_constant())
+            .map(|op| op.borrow().get_type())
+            .collect();
+        let Some((first, rest)) = types.split_first() else {
+            return;
+        };
+
+        let mut common = first.clone();
+        for type_ in rest {
+            match Type::get_common_type(&common, type_) {
+                Some(unified) => common = unified,
+                None => {
+                    errors.push(format!(
+                        "{} 的操作数类型 {} 与 {} 不兼容",
+                        instr.get_opcode(),
+                        common.borrow(),
+                        type_.borrow()
+                    ));
+                    return;
+                }
+            }
+        }
+
+        if let Some(result) = instr.get_result() {
+            let result_type = result.borrow().get_type();
+            if *result_type.borrow() != *common.borrow() {
+                errors.push(format!(
+                    "{} 的结果类型应为 {}，实际为 {}",
+                    instr.get_opcode(),
+                    common.borrow(),
+                    result_type.borrow()
+                ));
+            }
+        }
+    }
+
+    /// 按位重新解释：单个操作数，源类型与目标类型位宽相同
+    fn check_bitcast(instr: &Instruction, errors: &mut Vec<String>) {
+        let Some(result) = instr.get_result() else {
+            errors.push("bitcast 必须产生结果".to_string());
+            return;
+        };
+        if instr.get_operand_count() != 1 {
+            errors.push(format!(
+                "bitcast 需要 1 个操作数，实际为 {}",
+                instr.get_operand_count()
+            ));
+            return;
+        }
+        let source = instr.get_operand(0).borrow().get_type();
+        let target = result.borrow().get_type();
+        let (source, target) = (source.borrow(), target.borrow());
+        if source.get_bit_width() != target.get_bit_width() {
+            errors.push(format!(
+                "bitcast 的源类型 {} ({} 位) 与目标类型 {} ({} 位) 位宽不同",
+                source,
+                source.get_bit_width(),
+                target,
+                target.get_bit_width()
+            ));
+        }
+    }
+
+    /// 整数宽度转换：sext/zext 扩展到更宽的整数类型，trunc 截断到更窄的整数类型
+    ///
+    /// 向量按元素类型比较，且源与目标向量长度相同。
+    fn check_int_conversion(instr: &Instruction, errors: &mut Vec<String>) {
+        let opcode = instr.get_opcode();
+        let Some(result) = instr.get_result() else {
+            errors.push(format!("{} 必须产生结果", opcode));
+            return;
+        };
+        if instr.get_operand_count() != 1 {
+            errors.push(format!(
+                "{} 需要 1 个操作数，实际为 {}",
+                opcode,
+                instr.get_operand_count()
+            ));
+            return;
+        }
+
+        // 拆出 (元素类型, 向量长度)，标量长度记为 None
+        let split = |type_: TypeRef| match type_.borrow().get_kind() {
+            TypeKind::Vector(elem, length) => (elem.clone(), Some(*length)),
+            _ => (type_.clone(), None),
+        };
+        let source = instr.get_operand(0).borrow().get_type();
+        let target = result.borrow().get_type();
+        let (source_elem, source_len) = split(source.clone());
+        let (target_elem, target_len) = split(target.clone());
+        if !source_elem.borrow().is_integer() || !target_elem.borrow().is_integer() {
+            errors.push(format!(
+                "{} 要求整数类型，实际为 {} 与 {}",
+                opcode,
+                source.borrow(),
+                target.borrow()
+            ));
+            return;
+        }
+        if source_len != target_len {
+            errors.push(format!(
+                "{} 的源类型 {} 与目标类型 {} 长度不同",
+                opcode,
+                source.borrow(),
+                target.borrow()
+            ));
+            return;
+        }
+
+        let source_width = source_elem.borrow().get_bit_width();
+        let target_width = target_elem.borrow().get_bit_width();
+        let widening = opcode != Opcode::Trunc;
+        if (widening && target_width <= source_width) || (!widening && target_width >= source_width)
+        {
+            errors.push(format!(
+                "{} 要求目标类型{}于源类型，实际为 {} -> {}",
+                opcode,
+                if widening { "宽" } else { "窄" },
+                source.borrow(),
+                target.borrow()
+            ));
+        }
+    }
+
+    /// 广播：结果向量的元素类型与标量输入一致
+    fn check_broadcast(instr: &Instruction, errors: &mut Vec<String>) {
+        let Some(result) = instr.get_result() else {
+            errors.push("broadcast 必须产生结果".to_string());
+            return;
+        };
+        if instr.get_operand_count() == 0 {
+            errors.push("broadcast 缺少操作数".to_string());
+            return;
+        }
+        let input = instr.get_operand(0).borrow().get_type();
+        let result_type = result.borrow().get_type();
+        match result_type.borrow().get_kind() {
+            TypeKind::Vector(elem, _) => {
+                if *elem.borrow() != *input.borrow() {
+                    errors.push(format!(
+                        "broadcast 结果元素类型 {} 与输入类型 {} 不一致",
+                        elem.borrow(),
+                        input.borrow()
+                    ));
+                }
+            }
+            _ => errors.push(format!(
+                "broadcast 结果应为向量类型，实际为 {}",
+                result_type.borrow()
+            )),
+        }
+    }
+
+    /// 归约：输入为长度非零的向量，结果类型与其元素类型一致
+    fn check_reduction(instr: &Instruction, errors: &mut Vec<String>) {
+        let opcode = instr.get_opcode();
+        let Some(result) = instr.get_result() else {
+            errors.push(format!("{} 必须产生结果", opcode));
+            return;
+        };
+        if instr.get_operand_count() == 0 {
+            errors.push(format!("{} 缺少操作数", opcode));
+            return;
+        }
+        let input = instr.get_operand(0).borrow().get_type();
+        let result_type = result.borrow().get_type();
+        match input.borrow().get_kind() {
+            TypeKind::Vector(_, 0) => errors.push(format!(
+                "{} 的输入向量 {} 长度为 0",
+                opcode,
+                input.borrow()
+            )),
+            TypeKind::Vector(elem, _) => {
+                if *elem.borrow() != *result_type.borrow() {
+                    errors.push(format!(
+                        "{} 结果类型 {} 与输入向量元素类型 {} 不一致",
+                        opcode,
+                        result_type.borrow(),
+                        elem.borrow()
+                    ));
+                }
+            }
+            _ => errors.push(format!(
+                "{} 的输入应为向量类型，实际为 {}",
+                opcode,
+                input.borrow()
+            )),
+        }
+    }
+
+    /// 洗牌：源操作数为向量，常量索引均位于 `[0, len)` 内，结果为与索引等长的同元素向量
+    fn check_shuffle(instr: &Instruction, errors: &mut Vec<String>) {
+        let opcode = instr.get_opcode();
+        if instr.get_operand_count() < 2 {
+            errors.push(format!("{} 需要源向量与索引两个操作数", opcode));
+            return;
+        }
+        let source = instr.get_operand(0).borrow().get_type();
+        let (elem, length) = match source.borrow().get_kind() {
+            TypeKind::Vector(elem, length) => (elem.clone(), *length),
+            _ => {
+                errors.push(format!(
+                    "{} 的源操作数应为向量类型，实际为 {}",
+                    opcode,
+                    source.borrow()
+                ));
+                return;
+            }
+        };
+        // 以向量值或立即数给出的索引在运行时确定，不做范围检查
+        let Some(indices) = instr.shuffle_indices() else {
+            return;
+        };
+        for (i, index) in indices.iter().enumerate() {
+            if !(0..i64::from(length)).contains(index) {
+                errors.push(format!(
+                    "{} 的第 {} 个索引 {} 超出范围 [0, {})",
+                    opcode, i, index, length
+                ));
+            }
+        }
+        if let Some(result) = instr.get_result() {
+            let expected = Type::get_vector_type(elem, indices.len() as u32);
+            let actual = result.borrow().get_type();
+            if *actual.borrow() != *expected.borrow() {
+                errors.push(format!(
+                    "{} 结果类型应为 {}，实际为 {}",
+                    opcode,
+                    expected.borrow(),
+                    actual.borrow()
+                ));
+            }
+        }
+    }
+
+    /// `extract %t, i`：%t 为元组，i 为范围内的常量下标，结果类型为对应元素的类型
+    fn check_extract(instr: &Instruction, errors: &mut Vec<String>) {
+        if instr.get_operand_count() != 2 {
+            errors.push("extract 需要元组与下标两个操作数".to_string());
+            return;
+        }
+        let tuple = instr.get_operand(0).borrow().get_type();
+        let TypeKind::Tuple(elems) = tuple.borrow().get_kind().clone() else {
+            errors.push(format!(
+                "extract 的源操作数应为元组类型，实际为 {}",
+                tuple.borrow()
+            ));
+            return;
+        };
+        let Some(index) = instr.get_operand(1).borrow().as_i64() else {
+            errors.push("extract 的下标必须是常量".to_string());
+            return;
+        };
+        let Some(elem) = usize::try_from(index).ok().and_then(|i| elems.get(i)) else {
+            errors.push(format!(
+                "extract 的下标 {} 超出范围 [0, {})",
+                index,
+                elems.len()
+            ));
+            return;
+        };
+        if let Some(result) = instr.get_result()
+            && *result.borrow().get_type().borrow() != *elem.borrow()
+        {
+            errors.push(format!(
+                "extract 结果类型应为 {}，实际为 {}",
+                elem.borrow(),
+                result.borrow().get_type().borrow()
+            ));
+        }
+    }
+
+    /// 比较指令：向量输入产生等长的 `<pred N>`，标量输入产生 `<pred 1>`
+    fn check_comparison(instr: &Instruction, errors: &mut Vec<String>) {
+        let Some(result) = instr.get_result() else {
+            errors.push("比较指令必须产生结果".to_string());
+            return;
+        };
+        if instr.get_operand_count() == 0 {
+            errors.push("比较指令缺少操作数".to_string());
+            return;
+        }
+
+        let expected_len = match instr.get_operand(0).borrow().get_type().borrow().get_kind() {
+            TypeKind::Vector(_, length) => *length,
+            _ => 1,
+        };
+        let result_type = result.borrow().get_type();
+        let actual = result_type.borrow();
+        if !matches!(actual.get_kind(), TypeKind::Predicate(n) if *n == expected_len) {
+            errors.push(format!(
+                "比较结果类型应为 <pred {}>，实际为 {}",
+                expected_len, actual
+            ));
+        }
+    }
+}
+
+impl Analysis for Verifier {
+    fn name(&self) -> &'static str {
+        "analysis::Verifier"
+    }
+
+    /// 结果类型为 `Vec<VerifierError>`
+    fn compute(&self, module: &ModuleRef) -> Box<dyn Any> {
+        Box::new(Self::verify_module(&module.borrow()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::parse_vil;
+
+    #[test]
+    fn test_comparison_result_types() {
+        let source = r#".module m
+.function f(.param %a <i32 x 8>, .param %b <i32 x 8>, .param %x i32, .param %y i32) {
+    %p = cmplt %a, %b
+    %q = cmpeq %x, %y
+    ret
+}
+"#;
+        let module = parse_vil(source, "cmp.vil").expect("应成功解析模块");
+        assert!(Verifier::verify_module(&module.borrow()).is_empty());
+
+        // 将向量比较的结果改为错误的谓词长度
+        let func = module.borrow().get_function("f").unwrap();
+        let cmp = func.borrow().get_basic_blocks()[0]
+            .borrow()
+            .get_instructions()[0]
+            .clone();
+        cmp.borrow()
+            .get_result()
+            .unwrap()
+            .borrow_mut()
+            .set_type(Type::get_predicate_type(4));
+
+        let errors = Verifier::verify_module(&module.borrow());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].block, "entry");
+        assert!(errors[0].message.contains("<pred 8>"));
+    }
+
+    #[test]
+    fn test_scalar_comparison_requires_boolean() {
+        let source = r#".module m
+.function f(.param %x i32, .param %y i32) {
+    %q = cmpgt %x, %y
+    ret
+}
+"#;
+        let module = parse_vil(source, "cmp.vil").expect("应成功解析模块");
+        let func = module.borrow().get_function("f").unwrap();
+        let cmp = func.borrow().get_basic_blocks()[0]
+            .borrow()
+            .get_instructions()[0]
+            .clone();
+        cmp.borrow()
+            .get_result()
+            .unwrap()
+            .borrow_mut()
+            .set_type(Type::get_int_type(TypeKind::Int32));
+
+        let errors = Verifier::verify_module(&module.borrow());
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("<pred 1>"));
+    }
+
+    #[test]
+    fn test_vector_scalar_element_types() {
+        let source = r#".module m
+.function f(.param %v <i32 x 8>, .param %s i32, .param %h i16) {
+    %a = sadd %v, %s
+    %b = smul %v, %h
+    ret
+}
+"#;
+        let module = parse_vil(source, "sadd.vil").expect("应成功解析模块");
+        let errors = Verifier::verify_module(&module.borrow());
+
+        // 只有元素类型不匹配的 smul 报错
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].instruction.as_deref().unwrap().contains("smul"));
+        assert!(errors[0].message.contains("i32"));
+        assert!(errors[0].message.contains("i16"));
+    }
+
+    #[test]
+    fn test_binary_operand_types() {
+        let source = r#".module m
+.function f(.param %v <i16 x 8>, .param %h i16, .param %x i32) {
+    %a = add %v, %h
+    %b = mul %x, 3
+    %c = xor %h, %x
+    ret
+}
+"#;
+        let module = parse_vil(source, "binary.vil").expect("应成功解析模块");
+        let errors = Verifier::verify_module(&module.borrow());
+
+        // 向量与元素类型可以统一，立即数随另一操作数；只有 i16 与 i32 不兼容
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].instruction.as_deref().unwrap().contains("xor"));
+        assert!(errors[0].message.contains("不兼容"));
+    }
+
+    #[test]
+    fn test_block_missing_terminator() {
+        let source = r#".module m
+.function f(.param %x i32) {
+entry:
+    %y = add %x, 1
+next:
+    ret
+}
+"#;
+        let module = parse_vil(source, "term.vil").expect("应成功解析模块");
+        let errors = Verifier::verify_module(&module.borrow());
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert_eq!(errors[0].block, "entry");
+        assert!(errors[0].instruction.is_none());
+        assert!(errors[0].message.contains("未以终结指令结尾"));
+    }
+
+    #[test]
+    fn test_terminator_in_middle_of_block() {
+        let source = r#".module m
+.function f(.param %x i32) {
+entry:
+    br %next
+    %y = add %x, 1
+    br %next
+next:
+    ret
+}
+"#;
+        let module = parse_vil(source, "term.vil").expect("应成功解析模块");
+        let errors = Verifier::verify_module(&module.borrow());
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert_eq!(errors[0].block, "entry");
+        assert!(errors[0].message.contains("出现在基本块中间"));
+        assert!(errors[0].message.contains("br"));
+    }
+
+    #[test]
+    fn test_return_types() {
+        let source = r#".module m
+.function ok(.param %x i32) -> i32 {
+    %y = add %x, 1
+    ret %y
+}
+.function ok_void(.param %x i32) {
+    ret
+}
+.function wrong_type(.param %h i16) -> i32 {
+    ret %h
+}
+.function missing(.param %x i32) -> i32 {
+    ret
+}
+.function extra(.param %x i32) {
+    ret %x
+}
+"#;
+        let module = parse_vil(source, "ret.vil").expect("应成功解析模块");
+        let errors = Verifier::verify_module(&module.borrow());
+        assert_eq!(errors.len(), 3, "{:?}", errors);
+        assert_eq!(errors[0].function, "wrong_type");
+        assert!(errors[0].message.contains("不匹配"));
+        assert_eq!(errors[1].function, "missing");
+        assert!(errors[1].message.contains("缺少返回值"));
+        assert_eq!(errors[2].function, "extra");
+        assert!(errors[2].message.contains("void"));
+    }
+
+    #[test]
+    fn test_multi_return_arity() {
+        let source = r#".module m
+.function ok(.param %a i32, .result %q i32, .result %r i16) {
+    %h = trunc %a : i16
+    ret %a, %h
+}
+.function too_few(.param %a i32, .result %q i32, .result %r i32) {
+    ret %a
+}
+.function wrong_type(.param %h i16, .result %q i32, .result %r i32) {
+    ret %h, 1
+}
+.function output_memory(.param %v i32, .result %out i32* sram) {
+    store %v, %out
+    ret
+}
+"#;
+        let module = parse_vil(source, "multi_ret.vil").expect("应成功解析模块");
+        let errors = Verifier::verify_module(&module.borrow());
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert_eq!(errors[0].function, "too_few");
+        assert!(
+            errors[0].message.contains("返回值个数 1 与结果参数个数 2"),
+            "{}",
+            errors[0]
+        );
+        assert_eq!(errors[1].function, "wrong_type");
+        assert!(errors[1].message.contains("结果参数 %q"), "{}", errors[1]);
+    }
+
+    #[test]
+    fn test_multi_result_call() {
+        let source = r#".module m
+.function divmod(.param %a i32, .param %b i32, .result %q i32, .result %r i32) {
+    %x = div %a, %b
+    %y = rem %a, %b
+    ret %x, %y
+}
+.function output_memory(.param %v i32, .result %out i32* sram) {
+    store %v, %out
+    ret
+}
+.function caller(.param %a i32, .param %b i32) -> i32 {
+    %qr = call @divmod(%a, %b)
+    %q = extract %qr, 0
+    %r = extract %qr, 1
+    %p = call @output_memory(%q)
+    %v = load %p
+    %s = add %v, %r
+    ret %s
+}
+.function bad(.param %a i32) -> i32 {
+    %qr = call @divmod(%a, %a)
+    %x = extract %qr, 2
+    %y = extract %a, 0
+    ret %a
+}
+"#;
+        let module = parse_vil(source, "multi_call.vil").expect("应成功解析模块");
+        let caller = module.borrow().get_function("caller").unwrap();
+        let types: Vec<String> = caller
+            .borrow()
+            .instructions()
+            .filter_map(|instr| instr.borrow().get_result())
+            .map(|result| result.borrow().get_type().borrow().to_string())
+            .collect();
+        assert_eq!(types, ["{i32, i32}", "i32", "i32", "i32* sram", "i32", "i32"]);
+
+        let errors = Verifier::verify_module(&module.borrow());
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert_eq!(errors[0].function, "bad");
+        assert!(errors[0].message.contains("下标 2 超出范围"), "{}", errors[0]);
+        assert!(errors[1].message.contains("应为元组类型"), "{}", errors[1]);
+        crate::frontend::roundtrip_ok(source).unwrap();
+    }
+
+    #[test]
+    fn test_call_to_declaration() {
+        let source = r#".module m
+.declare @clamp(.param %x i32) -> i32
+.function f(.param %x i32) -> i32 {
+    %y = add 1, 2
+    %r = call @clamp(%y)
+    ret %r
+}
+"#;
+        let module = parse_vil(source, "declare.vil").expect("应成功解析模块");
+        assert!(Verifier::verify_module(&module.borrow()).is_empty());
+
+        // 声明可以被调用，但不参与优化
+        use crate::optimizer::Pass;
+        let folded = crate::optimizer::passes::ConstantFoldingPass::new().run(&module);
+        assert_eq!(folded, 1);
+        let clamp = module.borrow().get_function("clamp").unwrap();
+        assert!(clamp.borrow().is_declaration());
+        assert!(clamp.borrow().get_basic_blocks().is_empty());
+        assert!(Verifier::verify_module(&module.borrow()).is_empty());
+    }
+
+    #[test]
+    fn test_bitcast_widths() {
+        let source = r#".module m
+.function f(.param %x i32, .param %h i16) {
+    %a = bitcast %x : b32
+    %b = bitcast %x : u32
+    %c = bitcast %h : b32
+    ret
+}
+"#;
+        let module = parse_vil(source, "bitcast.vil").expect("应成功解析模块");
+        let errors = Verifier::verify_module(&module.borrow());
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("位宽不同"), "{}", errors[0]);
+        assert!(errors[0].message.contains("i16"));
+    }
+
+    #[test]
+    fn test_int_conversion_widths() {
+        let source = r#".module m
+.function f(.param %b i8, .param %w i32, .param %v <i8 x 4>) {
+    %a = sext %b : i32
+    %c = zext %v : <u16 x 4>
+    %d = trunc %w : i16
+    %e = sext %w : i16
+    %g = trunc %b : i32
+    %h = zext %v : <u16 x 8>
+    ret
+}
+"#;
+        let module = parse_vil(source, "conv.vil").expect("应成功解析模块");
+        let errors = Verifier::verify_module(&module.borrow());
+        assert_eq!(errors.len(), 3, "{:?}", errors);
+        assert!(errors[0].message.contains("sext 要求目标类型宽于源类型"));
+        assert!(errors[1].message.contains("trunc 要求目标类型窄于源类型"));
+        assert!(errors[2].message.contains("长度不同"));
+    }
+
+    #[test]
+    fn test_broadcast_result_type() {
+        let source = r#".module m
+.function f(.param %s i32) {
+    %b = broadcast %s
+    ret
+}
+"#;
+        let module = parse_vil(source, "broadcast.vil").expect("应成功解析模块");
+        let func = module.borrow().get_function("f").unwrap();
+        let broadcast = func.borrow().get_basic_blocks()[0]
+            .borrow()
+            .get_instructions()[0]
+            .clone();
+        let result = broadcast.borrow().get_result().unwrap();
+
+        let int16 = Type::get_int_type(TypeKind::Int16);
+        result
+            .borrow_mut()
+            .set_type(Type::get_vector_type(int16, 8));
+        let errors = Verifier::verify_module(&module.borrow());
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("broadcast"));
+
+        let int32 = Type::get_int_type(TypeKind::Int32);
+        result
+            .borrow_mut()
+            .set_type(Type::get_vector_type(int32, 8));
+        assert!(Verifier::verify_module(&module.borrow()).is_empty());
+    }
+
+    #[test]
+    fn test_reduction_input_types() {
+        let source = r#".module m
+.function f(.param %v <i32 x 8>, .param %z <i32 x 0>, .param %s i32) {
+    %ok = redsum %v
+    %empty = redmax %z
+    %scalar = redmin %s
+    ret
+}
+"#;
+        let module = parse_vil(source, "reduction.vil").expect("应成功解析模块");
+        let errors = Verifier::verify_module(&module.borrow());
+        let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "redmax 的输入向量 <i32 x 0> 长度为 0",
+                "redmin 的输入应为向量类型，实际为 i32"
+            ]
+        );
+
+        // 结果类型须为元素类型
+        let func = module.borrow().get_function("f").unwrap();
+        let redsum = func.borrow().get_basic_blocks()[0]
+            .borrow()
+            .get_instructions()[0]
+            .clone();
+        let result = redsum.borrow().get_result().unwrap();
+        result
+            .borrow_mut()
+            .set_type(Type::get_int_type(TypeKind::Int16));
+        let errors = Verifier::verify_instruction(&redsum.borrow());
+        assert_eq!(errors, ["redsum 结果类型 i16 与输入向量元素类型 i32 不一致"]);
+    }
+
+    #[test]
+    fn test_shuffle_index_bounds() {
+        let source = r#".module m
+.function f(.param %v <i32 x 4>, .param %s i32) {
+    %ok = shuffle %v, <0, 3, 3>
+    %bad = shuffle %v, <1, 4, 7>
+    %scalar = shuffle_clbmv %s, <0>
+    ret
+}
+"#;
+        let module = parse_vil(source, "shuffle.vil").expect("应成功解析模块");
+        let errors = Verifier::verify_module(&module.borrow());
+        let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "shuffle 的第 1 个索引 4 超出范围 [0, 4)",
+                "shuffle 的第 2 个索引 7 超出范围 [0, 4)",
+                "shuffle_clbmv 的源操作数应为向量类型，实际为 i32"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_modifier_validation_and_inference() {
+        let source = r#".module m
+.function f(.param %x i32, .param %y i32, .param %a <i32 x 8>, .param %b <i32 x 8>) {
+    %s = add.v %x, %y
+    %v = add %a, %b
+    ret
+}
+"#;
+        let module = parse_vil(source, "modifier.vil").expect("应成功解析模块");
+        let errors = Verifier::verify_module(&module.borrow());
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains(".v"));
+
+        let func = module.borrow().get_function("f").unwrap();
+        let entry = func.borrow().get_basic_blocks()[0].clone();
+        let instrs = entry.borrow().get_instructions().to_vec();
+        assert_eq!(
+            instrs[0].borrow().infer_modifier(),
+            InstructionModifier::Scalar
+        );
+        assert_eq!(
+            instrs[1].borrow().infer_modifier(),
+            InstructionModifier::Vector
+        );
+    }
+
+    #[test]
+    fn test_mask_length_matches_data() {
+        let source = r#".module m
+.function f(.param %a <i32 x 8>, .param %b <i32 x 8>, .param %m8 <pred 8>, .param %m4 <pred 4>, .param %x i32, .param %m1 <pred 1>) {
+    %ok = add.p %a, %b, %m8
+    %bad = add.p %a, %b, %m4
+    %s = mul.p %x, 3, %m1
+    %nomask = sub.p %a, %b
+    %notpred = sub.p %a, %b, %a
+    ret
+}
+"#;
+        let module = parse_vil(source, "mask.vil").expect("应成功解析模块");
+        let errors = Verifier::verify_module(&module.borrow());
+        assert_eq!(errors.len(), 3, "{:?}", errors);
+        assert!(errors[0].instruction.as_ref().unwrap().contains("bad"));
+        assert!(
+            errors[0]
+                .message
+                .contains("掩码类型 <pred 4> 与数据类型 <i32 x 8> 的宽度 8 不一致"),
+            "{}",
+            errors[0].message
+        );
+        assert!(errors[1].instruction.as_ref().unwrap().contains("nomask"));
+        assert!(
+            errors[1].message.contains("sub.p 缺少谓词掩码操作数"),
+            "{}",
+            errors[1].message
+        );
+        assert!(errors[2].message.contains("掩码操作数必须为谓词类型"));
+    }
+
+    #[test]
+    fn test_memory_space_matches_pointer() {
+        let source = r#".module m
+.memory vbuf [vspm] <i32 x 8>
+.function f(.param %g i32* generic, .param %v i32) {
+    %a = load @vbuf
+    %b = load %g
+    store %v, %g
+    ret
+}
+"#;
+        let module = parse_vil(source, "space.vil").expect("应成功解析模块");
+        assert!(Verifier::verify_module(&module.borrow()).is_empty());
+
+        let func = module.borrow().get_function("f").unwrap();
+        let instrs: Vec<_> = func.borrow().instructions().collect();
+        // generic 指针与任意内存空间兼容
+        instrs[1]
+            .borrow_mut()
+            .set_memory_space(Some(MemorySpace::SRAM));
+        assert!(Verifier::verify_module(&module.borrow()).is_empty());
+
+        // vspm 指针上的 sram 访存不一致
+        instrs[0]
+            .borrow_mut()
+            .set_memory_space(Some(MemorySpace::SRAM));
+        let errors = Verifier::verify_module(&module.borrow());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].message,
+            "load 的内存空间 sram 与指针 @vbuf 的内存空间 vspm 不一致"
+        );
+    }
+
+    #[test]
+    fn test_address_must_be_pointer() {
+        let source = r#".module m
+.memory vbuf [vspm] <i32 x 8>
+.function f(.param %g i32* generic, .param %v i32) {
+    %a = load @vbuf
+    %b = load %g
+    store %v, %g
+    %c = load %v
+    store %v, %v
+    ret
+}
+"#;
+        let module = parse_vil(source, "address.vil").expect("应成功解析模块");
+        let errors = Verifier::verify_module(&module.borrow());
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert_eq!(
+            errors[0].message,
+            "load 的地址操作数 %v 不是指针类型，实际为 i32"
+        );
+        assert_eq!(
+            errors[1].message,
+            "store 的地址操作数 %v 不是指针类型，实际为 i32"
+        );
+    }
+}
@@ -2,6 +2,10 @@
 //
 // 这个模块包含了 VIL 后端代码生成和优化的相关组件
 
+pub mod regalloc;
+pub mod venus;
+pub mod wasm;
+
 pub fn run_backend() {
     // TODO: 实现后端逻辑
     println!("Backend is running...");
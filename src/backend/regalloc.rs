@@ -0,0 +1,209 @@
+// 线性扫描寄存器分配
+//
+// 按基本块顺序把函数线性化，为每个 SSA 值计算活跃区间 [定义, 最后一次使用]，
+// 再按区间起点依次分配有限数量的寄存器；寄存器不足时溢出到栈槽。
+
+use crate::ir::function::Function;
+use crate::ir::instruction::Opcode;
+use crate::ir::operand::OperandKind;
+use crate::ir::value::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// 值被分配到的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Location {
+    Register(usize), // 物理寄存器编号
+    Stack(usize),    // 栈槽编号
+}
+
+/// 值的标识：参数按位置区分，指令结果按定义它的指令 ID 区分
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueId {
+    Argument(usize),
+    Instruction(u64),
+}
+
+/// 值的活跃区间，端点为线性化后的指令位置（参数定义在位置 0，指令从 1 开始编号）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiveInterval {
+    pub value: ValueId,
+    pub name: String, // 值名称，仅用于调试输出
+    pub start: usize,
+    pub end: usize,
+}
+
+/// 线性扫描寄存器分配器
+pub struct LinearScan {
+    num_registers: usize,
+}
+
+impl LinearScan {
+    pub fn new(num_registers: usize) -> Self {
+        LinearScan { num_registers }
+    }
+
+    /// 计算函数中所有值的活跃区间，按起点排序
+    ///
+    /// phi 的来源值视为在对应前驱的末尾被使用；回边（跳转到线性顺序中更早的基本块）
+    /// 把跨越循环头仍然活跃的区间延长到回边所在位置，保证值在整个循环内不被覆盖。
+    pub fn live_intervals(func: &Function) -> Vec<LiveInterval> {
+        let mut intervals: HashMap<ValueId, (usize, usize)> = HashMap::new();
+        let mut order: Vec<(ValueId, String)> = Vec::new();
+        // 使用处的值解析为定义：指令结果按 `Rc` 地址，参数按名称
+        let mut results: HashMap<*const RefCell<Value>, ValueId> = HashMap::new();
+        let mut arguments: HashMap<String, ValueId> = HashMap::new();
+        for (index, arg) in func.get_arguments().iter().enumerate() {
+            let id = ValueId::Argument(index);
+            let name = arg.borrow().get_name().to_string();
+            arguments.insert(name.clone(), id);
+            intervals.insert(id, (0, 0));
+            order.push((id, name));
+        }
+
+        // 基本块的起止位置
+        let mut block_ranges: HashMap<String, (usize, usize)> = HashMap::new();
+        let mut pos = 0;
+        for bb in func.get_basic_blocks() {
+            let bb = bb.borrow();
+            let start = pos + 1;
+            for instr in bb.get_instructions() {
+                pos += 1;
+                let instr = instr.borrow();
+                if let Some(result) = instr.get_result() {
+                    let id = ValueId::Instruction(instr.get_id());
+                    results.insert(Rc::as_ptr(&result), id);
+                    intervals.insert(id, (pos, pos));
+                    order.push((id, result.borrow().get_name().to_string()));
+                }
+            }
+            block_ranges.insert(bb.get_name().to_string(), (start, pos.max(start)));
+        }
+
+        let resolve = |value: &Rc<RefCell<Value>>| {
+            results
+                .get(&Rc::as_ptr(value))
+                .or_else(|| arguments.get(value.borrow().get_name()))
+                .copied()
+        };
+        let mut uses: Vec<(ValueId, usize)> = Vec::new();
+        let mut back_edges: Vec<(usize, usize)> = Vec::new();
+        let mut pos = 0;
+        for bb in func.get_basic_blocks() {
+            for instr in bb.borrow().get_instructions() {
+                pos += 1;
+                let instr = instr.borrow();
+                let operands = instr.get_operand_refs();
+                if instr.get_opcode() == Opcode::Phi {
+                    for pair in operands.chunks(2) {
+                        let incoming = pair.get(1).map(|bb| bb.borrow().to_value());
+                        let use_pos = incoming
+                            .and_then(|bb| {
+                                let name =
+                                    bb.borrow().get_name().trim_start_matches('%').to_string();
+                                block_ranges.get(&name).map(|&(_, end)| end)
+                            })
+                            .unwrap_or(pos);
+                        if let OperandKind::Value(value) = pair[0].borrow().get_kind()
+                            && let Some(id) = resolve(value)
+                        {
+                            uses.push((id, use_pos));
+                        }
+                    }
+                    continue;
+                }
+                for operand in operands {
+                    if let OperandKind::Value(value) = operand.borrow().get_kind()
+                        && let Some(id) = resolve(value)
+                    {
+                        uses.push((id, pos));
+                    }
+                }
+                for target in instr.branch_targets() {
+                    if let Some(&(start, _)) = block_ranges.get(&target)
+                        && start <= pos
+                    {
+                        back_edges.push((start, pos));
+                    }
+                }
+            }
+        }
+
+        for (id, use_pos) in uses {
+            if let Some(interval) = intervals.get_mut(&id) {
+                interval.1 = interval.1.max(use_pos);
+            }
+        }
+        // 回边可能嵌套，迭代直到区间不再变化
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &(header, latch) in &back_edges {
+                for interval in intervals.values_mut() {
+                    if interval.0 < header && interval.1 >= header && interval.1 < latch {
+                        interval.1 = latch;
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        let mut result: Vec<LiveInterval> = order
+            .into_iter()
+            .map(|(value, name)| {
+                let (start, end) = intervals[&value];
+                LiveInterval {
+                    value,
+                    name,
+                    start,
+                    end,
+                }
+            })
+            .collect();
+        result.sort_by_key(|interval| interval.start);
+        result
+    }
+
+    /// 为函数中的值分配位置，返回 值标识 -> 位置
+    ///
+    /// 寄存器不足时溢出当前活跃区间中结束最晚的一个（可能是新区间本身）。
+    pub fn allocate(&self, func: &Function) -> HashMap<ValueId, Location> {
+        let mut locations = HashMap::new();
+        let mut free: Vec<usize> = (0..self.num_registers).rev().collect();
+        // 占用寄存器的区间，按结束位置升序
+        let mut active: Vec<(LiveInterval, usize)> = Vec::new();
+        let mut next_slot = 0;
+
+        for interval in Self::live_intervals(func) {
+            // 释放已结束的区间
+            active.retain(|(other, reg)| {
+                if other.end < interval.start {
+                    free.push(*reg);
+                    false
+                } else {
+                    true
+                }
+            });
+
+            if let Some(reg) = free.pop() {
+                locations.insert(interval.value, Location::Register(reg));
+                active.push((interval, reg));
+            } else if let Some((last, reg)) = active.last().cloned()
+                && last.end > interval.end
+            {
+                // 溢出结束最晚的活跃区间，把寄存器让给新区间
+                locations.insert(last.value, Location::Stack(next_slot));
+                next_slot += 1;
+                locations.insert(interval.value, Location::Register(reg));
+                active.pop();
+                active.push((interval, reg));
+            } else {
+                locations.insert(interval.value, Location::Stack(next_slot));
+                next_slot += 1;
+            }
+            active.sort_by_key(|(interval, _)| interval.end);
+        }
+        locations
+    }
+}
@@ -0,0 +1,143 @@
+// Venus 伪汇编后端
+//
+// 将 VIL 指令一对一映射为 RVV 风格的 Venus 伪汇编。寄存器尚未分配，SSA 值直接作为
+// 虚拟寄存器名输出；指令修饰符（`.v`/`.s`/`.p`）附加在助记符之后，访存指令再附加
+// 内存空间后缀（如 `load.v.vspm`）。没有对应机器指令的操作码输出 `.err` 行而不是 panic。
+
+use crate::ir::function::Function;
+use crate::ir::instruction::{Instruction, Opcode};
+use crate::ir::module::ModuleRef;
+use crate::ir::operand::{OperandKind, OperandRef};
+use std::fmt::Write;
+
+/// 将模块输出为 Venus 伪汇编文本
+pub fn emit(module: &ModuleRef) -> String {
+    let module = module.borrow();
+    let mut out = String::new();
+
+    for mem in module.get_global_memory_spaces() {
+        let mem = mem.borrow();
+        let _ = writeln!(out, "    .section .{}", mem.get_space());
+        let _ = writeln!(out, "    .align {}", mem.get_alignment());
+        let _ = writeln!(out, "{}:", mem.get_name());
+        let _ = writeln!(out, "    .space {}", mem.byte_size());
+    }
+
+    if !module.get_functions().is_empty() {
+        let _ = writeln!(out, "    .text");
+    }
+    // 外部函数声明由链接器解析，不生成代码
+    for func in module.get_functions() {
+        if !func.borrow().is_declaration() {
+            emit_function(&mut out, &func.borrow());
+        }
+    }
+    out
+}
+
+/// 操作码对应的 Venus 助记符，没有对应机器指令时返回 None
+///
+/// `phi` 应在寄存器分配前消除，`extract` 只是取调用结果所在的寄存器，`alloc`/`free`
+/// 由运行时处理，均不直接对应机器指令。
+pub fn mnemonic(opcode: Opcode) -> Option<&'static str> {
+    match opcode {
+        Opcode::Phi | Opcode::Extract | Opcode::Alloc | Opcode::Free => None,
+        Opcode::Br => Some("j"),
+        Opcode::CondBr => Some("bnez"),
+        _ => Some(opcode.as_str()),
+    }
+}
+
+/// 基本块标签：`.L<函数>.<基本块>`
+fn block_label(func: &str, block: &str) -> String {
+    format!(".L{}.{}", func, block)
+}
+
+fn emit_function(out: &mut String, func: &Function) {
+    let name = func.get_name();
+    let _ = writeln!(out, "    .globl {}", name);
+    let _ = writeln!(out, "{}:", name);
+    for bb in func.get_basic_blocks() {
+        let bb = bb.borrow();
+        let _ = writeln!(out, "{}:", block_label(name, bb.get_name()));
+        for instr in bb.get_instructions() {
+            let _ = writeln!(out, "    {}", emit_instruction(name, &instr.borrow()));
+        }
+    }
+}
+
+/// 操作数文本：值取其名称（常量取数值），全局符号与基本块取对应标签
+fn operand_text(func: &str, operand: &OperandRef) -> String {
+    match operand.borrow().get_kind() {
+        OperandKind::Value(value) => {
+            let value = value.borrow();
+            match value.as_i64() {
+                Some(constant) => constant.to_string(),
+                None => value.get_name().to_string(),
+            }
+        }
+        OperandKind::Immediate(imm, _) => imm.to_string(),
+        OperandKind::BasicBlock(_) => {
+            block_label(func, &operand.borrow().get_block_name().unwrap_or_default())
+        }
+        OperandKind::Global(global) => global.get_name(),
+        OperandKind::IndexVector(_) => operand.borrow().to_string(),
+    }
+}
+
+fn emit_instruction(func: &str, instr: &Instruction) -> String {
+    let opcode = instr.get_opcode();
+    let base = match opcode {
+        // 已注册的扩展指令按源代码中的助记符输出；未知指令没有对应的 Venus 指令
+        Opcode::Extension(_) => instr.mnemonic(),
+        Opcode::Unknown => {
+            return format!(".err \"{}: 无对应的 Venus 指令\"", instr.mnemonic());
+        }
+        _ => match mnemonic(opcode) {
+            Some(base) => base.to_string(),
+            None => return format!(".err \"{}: 无对应的 Venus 指令\"", opcode),
+        },
+    };
+    let operands: Vec<String> = instr
+        .get_operand_refs()
+        .iter()
+        .map(|op| operand_text(func, op))
+        .collect();
+
+    match opcode {
+        // `condbr %c, %then, %else` 展开为条件跳转加无条件跳转
+        Opcode::CondBr if operands.len() == 3 => {
+            format!(
+                "bnez {}, {}\n    j {}",
+                operands[0], operands[1], operands[2]
+            )
+        }
+        // `load.v.vspm %x, (%p)` / `store.v.vspm %v, (%p)`
+        Opcode::Load | Opcode::Store => {
+            let mut text = format!("{}{}", base, instr.get_modifier());
+            if let Some(space) = instr.memory_space() {
+                let _ = write!(text, ".{}", space);
+            }
+            let (data, address) = match (opcode, instr.get_name()) {
+                (Opcode::Load, result) => (result, operands.first()),
+                _ => (operands.first().cloned(), operands.get(1)),
+            };
+            let address = address.map(|a| format!("({})", a)).unwrap_or_default();
+            match data {
+                Some(data) => format!("{} {}, {}", text, data, address),
+                None => format!("{} {}", text, address),
+            }
+        }
+        _ => {
+            let mut parts = Vec::new();
+            parts.extend(instr.get_name());
+            parts.extend(operands);
+            let text = format!("{}{}", base, instr.get_modifier());
+            if parts.is_empty() {
+                text
+            } else {
+                format!("{} {}", text, parts.join(", "))
+            }
+        }
+    }
+}
@@ -0,0 +1,489 @@
+// WebAssembly 文本格式 (WAT) 后端
+//
+// 将标量整数函数降级为 WAT 文本，便于在浏览器沙箱中运行 VIL 内核。
+// SSA 值映射为同名局部变量；i8/i16 等窄整数统一以 wasm i32 表示，运算后按结果类型
+// 截断（有符号类型符号扩展，无符号类型按位宽掩码）。向量指令暂不支持，生成 `unreachable`。
+
+use crate::ir::function::Function;
+use crate::ir::instruction::{Instruction, InstructionModifier, Opcode};
+use crate::ir::module::ModuleRef;
+use crate::ir::operand::{GlobalRef, OperandKind, OperandRef};
+use crate::ir::types::{Type, TypeKind, TypeRef};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// 将模块降级为 WAT 文本
+///
+/// 每个函数以同名导出；全局内存按声明顺序与对齐依次布局在线性内存中。
+pub fn emit_wat(module: &ModuleRef) -> String {
+    let module = module.borrow();
+    let mut memory_offsets = HashMap::new();
+    let mut memory_end = 0u64;
+    for mem in module.get_global_memory_spaces() {
+        let mem = mem.borrow();
+        let offset = memory_end.next_multiple_of(u64::from(mem.get_alignment().max(1)));
+        memory_offsets.insert(mem.get_name().to_string(), offset);
+        memory_end = offset + mem.byte_size();
+    }
+    let functions = module.get_functions();
+    let uses_memory = !memory_offsets.is_empty()
+        || functions.iter().any(|func| {
+            func.borrow()
+                .instructions()
+                .any(|instr| matches!(instr.borrow().get_opcode(), Opcode::Load | Opcode::Store))
+        });
+
+    let mut out = String::from("(module\n");
+    // 导入须位于其它定义之前
+    for func in &functions {
+        if func.borrow().is_declaration() {
+            emit_import(&mut out, &func.borrow());
+        }
+    }
+    if uses_memory {
+        let pages = memory_end.div_ceil(WASM_PAGE_SIZE).max(1);
+        let _ = writeln!(out, "  (memory (export \"memory\") {})", pages);
+    }
+    for func in functions.iter().filter(|f| !f.borrow().is_declaration()) {
+        let mut emitter = FunctionEmitter::new(&memory_offsets);
+        emitter.emit_function(&func.borrow());
+        out.push_str(&emitter.out);
+    }
+    out.push_str(")\n");
+    out
+}
+
+/// wasm 线性内存页大小（字节）
+const WASM_PAGE_SIZE: u64 = 65536;
+
+/// 多基本块函数中保存下一个基本块编号的局部变量
+const BLOCK_LOCAL: &str = "$__bb";
+/// 多基本块函数的分发循环标签
+const DISPATCH_LABEL: &str = "$__dispatch";
+
+/// 类型能否以单个 wasm i32 表示：标量整数、指针与单元素谓词
+fn is_scalar_like(ty: &TypeRef) -> bool {
+    let ty = ty.borrow();
+    ty.is_scalar()
+        || matches!(
+            ty.get_kind(),
+            TypeKind::Pointer(..) | TypeKind::Predicate(1)
+        )
+}
+
+/// 参数与返回值是否都能以 wasm i32 表示；有结果参数的多结果函数暂不支持
+fn has_scalar_signature(func: &Function) -> bool {
+    func.results().is_empty()
+        && func
+            .get_arguments()
+            .iter()
+            .all(|arg| is_scalar_like(&arg.borrow().get_type()))
+        && (func.get_return_type().borrow().get_kind() == &TypeKind::Void
+            || is_scalar_like(&func.get_return_type()))
+}
+
+/// 外部函数声明导入为 `env` 模块中的同名函数
+fn emit_import(out: &mut String, func: &Function) {
+    let name = func.get_name();
+    if !has_scalar_signature(func) {
+        let _ = writeln!(out, "  ;; 外部函数 {} 的非标量签名暂不支持", name);
+        return;
+    }
+    let mut signature = String::new();
+    for _ in func.get_arguments() {
+        signature.push_str(" (param i32)");
+    }
+    if func.get_return_type().borrow().get_kind() != &TypeKind::Void {
+        signature.push_str(" (result i32)");
+    }
+    let _ = writeln!(
+        out,
+        "  (import \"env\" \"{}\" (func ${}{}))",
+        name, name, signature
+    );
+}
+
+/// 无符号类型（含位域）使用 wasm 的 `_u` 指令
+fn is_unsigned(ty: &Type) -> bool {
+    ty.is_signed() == Some(false)
+        || matches!(
+            ty.get_kind(),
+            TypeKind::Bit8 | TypeKind::Bit16 | TypeKind::Bit32
+        )
+}
+
+/// 局部变量名：去掉 '%' 前缀，加上 wasm 标识符前缀 '$'
+fn local_name(name: &str) -> String {
+    format!("${}", name.strip_prefix('%').unwrap_or(name))
+}
+
+/// 基本块操作数引用的基本块名称（不含 '%' 前缀）
+fn block_name(operand: &OperandRef) -> String {
+    match operand.borrow().get_basic_block() {
+        Some(bb) => bb.borrow().get_name().to_string(),
+        None => operand
+            .borrow()
+            .to_value()
+            .borrow()
+            .get_name()
+            .trim_start_matches('%')
+            .to_string(),
+    }
+}
+
+struct FunctionEmitter<'a> {
+    out: String,
+    indent: usize,
+    memory_offsets: &'a HashMap<String, u64>,
+    block_indices: HashMap<String, usize>,
+}
+
+impl<'a> FunctionEmitter<'a> {
+    fn new(memory_offsets: &'a HashMap<String, u64>) -> Self {
+        FunctionEmitter {
+            out: String::new(),
+            indent: 1,
+            memory_offsets,
+            block_indices: HashMap::new(),
+        }
+    }
+
+    fn line(&mut self, text: &str) {
+        let _ = writeln!(self.out, "{}{}", "  ".repeat(self.indent), text);
+    }
+
+    fn emit_function(&mut self, func: &Function) {
+        let name = func.get_name().to_string();
+        let mut header = format!("(func ${} (export \"{}\")", name, name);
+        if !has_scalar_signature(func) {
+            self.line(&header);
+            self.indent += 1;
+            self.line("unreachable ;; 非标量签名暂不支持");
+            self.indent -= 1;
+            self.line(")");
+            return;
+        }
+
+        for arg in func.get_arguments() {
+            let _ = write!(
+                header,
+                " (param {} i32)",
+                local_name(arg.borrow().get_name())
+            );
+        }
+        let has_result = func.get_return_type().borrow().get_kind() != &TypeKind::Void;
+        if has_result {
+            header.push_str(" (result i32)");
+        }
+        self.line(&header);
+        self.indent += 1;
+
+        let mut locals: Vec<String> = Vec::new();
+        for instr in func.instructions() {
+            if let Some(name) = instr.borrow().get_name()
+                && !locals.contains(&name)
+            {
+                locals.push(name);
+            }
+        }
+        for name in &locals {
+            self.line(&format!("(local {} i32)", local_name(name)));
+        }
+
+        let blocks = func.get_basic_blocks();
+        self.block_indices = blocks
+            .iter()
+            .enumerate()
+            .map(|(idx, bb)| (bb.borrow().get_name().to_string(), idx))
+            .collect();
+        let needs_dispatch = func
+            .instructions()
+            .any(|instr| matches!(instr.borrow().get_opcode(), Opcode::Br | Opcode::CondBr));
+
+        if !needs_dispatch {
+            for bb in blocks {
+                for instr in bb.borrow().get_instructions() {
+                    self.emit_instruction(func, bb.borrow().get_name(), &instr.borrow());
+                }
+            }
+        } else {
+            // 分发循环：$__bb 选择要执行的基本块，每个基本块位于一层 block 之后，
+            // 跳转时设置 $__bb 并回到循环开头
+            self.line(&format!("(local {} i32)", BLOCK_LOCAL));
+            self.line(&format!("(loop {}", DISPATCH_LABEL));
+            self.indent += 1;
+            for idx in (0..blocks.len()).rev() {
+                self.line(&format!("(block $__bb{}", idx));
+                self.indent += 1;
+            }
+            self.line(&format!("local.get {}", BLOCK_LOCAL));
+            let targets: Vec<String> = (0..blocks.len())
+                .map(|idx| format!("$__bb{}", idx))
+                .collect();
+            self.line(&format!(
+                "br_table {} {}",
+                targets.join(" "),
+                targets[blocks.len() - 1]
+            ));
+            for bb in blocks {
+                self.indent -= 1;
+                self.line(")");
+                let bb = bb.borrow();
+                self.line(&format!(";; {}", bb.get_name()));
+                for instr in bb.get_instructions() {
+                    self.emit_instruction(func, bb.get_name(), &instr.borrow());
+                }
+            }
+            self.indent -= 1;
+            self.line(")");
+            if has_result {
+                self.line("unreachable");
+            }
+        }
+
+        self.indent -= 1;
+        self.line(")");
+    }
+
+    /// 将操作数压栈，返回是否支持该操作数
+    fn push_operand(&mut self, operand: &OperandRef) -> bool {
+        match operand.borrow().get_kind() {
+            OperandKind::Value(value) => {
+                let value = value.borrow();
+                match value.as_i64() {
+                    Some(constant) => self.line(&format!("i32.const {}", constant as i32)),
+                    None => self.line(&format!("local.get {}", local_name(value.get_name()))),
+                }
+            }
+            OperandKind::Immediate(imm, _) => self.line(&format!("i32.const {}", *imm as i32)),
+            OperandKind::Global(GlobalRef::Memory(mem)) => {
+                let offset = self
+                    .memory_offsets
+                    .get(mem.borrow().get_name())
+                    .copied()
+                    .unwrap_or(0);
+                self.line(&format!("i32.const {}", offset));
+            }
+            OperandKind::Global(GlobalRef::Function(_))
+            | OperandKind::BasicBlock(_)
+            | OperandKind::IndexVector(_) => {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// 按结果类型截断 i32 值，使其保持窄整数的取值范围
+    fn normalize(&mut self, ty: &TypeRef) {
+        match ty.borrow().get_kind() {
+            TypeKind::Int8 => self.line("i32.extend8_s"),
+            TypeKind::Int16 => self.line("i32.extend16_s"),
+            TypeKind::Uint8 | TypeKind::Bit8 => {
+                self.line("i32.const 255");
+                self.line("i32.and");
+            }
+            TypeKind::Uint16 | TypeKind::Bit16 => {
+                self.line("i32.const 65535");
+                self.line("i32.and");
+            }
+            _ => {}
+        }
+    }
+
+    /// 跳转到 `target` 前，为目标基本块中的 phi 写入来自 `from` 的值
+    ///
+    /// 先将全部来源值压栈再逆序写回，保证 phi 之间的并行赋值语义。
+    fn emit_jump(&mut self, func: &Function, from: &str, target: &OperandRef) {
+        let target_name = block_name(target);
+        let mut phis = Vec::new();
+        if let Some(&idx) = self.block_indices.get(&target_name) {
+            let bb = func.get_basic_blocks()[idx].clone();
+            for instr in bb.borrow().get_instructions() {
+                let instr = instr.borrow();
+                if instr.get_opcode() != Opcode::Phi {
+                    continue;
+                }
+                let operands = instr.get_operand_refs();
+                let incoming = operands
+                    .chunks(2)
+                    .find(|pair| pair.len() == 2 && block_name(&pair[1]) == from);
+                if let (Some(pair), Some(name)) = (incoming, instr.get_name())
+                    && self.push_operand(&pair[0])
+                {
+                    phis.push(name);
+                }
+            }
+            for name in phis.iter().rev() {
+                self.line(&format!("local.set {}", local_name(name)));
+            }
+            self.line(&format!("i32.const {}", idx));
+            self.line(&format!("local.set {}", BLOCK_LOCAL));
+        } else {
+            self.line(&format!("unreachable ;; 未知的跳转目标 {}", target_name));
+        }
+        self.line(&format!("br {}", DISPATCH_LABEL));
+    }
+
+    fn emit_instruction(&mut self, func: &Function, block: &str, instr: &Instruction) {
+        let opcode = instr.get_opcode();
+        let operands = instr.get_operand_refs();
+        let is_vector = instr.get_modifier() == InstructionModifier::Vector
+            || instr
+                .get_result()
+                .is_some_and(|r| r.borrow().get_type().borrow().is_vector())
+            || operands.iter().any(|op| {
+                op.borrow()
+                    .get_type()
+                    .is_some_and(|ty| ty.borrow().is_vector())
+            });
+        if is_vector {
+            self.line(&format!("unreachable ;; 向量指令暂不支持: {}", opcode));
+            return;
+        }
+
+        let binary = match opcode {
+            Opcode::Add => Some("i32.add"),
+            Opcode::Sub => Some("i32.sub"),
+            Opcode::Mul => Some("i32.mul"),
+            Opcode::And => Some("i32.and"),
+            Opcode::Or => Some("i32.or"),
+            Opcode::Xor => Some("i32.xor"),
+            Opcode::Sll => Some("i32.shl"),
+            Opcode::Sra => Some("i32.shr_s"),
+            Opcode::Srl => Some("i32.shr_u"),
+            _ => None,
+        };
+        let unsigned = operands
+            .first()
+            .and_then(|op| op.borrow().get_type())
+            .is_some_and(|ty| is_unsigned(&ty.borrow()));
+        let compare = match opcode {
+            Opcode::CmpEq => Some("i32.eq"),
+            Opcode::CmpNe => Some("i32.ne"),
+            Opcode::CmpGt => Some(if unsigned { "i32.gt_u" } else { "i32.gt_s" }),
+            Opcode::CmpGe => Some(if unsigned { "i32.ge_u" } else { "i32.ge_s" }),
+            Opcode::CmpLt => Some(if unsigned { "i32.lt_u" } else { "i32.lt_s" }),
+            Opcode::CmpLe => Some(if unsigned { "i32.le_u" } else { "i32.le_s" }),
+            _ => None,
+        };
+
+        match opcode {
+            _ if (binary.is_some() || compare.is_some()) && operands.len() == 2 => {
+                if !self.push_operand(&operands[0]) {
+                    self.line(&format!("unreachable ;; 不支持的操作数: {}", opcode));
+                    return;
+                }
+                // 有符号窄整数在局部变量中保持符号扩展，逻辑右移前先截取到原宽度
+                if opcode == Opcode::Srl
+                    && let Some(ty) = operands[0].borrow().get_type()
+                {
+                    match ty.borrow().get_kind() {
+                        TypeKind::Int8 => {
+                            self.line("i32.const 255");
+                            self.line("i32.and");
+                        }
+                        TypeKind::Int16 => {
+                            self.line("i32.const 65535");
+                            self.line("i32.and");
+                        }
+                        _ => {}
+                    }
+                }
+                if !self.push_operand(&operands[1]) {
+                    self.line(&format!("unreachable ;; 不支持的操作数: {}", opcode));
+                    return;
+                }
+                self.line(binary.or(compare).unwrap());
+            }
+            Opcode::Not | Opcode::Mov if operands.len() == 1 => {
+                if !self.push_operand(&operands[0]) {
+                    self.line(&format!("unreachable ;; 不支持的操作数: {}", opcode));
+                    return;
+                }
+                if opcode == Opcode::Not {
+                    self.line("i32.const -1");
+                    self.line("i32.xor");
+                }
+            }
+            Opcode::Load if operands.len() == 1 => {
+                self.push_operand(&operands[0]);
+                let ty = instr.get_type();
+                let ty = ty.borrow();
+                let suffix = if is_unsigned(&ty) { "u" } else { "s" };
+                match ty.get_bit_width() {
+                    8 => self.line(&format!("i32.load8_{}", suffix)),
+                    16 => self.line(&format!("i32.load16_{}", suffix)),
+                    _ => self.line("i32.load"),
+                }
+            }
+            Opcode::Store if operands.len() == 2 => {
+                self.push_operand(&operands[1]);
+                self.push_operand(&operands[0]);
+                let width = operands[0]
+                    .borrow()
+                    .get_type()
+                    .map_or(32, |ty| ty.borrow().get_bit_width());
+                match width {
+                    8 => self.line("i32.store8"),
+                    16 => self.line("i32.store16"),
+                    _ => self.line("i32.store"),
+                }
+                return;
+            }
+            Opcode::Call if !operands.is_empty() => {
+                for arg in &operands[1..] {
+                    self.push_operand(arg);
+                }
+                let callee = operands[0]
+                    .borrow()
+                    .get_global()
+                    .map(|global| global.get_name());
+                self.line(&format!("call ${}", callee.unwrap_or_default()));
+                if let Some(name) = instr.get_name() {
+                    self.line(&format!("local.set {}", local_name(&name)));
+                }
+                return;
+            }
+            Opcode::Ret => {
+                if let Some(value) = operands.first() {
+                    self.push_operand(value);
+                }
+                self.line("return");
+                return;
+            }
+            Opcode::Br if operands.len() == 1 => {
+                self.emit_jump(func, block, &operands[0]);
+                return;
+            }
+            Opcode::CondBr if operands.len() == 3 => {
+                self.push_operand(&operands[0]);
+                self.line("if");
+                self.indent += 1;
+                self.emit_jump(func, block, &operands[1]);
+                self.indent -= 1;
+                self.line("else");
+                self.indent += 1;
+                self.emit_jump(func, block, &operands[2]);
+                self.indent -= 1;
+                self.line("end");
+                return;
+            }
+            // phi 的值由前驱在跳转前写入
+            Opcode::Phi => return,
+            _ => {
+                self.line(&format!("unreachable ;; 不支持的指令: {}", opcode));
+                return;
+            }
+        }
+
+        if let Some(result) = instr.get_result() {
+            if opcode != Opcode::Load {
+                self.normalize(&result.borrow().get_type());
+            }
+            self.line(&format!(
+                "local.set {}",
+                local_name(result.borrow().get_name())
+            ));
+        }
+    }
+}
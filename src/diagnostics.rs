@@ -0,0 +1,154 @@
+// 诊断信息收集
+//
+// 前端、验证器与优化 Pass 共用的诊断容器。各阶段把错误、警告与提示推入同一个
+// `DiagnosticBag`，由调用方（如 vcc）统一按源代码位置排序后输出。
+
+use crate::frontend::error::{ParseError, ParseErrorKind, SourceLocation};
+use std::fmt;
+
+/// 诊断严重程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "错误"),
+            Severity::Warning => write!(f, "警告"),
+            Severity::Note => write!(f, "提示"),
+        }
+    }
+}
+
+/// 单条诊断
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// 源代码位置；IR 层面产生的诊断（验证器、优化 Pass）通常没有位置
+    pub location: Option<SourceLocation>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, message: &str, location: Option<SourceLocation>) -> Self {
+        Diagnostic {
+            severity,
+            message: message.to_string(),
+            location,
+        }
+    }
+
+    /// 渲染诊断，并附上所在的源代码行与指向列的 `^`
+    pub fn render(&self, source: &str) -> String {
+        let mut out = self.to_string();
+        if let Some(snippet) = self.location.as_ref().and_then(|loc| loc.snippet(source)) {
+            out.push_str(&snippet);
+        }
+        out
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.location {
+            Some(loc) => write!(f, "{}: {}: {}", loc, self.severity, self.message),
+            None => write!(f, "{}: {}", self.severity, self.message),
+        }
+    }
+}
+
+impl From<&ParseError> for Diagnostic {
+    fn from(error: &ParseError) -> Self {
+        // 位置单独保存，消息中只保留错误类别与内容
+        let message = match error.kind() {
+            ParseErrorKind::Lexical(msg) => format!("词法错误: {}", msg),
+            ParseErrorKind::Syntax(msg) => format!("语法错误: {}", msg),
+            ParseErrorKind::Semantic(msg) => format!("语义错误: {}", msg),
+            ParseErrorKind::IO(e) => format!("IO错误: {}", e),
+        };
+        Diagnostic::new(Severity::Error, &message, error.location().cloned())
+    }
+}
+
+/// 诊断集合
+#[derive(Debug, Clone, Default)]
+pub struct DiagnosticBag {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl DiagnosticBag {
+    pub fn new() -> Self {
+        DiagnosticBag::default()
+    }
+
+    /// 添加一条诊断
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// 添加一条错误
+    pub fn error(&mut self, message: &str, location: Option<SourceLocation>) {
+        self.push(Diagnostic::new(Severity::Error, message, location));
+    }
+
+    /// 添加一条警告
+    pub fn warning(&mut self, message: &str, location: Option<SourceLocation>) {
+        self.push(Diagnostic::new(Severity::Warning, message, location));
+    }
+
+    /// 添加一条提示
+    pub fn note(&mut self, message: &str, location: Option<SourceLocation>) {
+        self.push(Diagnostic::new(Severity::Note, message, location));
+    }
+
+    /// 合并另一个集合中的全部诊断
+    pub fn extend(&mut self, other: DiagnosticBag) {
+        self.diagnostics.extend(other.diagnostics);
+    }
+
+    /// 是否包含错误
+    pub fn has_errors(&self) -> bool {
+        self.count(Severity::Error) > 0
+    }
+
+    /// 指定严重程度的诊断数量
+    pub fn count(&self, severity: Severity) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .count()
+    }
+
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// 按添加顺序遍历
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter()
+    }
+
+    /// 按源代码位置（文件、行、列）排序后的诊断
+    ///
+    /// 没有位置的诊断排在最后；位置相同时保持添加顺序。
+    pub fn sorted(&self) -> Vec<&Diagnostic> {
+        let mut sorted: Vec<&Diagnostic> = self.diagnostics.iter().collect();
+        sorted.sort_by(|a, b| match (&a.location, &b.location) {
+            (Some(x), Some(y)) => {
+                (&x.filename, x.line, x.column).cmp(&(&y.filename, y.line, y.column))
+            }
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+        sorted
+    }
+}
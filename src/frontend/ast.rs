@@ -19,6 +19,6 @@ impl ASTNode {
     /// 获取节点的位置信息
     pub fn get_location(&self) -> SourceLocation {
         // 实际实现中，这里需要根据具体的 AST 节点类型返回其对应的 SourceLocation
-        SourceLocation::new("ast.rs", 0, 0) // 占位符
+        SourceLocation::new("ast.rs", 0, 0, 0) // 占位符
     }
 }
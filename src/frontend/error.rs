@@ -12,16 +12,44 @@ pub struct SourceLocation {
     pub filename: String,
     pub line: usize,
     pub column: usize,
+    /// 距源代码起始处的字节偏移
+    pub offset: usize,
 }
 
 impl SourceLocation {
-    pub fn new(filename: &str, line: usize, column: usize) -> Self {
+    pub fn new(filename: &str, line: usize, column: usize, offset: usize) -> Self {
         SourceLocation {
             filename: filename.to_string(),
             line,
             column,
+            offset,
         }
     }
+
+    /// 该位置所在的源代码行及指向列的 `^`（每行前带换行符）
+    ///
+    /// 行号超出源代码范围时返回 None。
+    pub fn snippet(&self, source: &str) -> Option<String> {
+        let line = self
+            .line
+            .checked_sub(1)
+            .and_then(|n| source.lines().nth(n))?;
+
+        let gutter = self.line.to_string();
+        // 保留制表符，使 `^` 与源代码中的列对齐
+        let padding: String = line
+            .chars()
+            .take(self.column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        Some(format!(
+            "\n{} | {}\n{} | {}^",
+            gutter,
+            line,
+            " ".repeat(gutter.len()),
+            padding
+        ))
+    }
 }
 
 impl fmt::Display for SourceLocation {
@@ -79,14 +107,38 @@ impl ParseError {
     pub fn new_io_error(filename: &str, error: io::Error) -> Self {
         ParseError {
             kind: ParseErrorKind::IO(error),
-            location: Some(SourceLocation::new(filename, 0, 0)),
+            location: Some(SourceLocation::new(filename, 0, 0, 0)),
         }
     }
 
+    /// 创建一个指向源代码位置的IO错误（例如 `.import` 的文件不存在）
+    pub fn new_io_error_at(location: SourceLocation, error: io::Error) -> Self {
+        ParseError {
+            kind: ParseErrorKind::IO(error),
+            location: Some(location),
+        }
+    }
+
+    /// 获取错误类型
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.kind
+    }
+
     /// 获取错误位置
     pub fn location(&self) -> Option<&SourceLocation> {
         self.location.as_ref()
     }
+
+    /// 渲染错误信息，并附上出错的源代码行与指向出错列的 `^`
+    ///
+    /// 没有位置信息或行号超出源代码范围时只输出错误信息本身。
+    pub fn render(&self, source: &str) -> String {
+        let mut out = self.to_string();
+        if let Some(snippet) = self.location.as_ref().and_then(|loc| loc.snippet(source)) {
+            out.push_str(&snippet);
+        }
+        out
+    }
 }
 
 impl fmt::Display for ParseError {
@@ -135,3 +187,30 @@ impl Error for ParseError {
 
 /// 解析结果类型
 pub type ParseResult<T> = Result<T, ParseError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_snippet() {
+        let source = ".module m\n.function f() {\n    %x = bogus 1\n}\n";
+        let err =
+            ParseError::new_syntax_error(SourceLocation::new("t.vil", 3, 10, 35), "未知操作码");
+        let rendered = err.render(source);
+        let lines: Vec<&str> = rendered.lines().collect();
+
+        assert_eq!(lines[0], "语法错误 at t.vil:3:10: 未知操作码");
+        assert_eq!(lines[1], "3 |     %x = bogus 1");
+        assert_eq!(lines[2], "  |          ^");
+        // `^` 位于出错列下方
+        assert_eq!(lines[2].find('^'), lines[1].find("bogus"));
+    }
+
+    #[test]
+    fn test_render_without_source_line() {
+        let err =
+            ParseError::new_syntax_error(SourceLocation::new("t.vil", 42, 1, 0), "意外的文件结束");
+        assert_eq!(err.render("one line"), err.to_string());
+    }
+}
@@ -2,26 +2,100 @@
 //
 // 这个模块实现了 VIL 的词法分析器，将源代码文本转换为词法单元序列
 
+use crate::diagnostics::DiagnosticBag;
 use crate::frontend::error::{ParseError, ParseResult, SourceLocation};
 use crate::frontend::token::{Token, TokenKind};
-use std::collections::HashMap;
+use crate::ir::instruction::Opcode;
+use std::collections::{HashMap, HashSet};
 use std::iter::Peekable;
+use std::rc::Rc;
 use std::str::Chars;
 
+/// 词法分析器选项
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LexerOptions {
+    /// 遇到无法识别的字符时立即报告词法错误，而不是产生 `TokenKind::Unknown`
+    pub strict_unknown: bool,
+    /// 宽松操作码模式：函数体内处于指令位置、但不在已知操作码集合中的标识符
+    /// 产生 `TokenKind::Opcode` 并记录一条警告，而不是普通标识符
+    pub lenient_opcodes: bool,
+}
+
+/// 登记的扩展指令助记符：编号与助记符一一对应
+#[derive(Debug, Clone, Default)]
+pub struct ExtensionMnemonics {
+    entries: Vec<(u16, Rc<str>)>,
+}
+
+impl ExtensionMnemonics {
+    /// 为扩展指令编号登记助记符
+    ///
+    /// 助记符与内建操作码重名，或编号 / 助记符已登记为其它扩展指令时返回 false。
+    pub fn register(&mut self, number: u16, mnemonic: &str) -> bool {
+        if Opcode::from_mnemonic(mnemonic).is_some() || mnemonic.starts_with("ext.") {
+            return false;
+        }
+        let conflict = self
+            .entries
+            .iter()
+            .any(|(n, name)| (*n == number) != (name.as_ref() == mnemonic));
+        if conflict {
+            return false;
+        }
+        if self.number_of(mnemonic).is_none() {
+            self.entries.push((number, Rc::from(mnemonic)));
+        }
+        true
+    }
+
+    /// 按助记符查找扩展指令编号
+    pub fn number_of(&self, mnemonic: &str) -> Option<u16> {
+        self.entries
+            .iter()
+            .find(|(_, name)| name.as_ref() == mnemonic)
+            .map(|(number, _)| *number)
+    }
+
+    /// 按编号查找登记的助记符
+    pub fn name_of(&self, number: u16) -> Option<Rc<str>> {
+        self.entries
+            .iter()
+            .find(|(n, _)| *n == number)
+            .map(|(_, name)| name.clone())
+    }
+}
+
 /// 词法分析器
 pub struct Lexer<'a> {
-    #[allow(dead_code)] // 允许未使用的代码，因为它通过 chars 字段间接使用
     source: &'a str,
     filename: String,
     chars: Peekable<Chars<'a>>,
     line: usize,
     column: usize,
+    offset: usize, // 当前字节偏移
     keywords: HashMap<String, TokenKind>,
+    options: LexerOptions,
+    /// 已知操作码助记符（不含修饰符后缀），宽松模式下据此判断是否需要警告
+    known_opcodes: HashSet<String>,
+    /// 登记的扩展指令助记符，与内建操作码一样不触发宽松模式的警告
+    extensions: ExtensionMnemonics,
+    /// 词法分析中产生的警告
+    diagnostics: DiagnosticBag,
+    /// 当前所在的 `{ }` 嵌套深度，大于 0 时位于函数体内
+    brace_depth: usize,
+    /// 上一个词法单元的种类与所在行，用于判断指令位置
+    prev_kind: Option<TokenKind>,
+    prev_line: usize,
 }
 
 impl<'a> Lexer<'a> {
-    /// 创建一个新的词法分析器
+    /// 创建一个新的词法分析器（默认选项）
     pub fn new(source: &'a str, filename: &str) -> Self {
+        Self::new_with_options(source, filename, LexerOptions::default())
+    }
+
+    /// 使用指定选项创建词法分析器
+    pub fn new_with_options(source: &'a str, filename: &str, options: LexerOptions) -> Self {
         let mut keywords = HashMap::new();
 
         // 关键字
@@ -31,7 +105,10 @@ impl<'a> Lexer<'a> {
         keywords.insert(".param".to_string(), TokenKind::Param);
         keywords.insert(".entry".to_string(), TokenKind::Entry);
         keywords.insert(".result".to_string(), TokenKind::Result);
+        keywords.insert(".align".to_string(), TokenKind::Align);
+        keywords.insert(".import".to_string(), TokenKind::Import);
         keywords.insert(".type".to_string(), TokenKind::Type);
+        keywords.insert(".declare".to_string(), TokenKind::Declare);
 
         // 操作码
         keywords.insert("add".to_string(), TokenKind::Add);
@@ -70,6 +147,7 @@ impl<'a> Lexer<'a> {
         keywords.insert("ret".to_string(), TokenKind::Ret);
         keywords.insert("mov".to_string(), TokenKind::Mov);
         keywords.insert("phi".to_string(), TokenKind::Phi);
+        keywords.insert("call".to_string(), TokenKind::Call);
 
         // 修饰符
         keywords.insert(".v".to_string(), TokenKind::Vector);
@@ -88,19 +166,113 @@ impl<'a> Lexer<'a> {
             chars: source.chars().peekable(),
             line: 1,
             column: 1,
+            offset: 0,
             keywords,
+            options,
+            known_opcodes: Opcode::ALL
+                .iter()
+                .map(|op| op.as_str().to_string())
+                .collect(),
+            extensions: ExtensionMnemonics::default(),
+            diagnostics: DiagnosticBag::new(),
+            brace_depth: 0,
+            prev_kind: None,
+            prev_line: 0,
         }
     }
 
+    /// 将原始字节解码为源代码文本
+    ///
+    /// 遇到无效的 UTF-8 序列时报告词法错误，位置指向第一个无效字节。
+    pub fn decode_source<'b>(bytes: &'b [u8], filename: &str) -> ParseResult<&'b str> {
+        std::str::from_utf8(bytes).map_err(|e| {
+            let valid = e.valid_up_to();
+            // 前 `valid` 个字节已确认是合法的 UTF-8
+            let prefix = std::str::from_utf8(&bytes[..valid]).unwrap_or_default();
+            let line = 1 + prefix.matches('\n').count();
+            let line_start = prefix.rfind('\n').map(|i| i + 1).unwrap_or(0);
+            let column = 1 + prefix[line_start..].chars().count();
+            ParseError::new_lexical_error(
+                SourceLocation::new(filename, line, column, valid),
+                &format!("无效的 UTF-8 字节: 0x{:02X}", bytes[valid]),
+            )
+        })
+    }
+
+    /// 将助记符加入已知操作码集合
+    ///
+    /// 宽松模式下，已知但尚未内建的助记符仍产生 `TokenKind::Opcode`，只是不再警告。
+    pub fn add_known_opcode(&mut self, mnemonic: &str) {
+        self.known_opcodes.insert(mnemonic.to_string());
+    }
+
+    /// 取出词法分析中产生的警告，并清空内部记录
+    pub fn take_diagnostics(&mut self) -> DiagnosticBag {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    /// 为扩展指令编号登记助记符，见 `ExtensionMnemonics::register`
+    pub fn register_extension(&mut self, number: u16, mnemonic: &str) -> bool {
+        self.extensions.register(number, mnemonic)
+    }
+
+    /// 登记的扩展指令助记符
+    pub fn extensions(&self) -> &ExtensionMnemonics {
+        &self.extensions
+    }
+
+    /// 替换登记的扩展指令助记符（导入文件的词法分析器沿用导入者的登记）
+    pub fn set_extensions(&mut self, extensions: ExtensionMnemonics) {
+        self.extensions = extensions;
+    }
+
+    /// 词法分析器选项
+    pub fn options(&self) -> LexerOptions {
+        self.options
+    }
+
+    /// 宽松模式下把指令位置上的未内建操作码标识符改为 `TokenKind::Opcode`
+    ///
+    /// `ext.<编号>` 与已登记的扩展指令助记符视为内建操作码。
+    ///
+    /// 指令位置指函数体内紧跟 `=`、`;`、`{` 之后或位于一行开头，且后面不是 `:`（基本块标签）。
+    fn classify_opcode(&mut self, kind: TokenKind, location: &SourceLocation) -> TokenKind {
+        let TokenKind::Identifier(name) = kind else {
+            return kind;
+        };
+        let after_separator = matches!(
+            self.prev_kind,
+            Some(TokenKind::Equal | TokenKind::Semicolon | TokenKind::LBrace)
+        );
+        let at_instruction = self.brace_depth > 0
+            && (after_separator || self.prev_line != location.line)
+            && self.peek_char() != Some(&':');
+        let base = name.split('.').next().unwrap_or_default();
+        let builtin = base == "ext"
+            || Opcode::from_mnemonic(base).is_some()
+            || self.extensions.number_of(base).is_some();
+        if !self.options.lenient_opcodes || !at_instruction || builtin {
+            return TokenKind::Identifier(name);
+        }
+        if !self.known_opcodes.contains(base) {
+            self.diagnostics.warning(
+                &format!("未知操作码 '{}'，按未知指令处理", base),
+                Some(location.clone()),
+            );
+        }
+        TokenKind::Opcode(name)
+    }
+
     /// 获取当前位置
     fn current_location(&self) -> SourceLocation {
-        SourceLocation::new(&self.filename, self.line, self.column)
+        SourceLocation::new(&self.filename, self.line, self.column, self.offset)
     }
 
     /// 读取下一个字符
     fn next_char(&mut self) -> Option<char> {
         let c = self.chars.next();
         if let Some(c) = c {
+            self.offset += c.len_utf8();
             if c == '\n' {
                 self.line += 1;
                 self.column = 1;
@@ -127,6 +299,16 @@ impl<'a> Lexer<'a> {
         }
     }
 
+    /// 下一个字符为 `=` 时消费它并返回双字符运算符 `pair`，否则返回单字符记号 `single`
+    fn with_equal(&mut self, pair: TokenKind, single: TokenKind) -> TokenKind {
+        if let Some(&'=') = self.peek_char() {
+            self.next_char();
+            pair
+        } else {
+            single
+        }
+    }
+
     /// 跳过注释
     fn skip_comment(&mut self) {
         // 跳过 "//" 开头的行注释
@@ -160,6 +342,15 @@ impl<'a> Lexer<'a> {
             }
         }
 
+        // `%` 名称不参与关键字匹配
+        if first_char == '%' {
+            return if identifier.len() > 1 {
+                TokenKind::LocalName(identifier)
+            } else {
+                TokenKind::Unknown
+            };
+        }
+
         // 检查是否是关键字
         self.keywords
             .get(&identifier)
@@ -168,7 +359,11 @@ impl<'a> Lexer<'a> {
     }
 
     /// 读取数字
-    fn read_number(&mut self, first_char: char) -> TokenKind {
+    fn read_number(
+        &mut self,
+        first_char: char,
+        location: SourceLocation,
+    ) -> ParseResult<TokenKind> {
         let mut number = String::new();
         number.push(first_char);
 
@@ -182,11 +377,16 @@ impl<'a> Lexer<'a> {
             }
         }
 
-        // 解析为整数
-        match number.parse::<i64>() {
-            Ok(n) => TokenKind::IntLiteral(n),
-            Err(_) => TokenKind::Unknown, // 解析失败
-        }
+        // 只含数字时解析失败的唯一原因是超出 i64 范围
+        number
+            .parse::<i64>()
+            .map(TokenKind::IntLiteral)
+            .map_err(|_| {
+                ParseError::new_lexical_error(
+                    location,
+                    &format!("整数字面量超出范围: '{}' (超出 i64 的表示范围)", number),
+                )
+            })
     }
 
     /// 读取字符串字面量
@@ -318,17 +518,22 @@ impl<'a> Lexer<'a> {
                     self.next_char();
                     TokenKind::RBracket
                 }
+                // 单独的 `<`、`>` 用于类型语法，紧跟 `=` 时为比较运算符
                 '<' => {
                     self.next_char();
-                    TokenKind::LAngle
+                    self.with_equal(TokenKind::LessEqual, TokenKind::LAngle)
                 }
                 '>' => {
                     self.next_char();
-                    TokenKind::RAngle
+                    self.with_equal(TokenKind::GreaterEqual, TokenKind::RAngle)
                 }
                 '=' => {
                     self.next_char();
-                    TokenKind::Equal
+                    self.with_equal(TokenKind::EqualEqual, TokenKind::Equal)
+                }
+                '!' => {
+                    self.next_char();
+                    self.with_equal(TokenKind::NotEqual, TokenKind::Unknown)
                 }
                 '@' => {
                     self.next_char();
@@ -338,6 +543,15 @@ impl<'a> Lexer<'a> {
                     self.next_char();
                     TokenKind::Star
                 }
+                '-' => {
+                    self.next_char();
+                    if let Some(&'>') = self.peek_char() {
+                        self.next_char();
+                        TokenKind::Arrow
+                    } else {
+                        TokenKind::Unknown
+                    }
+                }
 
                 // 注释
                 '/' => {
@@ -352,12 +566,16 @@ impl<'a> Lexer<'a> {
                 }
 
                 // 字符串字面量
-                '"' => return self.read_string().map(|kind| Token::new(kind, location)),
+                '"' => {
+                    return self
+                        .read_string()
+                        .map(|kind| Token::with_span(kind, location, self.current_location()));
+                }
 
                 // 数字
                 c if c.is_ascii_digit() => {
                     self.next_char();
-                    self.read_number(c)
+                    self.read_number(c, location.clone())?
                 }
 
                 // 标识符或关键字
@@ -373,13 +591,65 @@ impl<'a> Lexer<'a> {
                 }
             };
 
-            Ok(Token::new(kind, location))
+            if kind == TokenKind::Unknown && self.options.strict_unknown {
+                return Err(ParseError::new_lexical_error(
+                    location,
+                    &format!("无法识别的字符: '{}'", c),
+                ));
+            }
+            let kind = self.classify_opcode(kind, &location);
+            match kind {
+                TokenKind::LBrace => self.brace_depth += 1,
+                TokenKind::RBrace => self.brace_depth = self.brace_depth.saturating_sub(1),
+                _ => {}
+            }
+            self.prev_kind = Some(kind.clone());
+            self.prev_line = location.line;
+            Ok(Token::with_span(kind, location, self.current_location()))
         } else {
             // 文件末尾
             Ok(Token::new(TokenKind::EOF, self.current_location()))
         }
     }
 
+    /// 重新词法分析源代码中 `[start_byte, end_byte)` 范围内的词法单元（不含 EOF）
+    ///
+    /// 从范围起点处的行列号开始，因此得到的位置与完整 `tokenize` 中对应的词法单元一致。
+    /// 范围应落在词法单元边界上；越界或不在字符边界上时报告词法错误。
+    /// 不影响当前词法分析器的状态。
+    pub fn tokenize_range(
+        &mut self,
+        start_byte: usize,
+        end_byte: usize,
+    ) -> ParseResult<Vec<Token>> {
+        let source = self.source;
+        let valid = start_byte <= end_byte
+            && end_byte <= source.len()
+            && source.is_char_boundary(start_byte)
+            && source.is_char_boundary(end_byte);
+        if !valid {
+            return Err(ParseError::new_lexical_error(
+                self.current_location(),
+                &format!("无效的字节范围: {}..{}", start_byte, end_byte),
+            ));
+        }
+
+        // 计算范围起点的行列号
+        let prefix = &source[..start_byte];
+        let line = 1 + prefix.matches('\n').count();
+        let line_start = prefix.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let column = 1 + prefix[line_start..].chars().count();
+
+        let mut lexer =
+            Lexer::new_with_options(&source[start_byte..end_byte], &self.filename, self.options);
+        lexer.line = line;
+        lexer.column = column;
+        lexer.offset = start_byte;
+        let mut tokens = lexer.tokenize()?;
+        tokens.pop(); // 去掉 EOF
+        Ok(tokens)
+    }
+
     /// 获取所有词法单元
     pub fn tokenize(&mut self) -> ParseResult<Vec<Token>> {
         let mut tokens = Vec::new();
@@ -435,6 +705,101 @@ mod tests {
         assert_eq!(tokens[2].kind, TokenKind::Mul);
     }
 
+    #[test]
+    fn test_lexer_local_names() {
+        let source = "%foo %1 %x.y %add";
+        let mut lexer = Lexer::new(source, "test.vil");
+        let tokens = lexer.tokenize().unwrap();
+
+        assert_eq!(tokens.len(), 5);
+        assert_eq!(tokens[0].kind, TokenKind::LocalName("%foo".to_string()));
+        assert_eq!(tokens[1].kind, TokenKind::LocalName("%1".to_string()));
+        assert_eq!(tokens[2].kind, TokenKind::LocalName("%x.y".to_string()));
+        // 与操作码同名的局部名称不会被识别为关键字
+        assert_eq!(tokens[3].kind, TokenKind::LocalName("%add".to_string()));
+    }
+
+    #[test]
+    fn test_lexer_strict_unknown() {
+        let source = "add $ sub";
+        let tokens = Lexer::new(source, "test.vil").tokenize().unwrap();
+        assert_eq!(tokens[1].kind, TokenKind::Unknown);
+
+        let options = LexerOptions {
+            strict_unknown: true,
+            ..Default::default()
+        };
+        let err = Lexer::new_with_options(source, "test.vil", options)
+            .tokenize()
+            .expect_err("严格模式下未知字符应报错");
+        assert!(err.to_string().contains("'$'"), "{}", err);
+        assert!(err.to_string().contains("1:5"), "{}", err);
+    }
+
+    #[test]
+    fn test_token_span() {
+        let source = "  foo 12345";
+        let tokens = Lexer::new(source, "test.vil").tokenize().unwrap();
+
+        assert_eq!(tokens[0].location.column, 3);
+        assert_eq!(tokens[0].end_location.column, 6);
+        assert_eq!(tokens[0].end_location.column - tokens[0].location.column, 3);
+        assert_eq!(tokens[1].end_location.column - tokens[1].location.column, 5);
+        assert_eq!(tokens[0].to_string(), "foo at test.vil:1:3-1:6");
+    }
+
+    #[test]
+    fn test_tokenize_range() {
+        let source = ".module m\n.function f(.param %a i32) {\n    %x = add %a, 1\n}\n";
+        let full = Lexer::new(source, "test.vil").tokenize().unwrap();
+
+        let start = source.find("%x").unwrap();
+        let end = source.find("\n}").unwrap();
+        let partial = Lexer::new(source, "test.vil")
+            .tokenize_range(start, end)
+            .unwrap();
+
+        let expected: Vec<&Token> = full.iter().filter(|t| t.location.line == 3).collect();
+        assert_eq!(partial.len(), expected.len());
+        for (a, b) in partial.iter().zip(expected) {
+            assert_eq!(a.kind, b.kind);
+            assert_eq!(a.location, b.location);
+            assert_eq!(a.end_location, b.end_location);
+            assert_eq!(a.location.offset, b.location.offset);
+        }
+
+        assert!(
+            Lexer::new(source, "test.vil")
+                .tokenize_range(5, source.len() + 1)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_byte_offsets() {
+        // 注释中的多字节字符使字节偏移与列号不同
+        let source = ".module m // 模块\n.function f() {\n    ret\n}\n";
+        let tokens = Lexer::new(source, "test.vil").tokenize().unwrap();
+
+        let mut last = 0;
+        for token in &tokens {
+            assert!(token.location.offset >= last);
+            assert!(token.end_location.offset >= token.location.offset);
+            last = token.end_location.offset;
+            if token.kind != TokenKind::EOF {
+                let text = &source[token.location.offset..token.end_location.offset];
+                assert_eq!(text, token.kind.to_string());
+            }
+        }
+
+        let ret = tokens.iter().find(|t| t.kind == TokenKind::Ret).unwrap();
+        assert_eq!(ret.location.offset, source.find("ret").unwrap());
+        assert_eq!(ret.location.line, 3);
+        assert_eq!(tokens.last().unwrap().location.offset, source.len());
+        // Display 仍为 file:line:col
+        assert_eq!(ret.location.to_string(), "test.vil:3:5");
+    }
+
     #[test]
     fn test_lexer_numbers() {
         let source = "123 456 789";
@@ -460,4 +825,132 @@ mod tests {
             panic!("Expected IntLiteral");
         }
     }
+
+    #[test]
+    fn test_lexer_comparison_operators() {
+        let source = "== != <= >= = < > !";
+        let kinds: Vec<TokenKind> = Lexer::new(source, "test.vil")
+            .tokenize()
+            .unwrap()
+            .into_iter()
+            .map(|t| t.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::EqualEqual,
+                TokenKind::NotEqual,
+                TokenKind::LessEqual,
+                TokenKind::GreaterEqual,
+                TokenKind::Equal,
+                TokenKind::LAngle,
+                TokenKind::RAngle,
+                TokenKind::Unknown,
+                TokenKind::EOF,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lexer_vector_type_not_comparison() {
+        let source = ".type v4 = <i32 x 4>
+%a = add %b, %c";
+        let kinds: Vec<TokenKind> = Lexer::new(source, "test.vil")
+            .tokenize()
+            .unwrap()
+            .into_iter()
+            .map(|t| t.kind)
+            .collect();
+        assert_eq!(kinds[2], TokenKind::Equal);
+        assert_eq!(kinds[3], TokenKind::LAngle);
+        assert_eq!(kinds[7], TokenKind::RAngle);
+        assert_eq!(kinds[9], TokenKind::Equal);
+        assert!(!kinds.iter().any(|k| matches!(
+            k,
+            TokenKind::EqualEqual
+                | TokenKind::NotEqual
+                | TokenKind::LessEqual
+                | TokenKind::GreaterEqual
+        )));
+    }
+
+    #[test]
+    fn test_lenient_opcodes_in_instruction_position() {
+        let source = ".function f() {\nentry:\n    %a = newop %x\n    oldop %a\n    ret\n}\n";
+        let options = LexerOptions {
+            lenient_opcodes: true,
+            ..Default::default()
+        };
+        let mut lexer = Lexer::new_with_options(source, "test.vil", options);
+        lexer.add_known_opcode("oldop");
+        let kinds: Vec<TokenKind> = lexer
+            .tokenize()
+            .unwrap()
+            .into_iter()
+            .map(|t| t.kind)
+            .collect();
+
+        // 标签与函数名仍是普通标识符
+        assert!(kinds.contains(&TokenKind::Identifier("entry".to_string())));
+        assert!(kinds.contains(&TokenKind::Identifier("f".to_string())));
+        assert!(kinds.contains(&TokenKind::Opcode("newop".to_string())));
+        assert!(kinds.contains(&TokenKind::Opcode("oldop".to_string())));
+        // 只有不在已知集合中的操作码产生警告
+        let diagnostics = lexer.take_diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics.iter().next().unwrap().message.contains("newop"));
+    }
+
+    #[test]
+    fn test_integer_literal_out_of_range() {
+        let source = "%x = add %a, 99999999999999999999";
+        let err = Lexer::new(source, "test.vil")
+            .tokenize()
+            .expect_err("超出 i64 范围的整数应报告词法错误");
+        assert!(err.to_string().contains("整数字面量超出范围"), "{}", err);
+        assert_eq!(err.location().map(|l| (l.line, l.column)), Some((1, 14)));
+
+        let tokens = Lexer::new("9223372036854775807", "test.vil")
+            .tokenize()
+            .unwrap();
+        assert_eq!(tokens[0].kind, TokenKind::IntLiteral(i64::MAX));
+    }
+
+    #[test]
+    fn test_decode_source_reports_invalid_utf8() {
+        let bytes = b".module m\n.function f\xff() {}\n";
+        let err = Lexer::decode_source(bytes, "test.vil").unwrap_err();
+        assert!(err.to_string().contains("0xFF"), "{}", err);
+        assert_eq!(err.location().map(|l| (l.line, l.column)), Some((2, 12)));
+        assert_eq!(Lexer::decode_source(b"ok", "test.vil").unwrap(), "ok");
+    }
+
+    #[test]
+    fn test_arbitrary_bytes_never_panic() {
+        // 确定性的伪随机字节序列，覆盖标点、数字、多字节字符与无效的 UTF-8
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        for _ in 0..500 {
+            let len = (state % 64) as usize;
+            let bytes: Vec<u8> = (0..len)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    (state >> 24) as u8
+                })
+                .collect();
+            let _ = Lexer::decode_source(&bytes, "fuzz.vil");
+            // 无效的 UTF-8 替换为 U+FFFD 后仍然交给词法分析器与解析器
+            let source = String::from_utf8_lossy(&bytes);
+            let options = LexerOptions {
+                lenient_opcodes: true,
+                ..Default::default()
+            };
+            let _ = Lexer::new_with_options(&source, "fuzz.vil", options).tokenize();
+            let _ = crate::frontend::parse_vil(&source, "fuzz.vil");
+            // 放在函数体内，使解析器也走到指令与操作数的解析路径
+            let body = format!(".module m\n.function f() {{\n%x = {}\n}}\n", source);
+            let _ = crate::frontend::parse_vil(&body, "fuzz.vil");
+        }
+    }
 }
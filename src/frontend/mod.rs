@@ -11,7 +11,7 @@ pub mod token;
 
 // 重新导出常用类型
 pub use error::{ParseError, ParseResult};
-pub use lexer::Lexer;
+pub use lexer::{ExtensionMnemonics, Lexer, LexerOptions};
 pub use parser::Parser;
 pub use token::{Token, TokenKind};
 
@@ -31,8 +31,32 @@ pub fn parse_vil(source: &str, filename: &str) -> ParseResult<crate::ir::ModuleR
     parser.parse_module()
 }
 
+/// 解析类型字符串，例如 `i32`、`<i32 x 4>* vspm`、`<pred 16>`
+///
+/// 复用词法分析器与类型语法，不需要完整的模块；类型别名在此没有定义，
+/// 引用别名会报告语义错误。
+pub fn parse_type(type_str: &str) -> ParseResult<crate::ir::TypeRef> {
+    let lexer = Lexer::new(type_str, "<type>");
+    Parser::new(lexer).parse_standalone_type()
+}
+
+/// 解析 VIL 源代码文本，并将警告与错误收集到 `diagnostics`
+///
+/// 解析失败时返回 None，失败原因已作为错误记录在 `diagnostics` 中。
+pub fn parse_vil_with_diagnostics(
+    source: &str,
+    filename: &str,
+    diagnostics: &mut crate::diagnostics::DiagnosticBag,
+) -> Option<crate::ir::ModuleRef> {
+    let lexer = Lexer::new(source, filename);
+    let mut parser = Parser::new(lexer);
+    parser.parse_module_with_diagnostics(diagnostics)
+}
+
 /// 解析 VIL 源代码文件，生成 IR 模块
 ///
+/// 文件中的 `.import` 指令相对于该文件所在目录解析。
+///
 /// # Arguments
 ///
 /// * `filepath` - VIL 源代码文件路径
@@ -41,10 +65,98 @@ pub fn parse_vil(source: &str, filename: &str) -> ParseResult<crate::ir::ModuleR
 ///
 /// 解析结果，成功则返回 IR 模块，失败则返回解析错误
 pub fn parse_vil_file(filepath: &str) -> ParseResult<crate::ir::ModuleRef> {
-    use std::fs::read_to_string;
-
-    match read_to_string(filepath) {
-        Ok(source) => parse_vil(&source, filepath),
+    match std::fs::read(filepath) {
+        Ok(bytes) => parse_vil(Lexer::decode_source(&bytes, filepath)?, filepath),
         Err(e) => Err(ParseError::new_io_error(filepath, e)),
     }
 }
+
+/// 检查源代码经过 解析 → 打印 → 重新解析 后结构保持不变
+///
+/// 打印使用不带类型后缀的 `PrinterOptions`，两次解析结果逐项（类型别名、内存空间、
+/// 按名称排序的函数）比较打印文本。不一致时返回首个差异行的说明。
+pub fn roundtrip_ok(source: &str) -> Result<(), String> {
+    use crate::ir::PrinterOptions;
+    use crate::ir::module::Module;
+    use crate::ir::printer::print_function;
+
+    let opts = PrinterOptions {
+        show_types: false,
+        ..PrinterOptions::default()
+    };
+
+    // 以与 HashMap 遍历顺序无关的方式输出模块的各个部分
+    fn sections(module: &Module, opts: &PrinterOptions) -> Vec<(String, String)> {
+        let mut sections = vec![("module".to_string(), module.get_name().to_string())];
+        if let Some(entry) = module.get_entry_function() {
+            sections.push(("entry".to_string(), entry.borrow().get_name().to_string()));
+        }
+        let mut aliases: Vec<_> = module
+            .get_type_aliases()
+            .into_iter()
+            .map(|(name, type_)| (format!("type {}", name), type_.borrow().to_string()))
+            .collect();
+        let mut memories: Vec<_> = module
+            .get_global_memory_spaces()
+            .iter()
+            .map(|m| {
+                let m = m.borrow();
+                (format!("memory {}", m.get_name()), m.to_string())
+            })
+            .collect();
+        let mut functions: Vec<_> = module
+            .get_functions()
+            .iter()
+            .map(|f| {
+                let f = f.borrow();
+                (format!("function {}", f.get_name()), print_function(&f, opts))
+            })
+            .collect();
+        aliases.sort();
+        memories.sort();
+        functions.sort();
+        sections.extend(aliases);
+        sections.extend(memories);
+        sections.extend(functions);
+        sections
+    }
+
+    let first = parse_vil(source, "roundtrip.vil").map_err(|e| format!("初次解析失败: {}", e))?;
+    let printed = crate::ir::print_module(&first.borrow(), &opts);
+    let second = parse_vil(&printed, "roundtrip.vil")
+        .map_err(|e| format!("重新解析失败: {}\n打印结果:\n{}", e, printed))?;
+
+    let expected = sections(&first.borrow(), &opts);
+    let actual = sections(&second.borrow(), &opts);
+    if expected.len() != actual.len() {
+        return Err(format!(
+            "顶层条目数量不一致: {} != {}\n打印结果:\n{}",
+            expected.len(),
+            actual.len(),
+            printed
+        ));
+    }
+    for ((name_a, text_a), (name_b, text_b)) in expected.iter().zip(&actual) {
+        if name_a != name_b {
+            return Err(format!("条目不一致: '{}' != '{}'", name_a, name_b));
+        }
+        for (line, (a, b)) in text_a.lines().zip(text_b.lines()).enumerate() {
+            if a != b {
+                return Err(format!(
+                    "{} 第 {} 行不一致:\n- {}\n+ {}",
+                    name_a,
+                    line + 1,
+                    a,
+                    b
+                ));
+            }
+        }
+        if text_a.lines().count() != text_b.lines().count() {
+            return Err(format!("{} 行数不一致", name_a));
+        }
+    }
+    if !first.borrow().structurally_eq(&second.borrow()) {
+        return Err("打印文本一致，但模块结构不一致（如操作数类型或常量）".to_string());
+    }
+    Ok(())
+}
@@ -1,20 +1,130 @@
 // Parser 模块
 //
 // 这个模块实现了 VIL 的语法分析器，将词法单元序列转换为抽象语法树 (AST)
+//
+// 语句结束规则：函数体内的每条指令以换行或 ';' 结束。';' 总是可选的，行尾的 ';' 与
+// 单独的 ';'（空语句）都会被忽略；同一行上的多条指令之间必须用 ';' 分隔。一条指令的
+// 操作数可以跨行书写，结束位置以其最后一个 token 所在的行为准。
 
+use crate::diagnostics::{Diagnostic, DiagnosticBag};
 use crate::frontend::error::{ParseError, ParseResult, SourceLocation};
 use crate::frontend::lexer::Lexer;
 use crate::frontend::token::{Token, TokenKind};
-use crate::ir::{Module, ModuleRef};
+use crate::ir::function::FUNCTION_ATTRIBUTES;
+use crate::ir::instruction::{Instruction, InstructionModifier, InstructionRef, Opcode};
+use crate::ir::operand::{GlobalRef, Operand, OperandRef};
+use crate::ir::types::{Type, TypeKind};
+use crate::ir::value::{Value, ValueRef};
+use crate::ir::{BasicBlock, BasicBlockRef, FunctionRef, Linkage, Module, ModuleRef, TypeRef};
 use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
+/// 类型别名的定义目标
+#[derive(Debug, Clone)]
+enum TypeAliasTarget {
+    /// 具体类型
+    Type(TypeRef),
+    /// 另一个别名（允许前向引用，在模块末尾统一解析）
+    Alias(String),
+}
+
+/// 函数体解析过程中的局部符号表
+#[derive(Default)]
+struct FunctionScope {
+    /// 参数、指令结果以及前向引用的值
+    values: HashMap<String, ValueRef>,
+    /// 已定义的值名称
+    defined: HashSet<String>,
+    /// 首次出现时尚未定义的值（名称, 位置）
+    forward_refs: Vec<(String, SourceLocation)>,
+    /// 已定义的基本块标签
+    labels: HashSet<String>,
+    /// 已定义或被跳转引用的基本块（标签不含 '%'）
+    blocks: HashMap<String, BasicBlockRef>,
+    /// 首次出现时尚未定义的跳转目标（标签, 位置）
+    label_refs: Vec<(String, SourceLocation)>,
+    /// 正在解析的函数（允许递归调用引用自身）
+    function: Option<FunctionRef>,
+}
+
+impl FunctionScope {
+    /// 定义一个新的局部值；若此前已被前向引用，则复用同一个 ValueRef
+    fn define(
+        &mut self,
+        name: String,
+        type_: TypeRef,
+        location: SourceLocation,
+    ) -> ParseResult<ValueRef> {
+        if !self.defined.insert(name.clone()) {
+            return Err(ParseError::new_semantic_error(
+                location,
+                &format!("重复定义的值: '{}'", name),
+            ));
+        }
+        if let Some(value) = self.values.get(&name) {
+            value.borrow_mut().set_type(type_);
+            return Ok(value.clone());
+        }
+        let value = Rc::new(RefCell::new(Value::new(type_, name.clone())));
+        self.values.insert(name, value.clone());
+        Ok(value)
+    }
+
+    /// 引用一个局部值；未定义时创建占位值，并在函数末尾检查
+    fn use_value(&mut self, name: String, location: SourceLocation) -> ValueRef {
+        if let Some(value) = self.values.get(&name) {
+            return value.clone();
+        }
+        let value = Rc::new(RefCell::new(Value::new(
+            Type::get_void_type(),
+            name.clone(),
+        )));
+        self.values.insert(name.clone(), value.clone());
+        self.forward_refs.push((name, location));
+        value
+    }
+
+    /// 引用一个跳转目标；尚未定义时创建占位基本块，并在函数末尾检查
+    fn use_block(&mut self, label: String, location: SourceLocation) -> BasicBlockRef {
+        if let Some(block) = self.blocks.get(&label) {
+            return block.clone();
+        }
+        let block = Rc::new(RefCell::new(BasicBlock::new(
+            label.clone(),
+            self.function.clone(),
+        )));
+        self.blocks.insert(label.clone(), block.clone());
+        self.label_refs.push((label, location));
+        block
+    }
+}
+
 /// 语法分析器
 pub struct Parser<'a> {
     #[allow(dead_code)] // 允许未使用的字段，因为解析器仍在开发中
     lexer: Lexer<'a>,
     #[allow(dead_code)] // 允许未使用的字段，因为解析器仍在开发中
     current_token: Option<Token>,
+    /// 向前多看一个 token 时的缓冲
+    lookahead: Option<Token>,
+    /// 已声明的类型别名: 名称 -> (目标, 声明位置)
+    type_aliases: HashMap<String, (TypeAliasTarget, SourceLocation)>,
+    /// 正在解析的文件链（规范化路径），用于检测循环导入
+    import_stack: Vec<PathBuf>,
+    /// 已导入过的文件（规范化路径），重复导入时跳过
+    imported: HashSet<PathBuf>,
+    /// 当前文件直接导入的文件（规范化路径），用于报告重复的 `.import`
+    direct_imports: HashSet<PathBuf>,
+    /// 解析过程中产生的警告（错误仍通过 `ParseError` 返回）
+    diagnostics: DiagnosticBag,
+    /// 当前 token 所在的行
+    line: usize,
+    /// 上一个被消费的 token 所在的行，用于判断指令是否以换行结束
+    prev_line: usize,
+    /// 宽松模式下遇到的未知助记符，同一助记符的指令共享同一字符串
+    unknown_mnemonics: HashSet<Rc<str>>,
 }
 
 impl<'a> Parser<'a> {
@@ -23,6 +133,40 @@ impl<'a> Parser<'a> {
         Parser {
             lexer,
             current_token: None, // 初始为空，会在 advance() 中填充
+            lookahead: None,
+            type_aliases: HashMap::new(),
+            import_stack: Vec::new(),
+            imported: HashSet::new(),
+            direct_imports: HashSet::new(),
+            diagnostics: DiagnosticBag::new(),
+            line: 0,
+            prev_line: 0,
+            unknown_mnemonics: HashSet::new(),
+        }
+    }
+
+    /// 为扩展指令编号登记助记符，之后本解析器（及其导入的文件）接受该助记符，
+    /// 解析出的指令以该助记符显示。见 `ExtensionMnemonics::register`
+    pub fn register_extension(&mut self, number: u16, mnemonic: &str) -> bool {
+        self.lexer.register_extension(number, mnemonic)
+    }
+
+    /// 解析模块，并将解析过程中的警告与错误推入 `diagnostics`
+    ///
+    /// 解析失败时返回 None，失败原因已作为错误记录在 `diagnostics` 中。
+    pub fn parse_module_with_diagnostics(
+        &mut self,
+        diagnostics: &mut DiagnosticBag,
+    ) -> Option<ModuleRef> {
+        let result = self.parse_module();
+        diagnostics.extend(self.lexer.take_diagnostics());
+        diagnostics.extend(std::mem::take(&mut self.diagnostics));
+        match result {
+            Ok(module) => Some(module),
+            Err(e) => {
+                diagnostics.push(Diagnostic::from(&e));
+                None
+            }
         }
     }
 
@@ -31,6 +175,12 @@ impl<'a> Parser<'a> {
         // 解析入口: `.module <identifier>`
 
         self.advance()?; // Load the first token (should be .module)
+        // 源文件存在于磁盘上时，将其作为导入链的起点
+        if self.import_stack.is_empty()
+            && let Ok(path) = std::fs::canonicalize(&self.current_location().filename)
+        {
+            self.import_stack.push(path);
+        }
         self.consume_expected_token(TokenKind::Module, "期望关键字 '.module'")?;
         // After this, `self.current_token` holds the module name.
 
@@ -39,6 +189,8 @@ impl<'a> Parser<'a> {
         // After this, `self.current_token` holds the first top-level declaration (or EOF).
 
         let module_ref: ModuleRef = Rc::new(RefCell::new(Module::new(module_name)));
+        // 入口函数可在其定义之前声明，待整个模块解析完成后再解析
+        let mut entry: Option<(String, SourceLocation)> = None;
 
         loop {
             let current_kind_clone = self.peek_token_kind().cloned();
@@ -52,9 +204,48 @@ impl<'a> Parser<'a> {
                         .borrow_mut()
                         .add_global_memory_space(Rc::new(RefCell::new(mem_space)));
                 }
+                Some(TokenKind::Type) => {
+                    self.consume_expected_token(TokenKind::Type, "期望关键字 '.type'")?;
+                    self.parse_type_alias()?;
+                }
+                Some(TokenKind::Entry) => {
+                    self.consume_expected_token(TokenKind::Entry, "期望关键字 '.entry'")?;
+                    self.consume_expected_token(TokenKind::At, "期望 '@' 开始入口函数名")?;
+                    let (name, location) = self.expect_identifier("期望入口函数名")?;
+                    if entry.is_some() {
+                        return Err(ParseError::new_semantic_error(
+                            location,
+                            "重复声明的入口函数 (.entry)",
+                        ));
+                    }
+                    entry = Some((name, location));
+                }
+                Some(TokenKind::Import) => {
+                    self.consume_expected_token(TokenKind::Import, "期望关键字 '.import'")?;
+                    self.parse_import(&module_ref, current_loc)?;
+                }
                 Some(TokenKind::Function) => {
                     self.consume_expected_token(TokenKind::Function, "期望关键字 '.function'")?; // Consumes and advances
-                    let func = self.parse_function()?; // parse_function will assume current_token is the function name, and consume/advance from there.
+                    let func = self.parse_function(&module_ref)?; // parse_function will assume current_token is the function name, and consume/advance from there.
+                    module_ref.borrow_mut().add_function(func);
+                }
+                Some(TokenKind::Identifier(keyword))
+                    if Linkage::from_keyword(&keyword).is_some() =>
+                {
+                    // 链接属性只能修饰其后的 `.function`
+                    let linkage = Linkage::from_keyword(&keyword).unwrap_or_default();
+                    self.advance()?;
+                    self.consume_expected_token(
+                        TokenKind::Function,
+                        "链接属性之后期望 '.function'",
+                    )?;
+                    let func = self.parse_function(&module_ref)?;
+                    func.borrow_mut().set_linkage(linkage);
+                    module_ref.borrow_mut().add_function(func);
+                }
+                Some(TokenKind::Declare) => {
+                    self.consume_expected_token(TokenKind::Declare, "期望关键字 '.declare'")?;
+                    let func = self.parse_declaration()?;
                     module_ref.borrow_mut().add_function(func);
                 }
                 Some(TokenKind::EOF) => break, // 文件结束
@@ -62,22 +253,109 @@ impl<'a> Parser<'a> {
                 _ => {
                     return Err(ParseError::new_syntax_error(
                         current_loc,
-                        "模块级声明格式不正确，期望 .memory 或 .function",
+                        "模块级声明格式不正确，期望 .type、.memory、.entry、.import、.declare 或 .function",
                     ));
                 }
             }
         }
 
+        // 解析所有类型别名（包括未被使用的），以报告未定义与循环引用
+        let mut alias_names: Vec<_> = self.type_aliases.keys().cloned().collect();
+        alias_names.sort();
+        for name in alias_names {
+            let location = self.type_aliases[&name].1.clone();
+            let type_ = self.resolve_type_alias(&name, location)?;
+            module_ref.borrow_mut().add_type_alias(name, type_);
+        }
+
+        if let Some((name, location)) = entry
+            && !module_ref.borrow_mut().set_entry_function(&name)
+        {
+            return Err(ParseError::new_semantic_error(
+                location,
+                &format!("未定义的入口函数: '@{}'", name),
+            ));
+        }
+
         Ok(module_ref)
     }
 
+    /// 解析类型别名声明: `.type %Name = <type>`
+    fn parse_type_alias(&mut self) -> ParseResult<()> {
+        // `current_token` should hold the alias name when this function is called.
+        let (name, name_location) =
+            self.expect_local_name("期望以 '%' 开头的类型别名名称 (例如: %Vec4)")?;
+        if self.type_aliases.contains_key(&name) {
+            return Err(ParseError::new_semantic_error(
+                name_location,
+                &format!("重复定义的类型别名: '{}'", name),
+            ));
+        }
+        self.consume_expected_token(TokenKind::Equal, "期望 '=' 分隔类型别名与类型")?;
+
+        // 直接指向另一个别名时允许前向引用，其它情况立即解析类型
+        let target = match self.peek_token_kind() {
+            Some(TokenKind::LocalName(_)) => {
+                let (target_name, _) = self.expect_local_name("期望类型别名")?;
+                TypeAliasTarget::Alias(target_name)
+            }
+            _ => TypeAliasTarget::Type(self.parse_type()?),
+        };
+
+        self.type_aliases.insert(name, (target, name_location));
+        Ok(())
+    }
+
+    /// 沿别名链解析类型别名，报告未定义别名与循环引用
+    fn resolve_type_alias(&self, name: &str, location: SourceLocation) -> ParseResult<TypeRef> {
+        let mut chain: Vec<String> = Vec::new();
+        let mut visited = HashSet::new();
+        let mut current = name.to_string();
+
+        loop {
+            if !visited.insert(current.clone()) {
+                chain.push(current);
+                return Err(ParseError::new_semantic_error(
+                    location,
+                    &format!("类型别名存在循环引用: {}", chain.join(" -> ")),
+                ));
+            }
+            chain.push(current.clone());
+
+            match self.type_aliases.get(&current) {
+                Some((TypeAliasTarget::Type(type_), _)) => return Ok(type_.clone()),
+                Some((TypeAliasTarget::Alias(next), _)) => current = next.clone(),
+                None => {
+                    return Err(ParseError::new_semantic_error(
+                        location,
+                        &format!("未定义的类型别名: '{}'", current),
+                    ));
+                }
+            }
+        }
+    }
+
     // 占位符方法，用于后续开发
     #[allow(dead_code)] // 允许未使用的代码，因为解析器仍在开发中
     fn advance(&mut self) -> ParseResult<()> {
-        self.current_token = Some(self.lexer.next_token()?);
+        let next = match self.lookahead.take() {
+            Some(token) => token,
+            None => self.lexer.next_token()?,
+        };
+        self.prev_line = self.line;
+        self.line = next.location.line;
+        self.current_token = Some(next);
         Ok(())
     }
 
+    /// 查看当前 token 之后的下一个 token 种类，但不消费
+    fn peek_next_token_kind(&mut self) -> ParseResult<Option<&TokenKind>> {
+        if self.lookahead.is_none() {
+            self.lookahead = Some(self.lexer.next_token()?);
+        }
+        Ok(self.lookahead.as_ref().map(|t| &t.kind))
+    }
+
     #[allow(dead_code)] // 允许未使用的代码，因为解析器仍在开发中
     fn peek_token_kind(&self) -> Option<&TokenKind> {
         self.current_token.as_ref().map(|t| &t.kind)
@@ -116,7 +394,30 @@ impl<'a> Parser<'a> {
         self.current_token
             .as_ref()
             .map(|t| t.location.clone())
-            .unwrap_or_else(|| SourceLocation::new("parser.rs", 0, 0))
+            .unwrap_or_else(|| SourceLocation::new("parser.rs", 0, 0, 0))
+    }
+
+    /// 期望并消费一个 `%` 局部名称 token，返回包含 `%` 前缀的名称。
+    fn expect_local_name(&mut self, message: &str) -> ParseResult<(String, SourceLocation)> {
+        let current_loc = self.current_location();
+        match self.current_token.take() {
+            Some(Token {
+                kind: TokenKind::LocalName(name),
+                location,
+                ..
+            }) => {
+                self.advance()?;
+                Ok((name, location))
+            }
+            Some(token) => {
+                self.current_token = Some(token); // 放回 token 以供错误报告
+                Err(ParseError::new_syntax_error(current_loc, message))
+            }
+            None => Err(ParseError::new_syntax_error(
+                current_loc,
+                "意外的文件结束，期望 '%' 名称",
+            )),
+        }
     }
 
     /// 期望并消费一个标识符 token，返回其字符串值。
@@ -216,6 +517,8 @@ impl<'a> Parser<'a> {
         let _location = token.location.clone(); // 已标记为未使用
 
         let result_type = match kind {
+            // 类型别名引用
+            TokenKind::LocalName(s) => self.resolve_type_alias(s, _location),
             TokenKind::Identifier(s) => match s.as_str() {
                 "i8" => Ok(crate::ir::Type::get_int_type(crate::ir::TypeKind::Int8)),
                 "u8" => Ok(crate::ir::Type::get_int_type(crate::ir::TypeKind::Uint8)),
@@ -245,6 +548,21 @@ impl<'a> Parser<'a> {
         Ok(result_type)
     }
 
+    /// 将整个输入解析为单个类型（不需要 `.module` 等上下文），见 `frontend::parse_type`
+    ///
+    /// 类型之后还有其它内容时报告语法错误。
+    pub fn parse_standalone_type(&mut self) -> ParseResult<TypeRef> {
+        self.advance()?;
+        let type_ = self.parse_type()?;
+        match self.peek_token_kind() {
+            Some(TokenKind::EOF) | None => Ok(type_),
+            Some(kind) => Err(ParseError::new_syntax_error(
+                self.current_location(),
+                &format!("类型之后存在多余的内容: '{}'", kind),
+            )),
+        }
+    }
+
     /// 解析 VIL 类型，例如 `<i32 x 4>`, `<pred 32>`, `i16* vspm`, `void`, `i32`
     fn parse_type(&mut self) -> ParseResult<crate::ir::TypeRef> {
         // `current_token` should hold the type's first token when this function is called.
@@ -326,24 +644,111 @@ impl<'a> Parser<'a> {
         &mut self,
         is_result_param: bool,
     ) -> ParseResult<crate::ir::function::ArgumentRef> {
-        let _is_result_param = is_result_param; // 已标记为未使用
         // `current_token` should hold the argument name when this function is called.
-        let (name, name_location) = self.expect_identifier("期望参数名称 (例如: %in1)")?;
-        if !name.starts_with("%") {
-            return Err(ParseError::new_syntax_error(
-                name_location,
-                "参数名称应以 '%' 开头",
-            ));
-        }
+        let (name, _) = self.expect_local_name("期望以 '%' 开头的参数名称 (例如: %in1)")?;
         // `current_token` now holds the argument type.
         let arg_type = self.parse_type()?; // parse_type consumes its tokens and advances `current_token`.
 
-        Ok(Rc::new(RefCell::new(crate::ir::function::Argument::new(
-            arg_type, name, None, 0,
-        ))))
+        let mut argument = crate::ir::function::Argument::new(arg_type, name, None, 0);
+        argument.set_result(is_result_param);
+        Ok(Rc::new(RefCell::new(argument)))
     }
 
-    /// 解析全局内存空间声明: `.memory <name> [memory_space] <element_type x length>`
+    /// 解析导入指令: `.import "path"`
+    ///
+    /// 路径相对于当前文件所在目录解析，`/` 与 `\` 均视为路径分隔符。
+    /// 导入模块的函数、全局内存空间与类型别名合并进当前模块；同一文件只导入一次，
+    /// 导入链中再次出现正在解析的文件时报告循环导入。
+    fn parse_import(&mut self, module: &ModuleRef, location: SourceLocation) -> ParseResult<()> {
+        let relative = match self.peek_token_kind() {
+            Some(TokenKind::StringLiteral(path)) => path.clone(),
+            _ => {
+                return Err(ParseError::new_syntax_error(
+                    self.current_location(),
+                    "期望带引号的导入路径 (例如: .import \"lib.vil\")",
+                ));
+            }
+        };
+        self.advance()?;
+
+        let path = resolve_import_path(&location.filename, &relative);
+        let bytes =
+            std::fs::read(&path).map_err(|e| ParseError::new_io_error_at(location.clone(), e))?;
+        let source = Lexer::decode_source(&bytes, &path.to_string_lossy())?;
+        let canonical = std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+
+        if self.import_stack.contains(&canonical) {
+            let chain: Vec<String> = self
+                .import_stack
+                .iter()
+                .skip_while(|p| **p != canonical)
+                .chain(std::iter::once(&canonical))
+                .map(|p| p.display().to_string())
+                .collect();
+            return Err(ParseError::new_semantic_error(
+                location,
+                &format!("检测到循环导入: {}", chain.join(" -> ")),
+            ));
+        }
+        if !self.direct_imports.insert(canonical.clone()) {
+            self.diagnostics
+                .warning(&format!("重复导入 '{}'，已忽略", relative), Some(location));
+            return Ok(());
+        }
+        if !self.imported.insert(canonical.clone()) {
+            return Ok(());
+        }
+
+        let filename = path.to_string_lossy().into_owned();
+        let options = self.lexer.options();
+        let mut parser = Parser::new(Lexer::new_with_options(source, &filename, options));
+        parser.lexer.set_extensions(self.lexer.extensions().clone());
+        parser.import_stack = self.import_stack.clone();
+        parser.import_stack.push(canonical);
+        parser.imported = std::mem::take(&mut self.imported);
+        let result = parser.parse_module();
+        self.imported = std::mem::take(&mut parser.imported);
+        self.diagnostics.extend(parser.lexer.take_diagnostics());
+        self.diagnostics
+            .extend(std::mem::take(&mut parser.diagnostics));
+        let imported = result?;
+        let imported = imported.borrow();
+
+        for (name, type_) in imported.get_type_aliases() {
+            if self.type_aliases.contains_key(&name) {
+                return Err(ParseError::new_semantic_error(
+                    location,
+                    &format!("导入的类型别名 '{}' 与已有定义重复", name),
+                ));
+            }
+            self.type_aliases
+                .insert(name, (TypeAliasTarget::Type(type_), location.clone()));
+        }
+        let mut module = module.borrow_mut();
+        for mem in imported.get_global_memory_spaces() {
+            let name = mem.borrow().get_name().to_string();
+            if module.get_global_memory_space(&name).is_some() {
+                return Err(ParseError::new_semantic_error(
+                    location,
+                    &format!("导入的全局内存空间 '@{}' 与已有定义重复", name),
+                ));
+            }
+            module.add_global_memory_space(mem);
+        }
+        for func in imported.get_functions() {
+            let name = func.borrow().get_name().to_string();
+            if module.get_function(&name).is_some() {
+                return Err(ParseError::new_semantic_error(
+                    location,
+                    &format!("导入的函数 '@{}' 与已有定义重复", name),
+                ));
+            }
+            module.add_function(func);
+        }
+        Ok(())
+    }
+
+    /// 解析全局内存空间声明: `.memory <name> [memory_space] <element_type x length> [.align N]`
     fn parse_global_memory_space(&mut self) -> ParseResult<crate::ir::module::GlobalMemorySpace> {
         let _start_location = self.current_location(); // 已标记为未使用
         // `current_token` should hold the memory name when this function is called.
@@ -382,17 +787,49 @@ impl<'a> Parser<'a> {
         // 处理完长度后，current_token 指向长度之后（若有显式长度）的 token。
         // `current_token` now holds the token *after* the length.
 
-        Ok(crate::ir::module::GlobalMemorySpace::new(
-            name,
-            space,
-            elem_type_token,
-            length,
-        ))
+        let mut memory =
+            crate::ir::module::GlobalMemorySpace::new(name, space, elem_type_token, length);
+        // 可选的对齐声明 `.align N`，缺省按元素类型宽度对齐
+        if self.peek_token_kind() == Some(&TokenKind::Align) {
+            self.consume_expected_token(TokenKind::Align, "期望关键字 '.align'")?;
+            let (value, location) = self.expect_int_literal("期望对齐字节数 (例如: .align 16)")?;
+            if value <= 0 || (value & (value - 1)) != 0 || value > u32::MAX as i64 {
+                return Err(ParseError::new_semantic_error(
+                    location,
+                    &format!("对齐必须是 2 的正整数次幂，实际为 {}", value),
+                ));
+            }
+            memory = memory.with_alignment(value as u32);
+        }
+        Ok(memory)
     }
 
     /// 解析函数声明: `.function <name>(<params>) { <body> }`
-    fn parse_function(&mut self) -> ParseResult<crate::ir::FunctionRef> {
-        let _start_location = self.current_location(); // 已标记为未使用
+    fn parse_function(&mut self, module: &ModuleRef) -> ParseResult<crate::ir::FunctionRef> {
+        let function_ref = self.parse_function_signature()?;
+
+        // 解析完函数签名后，期望出现函数体的大括号起始 '{'
+        self.consume_expected_token(TokenKind::LBrace, "期望 '{' 开始函数体")?;
+        self.parse_function_body(module, &function_ref)?;
+        // 函数体解析结束时已消费配对的 '}'，current_token 指向 '}' 之后的 token。
+
+        Ok(function_ref)
+    }
+
+    /// 解析只有签名、没有函数体的外部函数声明: `.declare @<name>(<params>) [-> <type>]`
+    ///
+    /// 名称前的 '@' 可以省略。
+    fn parse_declaration(&mut self) -> ParseResult<crate::ir::FunctionRef> {
+        if self.peek_token_kind() == Some(&TokenKind::At) {
+            self.advance()?;
+        }
+        let function_ref = self.parse_function_signature()?;
+        function_ref.borrow_mut().set_declaration(true);
+        Ok(function_ref)
+    }
+
+    /// 解析函数签名：名称、参数列表、可选的返回类型与函数属性，构造不含基本块的函数
+    fn parse_function_signature(&mut self) -> ParseResult<crate::ir::FunctionRef> {
         // `current_token` should hold the function name when this function is called.
         let (name, _) = self.expect_identifier("期望函数名称")?;
         // `current_token` now holds `(`.
@@ -414,7 +851,10 @@ impl<'a> Parser<'a> {
 
             // 现在 current_token 应为参数名称
             let arg_ref = self.parse_argument(is_result_param)?;
-            param_types.push(arg_ref.borrow().get_type());
+            // 结果参数不由调用方传入，不计入函数类型的参数
+            if !is_result_param {
+                param_types.push(arg_ref.borrow().get_type());
+            }
             arguments.push(arg_ref);
 
             // 如果后面还有逗号，则消费
@@ -426,21 +866,86 @@ impl<'a> Parser<'a> {
         // 参数解析完毕，期望 ')'
         self.consume_expected_token(TokenKind::RParen, "期望 ')' 闭合参数列表")?;
 
-        // 解析完参数列表后，期望出现函数体的大括号起始 '{'
-        self.consume_expected_token(TokenKind::LBrace, "期望 '{' 开始函数体")?;
+        // 可选的返回类型 `-> type`，缺省为 void
+        let return_type = if self.peek_token_kind() == Some(&TokenKind::Arrow) {
+            self.consume_expected_token(TokenKind::Arrow, "期望 '->'")?;
+            self.parse_type()?
+        } else {
+            crate::ir::Type::get_void_type()
+        };
 
-        // 跳过函数体：使用大括号深度计数，直到匹配完毕。
-        let mut brace_depth = 1;
-        while brace_depth > 0 {
-            let kind_opt = self.peek_token_kind().cloned();
-            match kind_opt {
-                Some(TokenKind::LBrace) => {
-                    self.advance()?; // consume '{'
-                    brace_depth += 1;
+        // 可选的函数属性，只接受 `FUNCTION_ATTRIBUTES` 中列出的属性；
+        // 链接属性修饰下一个 `.function`，遇到时结束
+        let mut attributes = Vec::new();
+        while let Some(TokenKind::Identifier(attr)) = self.peek_token_kind() {
+            if Linkage::from_keyword(attr).is_some() {
+                break;
+            }
+            if !FUNCTION_ATTRIBUTES.contains(&attr.as_str()) {
+                return Err(ParseError::new_semantic_error(
+                    self.current_location(),
+                    &format!("未知的函数属性: '{}'", attr),
+                ));
+            }
+            attributes.push(attr.clone());
+            self.advance()?;
+        }
+
+        // 构造函数 IR 对象
+        let function_ref = Rc::new(RefCell::new(crate::ir::Function::new(
+            name,
+            return_type,
+            param_types,
+        )));
+
+        for arg in &arguments {
+            arg.borrow_mut()
+                .set_parent(Some(Rc::downgrade(&function_ref)));
+            function_ref.borrow_mut().add_argument(arg.clone());
+        }
+        for attr in attributes {
+            function_ref.borrow_mut().add_attribute(attr);
+        }
+
+        Ok(function_ref)
+    }
+
+    /// 解析函数体，直到并包括闭合的 '}'
+    ///
+    /// 函数体由基本块标签 (`name:`) 与指令组成；第一条指令之前没有标签时，
+    /// 隐式创建名为 `entry` 的基本块。指令以换行或可选的 ';' 结束。
+    fn parse_function_body(
+        &mut self,
+        module: &ModuleRef,
+        function: &FunctionRef,
+    ) -> ParseResult<()> {
+        let mut scope = FunctionScope {
+            function: Some(function.clone()),
+            ..FunctionScope::default()
+        };
+        for arg in function.borrow().get_arguments() {
+            let arg = arg.borrow();
+            scope.define(
+                arg.get_name().to_string(),
+                arg.get_type(),
+                self.current_location(),
+            )?;
+        }
+
+        let mut current_block = None;
+        loop {
+            let kind = self.peek_token_kind().cloned();
+            let is_label = match &kind {
+                Some(TokenKind::Identifier(_)) => {
+                    self.peek_next_token_kind()? == Some(&TokenKind::Colon)
                 }
+                _ => false,
+            };
+
+            match kind {
                 Some(TokenKind::RBrace) => {
                     self.advance()?; // consume '}'
-                    brace_depth -= 1;
+                    break;
                 }
                 Some(TokenKind::EOF) | None => {
                     return Err(ParseError::new_syntax_error(
@@ -448,29 +953,598 @@ impl<'a> Parser<'a> {
                         "函数体未正确闭合",
                     ));
                 }
+                Some(TokenKind::Semicolon) => {
+                    self.advance()?; // 可选的语句结束符
+                }
+                _ if is_label => {
+                    let (label, label_location) = self.expect_identifier("期望基本块标签")?;
+                    self.consume_expected_token(TokenKind::Colon, "期望 ':' 结束基本块标签")?;
+                    current_block =
+                        Some(self.create_block(function, &mut scope, label, label_location)?);
+                }
                 _ => {
-                    self.advance()?; // consume other tokens
+                    let block = match &current_block {
+                        Some(block) => block,
+                        None => {
+                            let location = self.current_location();
+                            current_block.insert(self.create_block(
+                                function,
+                                &mut scope,
+                                "entry".to_string(),
+                                location,
+                            )?)
+                        }
+                    };
+                    let instruction = self.parse_instruction(module, &mut scope)?;
+                    block
+                        .borrow_mut()
+                        .add_instruction(instruction, block.clone());
+                    // 指令以换行或 ';' 结束
+                    let terminated = self.line > self.prev_line
+                        || matches!(
+                            self.peek_token_kind(),
+                            Some(TokenKind::Semicolon | TokenKind::RBrace | TokenKind::EOF) | None
+                        );
+                    if !terminated {
+                        return Err(ParseError::new_syntax_error(
+                            self.current_location(),
+                            "同一行上的多条指令之间需要用 ';' 分隔",
+                        ));
+                    }
                 }
             }
         }
-        // 结束循环时已消费配对的 '}'，current_token 指向 '}' 之后的 token。
 
-        // 构造函数 IR 对象
-        let return_type = crate::ir::Type::get_void_type();
-        let function_ref = Rc::new(RefCell::new(crate::ir::Function::new(
-            name,
-            return_type,
-            param_types,
-        )));
+        // 前向引用必须在函数内被定义，或者是基本块标签（跳转目标）
+        for (name, location) in &scope.forward_refs {
+            if !scope.defined.contains(name) && !scope.labels.contains(&name[1..]) {
+                return Err(ParseError::new_semantic_error(
+                    location.clone(),
+                    &format!("未定义的值: '{}'", name),
+                ));
+            }
+        }
 
-        for arg in &arguments {
-            arg.borrow_mut()
-                .set_parent(Some(Rc::downgrade(&function_ref)));
-            function_ref.borrow_mut().add_argument(arg.clone());
+        for (label, location) in &scope.label_refs {
+            if !scope.labels.contains(label) {
+                return Err(ParseError::new_semantic_error(
+                    location.clone(),
+                    &format!("跳转到未定义的基本块: '%{}'", label),
+                ));
+            }
         }
 
-        Ok(function_ref)
+        Ok(())
+    }
+
+    /// 创建并登记一个新的基本块；已被跳转引用过时复用占位基本块
+    fn create_block(
+        &mut self,
+        function: &FunctionRef,
+        scope: &mut FunctionScope,
+        label: String,
+        location: SourceLocation,
+    ) -> ParseResult<crate::ir::BasicBlockRef> {
+        if !scope.labels.insert(label.clone()) {
+            return Err(ParseError::new_semantic_error(
+                location,
+                &format!("重复定义的基本块标签: '{}'", label),
+            ));
+        }
+        let block = scope
+            .blocks
+            .entry(label.clone())
+            .or_insert_with(|| {
+                Rc::new(RefCell::new(BasicBlock::new(label, Some(function.clone()))))
+            })
+            .clone();
+        function.borrow_mut().add_basic_block(block.clone());
+        Ok(block)
+    }
+
+    /// 解析单条指令: `[%result =] <opcode>[.modifier] [operand {, operand}]`
+    fn parse_instruction(
+        &mut self,
+        module: &ModuleRef,
+        scope: &mut FunctionScope,
+    ) -> ParseResult<InstructionRef> {
+        // 可选的结果定义 `%name =`
+        let result_name = match self.peek_token_kind() {
+            Some(TokenKind::LocalName(_)) => {
+                let (name, location) = self.expect_local_name("期望结果名称")?;
+                self.consume_expected_token(TokenKind::Equal, "期望 '=' 分隔结果与指令")?;
+                Some((name, location))
+            }
+            _ => None,
+        };
+
+        let opcode_location = self.current_location();
+        let (opcode, mnemonic, modifier) = self.parse_opcode()?;
+
+        let mut operands = Vec::new();
+        let mut result_type = None;
+        if opcode == Opcode::Alloc {
+            // `alloc <type> [space]` 以类型与内存空间代替操作数，结果为指向该类型的指针
+            if result_name.is_none() {
+                return Err(ParseError::new_semantic_error(
+                    opcode_location,
+                    "alloc 指令必须定义结果 (例如: %p = alloc i32 [sram])",
+                ));
+            }
+            let elem_type = self.parse_type()?;
+            self.consume_expected_token(TokenKind::LBracket, "期望 '[' 开始内存空间指定")?;
+            let (space_name, space_location) =
+                self.expect_memory_space_identifier("期望内存空间类型 (e.g., vspm, sram)")?;
+            let space = parse_memory_space_from_ident(&space_name, space_location)?;
+            self.consume_expected_token(TokenKind::RBracket, "期望 ']' 闭合内存空间指定")?;
+            result_type = Some(Type::get_pointer_type(elem_type, space));
+        } else if opcode == Opcode::Call {
+            // `call @callee(args)`：第 0 个操作数为被调函数，其余为实参
+            let (callee, args) = self.parse_call(module, scope, opcode_location)?;
+            result_type = Some(callee.borrow().call_result_type());
+            operands.push(Operand::create_global(GlobalRef::Function(callee)));
+            operands.extend(args);
+        } else if opcode == Opcode::Br {
+            // `br %label`
+            operands.push(self.parse_branch_target(scope)?);
+        } else if opcode == Opcode::CondBr {
+            // `condbr %cond, %then, %else`
+            operands.push(self.parse_operand(module, scope)?);
+            self.consume_expected_token(TokenKind::Comma, "期望 ',' 分隔条件与跳转目标")?;
+            operands.push(self.parse_branch_target(scope)?);
+            self.consume_expected_token(TokenKind::Comma, "期望 ',' 分隔跳转目标")?;
+            operands.push(self.parse_branch_target(scope)?);
+        } else if matches!(opcode, Opcode::Shuffle | Opcode::ShuffleClbmv)
+            && self.at_operand_start()?
+        {
+            // `shuffle %v, <i0, i1, ...>` 或以向量值给出索引的 `shuffle %v, %idx`
+            operands.push(self.parse_operand(module, scope)?);
+            self.consume_expected_token(TokenKind::Comma, "期望 ',' 分隔源向量与索引")?;
+            if self.peek_token_kind() == Some(&TokenKind::LAngle) {
+                let indices = self.parse_shuffle_indices()?;
+                let source = operands[0].borrow().to_value();
+                if let TypeKind::Vector(elem, _) = source.borrow().get_type().borrow().get_kind() {
+                    result_type = Some(Type::get_vector_type(elem.clone(), indices.len() as u32));
+                }
+                operands.push(Operand::create_index_vector(indices));
+            } else {
+                operands.push(self.parse_operand(module, scope)?);
+            }
+        } else if self.at_operand_start()? {
+            // 记录立即数的位置，供范围检查报告错误
+            let mut immediates = Vec::new();
+            loop {
+                let location = self.current_location();
+                let operand = self.parse_operand(module, scope)?;
+                if operand.borrow().is_immediate() {
+                    immediates.push((operands.len(), location));
+                }
+                operands.push(operand);
+                if self.peek_token_kind() != Some(&TokenKind::Comma) {
+                    break;
+                }
+                self.consume_expected_token(TokenKind::Comma, "期望 ',' 分隔操作数")?;
+            }
+            check_immediate_ranges(opcode, &operands, immediates)?;
+        }
+        if opcode.is_cast() {
+            // `bitcast %x : <type>`：显式给出目标类型
+            self.consume_expected_token(
+                TokenKind::Colon,
+                &format!("期望 ':' 指定 {} 的目标类型", opcode),
+            )?;
+            result_type = Some(self.parse_type()?);
+        }
+        let attributes = self.parse_instruction_attributes()?;
+
+        let result = match result_name {
+            Some((name, location)) => {
+                let type_ = result_type.unwrap_or_else(|| {
+                    let values: Vec<ValueRef> =
+                        operands.iter().map(|op| op.borrow().to_value()).collect();
+                    infer_result_type(opcode, &values)
+                });
+                Some(scope.define(name, type_, location)?)
+            }
+            None => None,
+        };
+
+        let mut instruction = Instruction::with_operands(opcode, result, operands, modifier);
+        instruction.set_mnemonic(mnemonic);
+        for attr in attributes {
+            instruction.add_attribute(attr);
+        }
+        Ok(Rc::new(RefCell::new(instruction)))
+    }
+
+    /// 解析洗牌的常量索引向量 `<i0, i1, ...>`
+    fn parse_shuffle_indices(&mut self) -> ParseResult<Vec<i64>> {
+        self.consume_expected_token(TokenKind::LAngle, "期望 '<' 开始洗牌索引")?;
+        let mut indices = Vec::new();
+        loop {
+            let (index, _) = self.expect_int_literal("期望整数洗牌索引")?;
+            indices.push(index);
+            if self.peek_token_kind() != Some(&TokenKind::Comma) {
+                break;
+            }
+            self.consume_expected_token(TokenKind::Comma, "期望 ',' 分隔洗牌索引")?;
+        }
+        self.consume_expected_token(TokenKind::RAngle, "期望 '>' 结束洗牌索引")?;
+        Ok(indices)
+    }
+
+    /// 解析跳转目标 `%label`，得到基本块操作数
+    fn parse_branch_target(&mut self, scope: &mut FunctionScope) -> ParseResult<OperandRef> {
+        let (name, location) = self.expect_local_name("期望以 '%' 开头的跳转目标标签")?;
+        let label = name.strip_prefix('%').unwrap_or(&name).to_string();
+        Ok(Operand::create_basic_block(
+            scope.use_block(label, location),
+        ))
+    }
+
+    /// 解析指令末尾的属性: `volatile`、`align N`
+    ///
+    /// 属性按文本形式保存（如 `"volatile"`、`"align 16"`）。
+    /// 后面紧跟 ':' 的同名标识符是基本块标签，不作为属性处理。
+    fn parse_instruction_attributes(&mut self) -> ParseResult<Vec<String>> {
+        let mut attributes = Vec::new();
+        loop {
+            let name = match self.peek_token_kind() {
+                Some(TokenKind::Identifier(name)) if name == "volatile" || name == "align" => {
+                    name.clone()
+                }
+                _ => break,
+            };
+            if self.peek_next_token_kind()? == Some(&TokenKind::Colon) {
+                break;
+            }
+            self.advance()?;
+            if name == "align" {
+                let (value, location) =
+                    self.expect_int_literal("期望对齐字节数 (例如: align 16)")?;
+                if value <= 0 || (value & (value - 1)) != 0 {
+                    return Err(ParseError::new_semantic_error(
+                        location,
+                        &format!("对齐必须是 2 的正整数次幂，实际为 {}", value),
+                    ));
+                }
+                attributes.push(format!("align {}", value));
+            } else {
+                attributes.push(name);
+            }
+        }
+        Ok(attributes)
+    }
+
+    /// 解析调用的被调函数与实参列表: `@callee(%a, %b)`，并检查实参个数与类型
+    fn parse_call(
+        &mut self,
+        module: &ModuleRef,
+        scope: &mut FunctionScope,
+        location: SourceLocation,
+    ) -> ParseResult<(FunctionRef, Vec<OperandRef>)> {
+        let callee_location = self.current_location();
+        if self.peek_token_kind() != Some(&TokenKind::At) {
+            return Err(ParseError::new_syntax_error(
+                callee_location,
+                "期望被调函数 (例如: @foo)",
+            ));
+        }
+        let callee = match self.parse_operand(module, scope)?.borrow().get_global() {
+            Some(GlobalRef::Function(func)) => func,
+            _ => {
+                return Err(ParseError::new_semantic_error(
+                    callee_location,
+                    "call 的目标必须是函数",
+                ));
+            }
+        };
+
+        self.consume_expected_token(TokenKind::LParen, "期望 '(' 开始实参列表")?;
+        let mut args = Vec::new();
+        if self.peek_token_kind() != Some(&TokenKind::RParen) {
+            args.push(self.parse_operand(module, scope)?);
+            while self.peek_token_kind() == Some(&TokenKind::Comma) {
+                self.consume_expected_token(TokenKind::Comma, "期望 ',' 分隔实参")?;
+                args.push(self.parse_operand(module, scope)?);
+            }
+        }
+        self.consume_expected_token(TokenKind::RParen, "期望 ')' 闭合实参列表")?;
+
+        // 语义检查：实参个数与类型需与被调函数签名一致
+        let callee_name = callee.borrow().get_name().to_string();
+        let param_types = callee.borrow().get_param_types();
+        if args.len() != param_types.len() {
+            return Err(ParseError::new_semantic_error(
+                location,
+                &format!(
+                    "调用 '@{}' 的实参个数不匹配: 期望 {}，实际 {} (签名: {})",
+                    callee_name,
+                    param_types.len(),
+                    args.len(),
+                    callee.borrow().signature_string()
+                ),
+            ));
+        }
+        for (idx, (arg, param_type)) in args.iter().zip(&param_types).enumerate() {
+            let arg = arg.borrow();
+            // 尚未定义的前向引用没有确定的类型，跳过检查
+            if let Some(value) = arg.get_value()
+                && !scope.defined.contains(value.borrow().get_name())
+            {
+                continue;
+            }
+            let Some(arg_type) = arg.get_type() else {
+                continue;
+            };
+            if *arg_type.borrow() != *param_type.borrow() {
+                return Err(ParseError::new_semantic_error(
+                    location,
+                    &format!(
+                        "调用 '@{}' 的第 {} 个实参类型不匹配: 期望 {}，实际 {}",
+                        callee_name,
+                        idx,
+                        param_type.borrow(),
+                        arg_type.borrow()
+                    ),
+                ));
+            }
+        }
+
+        Ok((callee, args))
+    }
+
+    /// 解析操作码及其可选的修饰符后缀，例如 `add`、`add.v`、`mulh`
+    ///
+    /// 未知指令与登记过助记符的扩展指令同时返回该助记符，由指令保存以便打印。
+    fn parse_opcode(&mut self) -> ParseResult<(Opcode, Option<Rc<str>>, InstructionModifier)> {
+        let location = self.current_location();
+        // 操作码既可能是关键字 token，也可能是未登记为关键字的标识符（如 Venus 扩展指令）
+        let mnemonic = match self.peek_token_kind() {
+            Some(TokenKind::Identifier(s)) => s.clone(),
+            // 宽松模式下的未登记操作码，词法分析器已记录警告
+            Some(TokenKind::Opcode(s)) => s.clone(),
+            Some(kind) => kind.to_string(),
+            None => {
+                return Err(ParseError::new_syntax_error(
+                    location,
+                    "意外的文件结束，期望操作码",
+                ));
+            }
+        };
+
+        let (name, mut suffix) = match mnemonic.split_once('.') {
+            Some((name, suffix)) => (name, Some(suffix)),
+            None => (mnemonic.as_str(), None),
+        };
+        let mut custom_mnemonic = None;
+        let opcode = match (name, suffix) {
+            // `ext.<编号>[.修饰符]`：按原始编号给出的硬件扩展指令
+            ("ext", Some(rest)) => {
+                let (number, modifier) = match rest.split_once('.') {
+                    Some((number, modifier)) => (number, Some(modifier)),
+                    None => (rest, None),
+                };
+                suffix = modifier;
+                let number = number.parse::<u16>().map_err(|_| {
+                    ParseError::new_syntax_error(
+                        location.clone(),
+                        &format!("无效的扩展指令编号: '{}' (应为 0-65535)", number),
+                    )
+                })?;
+                custom_mnemonic = self.lexer.extensions().name_of(number);
+                Opcode::Extension(number)
+            }
+            _ if matches!(self.peek_token_kind(), Some(TokenKind::Opcode(_))) => {
+                let interned = match self.unknown_mnemonics.get(name) {
+                    Some(interned) => interned.clone(),
+                    None => {
+                        let interned: Rc<str> = Rc::from(name);
+                        self.unknown_mnemonics.insert(interned.clone());
+                        interned
+                    }
+                };
+                custom_mnemonic = Some(interned);
+                Opcode::Unknown
+            }
+            _ => match Opcode::from_mnemonic(name) {
+                Some(opcode) => opcode,
+                None => {
+                    let number = self.lexer.extensions().number_of(name).ok_or_else(|| {
+                        ParseError::new_syntax_error(
+                            location.clone(),
+                            &format!("未知操作码: '{}'", mnemonic),
+                        )
+                    })?;
+                    custom_mnemonic = self.lexer.extensions().name_of(number);
+                    Opcode::Extension(number)
+                }
+            },
+        };
+        let modifier = match suffix {
+            None => InstructionModifier::None,
+            Some("v") => InstructionModifier::Vector,
+            Some("s") => InstructionModifier::Scalar,
+            Some("p") => InstructionModifier::Predicate,
+            Some(other) => {
+                return Err(ParseError::new_syntax_error(
+                    location,
+                    &format!("未知指令修饰符: '.{}'", other),
+                ));
+            }
+        };
+
+        self.advance()?; // consume opcode
+        Ok((opcode, custom_mnemonic, modifier))
+    }
+
+    /// 当前 token 是否为操作数的起始
+    ///
+    /// `%name` 后跟 '=' 时是下一条指令的结果定义，而不是操作数。
+    fn at_operand_start(&mut self) -> ParseResult<bool> {
+        match self.peek_token_kind() {
+            Some(TokenKind::At) | Some(TokenKind::IntLiteral(_)) => Ok(true),
+            Some(TokenKind::LocalName(_)) => {
+                Ok(self.peek_next_token_kind()? != Some(&TokenKind::Equal))
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// 解析操作数: `%local`、`@global` 或整数立即数
+    fn parse_operand(
+        &mut self,
+        module: &ModuleRef,
+        scope: &mut FunctionScope,
+    ) -> ParseResult<OperandRef> {
+        match self.peek_token_kind().cloned() {
+            Some(TokenKind::At) => {
+                self.consume_expected_token(TokenKind::At, "期望 '@'")?;
+                let (name, location) = self.expect_identifier("期望全局符号名称")?;
+                let module = module.borrow();
+                let current = scope
+                    .function
+                    .clone()
+                    .filter(|f| f.borrow().get_name() == name);
+                let global = if let Some(mem) = module.get_global_memory_space(&name) {
+                    GlobalRef::Memory(mem)
+                } else if let Some(func) = module.get_function(&name).or(current) {
+                    GlobalRef::Function(func)
+                } else {
+                    return Err(ParseError::new_semantic_error(
+                        location,
+                        &format!("未定义的全局符号: '@{}'", name),
+                    ));
+                };
+                Ok(Operand::create_global(global))
+            }
+            Some(TokenKind::IntLiteral(_)) => {
+                let (value, _) = self.expect_int_literal("期望整数常量")?;
+                Ok(Operand::create_immediate(
+                    value,
+                    Type::get_int_type(TypeKind::Int32),
+                ))
+            }
+            Some(TokenKind::LocalName(_)) => {
+                let (name, location) = self.expect_local_name("期望操作数")?;
+                Ok(Operand::create_value(scope.use_value(name, location)))
+            }
+            _ => Err(ParseError::new_syntax_error(
+                self.current_location(),
+                "期望操作数 (%name、@name 或整数常量)",
+            )),
+        }
+    }
+}
+
+/// 根据操作码与操作数推导指令结果类型
+///
+/// `load` 产生指针所指类型，比较产生与输入向量等长的谓词（标量比较为 `<pred 1>`），
+/// 归约产生向量元素类型，其余指令沿用第一个操作数的类型。
+fn infer_result_type(opcode: Opcode, operands: &[ValueRef]) -> TypeRef {
+    let Some(first) = operands.first().map(|op| op.borrow().get_type()) else {
+        return Type::get_int_type(TypeKind::Int32);
+    };
+    let kind = first.borrow().get_kind().clone();
+    match (opcode, kind) {
+        (Opcode::Load, TypeKind::Pointer(pointee, _)) => pointee,
+        (op, TypeKind::Vector(_, length)) if op.is_comparison() => Type::get_predicate_type(length),
+        (op, _) if op.is_comparison() => Type::get_predicate_type(1),
+        (Opcode::RedSum | Opcode::RedMax | Opcode::RedMin, TypeKind::Vector(elem, _)) => elem,
+        (Opcode::Extract, TypeKind::Tuple(elems)) => operands
+            .get(1)
+            .and_then(|index| index.borrow().as_i64())
+            .and_then(|index| usize::try_from(index).ok())
+            .and_then(|index| elems.get(index).cloned())
+            .unwrap_or(first),
+        _ => first,
+    }
+}
+
+/// 立即数操作数的期望类型：与指令中第一个非立即数的操作数相同，store 的数据取指针指向的类型
+///
+/// 移位量、类型转换、调用实参等不要求与其它操作数同类型的位置返回 None。
+fn immediate_expected_type(opcode: Opcode, operands: &[OperandRef]) -> Option<TypeRef> {
+    match opcode {
+        Opcode::Store => {
+            let pointer = operands.get(1)?.borrow().get_type()?;
+            let pointer = pointer.borrow();
+            match pointer.get_kind() {
+                TypeKind::Pointer(pointee, _) => Some(pointee.clone()),
+                _ => None,
+            }
+        }
+        Opcode::Sra
+        | Opcode::Srl
+        | Opcode::Sll
+        | Opcode::Load
+        | Opcode::Range
+        | Opcode::Broadcast
+        | Opcode::Shuffle
+        | Opcode::Phi
+        | Opcode::Extract
+        | Opcode::Unknown
+        | Opcode::Extension(_) => None,
+        op if op.is_cast() => None,
+        _ => operands
+            .iter()
+            .find(|op| !op.borrow().is_immediate())
+            .and_then(|op| op.borrow().get_type()),
+    }
+}
+
+/// 检查立即数是否在期望类型的表示范围内，`immediates` 为 (操作数下标, 源代码位置)
+fn check_immediate_ranges(
+    opcode: Opcode,
+    operands: &[OperandRef],
+    immediates: Vec<(usize, SourceLocation)>,
+) -> ParseResult<()> {
+    if immediates.is_empty() {
+        return Ok(());
+    }
+    let Some(expected) = immediate_expected_type(opcode, operands) else {
+        return Ok(());
+    };
+    let Some((min, max)) = expected.borrow().integer_range() else {
+        return Ok(());
+    };
+    for (index, location) in immediates {
+        let Some(value) = operands[index].borrow().get_immediate() else {
+            continue;
+        };
+        if value < min || value > max {
+            return Err(ParseError::new_semantic_error(
+                location,
+                &format!(
+                    "整数常量 {} 超出 {} 的表示范围 [{}, {}]",
+                    value,
+                    expected.borrow(),
+                    min,
+                    max
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 将导入路径解析为相对于导入者所在目录的路径
+///
+/// 路径中的 `/` 与 `\` 都按分隔符处理，使同一份源文件可在 Windows 与 Unix 上使用；
+/// 绝对路径保持不变。
+fn resolve_import_path(importer: &str, relative: &str) -> PathBuf {
+    let relative_path = Path::new(relative);
+    if relative_path.is_absolute() {
+        return relative_path.to_path_buf();
     }
+    let mut path = Path::new(importer)
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    for part in relative.split(['/', '\\']).filter(|part| !part.is_empty()) {
+        path.push(part);
+    }
+    path
 }
 
 /// 解析内存空间标识符到 MemorySpace 枚举
@@ -493,7 +1567,7 @@ fn parse_memory_space_from_ident(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::frontend::Lexer;
+    use crate::frontend::{Lexer, LexerOptions};
 
     #[test]
     fn test_parse_simple_module() {
@@ -539,6 +1613,46 @@ mod tests {
         assert_eq!(mem2.borrow().get_length(), 512);
     }
 
+    #[test]
+    fn test_parse_memory_alignment() {
+        let source = r#".module m
+.memory vbuf [vspm] <i16 x 64>
+.memory sbuf [sram] i32 10
+.memory aligned [sram] i8 100 .align 64
+.memory big [sram] i32 200000000
+            "#;
+        let mut parser = Parser::new(Lexer::new(source, "test.vil"));
+        let module = parser.parse_module().expect("应成功解析模块");
+        let module = module.borrow();
+
+        // 缺省对齐取元素宽度
+        let vbuf = module.get_global_memory_space("vbuf").unwrap();
+        assert_eq!(vbuf.borrow().get_alignment(), 2);
+        assert_eq!(vbuf.borrow().byte_size(), 128);
+        let sbuf = module.get_global_memory_space("sbuf").unwrap();
+        assert_eq!(sbuf.borrow().get_alignment(), 4);
+        assert_eq!(sbuf.borrow().byte_size(), 40);
+
+        let aligned = module.get_global_memory_space("aligned").unwrap();
+        assert_eq!(aligned.borrow().get_alignment(), 64);
+        assert_eq!(aligned.borrow().byte_size(), 100);
+        let big = module.get_global_memory_space("big").unwrap();
+        assert_eq!(big.borrow().byte_size(), 800_000_000);
+        assert_eq!(
+            aligned.borrow().to_string(),
+            ".memory aligned [sram] i8 100 .align 64"
+        );
+        assert_eq!(sbuf.borrow().to_string(), ".memory sbuf [sram] i32 10");
+    }
+
+    #[test]
+    fn test_parse_invalid_memory_alignment() {
+        let source = ".module m\n.memory buf [sram] i32 4 .align 6\n";
+        let mut parser = Parser::new(Lexer::new(source, "test.vil"));
+        let err = parser.parse_module().unwrap_err();
+        assert!(err.to_string().contains("对齐必须是 2 的正整数次幂"));
+    }
+
     #[test]
     fn test_parse_module_with_function() {
         let source = r#".module my_module
@@ -569,5 +1683,733 @@ mod tests {
         let arg3 = func_borrowed.get_arguments()[2].borrow();
         assert_eq!(arg3.get_name(), "%out");
         assert_eq!(arg3.get_type().borrow().to_string(), "i32* sram");
+
+        // .result 参数被单独分类
+        assert!(arg3.is_result());
+        assert!(!arg1.is_result() && !arg2.is_result());
+        assert_eq!(func_borrowed.inputs().len(), 2);
+        let results = func_borrowed.results();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].borrow().get_name(), "%out");
+
+        // 参数索引按声明顺序分配
+        let indices: Vec<usize> = func_borrowed
+            .get_arguments()
+            .iter()
+            .map(|arg| arg.borrow().get_arg_idx())
+            .collect();
+        assert_eq!(indices, [0, 1, 2]);
+        assert!(Rc::ptr_eq(&arg1.get_parent().unwrap(), &func));
+    }
+
+    #[test]
+    fn test_parse_two_result_function() {
+        let source = r#".module m
+.function divmod(.param %a i32, .param %b i32, .result %q i32, .result %r i32) {
+entry:
+    %x = div %a, %b
+    %y = rem %a, %b
+    ret %x, %y
+}
+"#;
+        let module =
+            crate::frontend::parse_vil(source, "divmod.vil").expect("应成功解析多返回值函数");
+        let func = module.borrow().get_function("divmod").unwrap();
+        let func = func.borrow();
+        assert_eq!(func.get_arguments().len(), 4);
+        let results: Vec<String> = func
+            .results()
+            .iter()
+            .map(|r| r.borrow().get_name().to_string())
+            .collect();
+        assert_eq!(results, ["%q", "%r"]);
+        assert!(!func.get_arguments()[0].borrow().is_result());
+
+        let entry = func.get_entry_block().unwrap();
+        let ret = entry.borrow().get_terminator().unwrap();
+        assert_eq!(ret.borrow().get_opcode(), Opcode::Ret);
+        assert_eq!(ret.borrow().get_operand_count(), 2);
+        assert!(func.to_string().contains(".result %q"));
+        crate::frontend::roundtrip_ok(source).unwrap();
+    }
+
+    #[test]
+    fn test_parse_declaration() {
+        let source = r#".module m
+.declare @memcpy(.param %dst i32* generic, .param %src i32* generic, .param %n i32)
+.declare @rand() -> i32
+.function f(.param %a i32* generic, .param %b i32* generic) -> i32 {
+    call @memcpy(%a, %b, 4)
+    %r = call @rand()
+    ret %r
+}
+"#;
+        let module = crate::frontend::parse_vil(source, "declare.vil").expect("应成功解析函数声明");
+        let memcpy = module.borrow().get_function("memcpy").unwrap();
+        let memcpy = memcpy.borrow();
+        assert!(memcpy.is_declaration());
+        assert!(memcpy.get_basic_blocks().is_empty());
+        assert_eq!(memcpy.get_arguments().len(), 3);
+        let rand = module.borrow().get_function("rand").unwrap();
+        assert_eq!(rand.borrow().get_return_type().borrow().to_string(), "i32");
+        assert!(
+            !module
+                .borrow()
+                .get_function("f")
+                .unwrap()
+                .borrow()
+                .is_declaration()
+        );
+
+        let printed = crate::ir::print_module(&module.borrow(), &Default::default());
+        assert!(printed.contains(".declare @rand() -> i32\n"), "{}", printed);
+        crate::frontend::roundtrip_ok(source).unwrap();
+    }
+
+    #[test]
+    fn test_parse_shuffle_mask() {
+        let source = r#".module m
+.function f(.param %v <i16 x 4>, .param %idx <i32 x 4>) {
+    %s = shuffle %v, <3, 2, 1, 0, 0, 1>
+    %c = shuffle_clbmv %v, <1, 1>
+    %d = shuffle %v, %idx
+    ret
+}
+"#;
+        let module = crate::frontend::parse_vil(source, "shuffle.vil").expect("应成功解析洗牌指令");
+        let func = module.borrow().get_function("f").unwrap();
+        let instrs: Vec<_> = func.borrow().instructions().collect();
+
+        let shuffle = instrs[0].borrow();
+        assert_eq!(shuffle.get_opcode(), Opcode::Shuffle);
+        assert_eq!(shuffle.shuffle_indices(), Some(vec![3, 2, 1, 0, 0, 1]));
+        assert_eq!(shuffle.get_type().borrow().to_string(), "<i16 x 6>");
+        assert_eq!(shuffle.to_string(), "%s = shuffle %v, <3, 2, 1, 0, 0, 1>");
+
+        let clbmv = instrs[1].borrow();
+        assert_eq!(clbmv.get_opcode(), Opcode::ShuffleClbmv);
+        assert_eq!(clbmv.shuffle_indices(), Some(vec![1, 1]));
+        assert_eq!(clbmv.get_type().borrow().to_string(), "<i16 x 2>");
+
+        // 常量索引作为单个操作数保存在源向量之后
+        assert_eq!(shuffle.get_operand_count(), 2);
+        assert_eq!(clbmv.get_operand_count(), 2);
+
+        // 以向量值给出的索引没有常量形式
+        assert_eq!(instrs[2].borrow().shuffle_indices(), None);
+        assert_eq!(instrs[2].borrow().get_operand_count(), 2);
+        crate::frontend::roundtrip_ok(source).unwrap();
+    }
+
+    #[test]
+    fn test_parse_shuffle_scalar_operand() {
+        // 不带 `<...>` 的立即数仍是普通操作数，不会被当作单元素索引向量
+        let source = r#".module m
+.function f(.param %v <i32 x 4>) {
+    %s = shuffle %v, 2
+    ret
+}
+"#;
+        let module = crate::frontend::parse_vil(source, "shuffle.vil").expect("应成功解析洗牌指令");
+        let func = module.borrow().get_function("f").unwrap();
+        let instrs: Vec<_> = func.borrow().instructions().collect();
+
+        let shuffle = instrs[0].borrow();
+        assert_eq!(shuffle.shuffle_indices(), None);
+        assert_eq!(shuffle.get_operand_ref(1).borrow().get_immediate(), Some(2));
+        assert_eq!(shuffle.get_type().borrow().to_string(), "<i32 x 4>");
+        assert_eq!(shuffle.to_string(), "%s = shuffle %v, 2");
+        assert!(crate::analysis::verifier::Verifier::verify_module(&module.borrow()).is_empty());
+        crate::frontend::roundtrip_ok(source).unwrap();
+    }
+
+    #[test]
+    fn test_statement_terminators() {
+        let count = |source: &str| {
+            let module = crate::frontend::parse_vil(source, "terminators.vil").unwrap();
+            let func = module.borrow().get_function("f").unwrap();
+            func.borrow().instructions().count()
+        };
+        let newline = ".module m\n.function f(.param %a i32) -> i32 {\n    %x = add %a, 1\n    %y = mul %x,\n        2\n    ret %y\n}\n";
+        let semicolon = ".module m\n.function f(.param %a i32) -> i32 {\n    %x = add %a, 1;\n    %y = mul %x, 2; ;\n    ret %y;\n}\n";
+        let one_line = ".module m\n.function f(.param %a i32) -> i32 { %x = add %a, 1; %y = mul %x, 2; ret %y }\n";
+        assert_eq!(count(newline), 3);
+        assert_eq!(count(semicolon), 3);
+        assert_eq!(count(one_line), 3);
+
+        // 同一行上缺少 ';' 分隔的两条指令
+        let err = crate::frontend::parse_vil(
+            ".module m\n.function f(.param %a i32) -> i32 {\n    %x = add %a, 1 ret %x\n}\n",
+            "terminators.vil",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("需要用 ';' 分隔"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_linkage_keywords() {
+        let source = r#".module m
+.function plain() {
+    ret
+}
+internal .function hidden() {
+    ret
+}
+.declare @ext() -> i32
+external .function api() {
+    ret
+}
+weak .function hook() {
+    ret
+}
+"#;
+        let module = crate::frontend::parse_vil(source, "linkage.vil").expect("应成功解析链接属性");
+        let linkage = |name: &str| {
+            module
+                .borrow()
+                .get_function(name)
+                .unwrap()
+                .borrow()
+                .get_linkage()
+        };
+        assert_eq!(linkage("plain"), Linkage::Internal);
+        assert_eq!(linkage("hidden"), Linkage::Internal);
+        assert_eq!(linkage("api"), Linkage::External);
+        assert_eq!(linkage("hook"), Linkage::Weak);
+        crate::frontend::roundtrip_ok(source).unwrap();
+
+        let err =
+            crate::frontend::parse_vil(".module m\nweak .memory buf [sram] i32 4\n", "linkage.vil")
+                .unwrap_err();
+        assert!(
+            err.to_string().contains("链接属性之后期望 '.function'"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_parse_type_alias() {
+        let source = r#".module my_module
+.type %Elem = %Lane
+.type %Lane = i16
+.type %Vec4 = <i16 x 4>
+.memory buf [vspm] %Vec4
+.function f(.param %v %Vec4, .param %e %Elem) {
+}
+            "#;
+        let lexer = Lexer::new(source, "test.vil");
+        let mut parser = Parser::new(lexer);
+        let module = parser.parse_module().expect("应成功解析模块");
+
+        let module = module.borrow();
+        let vec4 = module.get_type_alias("%Vec4").unwrap();
+        assert_eq!(vec4.borrow().to_string(), "<i16 x 4>");
+        assert_eq!(
+            module.get_type_alias("%Elem").unwrap().borrow().to_string(),
+            "i16"
+        );
+
+        let mem = module.get_global_memory_space("buf").unwrap();
+        assert_eq!(
+            mem.borrow().get_element_type().borrow().to_string(),
+            "<i16 x 4>"
+        );
+        assert_eq!(mem.borrow().get_length(), 4);
+
+        let func = module.get_function("f").unwrap();
+        let func = func.borrow();
+        assert_eq!(
+            func.get_arguments()[1]
+                .borrow()
+                .get_type()
+                .borrow()
+                .to_string(),
+            "i16"
+        );
+    }
+
+    #[test]
+    fn test_parse_undefined_type_alias() {
+        let source = ".module m\n.memory buf [vspm] %Missing\n";
+        let mut parser = Parser::new(Lexer::new(source, "test.vil"));
+        let err = parser.parse_module().unwrap_err();
+        assert!(err.to_string().contains("未定义的类型别名"));
+
+        let source = ".module m\n.type %A = %Missing\n";
+        let mut parser = Parser::new(Lexer::new(source, "test.vil"));
+        let err = parser.parse_module().unwrap_err();
+        assert!(err.to_string().contains("未定义的类型别名"));
+    }
+
+    #[test]
+    fn test_parse_cyclic_type_alias() {
+        let source = ".module m\n.type %A = %B\n.type %B = %A\n";
+        let mut parser = Parser::new(Lexer::new(source, "test.vil"));
+        let err = parser.parse_module().unwrap_err();
+        assert!(err.to_string().contains("类型别名存在循环引用"));
+    }
+
+    #[test]
+    fn test_parse_mask_operand() {
+        let source = r#".module m
+.function f(.param %a <i32 x 4>, .param %b <i32 x 4>, .param %m <pred 4>) {
+entry:
+    %r = add.p %a, %b, %m
+    %c = cmplt.p %a, %b
+    ret
+}
+"#;
+        let lexer = Lexer::new(source, "test.vil");
+        let mut parser = Parser::new(lexer);
+        let module = parser.parse_module().expect("应成功解析模块");
+        let func = module.borrow().get_function("f").unwrap();
+        let instrs: Vec<_> = func.borrow().instructions().collect();
+
+        let add = instrs[0].borrow();
+        assert!(add.is_masked());
+        assert_eq!(add.get_mask().unwrap().borrow().get_name(), "%m");
+        assert_eq!(add.get_data_operands().len(), 2);
+        assert_eq!(add.get_type().borrow().to_string(), "<i32 x 4>");
+
+        // 比较的 `.p` 表示结果为谓词，不带掩码
+        let cmp = instrs[1].borrow();
+        assert!(!cmp.is_masked());
+        assert!(cmp.get_mask().is_none());
+    }
+
+    #[test]
+    fn test_parse_memory_space_of_instructions() {
+        let source = r#".module my_module
+.memory vbuf [vspm] <i16 x 8>
+.function f() {
+entry:
+    %v = load @vbuf
+    %p = alloc i32 [sram]
+    store 1, %p
+    %w = add %v, %v
+    ret
+}
+            "#;
+        let lexer = Lexer::new(source, "test.vil");
+        let mut parser = Parser::new(lexer);
+        let module = parser.parse_module().expect("应成功解析模块");
+
+        let func = module.borrow().get_function("f").unwrap();
+        let spaces: Vec<_> = func
+            .borrow()
+            .instructions()
+            .map(|instr| instr.borrow().memory_space())
+            .collect();
+        assert_eq!(
+            spaces,
+            vec![
+                Some(crate::ir::MemorySpace::VSPM),
+                Some(crate::ir::MemorySpace::SRAM),
+                Some(crate::ir::MemorySpace::SRAM),
+                None,
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_global_reference() {
+        let source = r#".module my_module
+.memory vspm_buffer [vspm] <i16 x 8>
+.function f() {
+entry:
+    %v = load @vspm_buffer
+    ret
+}
+            "#;
+        let lexer = Lexer::new(source, "test.vil");
+        let mut parser = Parser::new(lexer);
+        let module = parser.parse_module().expect("应成功解析模块");
+
+        let func = module.borrow().get_function("f").unwrap();
+        let func = func.borrow();
+        let entry = func.get_entry_block().unwrap();
+        let entry = entry.borrow();
+        assert_eq!(entry.get_name(), "entry");
+        assert_eq!(entry.get_instructions().len(), 2);
+
+        let load = entry.get_instructions()[0].borrow();
+        assert_eq!(load.get_opcode(), Opcode::Load);
+        let address = load.get_operand(0);
+        assert_eq!(address.borrow().get_name(), "@vspm_buffer");
+        assert_eq!(
+            address.borrow().get_type().borrow().to_string(),
+            "<i16 x 8>* vspm"
+        );
+        assert_eq!(load.get_type().borrow().to_string(), "<i16 x 8>");
+        assert_eq!(
+            entry.get_instructions()[1].borrow().get_opcode(),
+            Opcode::Ret
+        );
+    }
+
+    #[test]
+    fn test_parse_undefined_global_reference() {
+        let source = ".module m\n.function f() {\n    %v = load @missing\n}\n";
+        let mut parser = Parser::new(Lexer::new(source, "test.vil"));
+        let err = parser.parse_module().unwrap_err();
+        assert!(err.to_string().contains("未定义的全局符号: '@missing'"));
+    }
+
+    #[test]
+    fn test_parse_branch_targets() {
+        let source = r#".module m
+.function f(.param %c <pred 1>) {
+entry:
+    condbr %c, %then, %done
+then:
+    br %done
+done:
+    ret
+}
+            "#;
+        let mut parser = Parser::new(Lexer::new(source, "test.vil"));
+        let module = parser.parse_module().expect("应成功解析模块");
+        let func = module.borrow().get_function("f").unwrap();
+        let func = func.borrow();
+        let blocks = func.get_basic_blocks();
+
+        // 前向引用的跳转目标解析为函数中的同一个基本块
+        let condbr = blocks[0].borrow().get_instructions()[0].clone();
+        let condbr = condbr.borrow();
+        assert_eq!(condbr.get_opcode(), Opcode::CondBr);
+        assert!(condbr.get_operand_ref(0).borrow().is_value());
+        let then = condbr
+            .get_operand_ref(1)
+            .borrow()
+            .get_basic_block()
+            .unwrap();
+        assert!(Rc::ptr_eq(&then, &blocks[1]));
+        let done = condbr
+            .get_operand_ref(2)
+            .borrow()
+            .get_basic_block()
+            .unwrap();
+        assert!(Rc::ptr_eq(&done, &blocks[2]));
+
+        let br = blocks[1].borrow().get_instructions()[0].clone();
+        let target = br.borrow().get_operand_ref(0).borrow().get_basic_block();
+        assert!(Rc::ptr_eq(&target.unwrap(), &blocks[2]));
+        assert_eq!(br.borrow().get_operand(0).borrow().get_name(), "%done");
+    }
+
+    #[test]
+    fn test_parse_branch_to_undefined_label() {
+        let source = ".module m\n.function f() {\nentry:\n    br %missing\n}\n";
+        let mut parser = Parser::new(Lexer::new(source, "test.vil"));
+        let err = parser.parse_module().unwrap_err();
+        assert!(err.to_string().contains("跳转到未定义的基本块: '%missing'"));
+    }
+
+    #[test]
+    fn test_parse_instruction_attributes() {
+        let source = r#".module m
+.function f(.param %v i32) {
+    %p = alloc i32 [sram]
+    %x = load %p volatile
+    store %v, %p align 16 volatile
+    ret
+}
+            "#;
+        let mut parser = Parser::new(Lexer::new(source, "test.vil"));
+        let module = parser.parse_module().expect("应成功解析模块");
+
+        let func = module.borrow().get_function("f").unwrap();
+        let func = func.borrow();
+        let entry = func.get_entry_block().unwrap();
+        let entry = entry.borrow();
+        let load = entry.get_instructions()[1].borrow();
+        assert!(load.has_attribute("volatile"));
+        let store = entry.get_instructions()[2].borrow();
+        assert_eq!(store.get_operand_count(), 2);
+        assert_eq!(store.get_attributes(), ["align 16", "volatile"]);
+    }
+
+    #[test]
+    fn test_parse_invalid_alignment() {
+        let source = r#".module m
+.function f(.param %v i32) {
+    %p = alloc i32 [sram]
+    store %v, %p align 3
+    ret
+}
+            "#;
+        let mut parser = Parser::new(Lexer::new(source, "test.vil"));
+        let err = parser.parse_module().expect_err("非 2 的幂对齐应报错");
+        assert!(err.to_string().contains("对齐必须是 2 的正整数次幂"));
+    }
+
+    #[test]
+    fn test_parse_call() {
+        let source = r#".module m
+.function add2(.param %x i32, .param %y i32) {
+    %s = add %x, %y
+    ret
+}
+.function main(.param %a i32, .param %b i32) {
+    %r = call @add2(%a, %b)
+    call @main(%a, %b)
+    ret
+}
+            "#;
+        let mut parser = Parser::new(Lexer::new(source, "test.vil"));
+        let module = parser.parse_module().expect("应成功解析模块");
+
+        let main = module.borrow().get_function("main").unwrap();
+        let main = main.borrow();
+        let entry = main.get_entry_block().unwrap();
+        let entry = entry.borrow();
+        let call = entry.get_instructions()[0].borrow();
+        assert_eq!(call.get_opcode(), Opcode::Call);
+        assert_eq!(call.get_operand_count(), 3);
+        assert_eq!(call.get_operand(0).borrow().get_name(), "@add2");
+        assert_eq!(call.get_operand(1).borrow().get_name(), "%a");
+        assert_eq!(call.get_operand(2).borrow().get_name(), "%b");
+        assert_eq!(call.get_type().borrow().to_string(), "void");
+
+        // 递归调用引用自身
+        let recursive = entry.get_instructions()[1].borrow();
+        assert!(!recursive.has_result());
+        assert_eq!(recursive.get_operand(0).borrow().get_name(), "@main");
+    }
+
+    #[test]
+    fn test_parse_operand_kinds() {
+        let source = r#".module m
+.function f(.param %x i32) {
+    %y = add %x, 7
+    call @f(%y)
+    ret
+}
+            "#;
+        let mut parser = Parser::new(Lexer::new(source, "test.vil"));
+        let module = parser.parse_module().expect("应成功解析模块");
+        let func = module.borrow().get_function("f").unwrap();
+        let entry = func.borrow().get_entry_block().unwrap();
+        let entry = entry.borrow();
+
+        // 立即数与全局符号作为一等操作数保存，值访问接口仍可用
+        let add = entry.get_instructions()[0].borrow();
+        assert!(add.get_operand_ref(0).borrow().is_value());
+        assert_eq!(add.get_operand_ref(1).borrow().get_immediate(), Some(7));
+        assert_eq!(add.get_operand(1).borrow().as_i64(), Some(7));
+
+        let call = entry.get_instructions()[1].borrow();
+        assert!(call.get_operand_ref(0).borrow().is_global());
+        assert_eq!(call.get_operand(0).borrow().get_name(), "@f");
+        assert!(call.get_operand_refs()[1].borrow().is_value());
+    }
+
+    #[test]
+    fn test_parse_return_type() {
+        let source = r#".module m
+.function splat(.param %x i32) -> <i32 x 4> {
+    %v = broadcast %x
+    ret %v
+}
+.function main(.param %a i32) {
+    %r = call @splat(%a)
+    ret
+}
+            "#;
+        let mut parser = Parser::new(Lexer::new(source, "test.vil"));
+        let module = parser.parse_module().expect("应成功解析模块");
+
+        let splat = module.borrow().get_function("splat").unwrap();
+        assert_eq!(splat.borrow().signature_string(), "splat(i32) -> <i32 x 4>");
+        let main = module.borrow().get_function("main").unwrap();
+        assert_eq!(main.borrow().signature_string(), "main(i32) -> void");
+
+        // 调用结果类型取自被调函数的返回类型
+        let main = main.borrow();
+        let entry = main.get_entry_block().unwrap();
+        let call = entry.borrow().get_instructions()[0].clone();
+        assert_eq!(call.borrow().get_type().borrow().to_string(), "<i32 x 4>");
+    }
+
+    #[test]
+    fn test_parse_call_signature_mismatch() {
+        let header = ".module m\n.function f(.param %x i32) {\n    ret\n}\n";
+
+        let source = format!(
+            "{}.function g(.param %v <i32 x 4>) {{\n    call @f(%v)\n}}\n",
+            header
+        );
+        let mut parser = Parser::new(Lexer::new(&source, "test.vil"));
+        let err = parser.parse_module().unwrap_err();
+        assert!(err.to_string().contains("第 0 个实参类型不匹配"), "{}", err);
+
+        let source = format!(
+            "{}.function g(.param %a i32) {{\n    call @f(%a, %a)\n}}\n",
+            header
+        );
+        let mut parser = Parser::new(Lexer::new(&source, "test.vil"));
+        let err = parser.parse_module().unwrap_err();
+        assert!(err.to_string().contains("实参个数不匹配"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_bitcast() {
+        let source =
+            ".module m\n.function f(.param %x i32) {\n    %b = bitcast %x : b32\n    ret\n}\n";
+        let mut parser = Parser::new(Lexer::new(source, "test.vil"));
+        let module = parser.parse_module().unwrap();
+        let func = module.borrow().get_function("f").unwrap();
+        let bitcast = func.borrow().get_basic_blocks()[0]
+            .borrow()
+            .get_instructions()[0]
+            .clone();
+        assert_eq!(bitcast.borrow().get_opcode(), Opcode::Bitcast);
+        assert_eq!(bitcast.borrow().get_type().borrow().to_string(), "b32");
+
+        let source = ".module m\n.function f(.param %x i32) {\n    %b = bitcast %x\n    ret\n}\n";
+        let mut parser = Parser::new(Lexer::new(source, "test.vil"));
+        let err = parser.parse_module().unwrap_err();
+        assert!(err.to_string().contains("目标类型"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_entry_function() {
+        // 入口函数可以在定义之前声明
+        let source = ".module m\n.entry @main\n.function main() {\n    ret\n}\n";
+        let mut parser = Parser::new(Lexer::new(source, "test.vil"));
+        let module = parser.parse_module().unwrap();
+        let entry = module.borrow().get_entry_function().unwrap();
+        assert_eq!(entry.borrow().get_name(), "main");
+
+        let source = ".module m\n.entry @missing\n";
+        let mut parser = Parser::new(Lexer::new(source, "test.vil"));
+        let err = parser.parse_module().unwrap_err();
+        assert!(err.to_string().contains("未定义的入口函数"), "{}", err);
+
+        let source = ".module m\n.entry @f\n.entry @f\n.function f() {\n    ret\n}\n";
+        let mut parser = Parser::new(Lexer::new(source, "test.vil"));
+        let err = parser.parse_module().unwrap_err();
+        assert!(err.to_string().contains("重复声明的入口函数"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_unknown_opcode_in_lenient_mode() {
+        let source = ".module m\n.function f(.param %x i32) -> i32 {\nentry:\n    %y = vfrobnicate.v %x, 3 volatile\n    ret %y\n}\n";
+        let options = LexerOptions {
+            lenient_opcodes: true,
+            ..Default::default()
+        };
+        let mut parser = Parser::new(Lexer::new_with_options(source, "test.vil", options));
+        let mut diagnostics = DiagnosticBag::new();
+        let module = parser
+            .parse_module_with_diagnostics(&mut diagnostics)
+            .expect("未知操作码在宽松模式下不应导致解析失败");
+
+        let warnings: Vec<_> = diagnostics.iter().collect();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].severity, crate::diagnostics::Severity::Warning);
+        assert!(
+            warnings[0].message.contains("vfrobnicate"),
+            "{}",
+            warnings[0]
+        );
+        assert_eq!(warnings[0].location.as_ref().unwrap().line, 4);
+
+        let func = module.borrow().get_function("f").unwrap();
+        let instr = func.borrow().get_basic_blocks()[0]
+            .borrow()
+            .get_instructions()[0]
+            .clone();
+        let instr = instr.borrow();
+        assert!(instr.get_opcode().is_unknown());
+        assert_eq!(instr.mnemonic(), "vfrobnicate");
+        assert_eq!(instr.get_modifier(), InstructionModifier::Vector);
+        assert_eq!(instr.get_operands().len(), 2);
+        assert!(instr.has_side_effects());
+
+        // 默认模式下仍然报告语法错误
+        let mut parser = Parser::new(Lexer::new(source, "test.vil"));
+        let err = parser.parse_module().unwrap_err();
+        assert!(err.to_string().contains("未知操作码"), "{}", err);
+    }
+
+    #[test]
+    fn test_immediate_range_for_i8_and_u8() {
+        let parse = |ty: &str, imm: i64| {
+            let source = format!(
+                ".module m\n.function f(.param %x {ty}) {{\n    %y = add %x, {imm}\n    ret\n}}\n"
+            );
+            Parser::new(Lexer::new(&source, "test.vil")).parse_module()
+        };
+        assert!(parse("i8", 127).is_ok());
+        assert!(parse("u8", 255).is_ok());
+        assert!(parse("u8", 0).is_ok());
+
+        let err = parse("i8", 128).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("整数常量 128 超出 i8 的表示范围 [-128, 127]"),
+            "{}",
+            err
+        );
+        assert_eq!(err.location().map(|l| (l.line, l.column)), Some((3, 18)));
+        let err = parse("u8", 256).unwrap_err();
+        assert!(
+            err.to_string().contains("超出 u8 的表示范围 [0, 255]"),
+            "{}",
+            err
+        );
+
+        // store 的数据按指针指向的类型检查；移位量不受约束
+        let source = ".module m\n.function f(.param %x u8) {\n    %p = alloc u8 [sram]\n    store 300, %p\n    ret\n}\n";
+        let err = Parser::new(Lexer::new(source, "test.vil"))
+            .parse_module()
+            .unwrap_err();
+        assert!(err.to_string().contains("超出 u8"), "{}", err);
+        let source = ".module m\n.function f(.param %x i8) {\n    %y = sll %x, 200\n    ret\n}\n";
+        assert!(
+            Parser::new(Lexer::new(source, "test.vil"))
+                .parse_module()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_parse_type_strings() {
+        let vector = crate::frontend::parse_type("<i32 x 4>").unwrap();
+        assert!(matches!(vector.borrow().get_kind(), TypeKind::Vector(_, 4)));
+        assert_eq!(vector.borrow().to_canonical_string(), "<i32 x 4>");
+
+        let pointer = crate::frontend::parse_type("<i32 x 4>* vspm").unwrap();
+        assert_eq!(
+            pointer.borrow().pointer_space(),
+            Some(crate::ir::MemorySpace::VSPM)
+        );
+        assert_eq!(pointer.borrow().to_canonical_string(), "<i32 x 4>* vspm");
+
+        let predicate = crate::frontend::parse_type("<pred 16>").unwrap();
+        assert!(predicate.borrow().is_predicate());
+        assert_eq!(predicate.borrow().to_canonical_string(), "<pred 16>");
+
+        // 规范文本可以重新解析为相同的类型
+        for text in ["u16", "b32* sram", "void", "<u8 x 64>* generic"] {
+            let parsed = crate::frontend::parse_type(text).unwrap();
+            assert_eq!(parsed.borrow().to_canonical_string(), text);
+            let reparsed =
+                crate::frontend::parse_type(&parsed.borrow().to_canonical_string()).unwrap();
+            assert_eq!(*reparsed.borrow(), *parsed.borrow());
+        }
+    }
+
+    #[test]
+    fn test_parse_type_errors() {
+        assert!(crate::frontend::parse_type("<i32 x>").is_err());
+        assert!(crate::frontend::parse_type("i64").is_err());
+        let err = crate::frontend::parse_type("i32 i32").unwrap_err();
+        assert!(err.to_string().contains("多余的内容"), "{}", err);
+        let err = crate::frontend::parse_type("%Vec4").unwrap_err();
+        assert!(err.to_string().contains("未定义的类型别名"), "{}", err);
     }
 }
@@ -23,6 +23,13 @@ pub enum TokenKind {
     RAngle,    // >
     Equal,     // =
     At,        // @
+    Arrow,     // ->
+
+    // 双字符运算符（比较）
+    EqualEqual,   // ==
+    NotEqual,     // !=
+    LessEqual,    // <=
+    GreaterEqual, // >=
 
     // 关键字
     Module,   // .module
@@ -31,6 +38,9 @@ pub enum TokenKind {
     Param,    // .param
     Entry,    // .entry
     Result,   // .result
+    Align,    // .align
+    Import,   // .import
+    Declare,  // .declare
 
     // 类型相关
     Type, // .type (用于表示类型声明)
@@ -73,6 +83,7 @@ pub enum TokenKind {
     Ret,       // ret
     Mov,       // mov
     Phi,       // phi
+    Call,      // call
 
     // 修饰符
     Vector,    // .v
@@ -87,8 +98,10 @@ pub enum TokenKind {
 
     // 标识符和字面量
     Identifier(String),    // 标识符
+    LocalName(String),     // `%` 局部名称（含 `%` 前缀），如 %x、%1、%x.y
     IntLiteral(i64),       // 整数字面量
     StringLiteral(String), // 字符串字面量
+    Opcode(String),        // 宽松模式下指令位置上的未登记操作码（含修饰符后缀）
 
     // 特殊标记
     EOF,     // 文件结束
@@ -112,6 +125,11 @@ impl fmt::Display for TokenKind {
             TokenKind::RAngle => write!(f, ">"),
             TokenKind::Equal => write!(f, "="),
             TokenKind::At => write!(f, "@"),
+            TokenKind::Arrow => write!(f, "->"),
+            TokenKind::EqualEqual => write!(f, "=="),
+            TokenKind::NotEqual => write!(f, "!="),
+            TokenKind::LessEqual => write!(f, "<="),
+            TokenKind::GreaterEqual => write!(f, ">="),
 
             TokenKind::Module => write!(f, ".module"),
             TokenKind::Function => write!(f, ".function"),
@@ -119,6 +137,9 @@ impl fmt::Display for TokenKind {
             TokenKind::Param => write!(f, ".param"),
             TokenKind::Entry => write!(f, ".entry"),
             TokenKind::Result => write!(f, ".result"),
+            TokenKind::Align => write!(f, ".align"),
+            TokenKind::Import => write!(f, ".import"),
+            TokenKind::Declare => write!(f, ".declare"),
 
             TokenKind::Type => write!(f, ".type"),
             TokenKind::Star => write!(f, "*"),
@@ -159,6 +180,7 @@ impl fmt::Display for TokenKind {
             TokenKind::Ret => write!(f, "ret"),
             TokenKind::Mov => write!(f, "mov"),
             TokenKind::Phi => write!(f, "phi"),
+            TokenKind::Call => write!(f, "call"),
 
             TokenKind::Vector => write!(f, ".v"),
             TokenKind::Scalar => write!(f, ".s"),
@@ -170,8 +192,10 @@ impl fmt::Display for TokenKind {
             TokenKind::Parameter => write!(f, "param"),
 
             TokenKind::Identifier(s) => write!(f, "{}", s),
+            TokenKind::LocalName(s) => write!(f, "{}", s),
             TokenKind::IntLiteral(n) => write!(f, "{}", n),
             TokenKind::StringLiteral(s) => write!(f, "\"{}\"", s),
+            TokenKind::Opcode(s) => write!(f, "{}", s),
 
             TokenKind::EOF => write!(f, "EOF"),
             TokenKind::Unknown => write!(f, "UNKNOWN"),
@@ -201,17 +225,43 @@ impl TokenKind {
 #[derive(Debug, Clone)]
 pub struct Token {
     pub kind: TokenKind,
+    /// 起始位置（第一个字符）
     pub location: SourceLocation,
+    /// 结束位置（最后一个字符之后），用于标出整个词法单元
+    pub end_location: SourceLocation,
 }
 
 impl Token {
+    /// 创建一个零长度的词法单元，结束位置与起始位置相同
     pub fn new(kind: TokenKind, location: SourceLocation) -> Self {
-        Token { kind, location }
+        let end_location = location.clone();
+        Token {
+            kind,
+            location,
+            end_location,
+        }
+    }
+
+    /// 创建覆盖 `[location, end_location)` 的词法单元
+    pub fn with_span(
+        kind: TokenKind,
+        location: SourceLocation,
+        end_location: SourceLocation,
+    ) -> Self {
+        Token {
+            kind,
+            location,
+            end_location,
+        }
     }
 }
 
 impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{} at {}", self.kind, self.location)
+        write!(
+            f,
+            "{} at {}-{}:{}",
+            self.kind, self.location, self.end_location.line, self.end_location.column
+        )
     }
 }
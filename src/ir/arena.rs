@@ -0,0 +1,452 @@
+// IR 的 arena 表示
+//
+// 值、指令与基本块分别存放在类型化 arena 中，节点之间以索引句柄相互引用，
+// 避免 Rc<RefCell<T>> 的逐节点分配与父指针循环。由 `arena` feature 启用，
+// 可与 Rc 表示（`Module`）双向转换。
+
+use crate::ir::MemorySpace;
+use crate::ir::basic_block::BasicBlock;
+use crate::ir::function::{Argument, Function, Linkage};
+use crate::ir::instruction::{Instruction, InstructionModifier, Opcode};
+use crate::ir::module::{GlobalMemorySpace, Module, ModuleRef};
+use crate::ir::operand::{GlobalRef, Operand, OperandKind};
+use crate::ir::types::{TypeKind, TypeRef};
+use crate::ir::value::{Value, ValueRef};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+use std::rc::Rc;
+
+/// 指向 `Arena<T>` 中元素的类型化索引句柄
+pub struct Id<T> {
+    index: u32,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Id<T> {
+    fn new(index: usize) -> Self {
+        Id {
+            index: u32::try_from(index).expect("arena 元素数量超出 u32 范围"),
+            _marker: PhantomData,
+        }
+    }
+
+    /// 句柄在 arena 中的下标
+    pub fn index(self) -> usize {
+        self.index as usize
+    }
+}
+
+impl<T> Clone for Id<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Id<T> {}
+
+impl<T> PartialEq for Id<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<T> Eq for Id<T> {}
+
+impl<T> Hash for Id<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for Id<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.index)
+    }
+}
+
+/// 只增不减的类型化 arena，元素通过 `Id<T>` 访问
+#[derive(Debug)]
+pub struct Arena<T> {
+    items: Vec<T>,
+}
+
+impl<T> Arena<T> {
+    pub fn new() -> Self {
+        Arena { items: Vec::new() }
+    }
+
+    /// 放入元素并返回其句柄
+    pub fn alloc(&mut self, item: T) -> Id<T> {
+        let id = Id::new(self.items.len());
+        self.items.push(item);
+        id
+    }
+
+    pub fn get(&self, id: Id<T>) -> Option<&T> {
+        self.items.get(id.index())
+    }
+
+    pub fn get_mut(&mut self, id: Id<T>) -> Option<&mut T> {
+        self.items.get_mut(id.index())
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// 按分配顺序遍历 (句柄, 元素)
+    pub fn iter(&self) -> impl Iterator<Item = (Id<T>, &T)> {
+        self.items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| (Id::new(index), item))
+    }
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<Id<T>> for Arena<T> {
+    type Output = T;
+
+    fn index(&self, id: Id<T>) -> &T {
+        &self.items[id.index()]
+    }
+}
+
+impl<T> IndexMut<Id<T>> for Arena<T> {
+    fn index_mut(&mut self, id: Id<T>) -> &mut T {
+        &mut self.items[id.index()]
+    }
+}
+
+pub type ValueId = Id<Value>;
+pub type InstructionId = Id<ArenaInstruction>;
+pub type BlockId = Id<ArenaBlock>;
+
+/// arena 表示中的操作数
+#[derive(Debug, Clone)]
+pub enum ArenaOperand {
+    Value(ValueId),          // 值操作数
+    Immediate(i64, TypeRef), // 立即数操作数
+    Block(BlockId),          // 基本块操作数
+    Global(String),          // 全局符号（不含 '@' 前缀），按名称解析
+    IndexVector(Vec<i64>),   // 常量索引向量
+}
+
+/// arena 表示中的指令
+#[derive(Debug, Clone)]
+pub struct ArenaInstruction {
+    pub opcode: Opcode,
+    pub modifier: InstructionModifier,
+    pub result: Option<ValueId>,
+    pub operands: Vec<ArenaOperand>,
+    pub attributes: Vec<String>,
+    pub memory_space: Option<MemorySpace>,
+    pub mnemonic: Option<Rc<str>>, // 源代码中的助记符，见 `Instruction::get_mnemonic`
+    pub parent: BlockId, // 所属基本块
+}
+
+/// arena 表示中的基本块
+#[derive(Debug, Clone)]
+pub struct ArenaBlock {
+    pub name: String,
+    pub instructions: Vec<InstructionId>,
+}
+
+/// arena 表示中的函数
+#[derive(Debug, Clone)]
+pub struct ArenaFunction {
+    pub name: String,
+    pub function_type: TypeRef,
+    pub arguments: Vec<ValueId>,
+    /// 结果参数（`arguments` 的子集）
+    pub results: Vec<ValueId>,
+    pub blocks: Vec<BlockId>,
+    pub attributes: Vec<String>,
+    /// 是否为外部函数声明
+    pub declaration: bool,
+    pub linkage: Linkage,
+}
+
+/// arena 表示的模块
+///
+/// 所有函数的值、指令与基本块共用模块级 arena；被多处引用的同一个 `ValueRef`
+/// 在 arena 中只占一个槽位，转换回 Rc 表示时恢复共享关系。
+#[derive(Debug, Default)]
+pub struct ArenaModule {
+    pub name: String,
+    pub entry_function: Option<String>,
+    pub type_aliases: Vec<(String, TypeRef)>,
+    pub memories: Vec<GlobalMemorySpace>,
+    pub functions: Vec<ArenaFunction>,
+    pub values: Arena<Value>,
+    pub instructions: Arena<ArenaInstruction>,
+    pub blocks: Arena<ArenaBlock>,
+}
+
+impl ArenaModule {
+    /// 从 Rc 表示构建 arena 表示
+    pub fn from_module(module: &Module) -> Self {
+        let mut arena = ArenaModule {
+            name: module.get_name().to_string(),
+            entry_function: module
+                .get_entry_function()
+                .map(|func| func.borrow().get_name().to_string()),
+            type_aliases: module.get_type_aliases(),
+            memories: module
+                .get_global_memory_spaces()
+                .iter()
+                .map(|mem| mem.borrow().clone())
+                .collect(),
+            ..Default::default()
+        };
+        let mut value_ids: HashMap<*const RefCell<Value>, ValueId> = HashMap::new();
+
+        for func in module.get_functions() {
+            let func = func.borrow();
+            let arguments: Vec<ValueId> = func
+                .get_arguments()
+                .iter()
+                .map(|arg| {
+                    let arg = arg.borrow();
+                    arena
+                        .values
+                        .alloc(Value::new(arg.get_type(), arg.get_name().to_string()))
+                })
+                .collect();
+            let results = func
+                .get_arguments()
+                .iter()
+                .zip(&arguments)
+                .filter(|(arg, _)| arg.borrow().is_result())
+                .map(|(_, &id)| id)
+                .collect();
+
+            // 先为所有基本块分配句柄，以便解析前向跳转
+            let mut block_ids: HashMap<*const RefCell<BasicBlock>, BlockId> = HashMap::new();
+            let blocks: Vec<BlockId> = func
+                .get_basic_blocks()
+                .iter()
+                .map(|bb| {
+                    let id = arena.blocks.alloc(ArenaBlock {
+                        name: bb.borrow().get_name().to_string(),
+                        instructions: Vec::new(),
+                    });
+                    block_ids.insert(Rc::as_ptr(bb), id);
+                    id
+                })
+                .collect();
+
+            for (bb, &block_id) in func.get_basic_blocks().iter().zip(&blocks) {
+                for instr in bb.borrow().get_instructions() {
+                    let instr = instr.borrow();
+                    let result = instr
+                        .get_result()
+                        .map(|value| arena.intern_value(&mut value_ids, &value));
+                    let operands = instr
+                        .get_operand_refs()
+                        .iter()
+                        .map(|operand| match operand.borrow().get_kind() {
+                            OperandKind::Value(value) => {
+                                ArenaOperand::Value(arena.intern_value(&mut value_ids, value))
+                            }
+                            OperandKind::Immediate(imm, ty) => {
+                                ArenaOperand::Immediate(*imm, ty.clone())
+                            }
+                            OperandKind::BasicBlock(target) => {
+                                match block_ids.get(&target.as_ptr()) {
+                                    Some(&id) => ArenaOperand::Block(id),
+                                    // 指向函数外的基本块：建立一个仅有名称的占位块
+                                    None => ArenaOperand::Block(arena.blocks.alloc(ArenaBlock {
+                                        name: operand.borrow().get_block_name().unwrap_or_default(),
+                                        instructions: Vec::new(),
+                                    })),
+                                }
+                            }
+                            OperandKind::Global(global) => ArenaOperand::Global(global.get_name()),
+                            OperandKind::IndexVector(indices) => {
+                                ArenaOperand::IndexVector(indices.clone())
+                            }
+                        })
+                        .collect();
+                    let id = arena.instructions.alloc(ArenaInstruction {
+                        opcode: instr.get_opcode(),
+                        modifier: instr.get_modifier(),
+                        result,
+                        operands,
+                        attributes: instr.get_attributes().to_vec(),
+                        memory_space: instr.memory_space(),
+                        mnemonic: instr.get_mnemonic(),
+                        parent: block_id,
+                    });
+                    arena.blocks[block_id].instructions.push(id);
+                }
+            }
+
+            arena.functions.push(ArenaFunction {
+                name: func.get_name().to_string(),
+                function_type: func.get_type(),
+                arguments,
+                results,
+                blocks,
+                attributes: func.get_attributes().to_vec(),
+                declaration: func.is_declaration(),
+                linkage: func.get_linkage(),
+            });
+        }
+        arena
+    }
+
+    /// 为 `ValueRef` 分配句柄，同一个 Rc 只分配一次
+    fn intern_value(
+        &mut self,
+        ids: &mut HashMap<*const RefCell<Value>, ValueId>,
+        value: &ValueRef,
+    ) -> ValueId {
+        *ids.entry(Rc::as_ptr(value))
+            .or_insert_with(|| self.values.alloc(value.borrow().clone()))
+    }
+
+    /// 转换回 Rc 表示
+    ///
+    /// 先创建全部函数与全局内存，再构建函数体，使全局符号可按名称解析。
+    pub fn to_module(&self) -> ModuleRef {
+        let module = Rc::new(RefCell::new(Module::new(self.name.clone())));
+        for (name, ty) in &self.type_aliases {
+            module.borrow_mut().add_type_alias(name.clone(), ty.clone());
+        }
+        for mem in &self.memories {
+            module
+                .borrow_mut()
+                .add_global_memory_space(Rc::new(RefCell::new(mem.clone())));
+        }
+
+        let mut value_refs: Vec<Option<ValueRef>> = vec![None; self.values.len()];
+        let mut value_ref = |id: ValueId| -> ValueRef {
+            value_refs[id.index()]
+                .get_or_insert_with(|| Rc::new(RefCell::new(self.values[id].clone())))
+                .clone()
+        };
+
+        let functions: Vec<_> = self
+            .functions
+            .iter()
+            .map(|arena_func| {
+                let (return_type, param_types) = match arena_func.function_type.borrow().get_kind()
+                {
+                    TypeKind::Function(ret, params) => (ret.clone(), params.clone()),
+                    _ => panic!("Expected Function type"),
+                };
+                let func = Rc::new(RefCell::new(Function::new(
+                    arena_func.name.clone(),
+                    return_type,
+                    param_types,
+                )));
+                for (idx, &arg) in arena_func.arguments.iter().enumerate() {
+                    let value = &self.values[arg];
+                    let mut argument = Argument::new(
+                        value.get_type(),
+                        value.get_name().to_string(),
+                        Some(Rc::downgrade(&func)),
+                        idx,
+                    );
+                    argument.set_result(arena_func.results.contains(&arg));
+                    func.borrow_mut()
+                        .add_argument(Rc::new(RefCell::new(argument)));
+                }
+                for attr in &arena_func.attributes {
+                    func.borrow_mut().add_attribute(attr.clone());
+                }
+                func.borrow_mut().set_declaration(arena_func.declaration);
+                func.borrow_mut().set_linkage(arena_func.linkage);
+                module.borrow_mut().add_function(func.clone());
+                func
+            })
+            .collect();
+
+        let mut block_refs = HashMap::new();
+        for (arena_func, func) in self.functions.iter().zip(&functions) {
+            for &block_id in &arena_func.blocks {
+                let bb = Rc::new(RefCell::new(BasicBlock::new(
+                    self.blocks[block_id].name.clone(),
+                    Some(func.clone()),
+                )));
+                block_refs.insert(block_id, bb.clone());
+                func.borrow_mut().add_basic_block(bb);
+            }
+        }
+
+        for arena_func in &self.functions {
+            for &block_id in &arena_func.blocks {
+                let bb = block_refs[&block_id].clone();
+                for &instr_id in &self.blocks[block_id].instructions {
+                    let arena_instr = &self.instructions[instr_id];
+                    let operands = arena_instr
+                        .operands
+                        .iter()
+                        .map(|operand| match operand {
+                            ArenaOperand::Value(id) => Operand::create_value(value_ref(*id)),
+                            ArenaOperand::Immediate(imm, ty) => {
+                                Operand::create_immediate(*imm, ty.clone())
+                            }
+                            ArenaOperand::Block(id) => {
+                                let target = block_refs.get(id).cloned().unwrap_or_else(|| {
+                                    Rc::new(RefCell::new(BasicBlock::new(
+                                        self.blocks[*id].name.clone(),
+                                        None,
+                                    )))
+                                });
+                                Operand::create_basic_block(target)
+                            }
+                            ArenaOperand::Global(name) => {
+                                let module = module.borrow();
+                                let global = match module.get_global_memory_space(name) {
+                                    Some(mem) => GlobalRef::Memory(mem),
+                                    None => GlobalRef::Function(
+                                        module.get_function(name).unwrap_or_else(|| {
+                                            panic!("未定义的全局符号 '@{}'", name)
+                                        }),
+                                    ),
+                                };
+                                Operand::create_global(global)
+                            }
+                            ArenaOperand::IndexVector(indices) => {
+                                Operand::create_index_vector(indices.clone())
+                            }
+                        })
+                        .collect();
+                    let mut instr = Instruction::with_operands(
+                        arena_instr.opcode,
+                        arena_instr.result.map(&mut value_ref),
+                        operands,
+                        arena_instr.modifier,
+                    );
+                    for attr in &arena_instr.attributes {
+                        instr.add_attribute(attr.clone());
+                    }
+                    instr.set_memory_space(arena_instr.memory_space);
+                    instr.set_mnemonic(arena_instr.mnemonic.clone());
+                    bb.borrow_mut()
+                        .add_instruction(Rc::new(RefCell::new(instr)), bb.clone());
+                }
+            }
+        }
+
+        if let Some(entry) = &self.entry_function {
+            module.borrow_mut().set_entry_function(entry);
+        }
+        module
+    }
+}
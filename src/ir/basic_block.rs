@@ -2,24 +2,31 @@
 //
 // 这个模块定义了 VIL 的基本块类，包含指令序列
 
-use crate::ir::function::Function;
-use crate::ir::instruction::InstructionRef;
+use crate::ir::function::{Function, WeakFunctionRef};
+use crate::ir::instruction::{Instruction, InstructionModifier, InstructionRef, Opcode};
+use crate::ir::operand::Operand;
 use crate::ir::types::Type;
 use crate::ir::value::Value;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::fmt;
-use std::rc::Rc;
+use std::hash::{Hash, Hasher};
+use std::rc::{Rc, Weak};
 
 // BasicBlock 引用
 pub type BasicBlockRef = Rc<RefCell<BasicBlock>>;
+// Weak BasicBlock 引用 (用于指令指回所属基本块，避免循环引用)
+pub type WeakBasicBlockRef = Weak<RefCell<BasicBlock>>;
 
 /// 基本块类，包含指令序列
 pub struct BasicBlock {
     // 继承自 Value
     value: Value,
     // BasicBlock 特有字段
-    parent: Option<Rc<RefCell<Function>>>,
+    parent: Option<WeakFunctionRef>, // 所属函数 (弱引用)
     instructions: Vec<InstructionRef>,
+    predecessors: Vec<WeakBasicBlockRef>, // 缓存的前驱 (弱引用)
+    predecessors_epoch: u64,              // 计算前驱缓存时所属函数的控制流修改计数，0 表示从未计算
+    cfg_epoch: Option<Rc<Cell<u64>>>,     // 所属函数的控制流修改计数，加入函数时设置
 }
 
 impl BasicBlock {
@@ -30,8 +37,11 @@ impl BasicBlock {
 
         BasicBlock {
             value: Value::new(void_type, name),
-            parent,
+            parent: parent.as_ref().map(Rc::downgrade),
             instructions: Vec::new(),
+            predecessors: Vec::new(),
+            predecessors_epoch: 0,
+            cfg_epoch: None,
         }
     }
 
@@ -43,16 +53,18 @@ impl BasicBlock {
     /// 设置基本块名称
     pub fn set_name(&mut self, name: String) {
         self.value.set_name(name);
+        // 跳转以名称指向基本块，改名会改变控制流
+        self.invalidate_predecessors();
     }
 
-    /// 获取所属函数
+    /// 获取所属函数 (尝试升级为强引用)
     pub fn get_parent(&self) -> Option<Rc<RefCell<Function>>> {
-        self.parent.clone()
+        self.parent.as_ref().and_then(|weak_ref| weak_ref.upgrade())
     }
 
-    /// 设置所属函数
+    /// 设置所属函数，内部只保存弱引用
     pub fn set_parent(&mut self, parent: Option<Rc<RefCell<Function>>>) {
-        self.parent = parent;
+        self.parent = parent.as_ref().map(Rc::downgrade);
     }
 
     /// 获取指令列表
@@ -66,6 +78,10 @@ impl BasicBlock {
         instruction
             .borrow_mut()
             .set_parent_bb(Some(this_bb_ref.clone())); // Clone the Rc to pass it
+        instruction.borrow_mut().set_cfg_epoch(self.cfg_epoch.clone());
+        if instruction.borrow().get_opcode().is_terminator() {
+            self.invalidate_predecessors();
+        }
         self.instructions.push(instruction);
     }
 
@@ -76,6 +92,10 @@ impl BasicBlock {
         instruction
             .borrow_mut()
             .set_parent_bb(Some(this_bb_ref.clone())); // Clone the Rc to pass it
+        instruction.borrow_mut().set_cfg_epoch(self.cfg_epoch.clone());
+        if instruction.borrow().get_opcode().is_terminator() {
+            self.invalidate_predecessors();
+        }
         self.instructions.insert(index, instruction);
     }
 
@@ -88,7 +108,11 @@ impl BasicBlock {
         {
             // 清除指令的父基本块
             self.instructions[pos].borrow_mut().set_parent_bb(None);
-            self.instructions.remove(pos);
+            let removed = self.instructions.remove(pos);
+            removed.borrow_mut().set_cfg_epoch(None);
+            if removed.borrow().get_opcode().is_terminator() {
+                self.invalidate_predecessors();
+            }
             true
         } else {
             false
@@ -99,15 +123,149 @@ impl BasicBlock {
     pub fn clear_instructions(&mut self) {
         // 清除所有指令的父基本块
         for instruction in &self.instructions {
-            instruction.borrow_mut().set_parent_bb(None);
+            let mut instruction = instruction.borrow_mut();
+            instruction.set_parent_bb(None);
+            instruction.set_cfg_epoch(None);
         }
         self.instructions.clear();
+        self.invalidate_predecessors();
+    }
+
+    /// 在指令下标 `index` 处拆分基本块
+    ///
+    /// 原基本块保留 `[0, index)` 的指令，其余指令移入名为 `new_name` 的新基本块，
+    /// 原基本块末尾追加跳转到新基本块的 `br`。新基本块插入所属函数中原基本块之后；
+    /// 原后继中 `phi` 的来源基本块随终结指令一起改为新基本块。
+    /// 没有所属函数时只拆分指令，不更新基本块列表与后继。
+    pub fn split_at(this: &BasicBlockRef, index: usize, new_name: String) -> BasicBlockRef {
+        let parent = this.borrow().get_parent();
+        let new_bb = Rc::new(RefCell::new(BasicBlock::new(new_name.clone(), parent.clone())));
+
+        let tail = this.borrow_mut().instructions.split_off(index);
+        for instruction in tail {
+            new_bb.borrow_mut().add_instruction(instruction, new_bb.clone());
+        }
+        let br = Instruction::with_operands(
+            Opcode::Br,
+            None,
+            vec![Operand::create_basic_block(new_bb.clone())],
+            InstructionModifier::None,
+        );
+        this.borrow_mut().add_instruction(Rc::new(RefCell::new(br)), this.clone());
+
+        let Some(parent) = parent else {
+            return new_bb;
+        };
+        let successors = match new_bb.borrow().get_terminator() {
+            Some(terminator) => terminator.borrow().branch_targets(),
+            None => Vec::new(),
+        };
+        let mut func = parent.borrow_mut();
+        let old_name = this.borrow().get_name().to_string();
+        for bb in func.get_basic_blocks() {
+            if successors.iter().any(|name| name == bb.borrow().get_name()) {
+                bb.borrow().replace_phi_incoming(&old_name, &new_name);
+            }
+        }
+        let position = func
+            .get_basic_blocks()
+            .iter()
+            .position(|bb| Rc::ptr_eq(bb, this))
+            .map_or(func.get_basic_blocks().len(), |pos| pos + 1);
+        func.insert_basic_block(position, new_bb.clone());
+        new_bb
+    }
+
+    /// 将本基本块中 `phi` 的来源基本块 `old_name` 改为 `new_name`（名称均不含 '%'）
+    pub fn replace_phi_incoming(&self, old_name: &str, new_name: &str) {
+        let old_label = format!("%{}", old_name);
+        for instruction in &self.instructions {
+            let mut instruction = instruction.borrow_mut();
+            if instruction.get_opcode() != Opcode::Phi {
+                continue;
+            }
+            // phi 操作数为 (值, 来源基本块) 对，来源位于奇数下标
+            for idx in (1..instruction.get_operand_count()).step_by(2) {
+                if instruction.get_operand(idx).borrow().get_name() == old_label {
+                    let label = Value::new(Type::get_void_type(), format!("%{}", new_name));
+                    instruction.set_operand(idx, Rc::new(RefCell::new(label)));
+                }
+            }
+        }
+    }
+
+    /// 获取前驱基本块
+    ///
+    /// 优先使用缓存；缓存过期时对所属函数的所有基本块重新计算（见
+    /// `Function::recompute_predecessors`），因此调用时不能持有同一函数中任何基本块的借用。
+    /// 已被释放的前驱会被过滤掉。从未加入函数的基本块不会重新计算，
+    /// 已移出函数的基本块保留移出前最后一次计算的结果。
+    pub fn get_predecessors(this: &BasicBlockRef) -> Vec<BasicBlockRef> {
+        let stale = match &this.borrow().cfg_epoch {
+            Some(epoch) => this.borrow().predecessors_epoch != epoch.get(),
+            None => false,
+        };
+        let parent = this.borrow().get_parent();
+        if stale && let Some(parent) = parent {
+            parent.borrow().recompute_predecessors();
+        }
+        this.borrow()
+            .predecessors
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect()
+    }
+
+    /// 前驱数量
+    pub fn predecessor_count(this: &BasicBlockRef) -> usize {
+        BasicBlock::get_predecessors(this).len()
+    }
+
+    /// 设置前驱缓存（由 `Function::recompute_predecessors` 调用）
+    pub(crate) fn set_cached_predecessors(&mut self, predecessors: Vec<WeakBasicBlockRef>) {
+        self.predecessors = predecessors;
+        self.predecessors_epoch = self.cfg_epoch.as_ref().map_or(0, |epoch| epoch.get());
+    }
+
+    /// 设置所属函数的控制流修改计数（由 `Function::add_basic_block` 等调用），同步到各条指令
+    pub(crate) fn set_cfg_epoch(&mut self, epoch: Option<Rc<Cell<u64>>>) {
+        for instruction in &self.instructions {
+            instruction.borrow_mut().set_cfg_epoch(epoch.clone());
+        }
+        self.cfg_epoch = epoch;
+    }
+
+    /// 使所属函数中所有基本块的前驱缓存失效，见 `Function::invalidate_predecessors`
+    pub fn invalidate_predecessors(&self) {
+        if let Some(epoch) = &self.cfg_epoch {
+            epoch.set(epoch.get() + 1);
+        }
     }
 
     /// 获取终结指令
     pub fn get_terminator(&self) -> Option<InstructionRef> {
         self.instructions.last().cloned()
     }
+
+    /// 按内容比较两个基本块（名称与指令序列），不比较所属函数
+    pub fn structurally_eq(&self, other: &BasicBlock) -> bool {
+        self.get_name() == other.get_name()
+            && self.instructions.len() == other.instructions.len()
+            && self
+                .instructions
+                .iter()
+                .zip(&other.instructions)
+                .all(|(a, b)| a.borrow().structurally_eq(&b.borrow()))
+    }
+
+    /// 将基本块内容（名称与各条指令）写入哈希器，与 `structurally_eq` 一致
+    pub fn hash_structure<H: Hasher>(&self, state: &mut H) {
+        self.get_name().hash(state);
+        self.instructions.len().hash(state);
+        for instruction in &self.instructions {
+            instruction.borrow().hash_structure(state);
+        }
+    }
 }
 
 impl fmt::Display for BasicBlock {
@@ -132,11 +290,16 @@ impl fmt::Debug for BasicBlock {
 }
 
 impl Clone for BasicBlock {
+    /// 浅拷贝：新基本块与原基本块共享同一批指令对象（`Rc` 被复制而非指令本身），
+    /// 修改其中一条指令会同时反映在两个基本块中。需要独立副本时使用 `Function::deep_clone`。
     fn clone(&self) -> Self {
         BasicBlock {
             value: Value::new(self.value.get_type(), self.value.get_name().to_string()),
             parent: self.parent.clone(),
             instructions: self.instructions.clone(),
+            predecessors: Vec::new(),
+            predecessors_epoch: 0,
+            cfg_epoch: None,
         }
     }
 }
@@ -2,11 +2,15 @@
 //
 // 这个模块定义了 VIL 的函数类，包含参数和基本块
 
-use crate::ir::basic_block::BasicBlockRef;
+use crate::ir::basic_block::{BasicBlock, BasicBlockRef};
+use crate::ir::instruction::{Instruction, InstructionRef, Opcode};
+use crate::ir::operand::{Operand, OperandKind};
 use crate::ir::types::{Type, TypeKind, TypeRef};
-use crate::ir::value::Value;
-use std::cell::RefCell;
+use crate::ir::value::{Value, ValueRef};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::rc::{Rc, Weak}; // 导入 BasicBlockRef
 
 // Function 引用
@@ -23,6 +27,7 @@ pub struct Argument {
     value: Value,
     parent: Option<WeakFunctionRef>, // 所属函数 (弱引用)
     arg_idx: usize,                  // 参数索引
+    is_result: bool,                 // 是否为 `.result` 结果参数
 }
 
 impl Argument {
@@ -37,6 +42,7 @@ impl Argument {
             value: Value::new(type_, name),
             parent,
             arg_idx,
+            is_result: false,
         }
     }
 
@@ -55,6 +61,24 @@ impl Argument {
         self.arg_idx
     }
 
+    /// 设置参数索引（`Function::add_argument` 按加入顺序自动设置）
+    pub fn set_arg_idx(&mut self, arg_idx: usize) {
+        self.arg_idx = arg_idx;
+    }
+
+    /// 是否为结果参数（`.result`）
+    ///
+    /// 结果参数是函数的输出：调用方不传入，调用结束后按声明顺序得到其值，见
+    /// `Function::call_result_type`。
+    pub fn is_result(&self) -> bool {
+        self.is_result
+    }
+
+    /// 标记是否为结果参数
+    pub fn set_result(&mut self, is_result: bool) {
+        self.is_result = is_result;
+    }
+
     /// 获取所属函数 (尝试升级为强引用)
     pub fn get_parent(&self) -> Option<FunctionRef> {
         self.parent.as_ref().and_then(|weak_ref| weak_ref.upgrade())
@@ -72,12 +96,64 @@ impl fmt::Display for Argument {
     }
 }
 
+/// 禁止优化的函数属性：逐函数的优化 Pass 与内联都会跳过带有该属性的函数
+pub const OPTNONE_ATTRIBUTE: &str = "optnone";
+
+/// 导出函数的属性：死函数消除将其视为根
+pub const EXPORT_ATTRIBUTE: &str = "export";
+
+/// 解析器接受的全部函数属性
+pub const FUNCTION_ATTRIBUTES: &[&str] = &[OPTNONE_ATTRIBUTE, EXPORT_ATTRIBUTE];
+
+/// 函数链接属性
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Linkage {
+    /// 仅在模块内部可见（默认）
+    #[default]
+    Internal,
+    /// 对模块外部可见
+    External,
+    /// 对外可见，链接时可被同名的非弱定义取代
+    Weak,
+}
+
+impl Linkage {
+    /// 由关键字解析链接属性
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword {
+            "internal" => Some(Linkage::Internal),
+            "external" => Some(Linkage::External),
+            "weak" => Some(Linkage::Weak),
+            _ => None,
+        }
+    }
+
+    /// 是否对模块外部可见
+    pub fn is_visible(&self) -> bool {
+        !matches!(self, Linkage::Internal)
+    }
+}
+
+impl fmt::Display for Linkage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Linkage::Internal => write!(f, "internal"),
+            Linkage::External => write!(f, "external"),
+            Linkage::Weak => write!(f, "weak"),
+        }
+    }
+}
+
 /// 函数类，包含基本块和参数
 #[derive(Debug)]
 pub struct Function {
     value: Value, // 函数名和函数类型 (TypeKind::Function)
     arguments: Vec<ArgumentRef>,
     basic_blocks: Vec<BasicBlockRef>,
+    attributes: Vec<String>, // 函数属性，如 "export"
+    declaration: bool,       // 只有签名、没有函数体的外部函数声明 (.declare)
+    linkage: Linkage,
+    cfg_epoch: Rc<Cell<u64>>, // 控制流修改计数，基本块的前驱缓存据此判断是否过期
 }
 
 impl Function {
@@ -88,6 +164,10 @@ impl Function {
             value: Value::new(function_type, name),
             arguments: Vec::new(),
             basic_blocks: Vec::new(),
+            attributes: Vec::new(),
+            declaration: false,
+            linkage: Linkage::Internal,
+            cfg_epoch: Rc::new(Cell::new(1)),
         }
     }
 
@@ -96,6 +176,13 @@ impl Function {
         self.value.get_name()
     }
 
+    /// 设置函数名称
+    ///
+    /// 注意：函数已加入模块时应使用 `Module::rename_function`，以同步模块的索引。
+    pub fn set_name(&mut self, name: String) {
+        self.value.set_name(name);
+    }
+
     /// 获取函数类型
     pub fn get_type(&self) -> TypeRef {
         self.value.get_type()
@@ -121,6 +208,51 @@ impl Function {
         }
     }
 
+    /// 函数签名文本，如 `foo(i32, <i16 x 4>) -> void`
+    pub fn signature_string(&self) -> String {
+        self.signature_string_with_names(false)
+    }
+
+    /// 函数签名文本；`with_names` 为 true 时在参数类型前附上参数名，如 `foo(%a: i32) -> void`
+    ///
+    /// 参数类型取自函数类型，只包含输入参数；参数名缺失时只输出类型。
+    /// 返回部分为调用结果类型，见 `call_result_type`。
+    pub fn signature_string_with_names(&self, with_names: bool) -> String {
+        let inputs = self.inputs();
+        let params: Vec<String> = self
+            .get_param_types()
+            .iter()
+            .enumerate()
+            .map(|(idx, type_)| match inputs.get(idx) {
+                Some(arg) if with_names => format!("{}: {}", arg.borrow().get_name(), type_.borrow()),
+                _ => type_.borrow().to_string(),
+            })
+            .collect();
+        format!(
+            "{}({}) -> {}",
+            self.get_name(),
+            params.join(", "),
+            self.call_result_type().borrow()
+        )
+    }
+
+    /// 调用该函数得到的结果类型
+    ///
+    /// 没有结果参数时为返回类型；只有一个结果参数时为其类型；有多个结果参数时为
+    /// 按声明顺序组成的元组，调用方以 `extract` 逐个取出。
+    pub fn call_result_type(&self) -> TypeRef {
+        let mut results: Vec<TypeRef> = self
+            .results()
+            .iter()
+            .map(|arg| arg.borrow().get_type())
+            .collect();
+        match results.len() {
+            0 => self.get_return_type(),
+            1 => results.remove(0),
+            _ => Type::get_tuple_type(results),
+        }
+    }
+
     /// 获取入口基本块
     pub fn get_entry_block(&self) -> Option<BasicBlockRef> {
         self.basic_blocks.first().cloned()
@@ -130,7 +262,29 @@ impl Function {
     /// 注意：此方法不负责设置基本块的父函数，调用者应在添加后手动设置，
     /// 以避免在此处产生循环引用或不必要的克隆。
     pub fn add_basic_block(&mut self, bb: BasicBlockRef) {
+        bb.borrow_mut().set_cfg_epoch(Some(self.cfg_epoch.clone()));
         self.basic_blocks.push(bb);
+        self.invalidate_predecessors();
+    }
+
+    /// 在指定位置插入基本块（同样不负责设置父函数）
+    pub fn insert_basic_block(&mut self, index: usize, bb: BasicBlockRef) {
+        assert!(index <= self.basic_blocks.len());
+        bb.borrow_mut().set_cfg_epoch(Some(self.cfg_epoch.clone()));
+        self.basic_blocks.insert(index, bb);
+        self.invalidate_predecessors();
+    }
+
+    /// 从函数中移除基本块，返回是否找到并移除
+    pub fn remove_basic_block(&mut self, bb: &BasicBlockRef) -> bool {
+        match self.basic_blocks.iter().position(|b| Rc::ptr_eq(b, bb)) {
+            Some(index) => {
+                self.basic_blocks.remove(index);
+                self.invalidate_predecessors();
+                true
+            }
+            None => false,
+        }
     }
 
     /// 获取所有基本块
@@ -138,27 +292,426 @@ impl Function {
         &self.basic_blocks
     }
 
+    /// 使本函数所有基本块的前驱缓存失效
+    ///
+    /// 修改终结指令、基本块名称或基本块列表的 IR 接口会自动调用；
+    /// 以其它方式改变控制流（如直接修改跳转目标操作数的值）的变换应手动调用。
+    pub fn invalidate_predecessors(&self) {
+        self.cfg_epoch.set(self.cfg_epoch.get() + 1);
+    }
+
+    /// 根据当前的终结指令重新计算每个基本块缓存的前驱
+    ///
+    /// 与 `FunctionCFG` 一致：同一前驱只记录一次，指向不存在基本块的跳转被忽略。
+    pub fn recompute_predecessors(&self) {
+        let index: HashMap<String, usize> = self
+            .basic_blocks
+            .iter()
+            .enumerate()
+            .map(|(i, bb)| (bb.borrow().get_name().to_string(), i))
+            .collect();
+        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); self.basic_blocks.len()];
+        for (i, bb) in self.basic_blocks.iter().enumerate() {
+            let Some(terminator) = bb.borrow().get_terminator() else {
+                continue;
+            };
+            for target in terminator.borrow().branch_targets() {
+                if let Some(&t) = index.get(&target)
+                    && !predecessors[t].contains(&i)
+                {
+                    predecessors[t].push(i);
+                }
+            }
+        }
+        for (bb, preds) in self.basic_blocks.iter().zip(predecessors) {
+            let preds = preds
+                .into_iter()
+                .map(|p| Rc::downgrade(&self.basic_blocks[p]))
+                .collect();
+            bb.borrow_mut().set_cached_predecessors(preds);
+        }
+    }
+
+    /// 按基本块顺序遍历函数中的所有指令
+    ///
+    /// 每个基本块的指令列表在遍历到该块时复制，因此遍历过程中可以借用或修改指令与基本块。
+    pub fn instructions(&self) -> impl Iterator<Item = InstructionRef> + '_ {
+        self.basic_blocks
+            .iter()
+            .flat_map(|bb| bb.borrow().get_instructions().to_vec())
+    }
+
+    /// 按基本块顺序遍历 (所属基本块, 指令) 对，见 `instructions`
+    pub fn instructions_with_blocks(
+        &self,
+    ) -> impl Iterator<Item = (BasicBlockRef, InstructionRef)> + '_ {
+        self.basic_blocks.iter().flat_map(|bb| {
+            bb.borrow()
+                .get_instructions()
+                .iter()
+                .map(|instr| (bb.clone(), instr.clone()))
+                .collect::<Vec<_>>()
+        })
+    }
+
     /// 获取参数列表
     pub fn get_arguments(&self) -> &[ArgumentRef] {
         &self.arguments
     }
 
-    /// 添加参数
+    /// 添加参数，参数索引设为其在参数列表中的位置
     pub fn add_argument(&mut self, arg: ArgumentRef) {
+        arg.borrow_mut().set_arg_idx(self.arguments.len());
         self.arguments.push(arg);
     }
+
+    /// 添加结果参数（`.result`）
+    ///
+    /// 结果参数按声明顺序与其它参数一起加入参数列表，并被标记为结果参数，但不属于
+    /// 函数类型的参数类型。有结果参数的函数以 `ret %a, %b, ...` 按顺序给出各结果的值；
+    /// 结果参数全为指针（输出内存）时也可以用不带值的 `ret` 返回这些指针本身。
+    pub fn add_result(&mut self, arg: ArgumentRef) {
+        arg.borrow_mut().set_result(true);
+        self.add_argument(arg);
+    }
+
+    /// 输入参数（`.param`），按声明顺序
+    pub fn inputs(&self) -> Vec<ArgumentRef> {
+        self.arguments
+            .iter()
+            .filter(|arg| !arg.borrow().is_result())
+            .cloned()
+            .collect()
+    }
+
+    /// 结果参数（`.result`），按声明顺序
+    pub fn results(&self) -> Vec<ArgumentRef> {
+        self.arguments
+            .iter()
+            .filter(|arg| arg.borrow().is_result())
+            .cloned()
+            .collect()
+    }
+
+    /// 是否为外部函数声明（`.declare`）：只有签名，没有基本块
+    ///
+    /// 声明可以被调用，但不会被优化，验证器也不检查其函数体。
+    pub fn is_declaration(&self) -> bool {
+        self.declaration
+    }
+
+    /// 标记是否为外部函数声明
+    pub fn set_declaration(&mut self, declaration: bool) {
+        self.declaration = declaration;
+    }
+
+    /// 获取链接属性
+    pub fn get_linkage(&self) -> Linkage {
+        self.linkage
+    }
+
+    /// 设置链接属性
+    pub fn set_linkage(&mut self, linkage: Linkage) {
+        self.linkage = linkage;
+    }
+
+    /// 添加函数属性
+    pub fn add_attribute(&mut self, attr: String) {
+        if !self.has_attribute(&attr) {
+            self.attributes.push(attr);
+        }
+    }
+
+    /// 检查函数是否带有指定属性
+    pub fn has_attribute(&self, attr: &str) -> bool {
+        self.attributes.iter().any(|a| a == attr)
+    }
+
+    /// 获取所有函数属性
+    pub fn get_attributes(&self) -> &[String] {
+        &self.attributes
+    }
+
+    /// 检查基本块的终结指令，返回 (基本块名称, 错误信息) 列表
+    ///
+    /// 每个基本块必须以终结指令结尾（空基本块与直落到下一块均视为错误），
+    /// 且终结指令不能出现在基本块中间。
+    pub fn verify_terminators(&self) -> Vec<(String, String)> {
+        let mut errors = Vec::new();
+        for bb in &self.basic_blocks {
+            let bb = bb.borrow();
+            let name = bb.get_name().to_string();
+            let instructions = bb.get_instructions();
+            let Some((last, body)) = instructions.split_last() else {
+                errors.push((name, "基本块为空，缺少终结指令".to_string()));
+                continue;
+            };
+            for instr in body {
+                let instr = instr.borrow();
+                if instr.get_opcode().is_terminator() {
+                    errors.push((
+                        name.clone(),
+                        format!("终结指令 `{}` 出现在基本块中间", instr),
+                    ));
+                }
+            }
+            if !last.borrow().get_opcode().is_terminator() {
+                errors.push((name, "基本块未以终结指令结尾".to_string()));
+            }
+        }
+        errors
+    }
+
+    /// 函数中是否有指令以 `value` 为操作数（按对象或名称匹配，与 `replace_all_uses_with` 一致）
+    pub fn has_uses(&self, value: &ValueRef) -> bool {
+        let name = value.borrow().get_name().to_string();
+        self.instructions().any(|instr| {
+            instr
+                .borrow()
+                .get_operand_refs()
+                .iter()
+                .any(|op| match op.borrow().get_kind() {
+                    OperandKind::Value(v) => Rc::ptr_eq(v, value) || v.borrow().get_name() == name,
+                    _ => false,
+                })
+        })
+    }
+
+    /// 将函数中所有引用 `old` 的值操作数替换为 `replacement`，返回替换的操作数数量
+    ///
+    /// 操作数与 `old` 为同一对象或同名时视为引用。`old` 的定义保持不变，由调用者删除。
+    pub fn replace_all_uses_with(&self, old: &ValueRef, replacement: &ValueRef) -> usize {
+        let old_name = old.borrow().get_name().to_string();
+        let mut replaced = 0;
+        for instr in self.instructions() {
+            let mut instr = instr.borrow_mut();
+            for idx in 0..instr.get_operand_count() {
+                let uses_old = match instr.get_operand_ref(idx).borrow().get_kind() {
+                    OperandKind::Value(value) => {
+                        Rc::ptr_eq(value, old) || value.borrow().get_name() == old_name
+                    }
+                    _ => false,
+                };
+                if uses_old {
+                    instr.set_operand_ref(idx, Operand::create_value(replacement.clone()));
+                    replaced += 1;
+                }
+            }
+        }
+        replaced
+    }
+
+    /// 深拷贝函数
+    ///
+    /// 与 `BasicBlock::clone` 等浅拷贝不同，参数、基本块、指令及其引用的值都分配新对象，
+    /// 函数内部的引用关系（操作数指向的值、跳转目标基本块）通过重映射指向新对象，
+    /// 修改克隆体不会影响原函数。参数、指令结果与基本块改用带 `.cN` 后缀的新名称
+    /// （N 取原函数中没有名称以该后缀结尾的最小值），`phi` 的来源与跳转目标随之改名，
+    /// 因此克隆出的基本块可以直接并入原函数。全局符号引用仍指向模块中的同一对象。
+    pub fn deep_clone(&self) -> FunctionRef {
+        let suffix = self.fresh_suffix();
+        let fresh = |name: &str| format!("{}{}", name, suffix);
+        let func = Rc::new(RefCell::new(Function {
+            value: self.value.clone(),
+            arguments: Vec::new(),
+            basic_blocks: Vec::new(),
+            attributes: self.attributes.clone(),
+            declaration: self.declaration,
+            linkage: self.linkage,
+            cfg_epoch: Rc::new(Cell::new(1)),
+        }));
+        for arg in &self.arguments {
+            let arg = arg.borrow();
+            let mut cloned = Argument::new(
+                arg.get_type(),
+                fresh(arg.get_name()),
+                Some(Rc::downgrade(&func)),
+                arg.get_arg_idx(),
+            );
+            cloned.set_result(arg.is_result());
+            func.borrow_mut().add_argument(Rc::new(RefCell::new(cloned)));
+        }
+
+        // 先创建全部基本块，使前向跳转也能重映射
+        let blocks: HashMap<*const RefCell<BasicBlock>, BasicBlockRef> = self
+            .basic_blocks
+            .iter()
+            .map(|bb| {
+                let cloned = Rc::new(RefCell::new(BasicBlock::new(
+                    fresh(bb.borrow().get_name()),
+                    Some(func.clone()),
+                )));
+                func.borrow_mut().add_basic_block(cloned.clone());
+                (Rc::as_ptr(bb), cloned)
+            })
+            .collect();
+        // 函数内定义的名称（参数、指令结果）与基本块标签（带 '%'）-> 新名称
+        let mut renamed: HashMap<String, String> = HashMap::new();
+        for arg in &self.arguments {
+            let name = arg.borrow().get_name().to_string();
+            renamed.insert(name.clone(), fresh(&name));
+        }
+        for instr in self.instructions() {
+            if let Some(name) = instr.borrow().get_name() {
+                renamed.insert(name.clone(), fresh(&name));
+            }
+        }
+        let labels: HashMap<String, String> = self
+            .basic_blocks
+            .iter()
+            .map(|bb| {
+                let name = bb.borrow().get_name().to_string();
+                (format!("%{}", name), format!("%{}", fresh(&name)))
+            })
+            .collect();
+        let mut values: HashMap<*const RefCell<Value>, ValueRef> = HashMap::new();
+        let mut remap = |value: &ValueRef, names: &HashMap<String, String>| -> ValueRef {
+            values
+                .entry(Rc::as_ptr(value))
+                .or_insert_with(|| {
+                    let mut cloned = value.borrow().clone();
+                    if let Some(name) = names.get(cloned.get_name()) {
+                        cloned.set_name(name.clone());
+                    }
+                    Rc::new(RefCell::new(cloned))
+                })
+                .clone()
+        };
+
+        for bb in &self.basic_blocks {
+            let cloned_bb = blocks[&Rc::as_ptr(bb)].clone();
+            for instr in bb.borrow().get_instructions() {
+                let instr = instr.borrow();
+                // 以值给出的基本块标签：phi 的来源（奇数下标）与 br / condbr 的跳转目标
+                let is_label = |idx: usize| match instr.get_opcode() {
+                    Opcode::Phi => idx % 2 == 1,
+                    Opcode::Br => idx == 0,
+                    Opcode::CondBr => idx > 0,
+                    _ => false,
+                };
+                let operands = instr
+                    .get_operand_refs()
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, operand)| match operand.borrow().get_kind() {
+                        OperandKind::Value(value) if is_label(idx) => {
+                            Operand::create_value(remap(value, &labels))
+                        }
+                        OperandKind::Value(value) => Operand::create_value(remap(value, &renamed)),
+                        OperandKind::Immediate(imm, ty) => {
+                            Operand::create_immediate(*imm, ty.clone())
+                        }
+                        OperandKind::BasicBlock(target) => match blocks.get(&target.as_ptr()) {
+                            Some(block) => Operand::create_basic_block(block.clone()),
+                            None => Rc::new(RefCell::new(operand.borrow().clone())),
+                        },
+                        OperandKind::Global(global) => Operand::create_global(global.clone()),
+                        OperandKind::IndexVector(indices) => {
+                            Operand::create_index_vector(indices.clone())
+                        }
+                    })
+                    .collect();
+                let mut cloned = Instruction::with_operands(
+                    instr.get_opcode(),
+                    instr.get_result().map(|result| remap(&result, &renamed)),
+                    operands,
+                    instr.get_modifier(),
+                );
+                for attr in instr.get_attributes() {
+                    cloned.add_attribute(attr.clone());
+                }
+                cloned.set_memory_space(instr.memory_space());
+                cloned.set_mnemonic(instr.get_mnemonic());
+                cloned_bb
+                    .borrow_mut()
+                    .add_instruction(Rc::new(RefCell::new(cloned)), cloned_bb.clone());
+            }
+        }
+        func
+    }
+
+    /// 选择一个后缀 `.cN`，使函数中没有参数、指令结果或基本块的名称以它结尾
+    fn fresh_suffix(&self) -> String {
+        let mut used: Vec<String> = self
+            .arguments
+            .iter()
+            .map(|arg| arg.borrow().get_name().to_string())
+            .collect();
+        for bb in &self.basic_blocks {
+            let bb = bb.borrow();
+            used.push(bb.get_name().to_string());
+            for instr in bb.get_instructions() {
+                used.extend(instr.borrow().get_name());
+            }
+        }
+        (1..)
+            .map(|n| format!(".c{}", n))
+            .find(|suffix| !used.iter().any(|name| name.ends_with(suffix.as_str())))
+            .unwrap()
+    }
+
+    /// 按内容比较两个函数（名称、类型、参数、属性与基本块序列）
+    pub fn structurally_eq(&self, other: &Function) -> bool {
+        self.value == other.value
+            && self.attributes == other.attributes
+            && self.arguments.len() == other.arguments.len()
+            && self
+                .arguments
+                .iter()
+                .zip(&other.arguments)
+                .all(|(a, b)| a.borrow().value == b.borrow().value)
+            && self.basic_blocks.len() == other.basic_blocks.len()
+            && self
+                .basic_blocks
+                .iter()
+                .zip(&other.basic_blocks)
+                .all(|(a, b)| a.borrow().structurally_eq(&b.borrow()))
+    }
+
+    /// 将函数内容（签名、属性、参数与基本块）写入哈希器，与 `structurally_eq` 一致
+    pub fn hash_structure<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+        self.attributes.hash(state);
+        self.arguments.len().hash(state);
+        for arg in &self.arguments {
+            arg.borrow().value.hash(state);
+        }
+        self.basic_blocks.len().hash(state);
+        for bb in &self.basic_blocks {
+            bb.borrow().hash_structure(state);
+        }
+    }
 }
 
 impl fmt::Display for Function {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, ".function {}(", self.get_name())?;
+        if self.declaration {
+            writeln!(f, ".declare @{}(", self.get_name())?;
+        } else {
+            if self.linkage != Linkage::Internal {
+                write!(f, "{} ", self.linkage)?;
+            }
+            writeln!(f, ".function {}(", self.get_name())?;
+        }
         for (i, arg) in self.arguments.iter().enumerate() {
             if i > 0 {
                 writeln!(f, ", ")?;
             }
-            write!(f, "    .param {}", arg.borrow())?;
+            let kw = if arg.borrow().is_result() { ".result" } else { ".param" };
+            write!(f, "    {} {}", kw, arg.borrow())?;
+        }
+        write!(f, ")")?;
+        let return_type = self.get_return_type();
+        if !matches!(return_type.borrow().get_kind(), TypeKind::Void) {
+            write!(f, " -> {}", return_type.borrow())?;
+        }
+        for attr in &self.attributes {
+            write!(f, " {}", attr)?;
+        }
+        if self.declaration {
+            return writeln!(f);
         }
-        writeln!(f, ") {{")?;
+        writeln!(f, " {{")?;
 
         for bb in &self.basic_blocks {
             writeln!(f, "{}", bb.borrow())?;
@@ -186,6 +739,31 @@ mod tests {
         assert_eq!(func.get_param_types().len(), 2);
         assert_eq!(func.get_param_types()[0].borrow().to_string(), "i32");
         assert_eq!(func.get_param_types()[1].borrow().to_string(), "<i16 x 4>");
+        assert_eq!(func.signature_string(), "my_func(i32, <i16 x 4>) -> void");
+    }
+
+    #[test]
+    fn test_signature_with_names() {
+        let int_type = Type::get_int_type(TypeKind::Int32);
+        let vec_type = Type::get_vector_type(Type::get_int_type(TypeKind::Int16), 4);
+        let mut func = Function::new(
+            "my_func".to_string(),
+            Type::get_void_type(),
+            vec![int_type.clone(), vec_type.clone()],
+        );
+        for (idx, (name, type_)) in [("%in1", int_type), ("%in2", vec_type)].into_iter().enumerate() {
+            let arg = Argument::new(type_, name.to_string(), None, idx);
+            func.add_argument(Rc::new(RefCell::new(arg)));
+        }
+
+        assert_eq!(
+            func.signature_string_with_names(true),
+            "my_func(%in1: i32, %in2: <i16 x 4>) -> void"
+        );
+        assert_eq!(
+            func.signature_string_with_names(false),
+            func.signature_string()
+        );
     }
 
     #[test]
@@ -196,4 +774,133 @@ mod tests {
         assert_eq!(arg.get_type().borrow().to_string(), "i32");
         assert_eq!(arg.get_arg_idx(), 0);
     }
+
+    #[test]
+    fn test_deep_clone_is_independent() {
+        let source = r#".module m
+.function f(.param %x i32) -> i32 {
+entry:
+    %a = add %x, 1
+    br %next
+next:
+    %b = mul %a, 2
+    ret %b
+}
+"#;
+        let module = crate::frontend::parse_vil(source, "clone.vil").expect("应成功解析模块");
+        let original = module.borrow().get_function("f").unwrap();
+        let cloned = original.borrow().deep_clone();
+
+        // 定义的名称与基本块均改用新名称，内部引用随之改名
+        let cloned_blocks = cloned.borrow().get_basic_blocks().to_vec();
+        let add = cloned_blocks[0].borrow().get_instructions()[0].clone();
+        let mul = cloned_blocks[1].borrow().get_instructions()[0].clone();
+        assert_eq!(cloned.borrow().get_arguments()[0].borrow().get_name(), "%x.c1");
+        assert_eq!(add.borrow().to_string(), "%a.c1 = add %x.c1, 1");
+        assert_eq!(mul.borrow().to_string(), "%b.c1 = mul %a.c1, 2");
+        assert_eq!(cloned_blocks[1].borrow().get_name(), "next.c1");
+
+        // 克隆体内部的引用指向克隆体自己的对象
+        assert!(Rc::ptr_eq(&add.borrow().get_result().unwrap(), &mul.borrow().get_operand(0)));
+        let br = cloned_blocks[0].borrow().get_terminator().unwrap();
+        let target = br.borrow().get_operand_ref(0).borrow().get_basic_block().unwrap();
+        assert!(Rc::ptr_eq(&target, &cloned_blocks[1]));
+        assert!(Rc::ptr_eq(&cloned_blocks[0].borrow().get_parent().unwrap(), &cloned));
+
+        // 修改克隆体不影响原函数
+        add.borrow_mut().set_name("%renamed".to_string());
+        cloned_blocks[1].borrow_mut().set_name("renamed".to_string());
+        let original = original.borrow();
+        let original_add = original.get_basic_blocks()[0].borrow().get_instructions()[0].clone();
+        assert_eq!(original_add.borrow().get_name().as_deref(), Some("%a"));
+        assert_eq!(original.get_basic_blocks()[1].borrow().get_name(), "next");
+    }
+
+    #[test]
+    fn test_deep_clone_renames_labels_and_avoids_used_suffixes() {
+        let source = r#".module m
+.function f(.param %c <pred 1>) -> i32 {
+entry:
+    %v.c1 = add 1, 2
+    condbr %c, %left, %join
+left:
+    br %join
+join:
+    %r = phi %v.c1, %entry, 3, %left
+    ret %r
+}
+"#;
+        let module = crate::frontend::parse_vil(source, "clone.vil").expect("应成功解析模块");
+        let original = module.borrow().get_function("f").unwrap();
+        let cloned = original.borrow().deep_clone();
+
+        // 原函数已有以 .c1 结尾的名称，改用 .c2
+        let lines: Vec<String> = cloned
+            .borrow()
+            .instructions()
+            .map(|instr| instr.borrow().to_string())
+            .collect();
+        assert_eq!(lines[1], "condbr %c.c2, %left.c2, %join.c2");
+        assert_eq!(lines[3], "%r.c2 = phi %v.c1.c2, %entry.c2, 3, %left.c2");
+
+        // 克隆体的基本块与原函数的名称互不冲突，可以并入原函数
+        for bb in cloned.borrow().get_basic_blocks() {
+            let name = bb.borrow().get_name().to_string();
+            assert!(
+                original
+                    .borrow()
+                    .get_basic_blocks()
+                    .iter()
+                    .all(|b| b.borrow().get_name() != name)
+            );
+        }
+    }
+
+    #[test]
+    fn test_instructions_across_blocks() {
+        let source = r#".module m
+.function f(.param %x i32) {
+entry:
+    %a = add %x, 1
+    br %next
+next:
+    %b = mul %a, 2
+    %c = sub %b, %x
+    ret
+}
+"#;
+        let module = crate::frontend::parse_vil(source, "iter.vil").expect("应成功解析模块");
+        let func = module.borrow().get_function("f").unwrap();
+        let func = func.borrow();
+        assert_eq!(func.instructions().count(), 5);
+
+        let names: Vec<Option<String>> = func.instructions().map(|i| i.borrow().get_name()).collect();
+        assert_eq!(names[0].as_deref(), Some("%a"));
+        assert_eq!(names[3].as_deref(), Some("%c"));
+
+        let blocks: Vec<String> = func
+            .instructions_with_blocks()
+            .map(|(bb, _)| bb.borrow().get_name().to_string())
+            .collect();
+        assert_eq!(blocks, ["entry", "entry", "next", "next", "next"]);
+    }
+
+    #[test]
+    fn test_verify_terminators_empty_block() {
+        let func = Rc::new(RefCell::new(Function::new(
+            "f".to_string(),
+            Type::get_void_type(),
+            vec![],
+        )));
+        let bb = Rc::new(RefCell::new(crate::ir::BasicBlock::new(
+            "empty".to_string(),
+            Some(func.clone()),
+        )));
+        func.borrow_mut().add_basic_block(bb);
+
+        let errors = func.borrow().verify_terminators();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, "empty");
+        assert!(errors[0].1.contains("为空"));
+    }
 }
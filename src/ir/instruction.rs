@@ -3,12 +3,16 @@
 // 这个模块定义了 VIL 的指令类，包括各种指令类型
 
 use crate::ir::MemorySpace;
-use crate::ir::basic_block::BasicBlockRef;
-use crate::ir::types::{Type, TypeRef};
-use crate::ir::value::{Value, ValueRef};
-use std::cell::RefCell;
+use crate::ir::basic_block::{BasicBlockRef, WeakBasicBlockRef};
+use crate::ir::operand::{Operand, OperandRef};
+use crate::ir::printer::{PrinterOptions, print_instruction};
+use crate::ir::types::{Type, TypeKind, TypeRef};
+use crate::ir::value::{Constant, Value, ValueRef};
+use std::cell::{Cell, RefCell};
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 // Instruction 引用
 pub type InstructionRef = Rc<RefCell<Instruction>>;
@@ -57,7 +61,7 @@ pub enum Opcode {
     // 特殊指令
     Range,     // 生成序列
     Broadcast, // 广播标量
-    Shuffle,   // 向量洗牌
+    Shuffle,   // 向量洗牌: shuffle %v, <i0, i1, ...>，结果第 k 个元素取 %v 的第 ik 个元素
     Alloc,     // 分配内存
     Free,      // 释放内存
 
@@ -65,10 +69,18 @@ pub enum Opcode {
     Br,     // 无条件跳转
     CondBr, // 条件跳转
     Ret,    // 函数返回
+    Call,   // 函数调用
 
     // 其他
-    Mov, // 移动/复制
-    Phi, // Phi节点
+    Mov,     // 移动/复制
+    Phi,     // Phi节点
+    Extract, // 取元组元素: extract %t, <index>，%t 为多结果函数的调用结果
+
+    // 类型转换指令
+    Bitcast, // 按位重新解释 (位宽不变)
+    SExt,    // 符号扩展
+    ZExt,    // 零扩展
+    Trunc,   // 截断
 
     // --- Venus 扩展指令 (来自硬件 OP_TYPE 枚举) ---
     // 乘法扩展
@@ -95,9 +107,15 @@ pub enum Opcode {
 
     // 其他扩展
     RSub,         // 反向减法
-    ShuffleClbmv, // 特殊洗牌指令
+    ShuffleClbmv, // 特殊洗牌指令，操作数形式同 shuffle，索引的具体含义由硬件的 clbmv 定义
     SetCsr,       // 设置 CSR
     Yield,        // 让出执行权
+
+    // 宽松模式下解析到的未登记操作码，源代码中的助记符保存在指令上（见 `Instruction::get_mnemonic`）
+    Unknown,
+    // 硬件扩展指令，携带原始操作码编号，文本形式为 `ext.<编号>`；
+    // 以登记的助记符写出时，该助记符同样保存在指令上
+    Extension(u16),
 }
 
 impl fmt::Display for Opcode {
@@ -137,8 +155,14 @@ impl fmt::Display for Opcode {
             Opcode::Br => "br",
             Opcode::CondBr => "condbr",
             Opcode::Ret => "ret",
+            Opcode::Call => "call",
             Opcode::Mov => "mov",
             Opcode::Phi => "phi",
+            Opcode::Extract => "extract",
+            Opcode::Bitcast => "bitcast",
+            Opcode::SExt => "sext",
+            Opcode::ZExt => "zext",
+            Opcode::Trunc => "trunc",
             Opcode::MulH => "mulh",
             Opcode::MulHU => "mulhu",
             Opcode::MulHSU => "mulhsu",
@@ -159,6 +183,8 @@ impl fmt::Display for Opcode {
             Opcode::ShuffleClbmv => "shuffle_clbmv",
             Opcode::SetCsr => "setcsr",
             Opcode::Yield => "yield",
+            Opcode::Unknown => self.as_str(),
+            Opcode::Extension(number) => return write!(f, "ext.{}", number),
         };
         write!(f, "{}", name)
     }
@@ -173,6 +199,26 @@ pub enum InstructionModifier {
     Predicate, // 谓词操作
 }
 
+impl InstructionModifier {
+    /// 根据操作数与结果类型推断修饰符（源代码省略修饰符时使用）
+    ///
+    /// 结果为谓词类型时为 `.p`；存在向量操作数时为 `.v`；操作数全为标量时为 `.s`；
+    /// 其余情况（如无操作数）不加修饰符。
+    pub fn infer(operands: &[ValueRef], result: Option<&ValueRef>) -> Self {
+        if result.is_some_and(|r| r.borrow().get_type().borrow().is_predicate()) {
+            return InstructionModifier::Predicate;
+        }
+        let types: Vec<TypeRef> = operands.iter().map(|op| op.borrow().get_type()).collect();
+        if types.iter().any(|t| t.borrow().is_vector()) {
+            InstructionModifier::Vector
+        } else if !types.is_empty() && types.iter().all(|t| t.borrow().is_scalar()) {
+            InstructionModifier::Scalar
+        } else {
+            InstructionModifier::None
+        }
+    }
+}
+
 impl fmt::Display for InstructionModifier {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -184,36 +230,197 @@ impl fmt::Display for InstructionModifier {
     }
 }
 
+/// 通过带检查的构建接口创建指令时发现的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    /// 操作码不是二元运算
+    NotBinary(Opcode),
+    /// 两个操作数的类型不兼容
+    TypeMismatch { lhs: String, rhs: String },
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::NotBinary(opcode) => write!(f, "'{}' 不是二元运算", opcode.as_str()),
+            BuildError::TypeMismatch { lhs, rhs } => {
+                write!(f, "操作数类型 {} 与 {} 不兼容", lhs, rhs)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+/// 指令 ID 计数器，全局单调递增
+static NEXT_INSTRUCTION_ID: AtomicU64 = AtomicU64::new(0);
+
+fn next_instruction_id() -> u64 {
+    NEXT_INSTRUCTION_ID.fetch_add(1, Ordering::Relaxed)
+}
+
 /// 指令基类
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct Instruction {
+    id: u64, // 创建时分配的唯一 ID，不随重命名变化
     opcode: Opcode,
     result: Option<ValueRef>, // 指令结果，如果指令产生一个值
-    operands: Vec<ValueRef>,   // 操作数 (Changed from OperandRef to ValueRef)
-    parent_bb: Option<BasicBlockRef>, // 所属的基本块
+    operands: Vec<OperandRef>, // 操作数：值、立即数、基本块或全局符号
+    parent_bb: Option<WeakBasicBlockRef>, // 所属的基本块 (弱引用)
     attributes: Vec<String>, // 指令属性，如 "volatile" (Moved from Value)
     modifier: InstructionModifier, // Added back modifier
+    memory_space: Option<MemorySpace>, // 访问的内存空间，仅 load / store / alloc 有值
+    cfg_epoch: Option<Rc<Cell<u64>>>, // 所属函数的控制流修改计数，终结指令修改跳转目标时递增
+    mnemonic: Option<Rc<str>>, // 未知指令与以登记助记符写出的扩展指令在源代码中的助记符
 }
 
 impl Instruction {
+    /// 以值作为操作数创建指令，每个值包装为值操作数
     pub fn new(
         opcode: Opcode,
         result: Option<ValueRef>,
         operands: Vec<ValueRef>,
         modifier: InstructionModifier,
     ) -> Self {
+        let operands = operands.into_iter().map(Operand::create_value).collect();
+        Self::with_operands(opcode, result, operands, modifier)
+    }
+
+    /// 以任意种类的操作数（立即数、基本块、全局符号等）创建指令
+    pub fn with_operands(
+        opcode: Opcode,
+        result: Option<ValueRef>,
+        operands: Vec<OperandRef>,
+        modifier: InstructionModifier,
+    ) -> Self {
+        let memory_space = Self::infer_memory_space(opcode, &result, &operands);
         Self {
+            id: next_instruction_id(),
             opcode,
             result,
             operands,
             parent_bb: None,
             attributes: Vec::new(),
             modifier,
+            memory_space,
+            cfg_epoch: None,
+            mnemonic: None,
         }
     }
 
+    /// 创建二元运算指令，并立即检查两个操作数的类型是否兼容
+    ///
+    /// 兼容规则与 `Type::get_common_type` 相同；结果类型为两者的公共类型，比较指令的结果
+    /// 为等长的谓词。结果值尚未命名。与 `BinaryInstruction::new` 不同，类型错误在构建时即报告。
+    pub fn try_binary(
+        opcode: Opcode,
+        lhs: ValueRef,
+        rhs: ValueRef,
+        modifier: InstructionModifier,
+    ) -> Result<Instruction, BuildError> {
+        if !opcode.is_binary() {
+            return Err(BuildError::NotBinary(opcode));
+        }
+        let (lhs_type, rhs_type) = (lhs.borrow().get_type(), rhs.borrow().get_type());
+        let Some(common) = Type::get_common_type(&lhs_type, &rhs_type) else {
+            return Err(BuildError::TypeMismatch {
+                lhs: lhs_type.borrow().to_string(),
+                rhs: rhs_type.borrow().to_string(),
+            });
+        };
+        let result_type = if opcode.is_comparison() {
+            let length = match common.borrow().get_kind() {
+                TypeKind::Vector(_, length) => *length,
+                _ => 1,
+            };
+            Type::get_predicate_type(length)
+        } else {
+            common
+        };
+        let result = Value::new(result_type, String::new());
+        Ok(Self::new(
+            opcode,
+            Some(Rc::new(RefCell::new(result))),
+            vec![lhs, rhs],
+            modifier,
+        ))
+    }
+
+    /// 从指针类型推导内存指令访问的内存空间
+    ///
+    /// `load` 取地址操作数（第 0 个），`store` 取地址操作数（第 1 个），
+    /// `alloc` 取结果指针的内存空间；其余指令返回 None。
+    fn infer_memory_space(
+        opcode: Opcode,
+        result: &Option<ValueRef>,
+        operands: &[OperandRef],
+    ) -> Option<MemorySpace> {
+        let pointer_type = match opcode {
+            Opcode::Load => operands.first()?.borrow().get_type()?,
+            Opcode::Store => operands.get(1)?.borrow().get_type()?,
+            Opcode::Alloc => result.as_ref()?.borrow().get_type(),
+            _ => return None,
+        };
+        match pointer_type.borrow().get_kind() {
+            TypeKind::Pointer(_, space) => Some(*space),
+            _ => None,
+        }
+    }
+
+    /// 将所属函数中对该指令结果的所有使用替换为 `replacement`，返回替换的操作数数量
+    ///
+    /// 指令本身保持定义不变，由调用者删除。指令没有结果或不在函数中时返回 0。
+    pub fn replace_all_uses_with(this: &InstructionRef, replacement: &ValueRef) -> usize {
+        let Some(result) = this.borrow().get_result() else {
+            return 0;
+        };
+        let Some(func) = this
+            .borrow()
+            .get_parent_bb()
+            .and_then(|bb| bb.borrow().get_parent())
+        else {
+            return 0;
+        };
+        func.borrow().replace_all_uses_with(&result, replacement)
+    }
+
+    /// 获取内存指令访问的内存空间，非内存指令返回 None
+    pub fn memory_space(&self) -> Option<MemorySpace> {
+        self.memory_space
+    }
+
+    /// 设置内存指令访问的内存空间
+    pub fn set_memory_space(&mut self, space: Option<MemorySpace>) {
+        self.memory_space = space;
+    }
+
+    /// 获取源代码中的助记符，仅未知指令与以登记助记符写出的扩展指令有值
+    pub fn get_mnemonic(&self) -> Option<Rc<str>> {
+        self.mnemonic.clone()
+    }
+
+    /// 设置源代码中的助记符，见 `get_mnemonic`
+    pub fn set_mnemonic(&mut self, mnemonic: Option<Rc<str>>) {
+        self.mnemonic = mnemonic;
+    }
+
+    /// 指令的助记符（不含修饰符）：优先使用源代码中的助记符，否则为操作码的文本形式
+    pub fn mnemonic(&self) -> String {
+        match &self.mnemonic {
+            Some(mnemonic) => mnemonic.to_string(),
+            None => self.opcode.to_string(),
+        }
+    }
+
+    /// 获取指令的唯一 ID
+    ///
+    /// ID 在创建时分配且不随重命名改变，可代替名称或指针作为映射键。
+    pub fn get_id(&self) -> u64 {
+        self.id
+    }
+
     pub fn get_opcode(&self) -> Opcode {
-        self.opcode.clone()
+        self.opcode
     }
 
     pub fn get_type(&self) -> TypeRef {
@@ -236,14 +443,63 @@ impl Instruction {
         }
     }
 
-    // Changed to return ValueRef directly
+    /// 以值的形式获取操作数，见 `Operand::to_value`
+    ///
+    /// 值操作数返回共享的 `ValueRef`；立即数、基本块与全局符号每次返回新建的值。
     pub fn get_operand(&self, index: usize) -> ValueRef {
-        self.operands[index].clone()
+        self.operands[index].borrow().to_value()
     }
 
-    // Changed to accept ValueRef directly
+    /// 以值替换操作数
     pub fn set_operand(&mut self, index: usize, operand: ValueRef) {
+        self.operands[index] = Operand::create_value(operand);
+        self.invalidate_cfg_if_terminator();
+    }
+
+    /// 获取原始操作数
+    pub fn get_operand_ref(&self, index: usize) -> OperandRef {
+        self.operands[index].clone()
+    }
+
+    /// 以任意种类的操作数替换操作数
+    pub fn set_operand_ref(&mut self, index: usize, operand: OperandRef) {
         self.operands[index] = operand;
+        self.invalidate_cfg_if_terminator();
+    }
+
+    /// 移除指定位置的操作数并以值的形式返回它
+    pub fn remove_operand(&mut self, index: usize) -> ValueRef {
+        self.invalidate_cfg_if_terminator();
+        self.operands.remove(index).borrow().to_value()
+    }
+
+    /// 终结指令的操作数（跳转目标）变化时使基本块的前驱缓存失效
+    fn invalidate_cfg_if_terminator(&self) {
+        if self.opcode.is_terminator()
+            && let Some(epoch) = &self.cfg_epoch
+        {
+            epoch.set(epoch.get() + 1);
+        }
+    }
+
+    /// 获取终结指令的跳转目标名称
+    ///
+    /// `br` 的第 0 个操作数、`condbr` 的第 1、2 个操作数为目标基本块名称，
+    /// 名称可带或不带 '%' 前缀。其它指令没有跳转目标。
+    pub fn branch_targets(&self) -> Vec<String> {
+        let indices: &[usize] = match self.opcode {
+            Opcode::Br => &[0],
+            Opcode::CondBr => &[1, 2],
+            _ => &[],
+        };
+        indices
+            .iter()
+            .filter(|&&idx| idx < self.operands.len())
+            .map(|&idx| {
+                let name = self.get_operand(idx).borrow().get_name().to_string();
+                name.strip_prefix('%').map(str::to_string).unwrap_or(name)
+            })
+            .collect()
     }
 
     // Renamed from get_num_operands
@@ -259,13 +515,19 @@ impl Instruction {
         self.result.clone()
     }
 
-    // Modified to accept Option<BasicBlockRef>
+    /// 设置所属基本块，内部只保存弱引用以避免与基本块形成引用环
     pub fn set_parent_bb(&mut self, bb: Option<BasicBlockRef>) {
-        self.parent_bb = bb;
+        self.parent_bb = bb.as_ref().map(Rc::downgrade);
     }
 
+    /// 设置所属函数的控制流修改计数（由 `BasicBlock::add_instruction` 等调用）
+    pub(crate) fn set_cfg_epoch(&mut self, epoch: Option<Rc<Cell<u64>>>) {
+        self.cfg_epoch = epoch;
+    }
+
+    /// 获取所属基本块 (尝试升级为强引用)
     pub fn get_parent_bb(&self) -> Option<BasicBlockRef> {
-        self.parent_bb.clone()
+        self.parent_bb.as_ref().and_then(|weak_ref| weak_ref.upgrade())
     }
 
     // New: Add an attribute to the instruction
@@ -278,19 +540,103 @@ impl Instruction {
         self.attributes.contains(&attr.to_string())
     }
 
+    /// 获取所有指令属性
+    pub fn get_attributes(&self) -> &[String] {
+        &self.attributes
+    }
+
+    /// 判断指令是否有副作用（不可删除、不可移动）
+    ///
+    /// 屏障指令 (setcsr/yield) 与 volatile load 也视为有副作用。
+    pub fn has_side_effects(&self) -> bool {
+        match self.opcode {
+            Opcode::Store | Opcode::Call | Opcode::Ret | Opcode::Br | Opcode::CondBr => true,
+            Opcode::Load => self.has_attribute("volatile"),
+            op => op.is_barrier(),
+        }
+    }
+
+    /// 判断指令是否为纯指令：结果只取决于操作数，且没有副作用
+    ///
+    /// load 读取内存、alloc 每次分配新对象、phi 取决于控制流来源，均不是纯指令。
+    pub fn is_pure(&self) -> bool {
+        !self.has_side_effects()
+            && !matches!(self.opcode, Opcode::Load | Opcode::Alloc | Opcode::Phi)
+    }
+
+    /// 判断指令是否可以直接删除：纯指令，且结果在所属函数中没有使用者
+    ///
+    /// 没有结果的纯指令总是可以删除；有结果但不在函数中的指令无法确定使用者，返回 false。
+    /// 每次调用都会扫描所属函数，需要批量判定时应像 `DeadCodeEliminationPass::fast`
+    /// 那样预先统计使用次数。
+    pub fn is_trivially_dead(&self) -> bool {
+        if !self.is_pure() {
+            return false;
+        }
+        let Some(result) = &self.result else {
+            return true;
+        };
+        self.get_parent_bb()
+            .and_then(|bb| bb.borrow().get_parent())
+            .is_some_and(|func| !func.borrow().has_uses(result))
+    }
+
+    /// 按内容比较两条指令（操作码、修饰符、结果、操作数与属性），不比较所属基本块
+    pub fn structurally_eq(&self, other: &Instruction) -> bool {
+        let values_eq = |a: &ValueRef, b: &ValueRef| *a.borrow() == *b.borrow();
+        self.opcode == other.opcode
+            && self.mnemonic == other.mnemonic
+            && self.modifier == other.modifier
+            && self.attributes == other.attributes
+            && match (&self.result, &other.result) {
+                (Some(a), Some(b)) => values_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+            && self.operands.len() == other.operands.len()
+            && self
+                .operands
+                .iter()
+                .zip(&other.operands)
+                .all(|(a, b)| values_eq(&a.borrow().to_value(), &b.borrow().to_value()))
+    }
+
+    /// 将指令内容写入哈希器，与 `structurally_eq` 比较的字段一致
+    pub fn hash_structure<H: Hasher>(&self, state: &mut H) {
+        self.opcode.hash(state);
+        self.mnemonic.hash(state);
+        self.modifier.hash(state);
+        self.attributes.hash(state);
+        self.result.is_some().hash(state);
+        if let Some(result) = &self.result {
+            result.borrow().hash(state);
+        }
+        self.operands.len().hash(state);
+        for operand in &self.operands {
+            operand.borrow().to_value().borrow().hash(state);
+        }
+    }
+
     /// 替换当前指令为一个常量值
-    /// 这将把指令的结果值名称设置为常量字符串，并清空操作数和操作码，使其成为一个"常量"指令。
-    pub fn replace_with_constant(&mut self, constant_name: String) {
+    /// 这将把指令的结果值标记为常量，并清空操作数和操作码，使其成为一个"常量"指令。
+    /// 引用该结果的操作数共享同一个 Value，因此会直接看到常量。
+    pub fn replace_with_constant(&mut self, constant: Constant) {
         if let Some(result_val_ref) = &self.result {
             let mut result_val = result_val_ref.borrow_mut();
-            result_val.set_name(constant_name);
+            result_val.set_constant(constant);
             // 清空操作数和操作码，表示这是一个常数指令
             self.opcode = Opcode::Mov; // 使用 Mov 指令来表示一个常量的直接移动
             self.operands.clear();
         }
     }
 
-    pub fn get_operands(&self) -> &Vec<ValueRef> {
+    /// 以值的形式获取全部操作数，见 `get_operand`
+    pub fn get_operands(&self) -> Vec<ValueRef> {
+        self.operands.iter().map(|op| op.borrow().to_value()).collect()
+    }
+
+    /// 获取全部原始操作数
+    pub fn get_operand_refs(&self) -> &[OperandRef] {
         &self.operands
     }
 
@@ -303,36 +649,93 @@ impl Instruction {
     pub fn set_modifier(&mut self, modifier: InstructionModifier) {
         self.modifier = modifier;
     }
-}
 
-impl fmt::Display for Instruction {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // 输出结果赋值部分
-        if self.has_result() {
-            let name_str = self.get_name().unwrap_or_default(); // Use unwrap_or_default() as get_name returns Option<String>
-            let formatted_name = if name_str.is_empty() {
-                "%_".to_string()
-            } else {
-                format!("%{}", name_str)
-            };
-            write!(f, "{} = ", formatted_name)?;
+    /// 根据当前操作数与结果推断修饰符，见 `InstructionModifier::infer`
+    pub fn infer_modifier(&self) -> InstructionModifier {
+        InstructionModifier::infer(&self.get_operands(), self.result.as_ref())
+    }
+
+    /// 指令的粗略执行代价：操作码代价乘以向量宽度
+    ///
+    /// 宽度取自结果类型（`store` 取被存储的值）的向量或谓词长度，标量为 1。
+    pub fn cost(&self) -> u32 {
+        let type_ = match (&self.result, self.opcode) {
+            (Some(result), _) => Some(result.borrow().get_type()),
+            (None, Opcode::Store) => self.operands.first().and_then(|op| op.borrow().get_type()),
+            _ => None,
+        };
+        let width = type_.map_or(1, |t| match t.borrow().get_kind() {
+            TypeKind::Vector(_, length) | TypeKind::Predicate(length) => *length,
+            _ => 1,
+        });
+        self.opcode.cost() * width
+    }
+
+    /// 是否为按谓词掩码执行的指令：可掩码的操作码带 `.p` 修饰符，
+    /// 此时最后一个操作数为掩码，见 `Opcode::is_maskable`
+    pub fn is_masked(&self) -> bool {
+        self.modifier == InstructionModifier::Predicate && self.opcode.is_maskable()
+    }
+
+    /// 获取谓词掩码操作数（带掩码指令的最后一个操作数）
+    ///
+    /// 操作数个数不超过操作码的数据操作数个数时视为缺少掩码，返回 None。
+    pub fn get_mask(&self) -> Option<ValueRef> {
+        if !self.is_masked() || self.operands.len() <= self.opcode.data_arity() {
+            return None;
         }
+        self.operands.last().map(|op| op.borrow().to_value())
+    }
 
-        // 输出操作码和修饰符
-        write!(f, "{}{}", self.opcode, self.modifier)?;
-
-        // 输出操作数
-        if !self.operands.is_empty() {
-            write!(f, " ")?;
-            for (i, op) in self.operands.iter().enumerate() {
-                if i > 0 {
-                    write!(f, ", ")?;
-                }
-                write!(f, "{}", op.borrow())?;
-            }
+    /// 获取 `shuffle` / `shuffle_clbmv` 的常量索引向量
+    ///
+    /// 常量索引以单个索引向量操作数保存在源向量之后。索引来自向量值或立即数
+    /// （运行时确定）或指令不是洗牌时返回 None。
+    pub fn shuffle_indices(&self) -> Option<Vec<i64>> {
+        if !matches!(self.opcode, Opcode::Shuffle | Opcode::ShuffleClbmv) {
+            return None;
+        }
+        self.operands.get(1)?.borrow().get_index_vector()
+    }
+
+    /// 获取除掩码以外的数据操作数；不带掩码时等同于 `get_operands`
+    pub fn get_data_operands(&self) -> Vec<ValueRef> {
+        let mut operands = self.get_operands();
+        if self.get_mask().is_some() {
+            operands.pop();
+        }
+        operands
+    }
+}
+
+impl Clone for Instruction {
+    /// 克隆得到的是一条新指令，分配新的 ID
+    ///
+    /// 结果值与操作数仍与原指令共享同一批 `Rc` 对象；需要独立副本时使用 `Function::deep_clone`。
+    fn clone(&self) -> Self {
+        Self {
+            id: next_instruction_id(),
+            opcode: self.opcode,
+            result: self.result.clone(),
+            operands: self.operands.clone(),
+            parent_bb: self.parent_bb.clone(),
+            attributes: self.attributes.clone(),
+            modifier: self.modifier,
+            memory_space: self.memory_space,
+            cfg_epoch: self.cfg_epoch.clone(),
+            mnemonic: self.mnemonic.clone(),
         }
+    }
+}
 
-        Ok(())
+impl fmt::Display for Instruction {
+    /// 与源代码写法一致：操作数只写名称或常量文本，不带类型后缀
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let opts = PrinterOptions {
+            show_types: false,
+            ..PrinterOptions::default()
+        };
+        f.write_str(&print_instruction(self, &opts))
     }
 }
 
@@ -415,10 +818,9 @@ impl MemoryInstruction {
         } else {
             None // For Store, it doesn't produce a value
         };
-        MemoryInstruction {
-            instruction: Instruction::new(opcode, result_val, Vec::new(), modifier),
-            space,
-        }
+        let mut instruction = Instruction::new(opcode, result_val, Vec::new(), modifier);
+        instruction.set_memory_space(Some(space));
+        MemoryInstruction { instruction, space }
     }
 
     /// 获取内存空间
@@ -608,7 +1010,231 @@ impl MoveInstruction {
 }
 
 impl Opcode {
+    /// 所有操作码，按枚举声明顺序
+    pub const ALL: [Opcode; 62] = [
+        Opcode::Add,
+        Opcode::Sub,
+        Opcode::Mul,
+        Opcode::SAdd,
+        Opcode::SMul,
+        Opcode::Sra,
+        Opcode::Srl,
+        Opcode::Sll,
+        Opcode::And,
+        Opcode::Or,
+        Opcode::Xor,
+        Opcode::Not,
+        Opcode::CmpEq,
+        Opcode::CmpNe,
+        Opcode::CmpGt,
+        Opcode::CmpGe,
+        Opcode::CmpLt,
+        Opcode::CmpLe,
+        Opcode::PredAnd,
+        Opcode::PredOr,
+        Opcode::PredNot,
+        Opcode::Load,
+        Opcode::Store,
+        Opcode::RedSum,
+        Opcode::RedMax,
+        Opcode::RedMin,
+        Opcode::Range,
+        Opcode::Broadcast,
+        Opcode::Shuffle,
+        Opcode::Alloc,
+        Opcode::Free,
+        Opcode::Br,
+        Opcode::CondBr,
+        Opcode::Ret,
+        Opcode::Call,
+        Opcode::Mov,
+        Opcode::Phi,
+        Opcode::Extract,
+        Opcode::Bitcast,
+        Opcode::SExt,
+        Opcode::ZExt,
+        Opcode::Trunc,
+        Opcode::MulH,
+        Opcode::MulHU,
+        Opcode::MulHSU,
+        Opcode::MulAdd,
+        Opcode::MulSub,
+        Opcode::AddMul,
+        Opcode::SubMul,
+        Opcode::CmxMul,
+        Opcode::Div,
+        Opcode::DivU,
+        Opcode::Rem,
+        Opcode::RemU,
+        Opcode::SAddSat,
+        Opcode::SAddUSat,
+        Opcode::SSubSat,
+        Opcode::SSubUSat,
+        Opcode::RSub,
+        Opcode::ShuffleClbmv,
+        Opcode::SetCsr,
+        Opcode::Yield,
+    ];
+
+    /// 是否为未登记的操作码
+    pub fn is_unknown(&self) -> bool {
+        matches!(self, Opcode::Unknown)
+    }
+
+    /// 是否为硬件扩展指令
+    pub fn is_extension(&self) -> bool {
+        matches!(self, Opcode::Extension(_))
+    }
+
+    /// 根据助记符查找操作码，例如 "add" -> `Opcode::Add`
+    pub fn from_mnemonic(mnemonic: &str) -> Option<Opcode> {
+        Opcode::ALL.iter().copied().find(|op| op.as_str() == mnemonic)
+    }
+
+    /// 是否为两个操作数类型一致的二元运算（算术、逻辑、比较与谓词运算）
+    pub fn is_binary(&self) -> bool {
+        self.is_comparison()
+            || matches!(
+                self,
+                Opcode::Add
+                    | Opcode::Sub
+                    | Opcode::Mul
+                    | Opcode::SAdd
+                    | Opcode::SMul
+                    | Opcode::And
+                    | Opcode::Or
+                    | Opcode::Xor
+                    | Opcode::PredAnd
+                    | Opcode::PredOr
+                    | Opcode::Div
+                    | Opcode::DivU
+                    | Opcode::Rem
+                    | Opcode::RemU
+            )
+    }
+
+    /// 是否为比较指令
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            Opcode::CmpEq
+                | Opcode::CmpNe
+                | Opcode::CmpGt
+                | Opcode::CmpGe
+                | Opcode::CmpLt
+                | Opcode::CmpLe
+        )
+    }
+
+    /// 单个元素上的粗略执行代价，供内联、展开、LICM 等启发式使用
+    ///
+    /// 算术与逻辑运算为 1，乘法类为 3，除法与取余为 10，访存为 4，调用为 20；
+    /// `phi` 与 `extract` 不产生实际指令，代价为 0。
+    pub fn cost(&self) -> u32 {
+        match self {
+            Opcode::Phi | Opcode::Extract => 0,
+            Opcode::Mul
+            | Opcode::SMul
+            | Opcode::MulH
+            | Opcode::MulHU
+            | Opcode::MulHSU
+            | Opcode::MulAdd
+            | Opcode::MulSub
+            | Opcode::AddMul
+            | Opcode::SubMul
+            | Opcode::CmxMul => 3,
+            Opcode::Div | Opcode::DivU | Opcode::Rem | Opcode::RemU => 10,
+            Opcode::Load | Opcode::Store | Opcode::Alloc | Opcode::Free => 4,
+            Opcode::RedSum | Opcode::RedMax | Opcode::RedMin => 2,
+            Opcode::Call => 20,
+            _ => 1,
+        }
+    }
+
+    /// 是否可按谓词掩码逐元素执行：带 `.p` 修饰时最后一个操作数为 `<pred N>` 掩码
+    ///
+    /// 比较与谓词运算的 `.p` 表示结果为谓词，不带掩码。
+    pub fn is_maskable(&self) -> bool {
+        matches!(
+            self,
+            Opcode::Add
+                | Opcode::Sub
+                | Opcode::Mul
+                | Opcode::SAdd
+                | Opcode::SMul
+                | Opcode::Sra
+                | Opcode::Srl
+                | Opcode::Sll
+                | Opcode::And
+                | Opcode::Or
+                | Opcode::Xor
+                | Opcode::Not
+                | Opcode::Mov
+                | Opcode::MulH
+                | Opcode::MulHU
+                | Opcode::MulHSU
+                | Opcode::MulAdd
+                | Opcode::MulSub
+                | Opcode::AddMul
+                | Opcode::SubMul
+                | Opcode::CmxMul
+                | Opcode::Div
+                | Opcode::DivU
+                | Opcode::Rem
+                | Opcode::RemU
+                | Opcode::SAddSat
+                | Opcode::SAddUSat
+                | Opcode::SSubSat
+                | Opcode::SSubUSat
+                | Opcode::RSub
+        )
+    }
+
+    /// 可掩码操作码的数据操作数个数（不含掩码）：`not` / `mov` 为一元，
+    /// 乘加类为三元，其余为二元
+    pub fn data_arity(&self) -> usize {
+        match self {
+            Opcode::Not | Opcode::Mov => 1,
+            Opcode::MulAdd | Opcode::MulSub | Opcode::AddMul | Opcode::SubMul => 3,
+            _ => 2,
+        }
+    }
+
+    /// 是否为类型转换指令，文本形式为 `op %x : <目标类型>`
+    pub fn is_cast(&self) -> bool {
+        matches!(
+            self,
+            Opcode::Bitcast | Opcode::SExt | Opcode::ZExt | Opcode::Trunc
+        )
+    }
+
+    /// 是否为屏障指令：修改机器状态（CSR）或让出执行权，
+    /// 其它指令不能跨越它被重排
+    ///
+    /// 语义对编译器不透明的 `Opcode::Unknown` 与 `Opcode::Extension` 也按屏障保守处理。
+    pub fn is_barrier(&self) -> bool {
+        matches!(
+            self,
+            Opcode::SetCsr | Opcode::Yield | Opcode::Unknown | Opcode::Extension(_)
+        )
+    }
+
+    /// 是否可能在运行时陷入：除法与取余在除数为 0 时陷入
+    ///
+    /// 这类指令虽然没有副作用，但不能被移动到原本不一定执行它的位置。
+    pub fn may_trap(&self) -> bool {
+        matches!(self, Opcode::Div | Opcode::DivU | Opcode::Rem | Opcode::RemU)
+    }
+
+    /// 是否为终结指令（只能出现在基本块末尾）
+    pub fn is_terminator(&self) -> bool {
+        matches!(self, Opcode::Br | Opcode::CondBr | Opcode::Ret)
+    }
+
     /// 返回指令助记符的 &str 形式，等价于 Display，但避免分配
+    ///
+    /// 扩展指令只返回 `ext`（不含编号）；未知指令与扩展指令在源代码中的助记符
+    /// 见 `Instruction::mnemonic`。
     pub fn as_str(&self) -> &'static str {
         match self {
             Opcode::Add => "add",
@@ -645,8 +1271,14 @@ impl Opcode {
             Opcode::Br => "br",
             Opcode::CondBr => "condbr",
             Opcode::Ret => "ret",
+            Opcode::Call => "call",
             Opcode::Mov => "mov",
             Opcode::Phi => "phi",
+            Opcode::Extract => "extract",
+            Opcode::Bitcast => "bitcast",
+            Opcode::SExt => "sext",
+            Opcode::ZExt => "zext",
+            Opcode::Trunc => "trunc",
             Opcode::MulH => "mulh",
             Opcode::MulHU => "mulhu",
             Opcode::MulHSU => "mulhsu",
@@ -667,6 +1299,8 @@ impl Opcode {
             Opcode::ShuffleClbmv => "shuffle_clbmv",
             Opcode::SetCsr => "setcsr",
             Opcode::Yield => "yield",
+            Opcode::Unknown => "<unknown>",
+            Opcode::Extension(_) => "ext",
         }
     }
 }
@@ -3,22 +3,26 @@
 // 这个模块包含中间表示(IR)的所有核心数据结构和操作
 
 // 子模块
+#[cfg(feature = "arena")]
+pub mod arena;
 pub mod basic_block;
 pub mod function;
 pub mod instruction;
 pub mod module;
 pub mod operand;
+pub mod printer;
 pub mod types;
 pub mod value;
 
 // 重新导出常用类型
 pub use basic_block::{BasicBlock, BasicBlockRef};
-pub use function::{Argument, ArgumentRef, Function, FunctionRef};
-pub use instruction::{Instruction, InstructionModifier, InstructionRef, Opcode};
-pub use module::{Module, ModuleRef};
-pub use operand::{Operand, OperandRef};
+pub use function::{Argument, ArgumentRef, Function, FunctionRef, Linkage};
+pub use instruction::{BuildError, Instruction, InstructionModifier, InstructionRef, Opcode};
+pub use module::{LinkError, Module, ModuleRef};
+pub use operand::{GlobalRef, Operand, OperandKind, OperandRef};
+pub use printer::{PrinterOptions, print_module};
 pub use types::{Type, TypeKind, TypeRef};
-pub use value::{Value, ValueRef};
+pub use value::{Constant, Value, ValueRef};
 
 // 内存空间枚举
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -29,6 +33,13 @@ pub enum MemorySpace {
     Parameter, // 参数内存空间
 }
 
+impl MemorySpace {
+    /// 两个内存空间是否兼容：相同，或任一方为 `generic`（通配）
+    pub fn spaces_compatible(a: MemorySpace, b: MemorySpace) -> bool {
+        a == b || a == MemorySpace::Generic || b == MemorySpace::Generic
+    }
+}
+
 impl std::fmt::Display for MemorySpace {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -3,36 +3,67 @@
 // 这个模块定义了 VIL 的模块类，包含函数和全局内存空间
 
 use crate::ir::MemorySpace;
-use crate::ir::function::FunctionRef; // 导入 FunctionRef
-use crate::ir::types::{Type, TypeRef};
+use crate::ir::function::{Function, FunctionRef, Linkage};
+use crate::ir::types::{Type, TypeKind, TypeRef};
 use crate::ir::value::Value;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::rc::Rc;
 
 // Module 引用
 pub type ModuleRef = Rc<RefCell<Module>>;
 
 /// 全局内存空间定义
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct GlobalMemorySpace {
     name: String,
     space: MemorySpace,
     element_type: TypeRef,
     length: u32,
+    alignment: u32, // 对齐字节数
+}
+
+impl Hash for GlobalMemorySpace {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.space.hash(state);
+        self.element_type.borrow().hash(state);
+        self.length.hash(state);
+        self.alignment.hash(state);
+    }
 }
 
 impl GlobalMemorySpace {
+    /// 创建全局内存空间，对齐取元素类型的默认对齐（见 `default_alignment`）
     pub fn new(name: String, space: MemorySpace, element_type: TypeRef, length: u32) -> Self {
+        let alignment = Self::default_alignment(&element_type);
         GlobalMemorySpace {
             name,
             space,
             element_type,
             length,
+            alignment,
         }
     }
 
+    /// 链式接口：设置对齐字节数
+    pub fn with_alignment(mut self, alignment: u32) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// 元素类型的默认对齐：标量元素的字节宽度，向量按其元素计算，至少为 1
+    pub fn default_alignment(element_type: &TypeRef) -> u32 {
+        let element_type = element_type.borrow();
+        let bits = match element_type.get_kind() {
+            TypeKind::Vector(elem, _) => elem.borrow().get_bit_width(),
+            _ => element_type.get_bit_width(),
+        };
+        bits.div_ceil(8).max(1)
+    }
+
     pub fn get_name(&self) -> &str {
         &self.name
     }
@@ -48,18 +79,86 @@ impl GlobalMemorySpace {
     pub fn get_length(&self) -> u32 {
         self.length
     }
+
+    /// 获取对齐字节数
+    pub fn get_alignment(&self) -> u32 {
+        self.alignment
+    }
+
+    /// 占用的字节数：向量元素类型自带长度，标量元素乘以长度
+    ///
+    /// 以 u64 计算，避免大长度的标量内存在位数相乘时溢出。
+    pub fn byte_size(&self) -> u64 {
+        let element_type = self.element_type.borrow();
+        let bits = u64::from(element_type.get_bit_width());
+        let bits = if matches!(element_type.get_kind(), TypeKind::Vector(..)) {
+            bits
+        } else {
+            bits * u64::from(self.length)
+        };
+        bits.div_ceil(8)
+    }
 }
 
 impl fmt::Display for GlobalMemorySpace {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            ".memory {} [{}] <{} x {}>",
-            self.name,
-            self.space,
-            self.element_type.borrow(),
-            self.length
-        )
+        write!(f, ".memory {} [{}] ", self.name, self.space)?;
+        // 向量元素类型自带长度；标量元素需要显式长度，与解析语法保持一致
+        if matches!(self.element_type.borrow().get_kind(), TypeKind::Vector(..)) {
+            write!(f, "{}", self.element_type.borrow())?;
+        } else {
+            write!(f, "{} {}", self.element_type.borrow(), self.length)?;
+        }
+        // 只在与默认对齐不同时输出
+        if self.alignment != Self::default_alignment(&self.element_type) {
+            write!(f, " .align {}", self.alignment)?;
+        }
+        Ok(())
+    }
+}
+
+/// 模块链接错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkError {
+    /// 两个模块定义了同名函数
+    DuplicateFunction(String),
+    /// 两个模块定义了同名全局内存空间
+    DuplicateGlobal(String),
+    /// 同名类型别名指向不同的类型
+    ConflictingTypeAlias(String),
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkError::DuplicateFunction(name) => write!(f, "函数 '{}' 重复定义", name),
+            LinkError::DuplicateGlobal(name) => {
+                write!(f, "全局内存空间 '@{}' 重复定义", name)
+            }
+            LinkError::ConflictingTypeAlias(name) => {
+                write!(f, "类型别名 '{}' 在两个模块中指向不同的类型", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LinkError {}
+
+/// 是否为模块私有的函数：`internal` 定义对其它模块不可见，链接时不参与同名解析
+///
+/// 外部函数声明（`.declare`）总是引用其它模块的符号，不属于私有函数。
+fn is_private(func: &Function) -> bool {
+    !func.is_declaration() && func.get_linkage() == Linkage::Internal
+}
+
+/// 链接时同名可见函数的优先级：声明 < weak 定义 < 普通定义
+fn link_rank(func: &Function) -> u8 {
+    if func.is_declaration() {
+        0
+    } else if func.get_linkage() == Linkage::Weak {
+        1
+    } else {
+        2
     }
 }
 
@@ -67,8 +166,13 @@ impl fmt::Display for GlobalMemorySpace {
 #[derive(Debug)]
 pub struct Module {
     value: Value, // 模块名
-    functions: HashMap<String, FunctionRef>,
-    global_memory_spaces: HashMap<String, Rc<RefCell<GlobalMemorySpace>>>,
+    // 函数与全局内存空间按插入顺序保存，另建名称索引，保证遍历与打印顺序稳定
+    functions: Vec<FunctionRef>,
+    function_index: HashMap<String, usize>,
+    global_memory_spaces: Vec<Rc<RefCell<GlobalMemorySpace>>>,
+    memory_index: HashMap<String, usize>,
+    type_aliases: HashMap<String, TypeRef>, // 命名类型别名，如 %Vec4 -> <i32 x 4>
+    entry_function: Option<String>,         // 程序入口函数名
 }
 
 impl Module {
@@ -77,8 +181,12 @@ impl Module {
         let void_type = Type::get_void_type(); // 模块没有具体类型
         Module {
             value: Value::new(void_type, name),
-            functions: HashMap::new(),
-            global_memory_spaces: HashMap::new(),
+            functions: Vec::new(),
+            function_index: HashMap::new(),
+            global_memory_spaces: Vec::new(),
+            memory_index: HashMap::new(),
+            type_aliases: HashMap::new(),
+            entry_function: None,
         }
     }
 
@@ -87,36 +195,299 @@ impl Module {
         self.value.get_name()
     }
 
-    /// 添加函数
+    /// 添加函数（同名函数原位替换）
     pub fn add_function(&mut self, func: FunctionRef) {
-        self.functions
-            .insert(func.borrow().get_name().to_string(), func.clone());
+        let name = func.borrow().get_name().to_string();
+        match self.function_index.get(&name) {
+            Some(&idx) => self.functions[idx] = func,
+            None => {
+                self.function_index.insert(name, self.functions.len());
+                self.functions.push(func);
+            }
+        }
     }
 
     /// 通过名称获取函数
     pub fn get_function(&self, name: &str) -> Option<FunctionRef> {
-        self.functions.get(name).cloned()
+        self.function_index
+            .get(name)
+            .map(|&idx| self.functions[idx].clone())
+    }
+
+    /// 移除函数，返回被移除的函数
+    pub fn remove_function(&mut self, name: &str) -> Option<FunctionRef> {
+        let idx = self.function_index.remove(name)?;
+        let func = self.functions.remove(idx);
+        if self.entry_function.as_deref() == Some(name) {
+            self.entry_function = None;
+        }
+        // 后续函数的位置前移
+        for pos in self.function_index.values_mut() {
+            if *pos > idx {
+                *pos -= 1;
+            }
+        }
+        Some(func)
+    }
+
+    /// 重命名函数；旧名称不存在或新名称已被占用时返回 false
+    ///
+    /// 函数保持原有位置。
+    pub fn rename_function(&mut self, old: &str, new: &str) -> bool {
+        if old == new {
+            return self.function_index.contains_key(old);
+        }
+        if self.function_index.contains_key(new) {
+            return false;
+        }
+        let Some(idx) = self.function_index.remove(old) else {
+            return false;
+        };
+        self.functions[idx].borrow_mut().set_name(new.to_string());
+        self.function_index.insert(new.to_string(), idx);
+        if self.entry_function.as_deref() == Some(old) {
+            self.entry_function = Some(new.to_string());
+        }
+        true
     }
 
-    /// 获取所有函数
+    /// 将指定函数设为程序入口；函数不存在时返回 false
+    pub fn set_entry_function(&mut self, name: &str) -> bool {
+        if !self.function_index.contains_key(name) {
+            return false;
+        }
+        self.entry_function = Some(name.to_string());
+        true
+    }
+
+    /// 获取程序入口函数
+    pub fn get_entry_function(&self) -> Option<FunctionRef> {
+        self.entry_function
+            .as_deref()
+            .and_then(|name| self.get_function(name))
+    }
+
+    /// 获取所有函数（按添加顺序）
     pub fn get_functions(&self) -> Vec<FunctionRef> {
-        self.functions.values().cloned().collect()
+        self.functions.clone()
     }
 
-    /// 添加全局内存空间
+    /// 按添加顺序对每个函数调用 `f`，期间允许通过 `f` 的模块参数增删函数
+    ///
+    /// 遍历的是调用时函数列表的快照：遍历中新添加的函数不会被访问，
+    /// 已被移除或替换的函数会被跳过。
+    pub fn for_each_function_mut(&mut self, mut f: impl FnMut(&mut Module, &FunctionRef)) {
+        let snapshot = self.functions.clone();
+        for func in &snapshot {
+            let name = func.borrow().get_name().to_string();
+            let still_present = self
+                .function_index
+                .get(&name)
+                .is_some_and(|&idx| Rc::ptr_eq(&self.functions[idx], func));
+            if still_present {
+                f(self, func);
+            }
+        }
+    }
+
+    /// 添加全局内存空间（同名内存空间原位替换）
     pub fn add_global_memory_space(&mut self, mem_space: Rc<RefCell<GlobalMemorySpace>>) {
-        self.global_memory_spaces
-            .insert(mem_space.borrow().get_name().to_string(), mem_space.clone());
+        let name = mem_space.borrow().get_name().to_string();
+        match self.memory_index.get(&name) {
+            Some(&idx) => self.global_memory_spaces[idx] = mem_space,
+            None => {
+                self.memory_index.insert(name, self.global_memory_spaces.len());
+                self.global_memory_spaces.push(mem_space);
+            }
+        }
     }
 
     /// 通过名称获取全局内存空间
     pub fn get_global_memory_space(&self, name: &str) -> Option<Rc<RefCell<GlobalMemorySpace>>> {
-        self.global_memory_spaces.get(name).cloned()
+        self.memory_index
+            .get(name)
+            .map(|&idx| self.global_memory_spaces[idx].clone())
     }
 
-    /// 获取所有全局内存空间
+    /// 获取所有全局内存空间（按添加顺序）
     pub fn get_global_memory_spaces(&self) -> Vec<Rc<RefCell<GlobalMemorySpace>>> {
-        self.global_memory_spaces.values().cloned().collect()
+        self.global_memory_spaces.clone()
+    }
+
+    /// 添加类型别名
+    pub fn add_type_alias(&mut self, name: String, type_: TypeRef) {
+        self.type_aliases.insert(name, type_);
+    }
+
+    /// 通过名称获取类型别名指向的类型
+    pub fn get_type_alias(&self, name: &str) -> Option<TypeRef> {
+        self.type_aliases.get(name).cloned()
+    }
+
+    /// 获取所有类型别名 (名称, 类型)，按名称排序
+    pub fn get_type_aliases(&self) -> Vec<(String, TypeRef)> {
+        let mut aliases: Vec<_> = self
+            .type_aliases
+            .iter()
+            .map(|(name, type_)| (name.clone(), type_.clone()))
+            .collect();
+        aliases.sort_by(|a, b| a.0.cmp(&b.0));
+        aliases
+    }
+
+    /// 将另一个模块链接进本模块
+    ///
+    /// `other` 的类型别名、全局内存空间与函数依次追加在本模块已有内容之后，保持各自的
+    /// 原有顺序；同名且类型相同的类型别名视为同一个，同名的外部函数声明（`.declare`）
+    /// 由另一方的 `external` / `weak` 定义取代，`weak` 定义由另一方的普通定义取代
+    /// （两者皆为 `weak` 时保留本模块的）。
+    /// `internal` 定义是模块私有的：与另一方重名时改用 `<名称>.N` 形式的新名称，
+    /// 既不报告重复定义，也不满足另一方的声明。
+    /// 可见的普通函数定义或全局内存空间重名、
+    /// 同名类型别名指向不同类型时返回错误，且本模块保持不变。
+    /// 本模块没有入口函数时沿用 `other` 的入口函数。
+    pub fn merge(&mut self, other: Module) -> Result<(), LinkError> {
+        for (name, type_) in other.get_type_aliases() {
+            if let Some(existing) = self.type_aliases.get(&name)
+                && *existing.borrow() != *type_.borrow()
+            {
+                return Err(LinkError::ConflictingTypeAlias(name));
+            }
+        }
+        if let Some(mem) = other
+            .global_memory_spaces
+            .iter()
+            .find(|mem| self.memory_index.contains_key(mem.borrow().get_name()))
+        {
+            return Err(LinkError::DuplicateGlobal(mem.borrow().get_name().to_string()));
+        }
+        if let Some(func) = other.functions.iter().find(|func| {
+            let func = func.borrow();
+            self.get_function(func.get_name()).is_some_and(|existing| {
+                let existing = existing.borrow();
+                !is_private(&existing)
+                    && !is_private(&func)
+                    && link_rank(&existing) == 2
+                    && link_rank(&func) == 2
+            })
+        }) {
+            return Err(LinkError::DuplicateFunction(
+                func.borrow().get_name().to_string(),
+            ));
+        }
+
+        let other_entry = other.get_entry_function();
+        let incoming: HashSet<String> = other
+            .functions
+            .iter()
+            .map(|func| func.borrow().get_name().to_string())
+            .collect();
+        self.type_aliases.extend(other.type_aliases);
+        for mem in other.global_memory_spaces {
+            self.add_global_memory_space(mem);
+        }
+        for func in other.functions {
+            let name = func.borrow().get_name().to_string();
+            if let Some(existing) = self.get_function(&name) {
+                // 私有函数改名让出名称；调用点引用的是函数对象本身，无需改写
+                if is_private(&func.borrow()) {
+                    let fresh = self.fresh_function_name(&name, &incoming);
+                    func.borrow_mut().set_name(fresh);
+                } else if is_private(&existing.borrow()) {
+                    let fresh = self.fresh_function_name(&name, &incoming);
+                    self.rename_function(&name, &fresh);
+                } else if link_rank(&existing.borrow()) >= link_rank(&func.borrow()) {
+                    // 同名可见函数保留优先级更高的一方：定义优先于声明，普通定义优先于 weak 定义
+                    continue;
+                }
+            }
+            self.add_function(func);
+        }
+        if self.entry_function.is_none() {
+            self.entry_function = other_entry.map(|func| func.borrow().get_name().to_string());
+        }
+        Ok(())
+    }
+
+    /// 为链接时改名的私有函数选择 `<名称>.N` 形式的新名称，避开两个模块中已有的名称
+    fn fresh_function_name(&self, name: &str, incoming: &HashSet<String>) -> String {
+        (1..)
+            .map(|n| format!("{}.{}", name, n))
+            .find(|fresh| !self.function_index.contains_key(fresh) && !incoming.contains(fresh))
+            .unwrap()
+    }
+
+    /// 按内容比较两个模块（名称、类型别名、全局内存空间、函数），与函数的插入顺序无关
+    pub fn structurally_eq(&self, other: &Module) -> bool {
+        self.get_name() == other.get_name()
+            && self.entry_function == other.entry_function
+            && self.type_aliases == other.type_aliases
+            && self.global_memory_spaces.len() == other.global_memory_spaces.len()
+            && self.global_memory_spaces.iter().all(|mem| {
+                other
+                    .get_global_memory_space(mem.borrow().get_name())
+                    .is_some_and(|o| *mem.borrow() == *o.borrow())
+            })
+            && self.functions.len() == other.functions.len()
+            && self.functions.iter().all(|func| {
+                other
+                    .get_function(func.borrow().get_name())
+                    .is_some_and(|o| func.borrow().structurally_eq(&o.borrow()))
+            })
+    }
+
+    /// 计算模块内容的确定性指纹，可用作缓存与增量编译的键
+    ///
+    /// 覆盖与 `structurally_eq` 相同的内容：全局内存空间与函数按名称排序后哈希，
+    /// 与插入顺序、`Rc` 地址和 HashMap 遍历顺序无关。使用固定算法（FNV-1a），
+    /// 结果在不同进程之间保持稳定。
+    pub fn content_hash(&self) -> u64 {
+        let mut state = Fnv1aHasher::default();
+        self.get_name().hash(&mut state);
+        self.entry_function.hash(&mut state);
+        let aliases = self.get_type_aliases();
+        aliases.len().hash(&mut state);
+        for (name, type_) in &aliases {
+            name.hash(&mut state);
+            type_.borrow().hash(&mut state);
+        }
+
+        let mut memories = self.global_memory_spaces.clone();
+        memories.sort_by(|a, b| a.borrow().get_name().cmp(b.borrow().get_name()));
+        memories.len().hash(&mut state);
+        for mem in &memories {
+            mem.borrow().hash(&mut state);
+        }
+
+        let mut functions = self.functions.clone();
+        functions.sort_by(|a, b| a.borrow().get_name().cmp(b.borrow().get_name()));
+        functions.len().hash(&mut state);
+        for func in &functions {
+            func.borrow().hash_structure(&mut state);
+        }
+        state.finish()
+    }
+}
+
+/// FNV-1a 哈希器：算法固定，不依赖标准库 `DefaultHasher` 的实现细节
+struct Fnv1aHasher(u64);
+
+impl Default for Fnv1aHasher {
+    fn default() -> Self {
+        Fnv1aHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1aHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
     }
 }
 
@@ -125,9 +496,16 @@ impl fmt::Display for Module {
         writeln!(f, ".module {}", self.get_name())?;
         writeln!(f)?;
 
+        for (name, type_) in self.get_type_aliases() {
+            writeln!(f, ".type {} = {}", name, type_.borrow())?;
+        }
+
         for mem_space in self.get_global_memory_spaces() {
             writeln!(f, "{}", mem_space.borrow())?;
         }
+        if let Some(entry) = &self.entry_function {
+            writeln!(f, ".entry @{}", entry)?;
+        }
         writeln!(f)?;
 
         for func in self.get_functions() {
@@ -140,9 +518,225 @@ impl fmt::Display for Module {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ir::basic_block::BasicBlock;
     use crate::ir::function::Function;
+    use crate::ir::instruction::{Instruction, InstructionModifier, Opcode};
     use crate::ir::types::{Type, TypeKind};
 
+    fn parse_owned(source: &str) -> Module {
+        let module = crate::frontend::parse_vil(source, "link.vil").expect("应成功解析模块");
+        Rc::try_unwrap(module).unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_merge_modules() {
+        let mut main = parse_owned(
+            r#".module main
+.memory a [sram] i32 4
+.function f() {
+    ret
+}
+.function g() {
+    ret
+}
+"#,
+        );
+        let lib = parse_owned(
+            r#".module lib
+.type %Word = i32
+.memory b [vspm] i32 8
+.function h() {
+    ret
+}
+.function k() {
+    ret
+}
+"#,
+        );
+        main.merge(lib).unwrap();
+
+        // 另一个模块的内容按原有顺序追加在已有内容之后
+        let names: Vec<String> = main
+            .get_functions()
+            .iter()
+            .map(|f| f.borrow().get_name().to_string())
+            .collect();
+        assert_eq!(names, ["f", "g", "h", "k"]);
+        let globals: Vec<String> = main
+            .get_global_memory_spaces()
+            .iter()
+            .map(|m| m.borrow().get_name().to_string())
+            .collect();
+        assert_eq!(globals, ["a", "b"]);
+        assert!(main.get_type_alias("%Word").is_some());
+        assert_eq!(main.get_name(), "main");
+    }
+
+    #[test]
+    fn test_merge_resolves_declarations() {
+        let mut main = parse_owned(
+            r#".module main
+.declare @h() -> i32
+external .function f() -> i32 {
+    %r = call @h()
+    ret %r
+}
+"#,
+        );
+        let lib = parse_owned(
+            r#".module lib
+.declare @f() -> i32
+external .function h() -> i32 {
+    ret 1
+}
+"#,
+        );
+        main.merge(lib).unwrap();
+        // 声明被定义取代，且保持其原有位置
+        let names: Vec<String> = main
+            .get_functions()
+            .iter()
+            .map(|f| f.borrow().get_name().to_string())
+            .collect();
+        assert_eq!(names, ["h", "f"]);
+        assert!(!main.get_function("h").unwrap().borrow().is_declaration());
+        assert!(!main.get_function("f").unwrap().borrow().is_declaration());
+    }
+
+    #[test]
+    fn test_merge_weak_definition_is_overridden() {
+        let mut main = parse_owned(
+            r#".module main
+weak .function hook() -> i32 {
+    ret 0
+}
+"#,
+        );
+        let lib = parse_owned(
+            r#".module lib
+external .function hook() -> i32 {
+    ret 1
+}
+"#,
+        );
+        main.merge(lib).unwrap();
+        let hook = main.get_function("hook").unwrap();
+        assert_eq!(hook.borrow().get_linkage(), Linkage::External);
+
+        // 两个 weak 定义不冲突，保留先出现的一个
+        let weak = parse_owned(".module w\nweak .function hook() -> i32 {\n    ret 2\n}\n");
+        main.merge(weak).unwrap();
+        assert!(Rc::ptr_eq(&main.get_function("hook").unwrap(), &hook));
+    }
+
+    #[test]
+    fn test_merge_internal_functions_are_private() {
+        let mut main = parse_owned(
+            r#".module main
+.declare @h() -> i32
+.function f() -> i32 {
+    %r = call @h()
+    ret %r
+}
+.function g() -> i32 {
+    %r = call @f()
+    ret %r
+}
+"#,
+        );
+        let lib = parse_owned(
+            r#".module lib
+.function h() -> i32 {
+    ret 1
+}
+.function f() -> i32 {
+    ret 2
+}
+.function f.1() -> i32 {
+    %r = call @f()
+    ret %r
+}
+"#,
+        );
+        let main_f = main.get_function("f").unwrap();
+        let lib_f = lib.get_function("f").unwrap();
+        main.merge(lib).unwrap();
+
+        // 同名的 internal 定义不冲突，另一方改名且避开两个模块中已有的名称
+        assert!(Rc::ptr_eq(&main.get_function("f").unwrap(), &main_f));
+        assert!(Rc::ptr_eq(&main.get_function("f.2").unwrap(), &lib_f));
+        // internal 定义不满足另一方的声明：本模块的声明改名后仍是声明
+        assert!(main.get_function("h").unwrap().borrow().is_declaration());
+        assert!(!main.get_function("h.1").unwrap().borrow().is_declaration());
+
+        // 调用点引用函数对象，改名后打印出新名称
+        let text = main.to_string();
+        assert!(text.contains("call @f.2()"), "{}", text);
+        assert!(text.contains("%r = call @f()"), "{}", text);
+    }
+
+    #[test]
+    fn test_merge_name_collision() {
+        let mut main = parse_owned(".module main\nexternal .function f() {\n    ret\n}\n");
+        let lib = parse_owned(
+            r#".module lib
+.function g() {
+    ret
+}
+external .function f() {
+    ret
+}
+"#,
+        );
+        let err = main.merge(lib).unwrap_err();
+        assert_eq!(err, LinkError::DuplicateFunction("f".to_string()));
+        assert!(err.to_string().contains("重复定义"));
+        // 出错时本模块保持不变
+        assert_eq!(main.get_functions().len(), 1);
+        assert!(main.get_function("g").is_none());
+
+        let lib = parse_owned(".module lib\n.type %T = i16\n");
+        let mut main = parse_owned(".module main\n.type %T = i32\n");
+        assert_eq!(
+            main.merge(lib).unwrap_err(),
+            LinkError::ConflictingTypeAlias("%T".to_string())
+        );
+    }
+
+    #[test]
+    fn test_for_each_function_mut_snapshot() {
+        let new_function = |name: &str| {
+            Rc::new(RefCell::new(Function::new(
+                name.to_string(),
+                Type::get_void_type(),
+                vec![],
+            )))
+        };
+        let mut module = Module::new("m".to_string());
+        module.add_function(new_function("a"));
+        module.add_function(new_function("b"));
+        module.add_function(new_function("c"));
+
+        let mut visited = Vec::new();
+        module.for_each_function_mut(|module, func| {
+            let name = func.borrow().get_name().to_string();
+            // 遍历中新增的函数不被访问，已移除的函数被跳过
+            if name == "a" {
+                module.add_function(new_function("a.clone"));
+                module.remove_function("b");
+            }
+            visited.push(name);
+        });
+
+        assert_eq!(visited, vec!["a", "c"]);
+        let names: Vec<String> = module
+            .get_functions()
+            .iter()
+            .map(|f| f.borrow().get_name().to_string())
+            .collect();
+        assert_eq!(names, vec!["a", "c", "a.clone"]);
+    }
+
     #[test]
     fn test_module_creation() {
         let module = Module::new("my_module".to_string());
@@ -175,4 +769,188 @@ mod tests {
         assert!(module.get_global_memory_space("vspm_buffer").is_some());
         assert_eq!(module.get_global_memory_spaces().len(), 1);
     }
+
+    #[test]
+    fn test_type_alias() {
+        let mut module = Module::new("test_module".to_string());
+        let vec_type = Type::get_vector_type(Type::get_int_type(TypeKind::Int32), 4);
+        module.add_type_alias("%Vec4".to_string(), vec_type);
+        assert_eq!(
+            module.get_type_alias("%Vec4").unwrap().borrow().to_string(),
+            "<i32 x 4>"
+        );
+        assert!(module.get_type_alias("%Missing").is_none());
+        assert!(module.to_string().contains(".type %Vec4 = <i32 x 4>"));
+    }
+
+    /// 独立构建的模块；`reversed` 控制函数的添加顺序
+    fn build_module(reversed: bool) -> Module {
+        let mut module = Module::new("m".to_string());
+        module.add_global_memory_space(Rc::new(RefCell::new(GlobalMemorySpace::new(
+            "buf".to_string(),
+            MemorySpace::SRAM,
+            Type::get_int_type(TypeKind::Int32),
+            16,
+        ))));
+        let mut names = vec!["f", "g"];
+        if reversed {
+            names.reverse();
+        }
+        for name in names {
+            let func = Rc::new(RefCell::new(Function::new(
+                name.to_string(),
+                Type::get_void_type(),
+                Vec::new(),
+            )));
+            let bb = Rc::new(RefCell::new(BasicBlock::new(
+                "entry".to_string(),
+                Some(func.clone()),
+            )));
+            let ret = Instruction::new(Opcode::Ret, None, Vec::new(), InstructionModifier::None);
+            bb.borrow_mut()
+                .add_instruction(Rc::new(RefCell::new(ret)), bb.clone());
+            func.borrow_mut().add_basic_block(bb);
+            module.add_function(func);
+        }
+        module
+    }
+
+    #[test]
+    fn test_structural_equality() {
+        let a = build_module(false);
+        let b = build_module(true);
+        assert!(a.structurally_eq(&b));
+
+        // 修改一条指令后不再相等
+        let g = b.get_function("g").unwrap();
+        let entry = g.borrow().get_entry_block().unwrap();
+        let ret = entry.borrow().get_instructions()[0].clone();
+        ret.borrow_mut().add_attribute("volatile".to_string());
+        assert!(!a.structurally_eq(&b));
+
+        // 内存空间不同
+        let mut c = build_module(false);
+        c.add_global_memory_space(Rc::new(RefCell::new(GlobalMemorySpace::new(
+            "buf".to_string(),
+            MemorySpace::VSPM,
+            Type::get_int_type(TypeKind::Int32),
+            16,
+        ))));
+        assert!(!a.structurally_eq(&c));
+    }
+
+    #[test]
+    fn test_function_order_is_stable() {
+        let mut module = Module::new("test_module".to_string());
+        let names = ["zeta", "alpha", "mid", "beta", "omega"];
+        for name in names {
+            module.add_function(Rc::new(RefCell::new(Function::new(
+                name.to_string(),
+                Type::get_void_type(),
+                Vec::new(),
+            ))));
+        }
+
+        let order = |module: &Module| -> Vec<String> {
+            module
+                .get_functions()
+                .iter()
+                .map(|f| f.borrow().get_name().to_string())
+                .collect()
+        };
+        for _ in 0..3 {
+            assert_eq!(order(&module), names);
+        }
+
+        // 删除与重命名不打乱其余函数的顺序
+        module.remove_function("mid");
+        assert!(module.rename_function("alpha", "first"));
+        assert_eq!(order(&module), ["zeta", "first", "beta", "omega"]);
+        assert_eq!(
+            module.get_function("omega").unwrap().borrow().get_name(),
+            "omega"
+        );
+    }
+
+    #[test]
+    fn test_remove_function() {
+        let mut module = Module::new("test_module".to_string());
+        let func = Rc::new(RefCell::new(Function::new(
+            "f".to_string(),
+            Type::get_void_type(),
+            Vec::new(),
+        )));
+        module.add_function(func.clone());
+
+        let removed = module.remove_function("f").unwrap();
+        assert!(Rc::ptr_eq(&removed, &func));
+        assert!(module.get_function("f").is_none());
+        assert!(module.remove_function("f").is_none());
+    }
+
+    #[test]
+    fn test_rename_function() {
+        let mut module = Module::new("test_module".to_string());
+        for name in ["f", "g"] {
+            module.add_function(Rc::new(RefCell::new(Function::new(
+                name.to_string(),
+                Type::get_void_type(),
+                Vec::new(),
+            ))));
+        }
+
+        assert!(module.rename_function("f", "h"));
+        assert!(module.get_function("f").is_none());
+        assert_eq!(module.get_function("h").unwrap().borrow().get_name(), "h");
+
+        // 新名称已存在或旧名称不存在时失败，且不修改模块
+        assert!(!module.rename_function("h", "g"));
+        assert!(!module.rename_function("missing", "x"));
+        assert!(module.get_function("h").is_some());
+        assert!(module.get_function("g").is_some());
+    }
+
+    #[test]
+    fn test_content_hash_matches_structural_equality() {
+        let a = build_module(false);
+        let b = build_module(true);
+        assert_eq!(a.content_hash(), b.content_hash());
+
+        let source = r#".module m
+.function f(.param %a i32) -> i32 {
+entry:
+    %x = add %a, 1
+    ret %x
+}
+"#;
+        let parsed_a = crate::frontend::parse_vil(source, "a.vil").unwrap();
+        let parsed_b = crate::frontend::parse_vil(source, "b.vil").unwrap();
+        assert_eq!(
+            parsed_a.borrow().content_hash(),
+            parsed_b.borrow().content_hash()
+        );
+    }
+
+    #[test]
+    fn test_content_hash_changes_with_opcode() {
+        let source = r#".module m
+.function f(.param %a i32) -> i32 {
+entry:
+    %x = add %a, 1
+    ret %x
+}
+"#;
+        let a = crate::frontend::parse_vil(source, "a.vil").unwrap();
+        let b = crate::frontend::parse_vil(&source.replace("add", "sub"), "b.vil").unwrap();
+        assert_ne!(a.borrow().content_hash(), b.borrow().content_hash());
+
+        // 只改变函数属性同样改变指纹
+        let before = a.borrow().content_hash();
+        a.borrow()
+            .get_function("f")
+            .unwrap()
+            .borrow_mut()
+            .add_attribute("export".to_string());
+        assert_ne!(a.borrow().content_hash(), before);
+    }
 }
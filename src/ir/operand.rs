@@ -2,9 +2,11 @@
 //
 // 这个模块定义了 VIL 的操作数类，表示指令的输入
 
-use crate::ir::basic_block::BasicBlock;
-use crate::ir::types::TypeRef;
-use crate::ir::value::ValueRef;
+use crate::ir::basic_block::{BasicBlock, WeakBasicBlockRef};
+use crate::ir::function::FunctionRef;
+use crate::ir::module::GlobalMemorySpace;
+use crate::ir::types::{Type, TypeKind, TypeRef};
+use crate::ir::value::{Value, ValueRef};
 use std::cell::RefCell;
 use std::fmt;
 use std::rc::Rc;
@@ -12,12 +14,52 @@ use std::rc::Rc;
 // Operand 引用
 pub type OperandRef = Rc<RefCell<Operand>>;
 
+/// 全局符号引用（`@name`）
+#[derive(Debug, Clone)]
+pub enum GlobalRef {
+    Memory(Rc<RefCell<GlobalMemorySpace>>), // 全局内存空间
+    Function(FunctionRef),                  // 函数
+}
+
+impl GlobalRef {
+    /// 获取全局符号名称（不含 '@' 前缀）
+    pub fn get_name(&self) -> String {
+        match self {
+            GlobalRef::Memory(mem) => mem.borrow().get_name().to_string(),
+            GlobalRef::Function(func) => func.borrow().get_name().to_string(),
+        }
+    }
+
+    /// 获取引用的类型：全局内存为指向元素类型的指针，函数为函数类型
+    pub fn get_type(&self) -> TypeRef {
+        match self {
+            GlobalRef::Memory(mem) => {
+                let mem = mem.borrow();
+                Type::get_pointer_type(mem.get_element_type(), mem.get_space())
+            }
+            GlobalRef::Function(func) => func.borrow().get_type(),
+        }
+    }
+}
+
+impl PartialEq for GlobalRef {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (GlobalRef::Memory(s), GlobalRef::Memory(o)) => Rc::ptr_eq(s, o),
+            (GlobalRef::Function(s), GlobalRef::Function(o)) => Rc::ptr_eq(s, o),
+            _ => false,
+        }
+    }
+}
+
 /// 操作数种类
 #[derive(Debug, Clone)]
 pub enum OperandKind {
     Value(ValueRef),                     // 值操作数
     Immediate(i64, TypeRef),             // 立即数操作数
-    BasicBlock(Rc<RefCell<BasicBlock>>), // 基本块操作数
+    BasicBlock(WeakBasicBlockRef),       // 基本块操作数 (弱引用，避免循环跳转形成引用环)
+    Global(GlobalRef),                   // 全局符号操作数
+    IndexVector(Vec<i64>),               // 常量索引向量，如洗牌的 `<3, 2, 1, 0>`
 }
 
 impl PartialEq for OperandKind {
@@ -27,7 +69,9 @@ impl PartialEq for OperandKind {
             (OperandKind::Immediate(s_val, s_type), OperandKind::Immediate(o_val, o_type)) => {
                 s_val == o_val && s_type.borrow().eq(&o_type.borrow())
             }
-            (OperandKind::BasicBlock(s), OperandKind::BasicBlock(o)) => Rc::ptr_eq(s, o),
+            (OperandKind::BasicBlock(s), OperandKind::BasicBlock(o)) => s.ptr_eq(o),
+            (OperandKind::Global(s), OperandKind::Global(o)) => s == o,
+            (OperandKind::IndexVector(s), OperandKind::IndexVector(o)) => s == o,
             _ => false,
         }
     }
@@ -55,9 +99,25 @@ impl Operand {
     }
 
     /// 创建基本块操作数
+    ///
+    /// 操作数只持有基本块的弱引用，基本块由所属函数持有。
     pub fn create_basic_block(bb: Rc<RefCell<BasicBlock>>) -> OperandRef {
         Rc::new(RefCell::new(Operand {
-            kind: OperandKind::BasicBlock(bb),
+            kind: OperandKind::BasicBlock(Rc::downgrade(&bb)),
+        }))
+    }
+
+    /// 创建全局符号操作数
+    pub fn create_global(global: GlobalRef) -> OperandRef {
+        Rc::new(RefCell::new(Operand {
+            kind: OperandKind::Global(global),
+        }))
+    }
+
+    /// 创建常量索引向量操作数
+    pub fn create_index_vector(indices: Vec<i64>) -> OperandRef {
+        Rc::new(RefCell::new(Operand {
+            kind: OperandKind::IndexVector(indices),
         }))
     }
 
@@ -81,6 +141,11 @@ impl Operand {
         matches!(self.kind, OperandKind::BasicBlock(_))
     }
 
+    /// 判断是否为全局符号操作数
+    pub fn is_global(&self) -> bool {
+        matches!(self.kind, OperandKind::Global(_))
+    }
+
     /// 获取值操作数
     pub fn get_value(&self) -> Option<ValueRef> {
         match &self.kind {
@@ -97,10 +162,34 @@ impl Operand {
         }
     }
 
-    /// 获取基本块操作数
+    /// 获取基本块操作数；基本块已被释放时返回 None
     pub fn get_basic_block(&self) -> Option<Rc<RefCell<BasicBlock>>> {
         match &self.kind {
-            OperandKind::BasicBlock(bb) => Some(bb.clone()),
+            OperandKind::BasicBlock(bb) => bb.upgrade(),
+            _ => None,
+        }
+    }
+
+    /// 获取基本块操作数的名称（不含 '%'）；基本块已被释放时为 `<dead>`
+    pub fn get_block_name(&self) -> Option<String> {
+        match &self.kind {
+            OperandKind::BasicBlock(bb) => Some(Self::block_name(bb)),
+            _ => None,
+        }
+    }
+
+    /// 获取全局符号操作数
+    pub fn get_global(&self) -> Option<GlobalRef> {
+        match &self.kind {
+            OperandKind::Global(global) => Some(global.clone()),
+            _ => None,
+        }
+    }
+
+    /// 获取常量索引向量操作数
+    pub fn get_index_vector(&self) -> Option<Vec<i64>> {
+        match &self.kind {
+            OperandKind::IndexVector(indices) => Some(indices.clone()),
             _ => None,
         }
     }
@@ -111,8 +200,54 @@ impl Operand {
             OperandKind::Value(value) => Some(value.borrow().get_type()),
             OperandKind::Immediate(_, type_) => Some(type_.clone()),
             OperandKind::BasicBlock(_) => None, // 基本块没有类型
+            OperandKind::Global(global) => Some(global.get_type()),
+            OperandKind::IndexVector(indices) => Some(Self::index_vector_type(indices)),
+        }
+    }
+
+    /// 转换为 `ValueRef` 形式，供 `Instruction` 的值访问接口使用
+    ///
+    /// 立即数转换为以数值命名的常量值，全局符号转换为 `@name` 值，
+    /// 基本块转换为 `%label` 值，索引向量转换为以 `<i0, i1, ...>` 命名的向量值。
+    pub fn to_value(&self) -> ValueRef {
+        match &self.kind {
+            OperandKind::Value(value) => value.clone(),
+            OperandKind::Immediate(value, type_) => {
+                Rc::new(RefCell::new(Value::new_constant(*value, type_.clone())))
+            }
+            OperandKind::BasicBlock(bb) => Rc::new(RefCell::new(Value::new(
+                Type::get_void_type(),
+                format!("%{}", Self::block_name(bb)),
+            ))),
+            OperandKind::Global(global) => Rc::new(RefCell::new(Value::new(
+                global.get_type(),
+                format!("@{}", global.get_name()),
+            ))),
+            OperandKind::IndexVector(indices) => Rc::new(RefCell::new(Value::new(
+                Self::index_vector_type(indices),
+                Self::index_vector_text(indices),
+            ))),
+        }
+    }
+
+    /// 基本块操作数的名称；基本块已被释放时为 `<dead>`
+    fn block_name(bb: &WeakBasicBlockRef) -> String {
+        match bb.upgrade() {
+            Some(bb) => bb.borrow().get_name().to_string(),
+            None => "<dead>".to_string(),
         }
     }
+
+    /// 索引向量的类型：元素个数与索引个数相同的 i32 向量
+    fn index_vector_type(indices: &[i64]) -> TypeRef {
+        Type::get_vector_type(Type::get_int_type(TypeKind::Int32), indices.len() as u32)
+    }
+
+    /// 索引向量的源码写法 `<i0, i1, ...>`
+    fn index_vector_text(indices: &[i64]) -> String {
+        let indices: Vec<String> = indices.iter().map(i64::to_string).collect();
+        format!("<{}>", indices.join(", "))
+    }
 }
 
 impl fmt::Display for Operand {
@@ -120,7 +255,9 @@ impl fmt::Display for Operand {
         match &self.kind {
             OperandKind::Value(value) => write!(f, "{}", value.borrow()),
             OperandKind::Immediate(value, type_) => write!(f, "{} {}", value, type_.borrow()),
-            OperandKind::BasicBlock(bb) => write!(f, "label {}", bb.borrow().get_name()),
+            OperandKind::BasicBlock(bb) => write!(f, "label {}", Self::block_name(bb)),
+            OperandKind::Global(global) => write!(f, "@{}", global.get_name()),
+            OperandKind::IndexVector(indices) => write!(f, "{}", Self::index_vector_text(indices)),
         }
     }
 }
@@ -156,4 +293,29 @@ mod tests {
 
         assert_eq!(operand.borrow().get_immediate().unwrap(), 42);
     }
+
+    #[test]
+    fn test_global_operand() {
+        let elem_type = Type::get_int_type(TypeKind::Int16);
+        let mem = Rc::new(RefCell::new(GlobalMemorySpace::new(
+            "buf".to_string(),
+            crate::ir::MemorySpace::VSPM,
+            elem_type,
+            64,
+        )));
+        let operand = Operand::create_global(GlobalRef::Memory(mem.clone()));
+
+        assert!(operand.borrow().is_global());
+        assert!(!operand.borrow().is_value());
+        assert_eq!(operand.borrow().to_string(), "@buf");
+        assert_eq!(
+            operand.borrow().get_type().unwrap().borrow().to_string(),
+            "i16* vspm"
+        );
+        assert_eq!(operand.borrow().to_value().borrow().get_name(), "@buf");
+        assert_eq!(
+            *operand.borrow(),
+            *Operand::create_global(GlobalRef::Memory(mem)).borrow()
+        );
+    }
 }
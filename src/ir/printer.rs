@@ -0,0 +1,323 @@
+// IR 打印器实现
+//
+// 这个模块提供可配置的 IR 文本输出（缩进宽度、ANSI 颜色、是否显示类型后缀），
+// 作为各个 Display 实现的补充
+
+use crate::ir::basic_block::BasicBlock;
+use crate::ir::function::{Function, Linkage};
+use crate::ir::instruction::{Instruction, Opcode};
+use crate::ir::module::Module;
+use crate::ir::types::TypeKind;
+use crate::ir::value::Value;
+use std::fmt::Write;
+
+// ANSI 颜色代码
+const COLOR_KEYWORD: &str = "\x1b[35m"; // 关键字：品红
+const COLOR_OPCODE: &str = "\x1b[36m"; // 操作码：青色
+const COLOR_LABEL: &str = "\x1b[33m"; // 基本块标签：黄色
+const COLOR_RESET: &str = "\x1b[0m";
+
+/// 打印选项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrinterOptions {
+    /// 指令缩进的空格数
+    pub indent: usize,
+    /// 是否为关键字/操作码输出 ANSI 颜色
+    pub color: bool,
+    /// 操作数是否带 `:type` 后缀
+    pub show_types: bool,
+}
+
+impl Default for PrinterOptions {
+    /// 默认选项与 Display 输出保持一致：两空格缩进、无颜色、显示类型
+    fn default() -> Self {
+        PrinterOptions {
+            indent: 2,
+            color: false,
+            show_types: true,
+        }
+    }
+}
+
+/// 按给定选项打印整个模块
+pub fn print_module(module: &Module, opts: &PrinterOptions) -> String {
+    let mut printer = Printer::new(opts);
+    printer.module(module);
+    printer.out
+}
+
+/// 按给定选项打印单个函数
+pub fn print_function(func: &Function, opts: &PrinterOptions) -> String {
+    let mut printer = Printer::new(opts);
+    printer.function(func);
+    printer.out
+}
+
+/// 按给定选项打印单条指令（不含缩进与换行）
+pub fn print_instruction(instr: &Instruction, opts: &PrinterOptions) -> String {
+    let mut printer = Printer::new(opts);
+    printer.instruction(instr);
+    printer.out
+}
+
+/// 打印器内部状态
+struct Printer<'o> {
+    opts: &'o PrinterOptions,
+    out: String,
+}
+
+impl<'o> Printer<'o> {
+    fn new(opts: &'o PrinterOptions) -> Self {
+        Printer {
+            opts,
+            out: String::new(),
+        }
+    }
+
+    /// 按需为文本加上颜色
+    fn paint(&self, text: &str, color: &str) -> String {
+        if self.opts.color {
+            format!("{}{}{}", color, text, COLOR_RESET)
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn module(&mut self, module: &Module) {
+        let kw = self.paint(".module", COLOR_KEYWORD);
+        let _ = writeln!(self.out, "{} {}", kw, module.get_name());
+        let _ = writeln!(self.out);
+
+        for (name, type_) in module.get_type_aliases() {
+            let kw = self.paint(".type", COLOR_KEYWORD);
+            let _ = writeln!(self.out, "{} {} = {}", kw, name, type_.borrow());
+        }
+
+        for mem_space in module.get_global_memory_spaces() {
+            let mem_space = mem_space.borrow();
+            let kw = self.paint(".memory", COLOR_KEYWORD);
+            // 去掉 GlobalMemorySpace 自带的 `.memory` 前缀，只替换关键字颜色
+            let text = mem_space.to_string();
+            let rest = text.strip_prefix(".memory").unwrap_or(&text);
+            let _ = writeln!(self.out, "{}{}", kw, rest);
+        }
+        if let Some(entry) = module.get_entry_function() {
+            let kw = self.paint(".entry", COLOR_KEYWORD);
+            let _ = writeln!(self.out, "{} @{}", kw, entry.borrow().get_name());
+        }
+        let _ = writeln!(self.out);
+
+        for func in module.get_functions() {
+            self.function(&func.borrow());
+            let _ = writeln!(self.out);
+        }
+    }
+
+    fn function(&mut self, func: &Function) {
+        if func.is_declaration() {
+            let kw = self.paint(".declare", COLOR_KEYWORD);
+            let _ = write!(self.out, "{} @{}(", kw, func.get_name());
+        } else {
+            if func.get_linkage() != Linkage::Internal {
+                let linkage = self.paint(&func.get_linkage().to_string(), COLOR_KEYWORD);
+                let _ = write!(self.out, "{} ", linkage);
+            }
+            let kw = self.paint(".function", COLOR_KEYWORD);
+            let _ = write!(self.out, "{} {}(", kw, func.get_name());
+        }
+        for (i, arg) in func.get_arguments().iter().enumerate() {
+            if i > 0 {
+                let _ = write!(self.out, ", ");
+            }
+            let arg = arg.borrow();
+            let kw = if arg.is_result() { ".result" } else { ".param" };
+            let kw = self.paint(kw, COLOR_KEYWORD);
+            let _ = write!(
+                self.out,
+                "{} {} {}",
+                kw,
+                arg.get_name(),
+                arg.get_type().borrow()
+            );
+        }
+        let _ = write!(self.out, ")");
+        let return_type = func.get_return_type();
+        if !matches!(return_type.borrow().get_kind(), TypeKind::Void) {
+            let _ = write!(self.out, " -> {}", return_type.borrow());
+        }
+        for attr in func.get_attributes() {
+            let _ = write!(self.out, " {}", attr);
+        }
+        if func.is_declaration() {
+            let _ = writeln!(self.out);
+            return;
+        }
+        let _ = writeln!(self.out, " {{");
+
+        for bb in func.get_basic_blocks() {
+            self.basic_block(&bb.borrow());
+        }
+        let _ = writeln!(self.out, "}}");
+    }
+
+    fn basic_block(&mut self, bb: &BasicBlock) {
+        let label = self.paint(&format!("{}:", bb.get_name()), COLOR_LABEL);
+        let _ = writeln!(self.out, "{}", label);
+        for instruction in bb.get_instructions() {
+            let _ = write!(self.out, "{:width$}", "", width = self.opts.indent);
+            self.instruction(&instruction.borrow());
+            let _ = writeln!(self.out);
+        }
+    }
+
+    fn instruction(&mut self, instr: &Instruction) {
+        if instr.has_result() {
+            let name = instr.get_name().unwrap_or_default();
+            let name = if name.is_empty() {
+                "%_".to_string()
+            } else if name.starts_with('%') {
+                name
+            } else {
+                format!("%{}", name)
+            };
+            let _ = write!(self.out, "{} = ", name);
+        }
+
+        let mnemonic = format!("{}{}", instr.mnemonic(), instr.get_modifier());
+        let mnemonic = self.paint(&mnemonic, COLOR_OPCODE);
+        let _ = write!(self.out, "{}", mnemonic);
+
+        let operands = instr.get_operands();
+        match instr.get_opcode() {
+            // `alloc <type> [space]`：类型与内存空间取自结果的指针类型
+            Opcode::Alloc => {
+                if let Some(result) = instr.get_result() {
+                    let type_ = result.borrow().get_type();
+                    if let TypeKind::Pointer(pointee, space) = type_.borrow().get_kind() {
+                        let _ = write!(self.out, " {} [{}]", pointee.borrow(), space);
+                    }
+                }
+            }
+            // `shuffle %v, <i0, i1, ...>`：常量索引向量本身就是源码写法，不带类型后缀
+            Opcode::Shuffle | Opcode::ShuffleClbmv if instr.shuffle_indices().is_some() => {
+                let source = self.value(&operands[0].borrow());
+                let mask = operands[1].borrow().operand_string();
+                let _ = write!(self.out, " {}, {}", source, mask);
+            }
+            // `call @callee(args)`
+            Opcode::Call if !operands.is_empty() => {
+                let _ = write!(self.out, " {}(", operands[0].borrow().get_name());
+                for (i, op) in operands[1..].iter().enumerate() {
+                    let sep = if i == 0 { "" } else { ", " };
+                    let operand = self.value(&op.borrow());
+                    let _ = write!(self.out, "{}{}", sep, operand);
+                }
+                let _ = write!(self.out, ")");
+            }
+            _ => {
+                for (i, op) in operands.iter().enumerate() {
+                    let sep = if i == 0 { " " } else { ", " };
+                    let operand = self.value(&op.borrow());
+                    let _ = write!(self.out, "{}{}", sep, operand);
+                }
+                // `bitcast %x : <type>`：目标类型即结果类型
+                if instr.get_opcode().is_cast() {
+                    let _ = write!(self.out, " : {}", instr.get_type().borrow());
+                }
+            }
+        }
+
+        for attr in instr.get_attributes() {
+            let _ = write!(self.out, " {}", attr);
+        }
+    }
+
+    fn value(&self, value: &Value) -> String {
+        if self.opts.show_types {
+            value.to_string()
+        } else {
+            value.operand_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::basic_block::BasicBlock;
+    use crate::ir::instruction::InstructionModifier;
+    use crate::ir::types::Type;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn build_module() -> Module {
+        let int_ty = Type::get_int_type(TypeKind::Int32);
+        let func = Rc::new(RefCell::new(Function::new(
+            "f".to_string(),
+            Type::get_void_type(),
+            vec![int_ty.clone(), int_ty.clone()],
+        )));
+        let bb = Rc::new(RefCell::new(BasicBlock::new(
+            "entry".to_string(),
+            Some(func.clone()),
+        )));
+        let a = Rc::new(RefCell::new(Value::new(int_ty.clone(), "%a".to_string())));
+        let b = Rc::new(RefCell::new(Value::new(int_ty.clone(), "%b".to_string())));
+        let x = Rc::new(RefCell::new(Value::new(int_ty, "%x".to_string())));
+        let add = Rc::new(RefCell::new(Instruction::new(
+            Opcode::Add,
+            Some(x),
+            vec![a, b],
+            InstructionModifier::None,
+        )));
+        bb.borrow_mut().add_instruction(add, bb.clone());
+        func.borrow_mut().add_basic_block(bb);
+
+        let mut module = Module::new("m".to_string());
+        module.add_function(func);
+        module
+    }
+
+    #[test]
+    fn test_indent_width() {
+        let module = build_module();
+        let opts = PrinterOptions {
+            indent: 4,
+            ..PrinterOptions::default()
+        };
+        let text = print_module(&module, &opts);
+        assert!(text.contains("\n    %x = add %a:i32, %b:i32\n"));
+
+        let opts = PrinterOptions {
+            indent: 1,
+            ..PrinterOptions::default()
+        };
+        let text = print_module(&module, &opts);
+        assert!(text.contains("\n %x = add"));
+    }
+
+    #[test]
+    fn test_types_off() {
+        let module = build_module();
+        let opts = PrinterOptions {
+            show_types: false,
+            ..PrinterOptions::default()
+        };
+        let text = print_module(&module, &opts);
+        assert!(text.contains("  %x = add %a, %b\n"));
+        assert!(!text.contains("%a:i32"));
+    }
+
+    #[test]
+    fn test_color() {
+        let module = build_module();
+        let opts = PrinterOptions {
+            color: true,
+            ..PrinterOptions::default()
+        };
+        let text = print_module(&module, &opts);
+        assert!(text.contains("\x1b[36madd\x1b[0m"));
+        assert!(text.contains("\x1b[35m.module\x1b[0m m"));
+        assert!(!print_module(&module, &PrinterOptions::default()).contains('\x1b'));
+    }
+}
@@ -31,6 +31,7 @@ pub enum TypeKind {
     Void,                            // 空类型
     Pointer(TypeRef, MemorySpace),   // 指针类型(指向类型, 内存空间)
     Function(TypeRef, Vec<TypeRef>), // 函数类型(返回类型, 参数类型)
+    Tuple(Vec<TypeRef>),             // 元组类型(各元素类型)，多结果函数的调用结果
 }
 
 impl PartialEq for TypeKind {
@@ -70,6 +71,13 @@ impl PartialEq for TypeKind {
                         .zip(param_tys_other.iter())
                         .all(|(s, o)| s.borrow().eq(&o.borrow()))
             }
+            (TypeKind::Tuple(elems_self), TypeKind::Tuple(elems_other)) => {
+                elems_self.len() == elems_other.len()
+                    && elems_self
+                        .iter()
+                        .zip(elems_other.iter())
+                        .all(|(s, o)| s.borrow().eq(&o.borrow()))
+            }
             _ => false,
         }
     }
@@ -111,6 +119,12 @@ impl Hash for TypeKind {
                     param_type.borrow().hash(state);
                 }
             }
+            TypeKind::Tuple(elem_types) => {
+                "Tuple".hash(state);
+                for elem_type in elem_types {
+                    elem_type.borrow().hash(state);
+                }
+            }
         }
     }
 }
@@ -143,6 +157,9 @@ impl Type {
             TypeKind::Void => 0,
             TypeKind::Pointer(_, _) => 32, // 假设所有指针都是32位
             TypeKind::Function(_, _) => 0, // 函数类型没有位宽
+            TypeKind::Tuple(elem_types) => {
+                elem_types.iter().map(|t| t.borrow().get_bit_width()).sum()
+            }
         }
     }
 
@@ -172,6 +189,66 @@ impl Type {
         matches!(self.kind, TypeKind::Predicate(_))
     }
 
+    /// 获取指针类型所在的内存空间，非指针返回 None
+    pub fn pointer_space(&self) -> Option<MemorySpace> {
+        match self.kind {
+            TypeKind::Pointer(_, space) => Some(space),
+            _ => None,
+        }
+    }
+
+    /// 两个指针类型是否兼容：指向类型相同，且内存空间相同或任一方为 `generic`
+    pub fn is_compatible_pointer(&self, other: &Type) -> bool {
+        match (&self.kind, &other.kind) {
+            (TypeKind::Pointer(a, space_a), TypeKind::Pointer(b, space_b)) => {
+                *a.borrow() == *b.borrow() && MemorySpace::spaces_compatible(*space_a, *space_b)
+            }
+            _ => false,
+        }
+    }
+
+    /// 检查是否为整数类型 (i8/u8/i16/u16/i32/u32)
+    pub fn is_integer(&self) -> bool {
+        matches!(
+            self.kind,
+            TypeKind::Int8
+                | TypeKind::Uint8
+                | TypeKind::Int16
+                | TypeKind::Uint16
+                | TypeKind::Int32
+                | TypeKind::Uint32
+        )
+    }
+
+    /// 整数类型的符号性：有符号返回 Some(true)，无符号返回 Some(false)
+    ///
+    /// 向量类型取其元素类型的符号性；位域、谓词等非整数类型返回 None。
+    pub fn is_signed(&self) -> Option<bool> {
+        match &self.kind {
+            TypeKind::Int8 | TypeKind::Int16 | TypeKind::Int32 => Some(true),
+            TypeKind::Uint8 | TypeKind::Uint16 | TypeKind::Uint32 => Some(false),
+            TypeKind::Vector(elem_type, _) => elem_type.borrow().is_signed(),
+            _ => None,
+        }
+    }
+
+    /// 整数类型可表示的取值范围 [最小值, 最大值]
+    ///
+    /// 向量类型取其元素类型的范围；非整数类型返回 None。
+    pub fn integer_range(&self) -> Option<(i64, i64)> {
+        if let TypeKind::Vector(elem_type, _) = &self.kind {
+            return elem_type.borrow().integer_range();
+        }
+        if !self.is_integer() {
+            return None;
+        }
+        let bits = self.get_bit_width();
+        match self.is_signed()? {
+            true => Some((-(1i64 << (bits - 1)), (1i64 << (bits - 1)) - 1)),
+            false => Some((0, (1i64 << bits) - 1)),
+        }
+    }
+
     /// 检查是否为位域类型
     pub fn is_bit_type(&self) -> bool {
         matches!(
@@ -180,6 +257,20 @@ impl Type {
         )
     }
 
+    /// 二元运算的公共类型
+    ///
+    /// 相同类型统一为自身；向量与其元素类型统一为该向量（标量广播）；其它组合返回 None。
+    pub fn get_common_type(a: &TypeRef, b: &TypeRef) -> Option<TypeRef> {
+        if *a.borrow() == *b.borrow() {
+            return Some(a.clone());
+        }
+        let splat = |vector: &TypeRef, scalar: &TypeRef| match vector.borrow().get_kind() {
+            TypeKind::Vector(elem, _) if *elem.borrow() == *scalar.borrow() => Some(vector.clone()),
+            _ => None,
+        };
+        splat(a, b).or_else(|| splat(b, a))
+    }
+
     /// 创建整数类型
     pub fn get_int_type(kind: TypeKind) -> TypeRef {
         assert!(matches!(
@@ -232,10 +323,20 @@ impl Type {
         ))))
     }
 
+    /// 创建元组类型
+    pub fn get_tuple_type(elem_types: Vec<TypeRef>) -> TypeRef {
+        Rc::new(RefCell::new(Type::new(TypeKind::Tuple(elem_types))))
+    }
+
     /// 创建空类型
     pub fn get_void_type() -> TypeRef {
         Rc::new(RefCell::new(Type::new(TypeKind::Void)))
     }
+
+    /// 类型的规范文本形式，与 `Display` 相同；除函数与元组类型外都可由 `frontend::parse_type` 解析回来
+    pub fn to_canonical_string(&self) -> String {
+        self.to_string()
+    }
 }
 
 impl fmt::Display for Type {
@@ -270,6 +371,16 @@ impl fmt::Display for Type {
                 }
                 write!(f, ")")
             }
+            TypeKind::Tuple(elem_types) => {
+                write!(f, "{{")?;
+                for (i, elem_type) in elem_types.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", elem_type.borrow())?;
+                }
+                write!(f, "}}")
+            }
         }
     }
 }
@@ -279,6 +390,7 @@ pub struct TypeUtils;
 
 impl TypeUtils {
     /// 解析类型字符串
+    #[deprecated(note = "使用 frontend::parse_type，它支持向量、谓词与指针类型")]
     pub fn parse_type(type_str: &str) -> Result<TypeRef, String> {
         // 简单实现，实际应该使用解析器
         match type_str {
@@ -310,6 +422,30 @@ mod tests {
         assert_eq!(i8_type.borrow().to_string(), "i8");
     }
 
+    #[test]
+    fn test_signedness() {
+        let cases = [
+            (TypeKind::Int8, true),
+            (TypeKind::Uint8, false),
+            (TypeKind::Int32, true),
+            (TypeKind::Uint32, false),
+        ];
+        for (kind, signed) in cases {
+            let scalar = Type::get_int_type(kind);
+            assert!(scalar.borrow().is_integer());
+            assert_eq!(scalar.borrow().is_signed(), Some(signed));
+
+            let vector = Type::get_vector_type(scalar, 8);
+            assert!(!vector.borrow().is_integer());
+            assert_eq!(vector.borrow().is_signed(), Some(signed));
+        }
+
+        let bits = Type::get_bit_type(TypeKind::Bit32);
+        assert!(!bits.borrow().is_integer());
+        assert_eq!(bits.borrow().is_signed(), None);
+        assert_eq!(Type::get_predicate_type(4).borrow().is_signed(), None);
+    }
+
     #[test]
     fn test_vector_type() {
         let elem_type = Type::get_int_type(TypeKind::Int32);
@@ -318,4 +454,46 @@ mod tests {
         assert!(vec_type.borrow().is_vector());
         assert_eq!(vec_type.borrow().to_string(), "<i32 x 4>");
     }
+
+    #[test]
+    fn test_common_type() {
+        let i16_type = Type::get_int_type(TypeKind::Int16);
+        let i32_type = Type::get_int_type(TypeKind::Int32);
+        let vec_type = Type::get_vector_type(i16_type.clone(), 8);
+
+        // 相同类型
+        let common = Type::get_common_type(&i16_type, &Type::get_int_type(TypeKind::Int16));
+        assert_eq!(common.unwrap().borrow().to_string(), "i16");
+
+        // 向量与元素类型（两种顺序）
+        let common = Type::get_common_type(&vec_type, &i16_type).unwrap();
+        assert_eq!(common.borrow().to_string(), "<i16 x 8>");
+        let common = Type::get_common_type(&i16_type, &vec_type).unwrap();
+        assert_eq!(common.borrow().to_string(), "<i16 x 8>");
+
+        // 不兼容
+        assert!(Type::get_common_type(&i16_type, &i32_type).is_none());
+        assert!(Type::get_common_type(&vec_type, &i32_type).is_none());
+        let other_vec = Type::get_vector_type(i16_type, 4);
+        assert!(Type::get_common_type(&vec_type, &other_vec).is_none());
+    }
+
+    #[test]
+    fn test_compatible_pointer() {
+        let i32_type = Type::get_int_type(TypeKind::Int32);
+        let vspm = Type::get_pointer_type(i32_type.clone(), MemorySpace::VSPM);
+        let sram = Type::get_pointer_type(i32_type.clone(), MemorySpace::SRAM);
+        let generic = Type::get_pointer_type(i32_type.clone(), MemorySpace::Generic);
+        let i16_vspm =
+            Type::get_pointer_type(Type::get_int_type(TypeKind::Int16), MemorySpace::VSPM);
+
+        assert_eq!(vspm.borrow().pointer_space(), Some(MemorySpace::VSPM));
+        assert_eq!(i32_type.borrow().pointer_space(), None);
+        assert!(vspm.borrow().is_compatible_pointer(&vspm.borrow()));
+        assert!(vspm.borrow().is_compatible_pointer(&generic.borrow()));
+        assert!(generic.borrow().is_compatible_pointer(&sram.borrow()));
+        assert!(!vspm.borrow().is_compatible_pointer(&sram.borrow()));
+        assert!(!vspm.borrow().is_compatible_pointer(&i16_vspm.borrow()));
+        assert!(!vspm.borrow().is_compatible_pointer(&i32_type.borrow()));
+    }
 }
@@ -11,17 +11,69 @@ use std::hash::{Hash, Hasher};
 // Value 引用，使用 Rc<RefCell<T>> 代替 C++ 中的 std::shared_ptr<T>
 pub type ValueRef = Rc<RefCell<Value>>;
 
+/// 常量值
+#[derive(Debug, Clone, Copy)]
+pub enum Constant {
+    Int(i64),
+    Float(f64),
+}
+
+impl PartialEq for Constant {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Constant::Int(a), Constant::Int(b)) => a == b,
+            // 按位比较，使 NaN 等于自身以满足 Eq
+            (Constant::Float(a), Constant::Float(b)) => a.to_bits() == b.to_bits(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Constant {}
+
+impl Hash for Constant {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Constant::Int(v) => v.hash(state),
+            Constant::Float(v) => v.to_bits().hash(state),
+        }
+    }
+}
+
+impl From<i64> for Constant {
+    fn from(value: i64) -> Self {
+        Constant::Int(value)
+    }
+}
+
+impl From<f64> for Constant {
+    fn from(value: f64) -> Self {
+        Constant::Float(value)
+    }
+}
+
+impl fmt::Display for Constant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Constant::Int(v) => write!(f, "{}", v),
+            Constant::Float(v) => write!(f, "{:?}", v),
+        }
+    }
+}
+
 /// IR 中的值。可以是指令结果、函数参数或常量。
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Value {
     type_: TypeRef,
     name: String,
+    constant: Option<Constant>, // 常量值，非常量为 None
 }
 
 impl Hash for Value {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.type_.borrow().hash(state);
         self.name.hash(state);
+        self.constant.hash(state);
     }
 }
 
@@ -31,6 +83,17 @@ impl Value {
         Self {
             type_,
             name,
+            constant: None,
+        }
+    }
+
+    /// 创建一个常量值，名称为常量的文本形式
+    pub fn new_constant(value: impl Into<Constant>, type_: TypeRef) -> Self {
+        let constant = value.into();
+        Self {
+            type_,
+            name: constant.to_string(),
+            constant: Some(constant),
         }
     }
 
@@ -54,9 +117,19 @@ impl Value {
         self.name = name;
     }
 
-    /// 检查此值是否为常量 (通过名称是否能解析为数字判断)
+    /// 检查此值是否为常量
     pub fn is_constant(&self) -> bool {
-        self.name.parse::<i64>().is_ok() || self.name.parse::<f64>().is_ok()
+        self.constant.is_some()
+    }
+
+    /// 获取常量值
+    pub fn get_constant(&self) -> Option<Constant> {
+        self.constant
+    }
+
+    /// 将该值设置为常量（保留名称与类型）
+    pub fn set_constant(&mut self, constant: Constant) {
+        self.constant = Some(constant);
     }
 
     /// 判断该值是否为对其他指令结果的引用（简单地认为以 '%' 开头且非常量）
@@ -64,15 +137,31 @@ impl Value {
         !self.is_constant() && self.name.starts_with('%')
     }
 
+    /// 操作数位置上的简洁形式：常量为其文本，其余为名称，不带类型后缀
+    ///
+    /// 与 VIL 源代码中操作数的写法一致，可被解析器重新读入；需要类型的位置使用 `Display`。
+    pub fn operand_string(&self) -> String {
+        match &self.constant {
+            Some(constant) => constant.to_string(),
+            None if self.name.is_empty() => "<unnamed>".to_string(),
+            None => self.name.clone(),
+        }
+    }
+
     /// 如果是整型常量，返回其 i64 值
     pub fn as_i64(&self) -> Option<i64> {
-        self.name.parse::<i64>().ok()
+        match self.constant {
+            Some(Constant::Int(value)) => Some(value),
+            _ => None,
+        }
     }
 }
 
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.name.is_empty() {
+        if let Some(constant) = &self.constant {
+            write!(f, "{} : {}", constant, self.type_.borrow())
+        } else if self.name.is_empty() {
             write!(f, "<unnamed:{}>", self.type_.borrow())
         } else {
             write!(f, "{}:{}", self.name, self.type_.borrow())
@@ -101,5 +190,22 @@ mod tests {
         value2.set_name("renamed".to_string());
         assert_eq!(value2.get_name(), "renamed");
         assert_eq!(value2.to_string(), "renamed:i32");
+        assert_eq!(value2.operand_string(), "renamed");
+    }
+
+    #[test]
+    fn test_constant_value() {
+        let int_type = Type::get_int_type(TypeKind::Int32);
+        let five = Value::new_constant(5, int_type.clone());
+        assert!(five.is_constant());
+        assert_eq!(five.as_i64(), Some(5));
+        assert_eq!(five.to_string(), "5 : i32");
+        assert_eq!(five.operand_string(), "5");
+
+        // 以数字命名的普通值不是常量
+        let named = Value::new(int_type, "5".to_string());
+        assert!(!named.is_constant());
+        assert_eq!(named.as_i64(), None);
+        assert_ne!(five, named);
     }
 }
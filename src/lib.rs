@@ -3,7 +3,9 @@
 // 这个文件是 vil 库的主入口点，导出所有公共模块和类型
 
 // 重新导出子模块
+pub mod analysis;
 pub mod backend;
+pub mod diagnostics;
 pub mod frontend;
 pub mod ir;
 pub mod optimizer;
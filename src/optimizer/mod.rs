@@ -9,16 +9,19 @@ pub mod pass_manager;
 // 引入子模块及占位 Pass
 pub mod passes;
 
-// 重新导出 pass_manager 中的 Pass trait
-pub use pass_manager::Pass;
+// 按名称构造 Pass 的注册表
+pub mod registry;
 
-pub fn run_optimizer(module: &crate::ir::ModuleRef) {
-    let mut pm = pass_manager::PassManager::new();
+// 按优化级别预设的标准流水线
+pub mod pipeline;
 
-    // 注册占位 Pass，并加入 pipeline
-    pm.register_pass(passes::ssa_renumber::SSARenumberPass::new());
-    pm.add_to_pipeline("optimizer::SSARenumberPass");
+// 重新导出 pass_manager 中的 Pass 与 FunctionPass trait
+pub use pass_manager::{FunctionPass, Pass};
+pub use pipeline::OptLevel;
+pub use registry::{available_passes, build_pass_manager, create_pass};
 
+/// 以给定优化级别运行标准优化流水线，见 `pipeline::standard`
+pub fn run_optimizer(module: &crate::ir::ModuleRef, level: OptLevel) {
     // 运行优化器，处理可能的错误
-    pm.run(module).expect("优化过程中出错");
+    pipeline::run_standard(module, level).expect("优化过程中出错");
 }
@@ -2,9 +2,14 @@
 // 负责注册、拓扑排序并依次运行各个优化 Pass。
 // 后续高级功能（依赖解析、重复执行等）将在该基础上迭代。
 
+use crate::analysis::Analysis;
+use crate::diagnostics::DiagnosticBag;
 use crate::ir::ModuleRef;
+use crate::ir::function::FunctionRef;
+use std::any::Any;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt;
+use std::rc::Rc;
 use std::time::{Duration, Instant};
 
 /// Pass 执行错误
@@ -45,6 +50,8 @@ pub struct PassStatistics {
     pub skipped: bool,
     /// 跳过原因（如果被跳过）
     pub skip_reason: Option<String>,
+    /// Pass 报告的修改数量（例如折叠/删除的指令数）
+    pub changes: usize,
 }
 
 impl fmt::Display for PassStatistics {
@@ -59,9 +66,10 @@ impl fmt::Display for PassStatistics {
         } else {
             write!(
                 f,
-                "{}: 执行时间 {:.2}ms",
+                "{}: 执行时间 {:.2}ms, 修改 {} 处",
                 self.name,
-                self.duration.as_secs_f64() * 1000.0
+                self.duration.as_secs_f64() * 1000.0,
+                self.changes
             )
         }
     }
@@ -114,7 +122,15 @@ pub trait Pass {
     /// Pass 唯一名称（建议使用 "namespace::PassName" 格式）
     fn name(&self) -> &'static str;
 
-    /// 指定依赖的其它 Pass 名称（可为空）
+    /// Pass 实例 ID，PassManager 以此作为注册与 pipeline 中的键
+    ///
+    /// 默认与 `name()` 相同。同一 Pass 类型的不同配置应返回不同的 ID，
+    /// 以便多个实例同时注册。
+    fn instance_id(&self) -> String {
+        self.name().to_string()
+    }
+
+    /// 指定依赖的其它 Pass 实例 ID（可为空）
     fn dependencies(&self) -> Vec<&'static str> {
         Vec::new()
     }
@@ -132,10 +148,129 @@ pub trait Pass {
         "No description provided"
     }
 
+    /// 运行后无论是否修改模块都需要失效的分析名称
+    fn invalidates(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// 修改模块后仍然有效的分析名称
+    ///
+    /// 当 Pass 报告了修改时，除此列表外的所有缓存分析都会失效。
+    fn preserves(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
     /// 运行 Pass
-    fn run(&self, module: &ModuleRef);
+    ///
+    /// 返回本次运行所做的修改数量（折叠/删除的指令数等），0 表示未修改模块。
+    /// `run_until_fixpoint` 以此判断是否收敛，统计信息也会记录该数值。
+    fn run(&self, module: &ModuleRef) -> usize;
+
+    /// 运行 Pass，并将运行中产生的警告等诊断推入 `diagnostics`
+    ///
+    /// 默认直接调用 `run`。需要报告诊断的 Pass 应重写此方法，
+    /// 并让 `run` 以一个临时的 `DiagnosticBag` 调用它。
+    fn run_with_diagnostics(
+        &self,
+        module: &ModuleRef,
+        _diagnostics: &mut DiagnosticBag,
+    ) -> usize {
+        self.run(module)
+    }
 }
 
+/// 逐函数运行的 Pass
+///
+/// 实现此 trait 的类型自动获得 `Pass` 实现：依次对模块中的每个函数调用
+/// `run_on_function`，并跳过外部函数声明与带有 `optnone` 属性的函数。
+pub trait FunctionPass {
+    /// Pass 唯一名称，含义同 `Pass::name`
+    fn name(&self) -> &'static str;
+
+    /// Pass 实例 ID，含义同 `Pass::instance_id`
+    fn instance_id(&self) -> String {
+        FunctionPass::name(self).to_string()
+    }
+
+    /// 获取 Pass 描述
+    fn description(&self) -> &'static str {
+        "No description provided"
+    }
+
+    /// 指定依赖的其它 Pass 实例 ID（可为空）
+    fn dependencies(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// 运行后无论是否修改模块都需要失效的分析名称
+    fn invalidates(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// 修改模块后仍然有效的分析名称
+    fn preserves(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// 在单个函数上运行，返回所做的修改数量
+    fn run_on_function(&self, func: &FunctionRef, diagnostics: &mut DiagnosticBag) -> usize;
+}
+
+pub use crate::ir::function::OPTNONE_ATTRIBUTE;
+
+impl<P: FunctionPass> Pass for P {
+    fn name(&self) -> &'static str {
+        FunctionPass::name(self)
+    }
+
+    fn instance_id(&self) -> String {
+        FunctionPass::instance_id(self)
+    }
+
+    fn description(&self) -> &'static str {
+        FunctionPass::description(self)
+    }
+
+    fn dependencies(&self) -> Vec<&'static str> {
+        FunctionPass::dependencies(self)
+    }
+
+    fn invalidates(&self) -> Vec<&'static str> {
+        FunctionPass::invalidates(self)
+    }
+
+    fn preserves(&self) -> Vec<&'static str> {
+        FunctionPass::preserves(self)
+    }
+
+    fn run(&self, module: &ModuleRef) -> usize {
+        let mut diagnostics = DiagnosticBag::new();
+        let changed = self.run_with_diagnostics(module, &mut diagnostics);
+        for diagnostic in diagnostics.iter() {
+            log::warn!("{}", diagnostic.message);
+        }
+        changed
+    }
+
+    fn run_with_diagnostics(&self, module: &ModuleRef, diagnostics: &mut DiagnosticBag) -> usize {
+        let mut changed = 0;
+        for func in module.borrow().get_functions() {
+            let skip = {
+                let func = func.borrow();
+                func.is_declaration() || func.has_attribute(OPTNONE_ATTRIBUTE)
+            };
+            if skip {
+                continue;
+            }
+            changed += self.run_on_function(&func, diagnostics);
+        }
+        changed
+    }
+}
+
+/// Pass 依赖图（邻接表, 入度表）
+type DependencyGraph = (HashMap<String, Vec<String>>, HashMap<String, usize>);
+
 /// PassManager：负责注册、依赖解析、拓扑排序并依次运行各个优化 Pass
 pub struct PassManager {
     registered: HashMap<String, Box<dyn Pass>>,
@@ -147,6 +282,16 @@ pub struct PassManager {
     last_run_stats: Vec<PassStatistics>,
     /// 是否启用详细日志
     verbose: bool,
+    /// 已注册的分析
+    analyses: HashMap<String, Box<dyn Analysis>>,
+    /// 分析结果缓存，以分析名称为键
+    analysis_cache: HashMap<String, Rc<dyn Any>>,
+    /// 单次运行的总时间预算，None 表示不限
+    time_budget: Option<Duration>,
+    /// 本次运行中各 Pass 已累计的执行时间
+    elapsed: Duration,
+    /// Pass 运行中产生的诊断，由 `take_diagnostics` 取出
+    diagnostics: DiagnosticBag,
 }
 
 impl PassManager {
@@ -159,6 +304,11 @@ impl PassManager {
             collect_stats: false,
             last_run_stats: Vec::new(),
             verbose: false,
+            analyses: HashMap::new(),
+            analysis_cache: HashMap::new(),
+            time_budget: None,
+            elapsed: Duration::ZERO,
+            diagnostics: DiagnosticBag::new(),
         }
     }
 
@@ -174,14 +324,117 @@ impl PassManager {
         self
     }
 
+    /// 设置单次运行（`run` / `run_until_fixpoint`）的总时间预算
+    ///
+    /// 已执行 Pass 的累计耗时超过预算后不再调度后续 Pass，它们被记为跳过，
+    /// 原因为 "时间预算耗尽"；正在运行的 Pass 不会被中断。
+    pub fn set_time_budget(&mut self, budget: Duration) -> &mut Self {
+        self.time_budget = Some(budget);
+        self
+    }
+
+    /// 累计耗时是否已超过时间预算
+    fn budget_exhausted(&self) -> bool {
+        self.time_budget.is_some_and(|budget| self.elapsed > budget)
+    }
+
     /// 注册一个 Pass
+    ///
+    /// 以 `instance_id()` 为键注册。相同 ID 已注册时输出警告并替换旧实例；
+    /// 有意替换请使用 `replace_pass`。
     pub fn register_pass<P: Pass + 'static>(&mut self, pass: P) {
-        let name = pass.name();
-        self.registered.insert(name.to_string(), Box::new(pass));
+        let id = pass.instance_id();
+        if self.registered.contains_key(&id) {
+            log::warn!("Pass '{}' 已注册，旧实例将被替换", id);
+        }
+        self.registered.insert(id, Box::new(pass));
+    }
+
+    /// 注册一个已装箱的 Pass，供按名称动态构造 Pass 的场景使用
+    pub fn register_boxed_pass(&mut self, pass: Box<dyn Pass>) {
+        let id = pass.instance_id();
+        if self.registered.contains_key(&id) {
+            log::warn!("Pass '{}' 已注册，旧实例将被替换", id);
+        }
+        self.registered.insert(id, pass);
+    }
+
+    /// 替换相同实例 ID 的已注册 Pass（未注册时直接注册）
+    pub fn replace_pass<P: Pass + 'static>(&mut self, pass: P) {
+        self.registered.insert(pass.instance_id(), Box::new(pass));
+    }
+
+    /// 按实例 ID 注销 Pass，并将其从 pipeline 中移除
+    ///
+    /// 返回该 Pass 之前是否已注册。
+    pub fn remove_pass(&mut self, name: &str) -> bool {
+        self.pipeline.retain(|p| p != name);
+        self.registered.remove(name).is_some()
+    }
+
+    /// 注册一个分析
+    pub fn register_analysis<A: Analysis + 'static>(&mut self, analysis: A) {
+        let name = analysis.name();
+        self.analysis_cache.remove(name);
+        self.analyses.insert(name.to_string(), Box::new(analysis));
+    }
+
+    /// 获取分析结果，若缓存中没有则重新计算并缓存
+    pub fn get_analysis(&mut self, name: &str, module: &ModuleRef) -> Result<Rc<dyn Any>, PassError> {
+        if let Some(result) = self.analysis_cache.get(name) {
+            return Ok(result.clone());
+        }
+
+        let analysis = self
+            .analyses
+            .get(name)
+            .ok_or_else(|| PassError::NotRegistered(format!("分析 '{}'", name)))?;
+        if self.verbose {
+            println!("计算分析: {}", name);
+        }
+        let result: Rc<dyn Any> = Rc::from(analysis.compute(module));
+        self.analysis_cache.insert(name.to_string(), result.clone());
+        Ok(result)
+    }
+
+    /// 获取指定类型的分析结果
+    pub fn get_analysis_as<T: 'static>(&mut self, name: &str, module: &ModuleRef) -> Result<Rc<T>, PassError> {
+        self.get_analysis(name, module)?
+            .downcast::<T>()
+            .map_err(|_| PassError::NotRegistered(format!("分析 '{}' (结果类型不匹配)", name)))
+    }
+
+    /// 检查分析结果是否已缓存
+    pub fn is_analysis_cached(&self, name: &str) -> bool {
+        self.analysis_cache.contains_key(name)
+    }
+
+    /// 手动使某个分析结果失效
+    pub fn invalidate_analysis(&mut self, name: &str) {
+        self.analysis_cache.remove(name);
+    }
+
+    /// 根据 Pass 的失效/保留声明清理分析缓存
+    fn update_analysis_cache(&mut self, pass_name: &str, changes: usize) {
+        let Some(pass) = self.registered.get(pass_name) else {
+            return;
+        };
+        let invalidated = pass.invalidates();
+        let preserved = pass.preserves();
+
+        let verbose = self.verbose;
+        self.analysis_cache.retain(|name, _| {
+            let keep = !invalidated.contains(&name.as_str())
+                && (changes == 0 || preserved.contains(&name.as_str()));
+            if !keep && verbose {
+                println!("  分析失效: {}", name);
+            }
+            keep
+        });
     }
 
     /// 将 Pass 加入执行流水线
-    pub fn add_to_pipeline(&mut self, pass_name: &'static str) {
+    pub fn add_to_pipeline(&mut self, pass_name: &str) {
         self.pipeline.push(pass_name.to_string());
     }
 
@@ -232,8 +485,8 @@ impl PassManager {
         Ok(())
     }
 
-    /// 对 pipeline 中的 Pass 进行拓扑排序，确保依赖先执行
-    fn topological_sort(&self) -> Result<Vec<String>, PassError> {
+    /// 构建 pipeline 的依赖图：边从依赖指向依赖它的 Pass，同时返回各节点入度
+    fn build_dependency_graph(&self) -> Result<DependencyGraph, PassError> {
         // 检查依赖是否都已注册
         self.check_dependencies()?;
 
@@ -263,6 +516,13 @@ impl PassManager {
             }
         }
 
+        Ok((graph, in_degree))
+    }
+
+    /// 对 pipeline 中的 Pass 进行拓扑排序，确保依赖先执行
+    fn topological_sort(&self) -> Result<Vec<String>, PassError> {
+        let (graph, mut in_degree) = self.build_dependency_graph()?;
+
         // Kahn 算法进行拓扑排序
         let mut sorted = Vec::new();
         let mut queue = VecDeque::new();
@@ -307,6 +567,68 @@ impl PassManager {
         Ok(sorted)
     }
 
+    /// 以 GraphViz DOT 格式输出 pipeline 的依赖图
+    ///
+    /// 节点为 pipeline 中的 Pass，边从 Pass 指向它声明的依赖。存在依赖循环时
+    /// 不会报错，而是将环上的节点标红并在图标签中注明。
+    pub fn pipeline_dot(&self) -> Result<String, PassError> {
+        let (graph, mut in_degree) = self.build_dependency_graph()?;
+
+        // 用 Kahn 算法找出无法排序（位于环上或依赖环）的节点
+        let mut queue: VecDeque<String> = in_degree
+            .iter()
+            .filter(|(_, deg)| **deg == 0)
+            .map(|(name, _)| name.clone())
+            .collect();
+        let mut sorted = HashSet::new();
+        while let Some(name) = queue.pop_front() {
+            for next in &graph[&name] {
+                let in_deg = in_degree.get_mut(next).unwrap();
+                *in_deg -= 1;
+                if *in_deg == 0 {
+                    queue.push_back(next.clone());
+                }
+            }
+            sorted.insert(name);
+        }
+
+        let mut dot = String::from("digraph pipeline {\n    rankdir=LR;\n");
+        if sorted.len() != graph.len() {
+            dot.push_str("    label=\"检测到 Pass 依赖循环\";\n");
+        }
+
+        let mut emitted = HashSet::new();
+        for name in &self.pipeline {
+            if !emitted.insert(name) {
+                continue;
+            }
+            if sorted.contains(name) {
+                dot.push_str(&format!("    \"{}\";\n", name));
+            } else {
+                dot.push_str(&format!("    \"{}\" [color=red];\n", name));
+            }
+        }
+
+        let mut emitted = HashSet::new();
+        for dep in &self.pipeline {
+            if !emitted.insert(dep) {
+                continue;
+            }
+            for dependent in &graph[dep] {
+                dot.push_str(&format!("    \"{}\" -> \"{}\";\n", dependent, dep));
+            }
+        }
+
+        dot.push_str("}\n");
+        Ok(dot)
+    }
+
+    /// 打印 pipeline 依赖图（DOT 格式）
+    pub fn dump_pipeline_graph(&self) -> Result<(), PassError> {
+        print!("{}", self.pipeline_dot()?);
+        Ok(())
+    }
+
     /// DFS 查找依赖环
     #[allow(clippy::only_used_in_recursion)]
     fn find_cycle(
@@ -355,18 +677,107 @@ impl PassManager {
         if self.collect_stats {
             self.last_run_stats.clear();
         }
+        self.elapsed = Duration::ZERO;
+
+        self.execute(&sorted_pipeline, module);
+        Ok(())
+    }
+
+    /// 只运行一个已注册的 Pass 及其传递依赖，便于调试
+    ///
+    /// 临时以该 Pass 与其全部传递依赖组成 pipeline，按依赖顺序运行后恢复原有 pipeline。
+    pub fn run_single(&mut self, name: &str, module: &ModuleRef) -> Result<(), PassError> {
+        if !self.registered.contains_key(name) {
+            return Err(PassError::NotRegistered(name.to_string()));
+        }
+
+        // 收集传递依赖；未注册的依赖留给 `run` 报告 MissingDependency
+        let mut closure: Vec<String> = Vec::new();
+        let mut stack = vec![name.to_string()];
+        while let Some(current) = stack.pop() {
+            if closure.contains(&current) {
+                continue;
+            }
+            if let Some(pass) = self.registered.get(&current) {
+                stack.extend(pass.dependencies().into_iter().map(str::to_string));
+            }
+            closure.push(current);
+        }
+
+        let saved = std::mem::replace(&mut self.pipeline, closure);
+        let result = self.run(module);
+        self.pipeline = saved;
+        result
+    }
+
+    /// 只解析依赖并返回 pipeline 的执行顺序，不运行任何 Pass
+    ///
+    /// 依赖缺失或存在环时返回与 `run` 相同的错误，便于在真正执行前做检查（如命令行试运行）。
+    pub fn validate_pipeline(&self) -> Result<Vec<String>, PassError> {
+        self.topological_sort()
+    }
+
+    /// 反复运行 pipeline，直到没有 Pass 报告修改或达到 `max_iters` 次
+    ///
+    /// 返回实际执行的迭代次数。统计信息会累积所有迭代。
+    pub fn run_until_fixpoint(
+        &mut self,
+        module: &ModuleRef,
+        max_iters: usize,
+    ) -> Result<usize, PassError> {
+        let sorted_pipeline = self.topological_sort()?;
+
+        if self.collect_stats {
+            self.last_run_stats.clear();
+        }
+        self.elapsed = Duration::ZERO;
+
+        let mut iterations = 0;
+        while iterations < max_iters && !self.budget_exhausted() {
+            iterations += 1;
+            if self.verbose {
+                println!("不动点迭代 #{}", iterations);
+            }
+            if !self.execute(&sorted_pipeline, module) {
+                break;
+            }
+        }
+
+        Ok(iterations)
+    }
+
+    /// 按给定顺序执行一遍 Pass，返回是否有 Pass 修改了模块
+    fn execute(&mut self, sorted_pipeline: &[String], module: &ModuleRef) -> bool {
+        let mut changed = false;
 
         // 按顺序执行
-        for name in &sorted_pipeline {
+        for name in sorted_pipeline {
             if let Some(pass) = self.registered.get(name) {
+                // 时间预算耗尽后不再调度后续 Pass
+                if self.budget_exhausted() {
+                    if self.verbose {
+                        println!("跳过 Pass: {} (时间预算耗尽)", name);
+                    }
+                    if self.collect_stats {
+                        self.last_run_stats.push(PassStatistics {
+                            name: name.clone(),
+                            duration: Duration::from_secs(0),
+                            skipped: true,
+                            skip_reason: Some("时间预算耗尽".to_string()),
+                            changes: 0,
+                        });
+                    }
+                    continue;
+                }
+
                 // 检查是否应该运行此 Pass
                 let should_run = pass.should_run(module);
                 
                 if self.verbose {
                     if should_run {
-                        println!("正在运行 Pass: {} ({})", pass.name(), pass.description());
+                        println!("正在运行 Pass: {} ({})", name, pass.description());
                     } else {
-                        println!("跳过 Pass: {} ({})", pass.name(), pass.description());
+                        println!("跳过 Pass: {} ({})", name, pass.description());
                     }
                 }
                 
@@ -374,14 +785,18 @@ impl PassManager {
                 if self.collect_stats {
                     if should_run {
                         let start = Instant::now();
-                        pass.run(module);
+                        let changes = pass.run_with_diagnostics(module, &mut self.diagnostics);
                         let duration = start.elapsed();
+                        self.elapsed += duration;
+                        changed |= changes > 0;
+                        self.update_analysis_cache(name, changes);
                         
                         let stats = PassStatistics {
                             name: name.clone(),
                             duration,
                             skipped: false,
                             skip_reason: None,
+                            changes,
                         };
                         
                         self.last_run_stats.push(stats);
@@ -395,20 +810,25 @@ impl PassManager {
                             duration: Duration::from_secs(0),
                             skipped: true,
                             skip_reason: Some("条件不满足".to_string()),
+                            changes: 0,
                         };
                         
                         self.last_run_stats.push(stats);
                     }
                 } else if should_run {
-                    pass.run(module);
+                    let start = Instant::now();
+                    let changes = pass.run_with_diagnostics(module, &mut self.diagnostics);
+                    self.elapsed += start.elapsed();
+                    changed |= changes > 0;
+                    self.update_analysis_cache(name, changes);
                 }
             }
         }
 
-        Ok(())
+        changed
     }
 
-    /// 获取当前注册的所有 Pass 名称
+    /// 获取当前注册的所有 Pass 实例 ID
     pub fn get_registered_passes(&self) -> Vec<String> {
         self.registered.keys().cloned().collect()
     }
@@ -423,6 +843,11 @@ impl PassManager {
         &self.last_run_stats
     }
 
+    /// 取出 Pass 运行中累计的诊断，并清空内部记录
+    pub fn take_diagnostics(&mut self) -> DiagnosticBag {
+        std::mem::take(&mut self.diagnostics)
+    }
+
     /// 打印最近一次运行的统计信息
     pub fn print_statistics(&self) {
         if self.last_run_stats.is_empty() {
@@ -434,6 +859,7 @@ impl PassManager {
         println!("----------------------------------------");
         
         let mut total_time = Duration::from_secs(0);
+        let mut total_changes = 0;
         let mut executed_count = 0;
         let mut skipped_count = 0;
         
@@ -445,14 +871,16 @@ impl PassManager {
             } else {
                 executed_count += 1;
                 total_time += stats.duration;
+                total_changes += stats.changes;
             }
         }
         
         println!("----------------------------------------");
         println!(
-            "总计: 执行 {} 个 Pass, 跳过 {} 个, 总时间: {:.2}ms",
+            "总计: 执行 {} 个 Pass, 跳过 {} 个, 修改 {} 处, 总时间: {:.2}ms",
             executed_count,
             skipped_count,
+            total_changes,
             total_time.as_secs_f64() * 1000.0
         );
     }
@@ -0,0 +1,144 @@
+use crate::analysis::FunctionCFG;
+use crate::diagnostics::DiagnosticBag;
+use crate::ir::function::FunctionRef;
+use crate::ir::instruction::{Instruction, InstructionModifier, Opcode};
+use crate::ir::operand::Operand;
+use crate::ir::{BasicBlock, BasicBlockRef};
+use crate::optimizer::pass_manager::FunctionPass;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+/// 关键边拆分 Pass
+///
+/// 关键边是从具有多个后继的基本块指向具有多个前驱的基本块的边。
+/// 每条关键边上插入一个只含 `br` 的新基本块：前驱的跳转目标改为新基本块，
+/// 后继中 `phi` 的来源改为新基本块。同一对基本块之间的多条边共用一个新基本块。
+pub struct BreakCriticalEdgesPass;
+
+impl BreakCriticalEdgesPass {
+    pub fn new() -> Self {
+        BreakCriticalEdgesPass
+    }
+
+    /// 生成函数内未使用的基本块名称
+    fn fresh_name(used: &mut HashSet<String>, base: &str) -> String {
+        let name = (0..)
+            .map(|n| match n {
+                0 => base.to_string(),
+                n => format!("{}{}", base, n),
+            })
+            .find(|name| !used.contains(name))
+            .unwrap();
+        used.insert(name.clone());
+        name
+    }
+
+    /// 在 `pred -> succ` 边上插入新基本块
+    fn split_edge(func: &FunctionRef, pred: &BasicBlockRef, succ: &BasicBlockRef, name: String) {
+        let succ_name = succ.borrow().get_name().to_string();
+        let edge = Rc::new(RefCell::new(BasicBlock::new(
+            name.clone(),
+            Some(func.clone()),
+        )));
+        let br = Instruction::with_operands(
+            Opcode::Br,
+            None,
+            vec![Operand::create_basic_block(succ.clone())],
+            InstructionModifier::None,
+        );
+        edge.borrow_mut()
+            .add_instruction(Rc::new(RefCell::new(br)), edge.clone());
+
+        // 前驱中指向后继的跳转目标改为新基本块
+        if let Some(terminator) = pred.borrow().get_terminator() {
+            let mut terminator = terminator.borrow_mut();
+            let indices: &[usize] = match terminator.get_opcode() {
+                Opcode::Br => &[0],
+                Opcode::CondBr => &[1, 2],
+                _ => &[],
+            };
+            for &idx in indices {
+                let target = terminator.get_operand(idx).borrow().get_name().to_string();
+                if target.strip_prefix('%').unwrap_or(&target) == succ_name {
+                    terminator.set_operand_ref(idx, Operand::create_basic_block(edge.clone()));
+                }
+            }
+        }
+        succ.borrow()
+            .replace_phi_incoming(pred.borrow().get_name(), &name);
+
+        let mut func = func.borrow_mut();
+        let position = func
+            .get_basic_blocks()
+            .iter()
+            .position(|bb| Rc::ptr_eq(bb, pred))
+            .map_or(func.get_basic_blocks().len(), |pos| pos + 1);
+        func.insert_basic_block(position, edge);
+    }
+
+    /// 拆分函数中的所有关键边，返回拆分数量
+    fn process_function(func: &FunctionRef) -> usize {
+        let (cfg, blocks) = {
+            let func = func.borrow();
+            (
+                FunctionCFG::compute(&func),
+                func.get_basic_blocks().to_vec(),
+            )
+        };
+        let find = |name: &str| {
+            blocks
+                .iter()
+                .find(|bb| bb.borrow().get_name() == name)
+                .cloned()
+        };
+        let mut used: HashSet<String> = blocks
+            .iter()
+            .map(|bb| bb.borrow().get_name().to_string())
+            .collect();
+
+        let mut split = 0;
+        for pred in &blocks {
+            let pred_name = pred.borrow().get_name().to_string();
+            let mut successors: Vec<String> = cfg.get_successors(&pred_name).to_vec();
+            successors.dedup();
+            if successors.len() < 2 {
+                continue;
+            }
+            for succ_name in successors {
+                let preds: HashSet<&String> = cfg.get_predecessors(&succ_name).iter().collect();
+                if preds.len() < 2 {
+                    continue;
+                }
+                let Some(succ) = find(&succ_name) else {
+                    continue;
+                };
+                let name =
+                    Self::fresh_name(&mut used, &format!("{}.{}.crit", pred_name, succ_name));
+                Self::split_edge(func, pred, &succ, name);
+                split += 1;
+            }
+        }
+        split
+    }
+}
+
+impl Default for BreakCriticalEdgesPass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FunctionPass for BreakCriticalEdgesPass {
+    fn name(&self) -> &'static str {
+        "optimizer::BreakCriticalEdgesPass"
+    }
+
+    fn description(&self) -> &'static str {
+        "在关键边上插入只含跳转的基本块"
+    }
+
+    fn run_on_function(&self, func: &FunctionRef, _diagnostics: &mut DiagnosticBag) -> usize {
+        Self::process_function(func)
+    }
+}
@@ -1,72 +1,297 @@
-use crate::ir::ModuleRef;
-use crate::optimizer::pass_manager::Pass;
+use crate::diagnostics::DiagnosticBag;
+use crate::ir::instruction::Opcode;
+use crate::ir::types::{Type, TypeRef};
+use crate::ir::value::Constant;
+use crate::optimizer::pass_manager::FunctionPass;
+use std::cell::RefCell;
 
 /// 支持折叠的二元整数运算指令
 const FOLDABLE_BIN_OPS: &[&str] = &[
-    "add", "sub", "mul", "sdiv", "udiv", "srem", "urem", "and", "or", "xor",
+    "add", "sub", "mul", "div", "divu", "rem", "remu", "and", "or", "xor",
 ];
 
+/// 将整数按类型位宽截断，视为无符号数；非整数类型保持原值
+fn to_unsigned(value: i64, type_: &Type) -> u64 {
+    if !type_.is_integer() {
+        return value as u64;
+    }
+    let width = type_.get_bit_width();
+    (value as u64) & ((1u64 << width) - 1)
+}
+
+/// 将运算结果截断到类型位宽，并按类型的符号性扩展回 i64
+fn normalize(value: i64, type_: &Type) -> i64 {
+    match type_.is_signed() {
+        Some(signed) if type_.is_integer() => {
+            let shift = 64 - type_.get_bit_width();
+            if signed {
+                (value << shift) >> shift
+            } else {
+                to_unsigned(value, type_) as i64
+            }
+        }
+        _ => value,
+    }
+}
+
+/// 计算操作数全为整型常量的指令结果
+///
+/// `operands` 为 (常量值, 操作数类型)。支持 `FOLDABLE_BIN_OPS` 中的二元运算与类型转换，
+/// 其它指令或无法折叠（如除以 0）时返回 None。常量传播分析与本 Pass 共用此函数。
+pub(crate) fn evaluate(
+    opcode: Opcode,
+    operands: &[(i64, TypeRef)],
+    result_type: &Type,
+) -> Option<i64> {
+    match operands {
+        [(lhs, type_), (rhs, _)] if FOLDABLE_BIN_OPS.contains(&opcode.as_str()) => {
+            fold_binary(opcode, *lhs, *rhs, &type_.borrow())
+        }
+        [(value, source)] if opcode.is_cast() => {
+            fold_cast(opcode, *value, &source.borrow(), result_type)
+        }
+        _ => None,
+    }
+}
+
+/// 折叠二元整数运算，结果按操作数类型截断
+fn fold_binary(opcode: Opcode, lhs: i64, rhs: i64, type_: &Type) -> Option<i64> {
+    let result = match opcode {
+        Opcode::Add => lhs.wrapping_add(rhs),
+        Opcode::Sub => lhs.wrapping_sub(rhs),
+        Opcode::Mul => lhs.wrapping_mul(rhs),
+        Opcode::And => lhs & rhs,
+        Opcode::Or => lhs | rhs,
+        Opcode::Xor => lhs ^ rhs,
+        Opcode::Div | Opcode::Rem => {
+            if rhs == 0 {
+                return None;
+            }
+            if opcode == Opcode::Div {
+                lhs.wrapping_div(rhs)
+            } else {
+                lhs.wrapping_rem(rhs)
+            }
+        }
+        Opcode::DivU | Opcode::RemU => {
+            // 按类型位宽把操作数视为无符号数
+            let (lhs_u, rhs_u) = (to_unsigned(lhs, type_), to_unsigned(rhs, type_));
+            if rhs_u == 0 {
+                return None;
+            }
+            let result = if opcode == Opcode::DivU { lhs_u / rhs_u } else { lhs_u % rhs_u };
+            result as i64
+        }
+        _ => return None,
+    };
+    Some(normalize(result, type_))
+}
+
+/// 二元运算的数学结果是否超出类型的表示范围（即折叠结果发生了回绕）
+///
+/// 位运算与无符号除法/取余不会溢出；除以 0 不算溢出，由 `fold_binary` 跳过。
+fn overflows(opcode: Opcode, lhs: i64, rhs: i64, type_: &Type) -> bool {
+    let (lhs, rhs) = (i128::from(lhs), i128::from(rhs));
+    let exact = match opcode {
+        Opcode::Add => lhs + rhs,
+        Opcode::Sub => lhs - rhs,
+        Opcode::Mul => lhs * rhs,
+        Opcode::Div if rhs != 0 => lhs / rhs,
+        _ => return false,
+    };
+    match fold_binary(opcode, lhs as i64, rhs as i64, type_) {
+        Some(folded) => i128::from(folded) != exact,
+        None => false,
+    }
+}
+
+/// 折叠类型转换
+///
+/// 等宽 bitcast 只是重新解释位模式：按源类型取出位模式，再按目标类型的符号性解释。
+/// sext/zext 按源类型位宽做符号/零扩展，trunc 截断到目标位宽。
+fn fold_cast(opcode: Opcode, value: i64, source: &Type, target: &Type) -> Option<i64> {
+    let result = match opcode {
+        Opcode::Bitcast if source.get_bit_width() == target.get_bit_width() => {
+            normalize(to_unsigned(value, source) as i64, target)
+        }
+        Opcode::SExt if source.is_integer() && target.is_integer() => {
+            let shift = 64 - source.get_bit_width();
+            normalize((value << shift) >> shift, target)
+        }
+        Opcode::ZExt if source.is_integer() && target.is_integer() => {
+            normalize(to_unsigned(value, source) as i64, target)
+        }
+        Opcode::Trunc if target.is_integer() => normalize(value, target),
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// 每个函数折叠迭代次数的默认上限
+pub const DEFAULT_MAX_ITERATIONS: usize = 1000;
+
 /// 常量折叠 Pass（简化占位实现）
-pub struct ConstantFoldingPass;
+///
+/// 默认按类型位宽回绕；严格模式下发生溢出的运算不折叠，改为记录一条警告
+/// （每条指令只报告一次），既推入诊断，也可通过 `warnings` 读取。
+/// 每个函数的折叠迭代次数有上限，防止相互抵消的规则导致死循环。
+pub struct ConstantFoldingPass {
+    strict: bool,
+    warnings: RefCell<Vec<String>>,
+    max_iterations: usize,
+}
 
 impl ConstantFoldingPass {
     /// 创建新的常量折叠 Pass
     pub fn new() -> Self {
-        Self
+        Self {
+            strict: false,
+            warnings: RefCell::new(Vec::new()),
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+        }
     }
 
-    fn try_fold(&self, instr: &crate::ir::instruction::InstructionRef) -> bool {
-        let opcode_str = instr.borrow().get_opcode().as_str();
-        if !FOLDABLE_BIN_OPS.contains(&opcode_str) {
-            return false;
+    /// 链式接口：设置每个函数的折叠迭代次数上限
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// 创建严格模式的常量折叠 Pass：溢出的运算保留原样并报告警告
+    pub fn new_strict() -> Self {
+        Self {
+            strict: true,
+            ..Self::new()
         }
-        if instr.borrow().get_operand_count() != 2 {
+    }
+
+    /// 严格模式下记录的溢出警告，包括通过不带诊断的 `Pass::run` 运行时产生的
+    pub fn warnings(&self) -> Vec<String> {
+        self.warnings.borrow().clone()
+    }
+
+    /// 严格模式下检查溢出，发生溢出时返回 true
+    ///
+    /// 折叠迭代中同一条指令会被反复检查，警告按指令 ID 去重后追加到 `reported`。
+    fn check_overflow(
+        &self,
+        instr: &crate::ir::instruction::Instruction,
+        operands: &[(i64, TypeRef)],
+        reported: &mut Vec<(u64, String)>,
+    ) -> bool {
+        let [(lhs, type_), (rhs, _)] = operands else {
+            return false;
+        };
+        if !self.strict || !overflows(instr.get_opcode(), *lhs, *rhs, &type_.borrow()) {
             return false;
         }
-        let lhs_ref = instr.borrow().get_operand(0);
-        let rhs_ref = instr.borrow().get_operand(1);
-        let lhs_val = lhs_ref.borrow();
-        let rhs_val = rhs_ref.borrow();
-        if let (Some(lhs_const), Some(rhs_const)) = (lhs_val.as_i64(), rhs_val.as_i64()) {
-            let result = match opcode_str {
-                "add" => lhs_const.wrapping_add(rhs_const),
-                "sub" => lhs_const.wrapping_sub(rhs_const),
-                "mul" => lhs_const.wrapping_mul(rhs_const),
-                "and" => lhs_const & rhs_const,
-                "or" => lhs_const | rhs_const,
-                "xor" => lhs_const ^ rhs_const,
-                "sdiv" | "udiv" => {
-                    if rhs_const == 0 { return false; } else { lhs_const / rhs_const }
-                }
-                "srem" | "urem" => {
-                    if rhs_const == 0 { return false; } else { lhs_const % rhs_const }
-                }
-                _ => return false,
-            };
-            drop(lhs_val);
-            drop(rhs_val);
-            instr.borrow_mut().replace_with_constant(result.to_string());
-            return true;
+        if !reported.iter().any(|(id, _)| *id == instr.get_id()) {
+            let message = format!(
+                "常量折叠溢出: {} = {} {}, {} 超出 {} 的表示范围",
+                instr.get_name().unwrap_or_default(),
+                instr.get_opcode(),
+                lhs,
+                rhs,
+                type_.borrow()
+            );
+            reported.push((instr.get_id(), message));
         }
-        false
-    }
-
-    fn process_function(&self, func: &crate::ir::function::FunctionRef) {
-        let mut changed = true;
-        while changed {
-            changed = false;
-            for bb in func.borrow().get_basic_blocks() {
-                for instr in bb.borrow().get_instructions() {
-                    if self.try_fold(instr) {
-                        changed = true;
-                    }
+        true
+    }
+
+    fn try_fold(
+        &self,
+        instr: &crate::ir::instruction::InstructionRef,
+        reported: &mut Vec<(u64, String)>,
+    ) -> bool {
+        let folded = {
+            let instr = instr.borrow();
+            if !instr.has_result() {
+                return false;
+            }
+            // 扩展指令与未知指令的语义对编译器不透明，不参与折叠
+            if instr.get_opcode().is_extension() || instr.get_opcode().is_unknown() {
+                return false;
+            }
+            let mut operands = Vec::new();
+            for op in instr.get_operands() {
+                let op = op.borrow();
+                let Some(value) = op.as_i64() else {
+                    return false;
+                };
+                operands.push((value, op.get_type()));
+            }
+            if self.check_overflow(&instr, &operands, reported) {
+                return false;
+            }
+            evaluate(instr.get_opcode(), &operands, &instr.get_type().borrow())
+        };
+        match folded {
+            Some(value) => {
+                instr.borrow_mut().replace_with_constant(Constant::Int(value));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 对函数反复折叠直到不再变化，返回折叠的指令数
+    fn process_function(
+        &self,
+        func: &crate::ir::function::FunctionRef,
+        diagnostics: &mut DiagnosticBag,
+    ) -> usize {
+        let mut reported = Vec::new();
+        let folded =
+            self.fold_until_stable(func, diagnostics, |instr| self.try_fold(instr, &mut reported));
+        for (_, message) in reported {
+            diagnostics.warning(&message, None);
+            self.warnings.borrow_mut().push(message);
+        }
+        folded
+    }
+
+    /// 对函数的每条指令反复应用 `rule`，直到一轮中没有指令被修改或达到迭代上限
+    ///
+    /// 达到上限时向 `diagnostics` 推入一条警告并停止，保证终止。返回 `rule` 报告修改的次数。
+    fn fold_until_stable(
+        &self,
+        func: &crate::ir::function::FunctionRef,
+        diagnostics: &mut DiagnosticBag,
+        mut rule: impl FnMut(&crate::ir::instruction::InstructionRef) -> bool,
+    ) -> usize {
+        let mut folded = 0;
+        for _ in 0..self.max_iterations {
+            let mut changed = false;
+            for instr in func.borrow().instructions() {
+                if rule(&instr) {
+                    changed = true;
+                    folded += 1;
                 }
             }
+            if !changed {
+                return folded;
+            }
         }
+        diagnostics.warning(
+            &format!(
+                "常量折叠在函数 '{}' 中达到迭代上限 {}，提前停止",
+                func.borrow().get_name(),
+                self.max_iterations
+            ),
+            None,
+        );
+        folded
+    }
+}
+
+impl Default for ConstantFoldingPass {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-impl Pass for ConstantFoldingPass {
+impl FunctionPass for ConstantFoldingPass {
     fn name(&self) -> &'static str {
         "optimizer::ConstantFoldingPass"
     }
@@ -79,21 +304,54 @@ impl Pass for ConstantFoldingPass {
         Vec::new()
     }
 
-    fn run(&self, module: &ModuleRef) {
-        for func in module.borrow().get_functions() {
-            self.process_function(&func);
-        }
+    fn run_on_function(
+        &self,
+        func: &crate::ir::function::FunctionRef,
+        diagnostics: &mut DiagnosticBag,
+    ) -> usize {
+        self.process_function(func, diagnostics)
     }
 }
 
-#[cfg(all(test, feature = "advanced_pass_tests"))]
+#[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "advanced_pass_tests")]
     use crate::ir::Module;
+    #[cfg(feature = "advanced_pass_tests")]
+    use crate::optimizer::Pass;
 
+    #[cfg(feature = "advanced_pass_tests")]
     #[test]
     fn test_const_fold_stub() {
         let module = Module::new("test_module".to_string());
         ConstantFoldingPass::new().run(&std::rc::Rc::new(std::cell::RefCell::new(module)));
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_oscillating_rule_terminates() {
+        let source = r#".module m
+.function f(.param %x i32) -> i32 {
+entry:
+    %a = add %x, 1
+    ret %a
+}
+"#;
+        let module = crate::frontend::parse_vil(source, "osc.vil").expect("应成功解析模块");
+        let func = module.borrow().get_function("f").unwrap();
+
+        // 人为构造的振荡规则：每次都把结果名在 %a 与 %b 之间切换，永远报告修改
+        let pass = ConstantFoldingPass::new().with_max_iterations(5);
+        let mut diagnostics = DiagnosticBag::new();
+        let folded = pass.fold_until_stable(&func, &mut diagnostics, |instr| {
+            let mut instr = instr.borrow_mut();
+            let Some(name) = instr.get_name() else {
+                return false;
+            };
+            instr.set_name(if name == "%a" { "%b" } else { "%a" }.to_string());
+            true
+        });
+        assert_eq!(folded, 5);
+        assert_eq!(diagnostics.count(crate::diagnostics::Severity::Warning), 1);
+    }
+}
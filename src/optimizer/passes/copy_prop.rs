@@ -0,0 +1,68 @@
+use crate::diagnostics::DiagnosticBag;
+use crate::ir::function::FunctionRef;
+use crate::ir::instruction::{Instruction, InstructionRef, Opcode};
+use crate::ir::operand::OperandKind;
+use crate::ir::value::ValueRef;
+use crate::optimizer::pass_manager::FunctionPass;
+
+/// 复制传播 Pass
+///
+/// 将 `%r = mov %v` 的结果的所有使用替换为 `%v` 并删除该 `mov`。只处理单个值操作数、
+/// 结果与源类型相同的复制；带掩码的 `mov` 与常量折叠留下的无操作数 `mov` 保持不变。
+pub struct CopyPropagationPass;
+
+impl CopyPropagationPass {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// 若指令是可以传播的复制，返回被复制的值
+    fn copy_source(instr: &Instruction) -> Option<ValueRef> {
+        if instr.get_opcode() != Opcode::Mov || instr.get_operand_count() != 1 {
+            return None;
+        }
+        let result = instr.get_result()?;
+        let source = match instr.get_operand_ref(0).borrow().get_kind() {
+            OperandKind::Value(value) => value.clone(),
+            _ => return None,
+        };
+        let same_type =
+            *result.borrow().get_type().borrow() == *source.borrow().get_type().borrow();
+        let self_copy = result.borrow().get_name() == source.borrow().get_name();
+        (same_type && !self_copy).then_some(source)
+    }
+}
+
+impl Default for CopyPropagationPass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FunctionPass for CopyPropagationPass {
+    fn name(&self) -> &'static str {
+        "optimizer::CopyPropagationPass"
+    }
+
+    fn description(&self) -> &'static str {
+        "以被复制的值替换 mov 的结果并删除复制"
+    }
+
+    fn run_on_function(&self, func: &FunctionRef, _diagnostics: &mut DiagnosticBag) -> usize {
+        // 逐条处理时重新读取源操作数：链式复制中前一条被传播后，后一条的源已被替换
+        let instrs: Vec<InstructionRef> = func.borrow().instructions().collect();
+        let mut propagated = 0;
+        for instr in instrs {
+            let Some(source) = Self::copy_source(&instr.borrow()) else {
+                continue;
+            };
+            Instruction::replace_all_uses_with(&instr, &source);
+            let parent = instr.borrow().get_parent_bb();
+            if let Some(bb) = parent {
+                bb.borrow_mut().remove_instruction(&instr);
+            }
+            propagated += 1;
+        }
+        propagated
+    }
+}
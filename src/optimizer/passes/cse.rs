@@ -1,25 +1,28 @@
-use crate::ir::ModuleRef;
-use crate::optimizer::pass_manager::Pass;
+use crate::analysis::MemDeps;
+use crate::ir::instruction::{Instruction, InstructionRef, Opcode};
+use crate::ir::value::ValueRef;
+use crate::diagnostics::DiagnosticBag;
+use crate::ir::function::FunctionRef;
+use crate::optimizer::pass_manager::FunctionPass;
 use std::collections::HashMap;
 
-use crate::ir::instruction::Instruction;
-
-fn has_side_effects(instr: &Instruction) -> bool {
-    match instr.get_opcode().as_str() {
-        "store" | "call" | "ret" | "br" | "condbr" => true,
-        "load" => instr.has_attribute("volatile"),
-        _ => false,
-    }
-}
-
 /// 公共子表达式消除 Pass（简化占位实现）
+///
+/// 在基本块内合并相同的表达式。`load` 同样参与合并，但遇到可能与其指针别名的
+/// `store`（由 `MemDeps` 判断，或任意 `call`）后，之前缓存的 load 表达式失效。
 pub struct CommonSubexpressionEliminationPass;
 
 impl CommonSubexpressionEliminationPass {
     pub fn new() -> Self { Self }
 }
 
-impl Pass for CommonSubexpressionEliminationPass {
+impl Default for CommonSubexpressionEliminationPass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FunctionPass for CommonSubexpressionEliminationPass {
     fn name(&self) -> &'static str {
         "optimizer::CommonSubexpressionEliminationPass"
     }
@@ -32,69 +35,76 @@ impl Pass for CommonSubexpressionEliminationPass {
         vec!["optimizer::ConstantFoldingPass"]
     }
 
-    fn run(&self, module: &ModuleRef) {
-        for func in module.borrow().get_functions() {
-            for bb in func.borrow().get_basic_blocks() {
-                let mut available: HashMap<String, String> = HashMap::new(); // sig -> name
-
-                // 收集需要删除的指令
-                let mut to_delete = Vec::new();
+    fn run_on_function(&self, func: &FunctionRef, _diagnostics: &mut DiagnosticBag) -> usize {
+        let mut eliminated = 0;
+        for bb in func.borrow().get_basic_blocks() {
+            let mut available: HashMap<String, ValueRef> = HashMap::new(); // sig -> 结果值
+            let mut loads: HashMap<String, InstructionRef> = HashMap::new(); // load sig -> load 指令
 
-                for instr in bb.borrow().get_instructions() {
-                    let ib = instr.borrow();
-                    if ib.has_result() && !has_side_effects(&ib) {
-                        // 构造签名
-                        let mut sig = String::from(ib.get_opcode().as_str());
-                        sig.push('(');
-                        for idx in 0..ib.get_operand_count() {
-                            if idx > 0 { sig.push(','); }
-                            let op = ib.get_operand(idx);
-                            let op_val = op.borrow();
-                            sig.push_str(op_val.get_name());
-                        }
-                        sig.push(')');
+            // 收集需要删除的指令
+            let mut to_delete = Vec::new();
 
-                        if let Some(existing) = available.get(&sig) {
-                            if let Some(cur_name) = ib.get_name() {
-                                // 替换所有引用
-                                drop(ib);
-                                Self::replace_uses(&func, cur_name.as_str(), existing.as_str());
-                                to_delete.push(instr.clone());
+            for instr in bb.borrow().get_instructions() {
+                let ib = instr.borrow();
+                // 不跨越屏障指令复用之前计算的表达式
+                if ib.get_opcode().is_barrier() {
+                    available.clear();
+                    loads.clear();
+                    continue;
+                }
+                // 写内存的指令使可能别名的 load 失效
+                match ib.get_opcode() {
+                    Opcode::Store => {
+                        loads.retain(|sig, load| {
+                            let aliased = !MemDeps::can_reorder(&load.borrow(), &ib);
+                            if aliased {
+                                available.remove(sig);
                             }
-                        } else if let Some(result_name) = ib.get_name() {
-                            available.insert(sig, result_name.to_string());
+                            !aliased
+                        });
+                    }
+                    Opcode::Call => {
+                        for sig in loads.keys() {
+                            available.remove(sig);
                         }
+                        loads.clear();
                     }
+                    _ => {}
                 }
+                // 纯指令可以直接合并；非 volatile load 由上面的 loads 表负责失效
+                let load = ib.get_opcode() == Opcode::Load && !ib.has_side_effects();
+                if ib.has_result() && (ib.is_pure() || load) {
+                    // 构造签名
+                    let mut sig = String::from(ib.get_opcode().as_str());
+                    sig.push('(');
+                    for idx in 0..ib.get_operand_count() {
+                        if idx > 0 { sig.push(','); }
+                        let op = ib.get_operand(idx);
+                        let op_val = op.borrow();
+                        sig.push_str(op_val.get_name());
+                    }
+                    sig.push(')');
 
-                for instr in to_delete {
-                    bb.borrow_mut().remove_instruction(&instr);
+                    if let Some(existing) = available.get(&sig).cloned() {
+                        // 替换所有引用
+                        drop(ib);
+                        Instruction::replace_all_uses_with(instr, &existing);
+                        to_delete.push(instr.clone());
+                    } else if let Some(result) = ib.get_result() {
+                        if ib.get_opcode() == Opcode::Load {
+                            loads.insert(sig.clone(), instr.clone());
+                        }
+                        available.insert(sig, result);
+                    }
                 }
             }
-        }
-    }
-}
 
-impl CommonSubexpressionEliminationPass {
-    fn replace_uses(
-        func: &crate::ir::function::FunctionRef,
-        old_name: &str,
-        new_name: &str,
-    ) {
-        for bb in func.borrow().get_basic_blocks() {
-            for instr in bb.borrow().get_instructions() {
-                let mut ib = instr.borrow_mut();
-                for idx in 0..ib.get_operand_count() {
-                    let op = ib.get_operand(idx);
-                    if op.borrow().get_name() == old_name {
-                        // 创建新 ValueRef 引用
-                        let ty = op.borrow().get_type();
-                        let new_val = crate::ir::value::Value::new(ty, new_name.to_string());
-                        ib.set_operand(idx, std::rc::Rc::new(std::cell::RefCell::new(new_val)));
-                    }
-                }
+            eliminated += to_delete.len();
+            for instr in to_delete {
+                bb.borrow_mut().remove_instruction(&instr);
             }
         }
+        eliminated
     }
 }
 
@@ -102,6 +112,7 @@ impl CommonSubexpressionEliminationPass {
 mod tests {
     use super::*;
     use crate::ir::Module;
+    use crate::optimizer::Pass;
 
     #[test]
     fn test_cse_stub() {
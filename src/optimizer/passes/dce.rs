@@ -1,26 +1,80 @@
-use crate::ir::{ModuleRef, Instruction};
-use crate::optimizer::pass_manager::Pass;
+use crate::diagnostics::DiagnosticBag;
+use crate::ir::function::FunctionRef;
+use crate::optimizer::pass_manager::FunctionPass;
 
-use std::collections::{HashSet, VecDeque};
-
-fn has_side_effects(instr: &Instruction) -> bool {
-    match instr.get_opcode().as_str() {
-        "store" | "call" | "ret" | "br" | "condbr" => true,
-        "load" => instr.has_attribute("volatile"),
-        _ => false,
-    }
-}
+use std::collections::{HashMap, HashSet, VecDeque};
 
 /// 死代码消除 Pass（简化占位实现）
-pub struct DeadCodeEliminationPass;
+///
+/// 默认以标记-清除方式删除所有不影响程序结果的指令；`fast()` 模式只做一遍逆序扫描，
+/// 删除 `Instruction::is_trivially_dead` 意义下的死指令，开销更低但不删除死 load 与 phi。
+pub struct DeadCodeEliminationPass {
+    fast: bool,
+}
 
 impl DeadCodeEliminationPass {
     pub fn new() -> Self {
-        Self
+        Self { fast: false }
+    }
+
+    /// 创建快速模式的死代码消除 Pass
+    pub fn fast() -> Self {
+        Self { fast: true }
+    }
+
+    /// 逆序扫描一遍，删除没有使用者的纯指令，返回删除的指令数
+    ///
+    /// 判定与 `Instruction::is_trivially_dead` 相同，但使用次数预先统计一次并在删除时递减，
+    /// 因此一条死指令被删除后，排在它之前、只被它使用的定义在同一遍中也会被删除。
+    fn backward_scan(func: &FunctionRef) -> usize {
+        let mut uses: HashMap<String, usize> = HashMap::new();
+        for instr in func.borrow().instructions() {
+            for op in instr.borrow().get_operands() {
+                let op = op.borrow();
+                if op.is_reference() {
+                    *uses.entry(op.get_name().to_string()).or_default() += 1;
+                }
+            }
+        }
+
+        let mut removed = 0;
+        let blocks = func.borrow().get_basic_blocks().to_vec();
+        for bb in blocks.iter().rev() {
+            let instrs = bb.borrow().get_instructions().to_vec();
+            for instr in instrs.iter().rev() {
+                let dead = {
+                    let instr = instr.borrow();
+                    instr.is_pure()
+                        && instr
+                            .get_name()
+                            .is_none_or(|name| uses.get(&name).copied().unwrap_or(0) == 0)
+                };
+                if !dead {
+                    continue;
+                }
+                for op in instr.borrow().get_operands() {
+                    let op = op.borrow();
+                    if op.is_reference()
+                        && let Some(count) = uses.get_mut(op.get_name())
+                    {
+                        *count = count.saturating_sub(1);
+                    }
+                }
+                bb.borrow_mut().remove_instruction(instr);
+                removed += 1;
+            }
+        }
+        removed
     }
 }
 
-impl Pass for DeadCodeEliminationPass {
+impl Default for DeadCodeEliminationPass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FunctionPass for DeadCodeEliminationPass {
     fn name(&self) -> &'static str {
         "optimizer::DeadCodeEliminationPass"
     }
@@ -29,75 +83,76 @@ impl Pass for DeadCodeEliminationPass {
         "删除不会影响程序结果的无用指令"
     }
 
+    fn instance_id(&self) -> String {
+        if self.fast {
+            format!("{}::Fast", FunctionPass::name(self))
+        } else {
+            FunctionPass::name(self).to_string()
+        }
+    }
+
     fn dependencies(&self) -> Vec<&'static str> {
         Vec::new()
     }
 
-    fn run(&self, module: &ModuleRef) {
-        for func in module.borrow().get_functions() {
-            // 第一遍：收集定义 map
-            let mut def_map = std::collections::HashMap::new(); // name -> instr ptr
-            for bb in func.borrow().get_basic_blocks() {
-                for instr in bb.borrow().get_instructions() {
-                    if let Some(name) = instr.borrow().get_name() {
-                        def_map.insert(name.clone(), instr.clone());
-                    }
-                }
+    fn run_on_function(&self, func: &FunctionRef, _diagnostics: &mut DiagnosticBag) -> usize {
+        if self.fast {
+            return Self::backward_scan(func);
+        }
+        let mut removed = 0;
+        // 第一遍：收集定义 map
+        let mut def_map = std::collections::HashMap::new(); // name -> instr ptr
+        for instr in func.borrow().instructions() {
+            let name = instr.borrow().get_name();
+            if let Some(name) = name {
+                def_map.insert(name, instr);
             }
+        }
 
-            // 活跃集合
-            let mut live: HashSet<*const std::cell::RefCell<crate::ir::instruction::Instruction>> = HashSet::new();
-            let mut work: VecDeque<crate::ir::instruction::InstructionRef> = VecDeque::new();
-
-            // 具有副作用的指令先入队
-            for bb in func.borrow().get_basic_blocks() {
-                for instr in bb.borrow().get_instructions() {
-                    if has_side_effects(&instr.borrow()) {
-                        let ptr = std::rc::Rc::as_ptr(instr);
-                        live.insert(ptr);
-                        work.push_back(instr.clone());
-                    }
-                }
+        // 活跃集合（按指令 ID）
+        let mut live: HashSet<u64> = HashSet::new();
+        let mut work: VecDeque<crate::ir::instruction::InstructionRef> = VecDeque::new();
+
+        // 具有副作用的指令先入队
+        for instr in func.borrow().instructions() {
+            if instr.borrow().has_side_effects() {
+                live.insert(instr.borrow().get_id());
+                work.push_back(instr);
             }
+        }
 
-            // 向后追踪依赖
-            while let Some(instr) = work.pop_front() {
-                let instr_borrow = instr.borrow();
-                for idx in 0..instr_borrow.get_operand_count() {
-                    let op = instr_borrow.get_operand(idx);
-                    let op_val = op.borrow();
-                    if op_val.is_reference() {
-                        let name = op_val.get_name();
-                        if let Some(def_instr) = def_map.get(name) {
-                            let ptr = std::rc::Rc::as_ptr(def_instr);
-                            if live.insert(ptr) {
-                                work.push_back(def_instr.clone());
-                            }
-                        }
+        // 向后追踪依赖
+        while let Some(instr) = work.pop_front() {
+            let instr_borrow = instr.borrow();
+            for idx in 0..instr_borrow.get_operand_count() {
+                let op = instr_borrow.get_operand(idx);
+                let op_val = op.borrow();
+                if op_val.is_reference() {
+                    let name = op_val.get_name();
+                    if let Some(def_instr) = def_map.get(name)
+                        && live.insert(def_instr.borrow().get_id())
+                    {
+                        work.push_back(def_instr.clone());
                     }
                 }
             }
+        }
 
-            // 第二遍：删除 dead 指令
-            for bb in func.borrow().get_basic_blocks() {
-                // 收集要删除的指令
-                let to_remove: Vec<_> = bb
-                    .borrow()
-                    .get_instructions()
-                    .iter()
-                    .filter(|instr| {
-                        if !instr.borrow().has_result() { return false; }
-                        let ptr = std::rc::Rc::as_ptr(*instr);
-                        !live.contains(&ptr)
-                    })
-                    .cloned()
-                    .collect();
-
-                for instr in to_remove {
-                    bb.borrow_mut().remove_instruction(&instr);
-                }
-            }
+        // 第二遍：删除 dead 指令
+        let to_remove: Vec<_> = func
+            .borrow()
+            .instructions_with_blocks()
+            .filter(|(_, instr)| {
+                let instr = instr.borrow();
+                instr.has_result() && !live.contains(&instr.get_id())
+            })
+            .collect();
+
+        removed += to_remove.len();
+        for (bb, instr) in to_remove {
+            bb.borrow_mut().remove_instruction(&instr);
         }
+        removed
     }
 }
 
@@ -105,6 +160,7 @@ impl Pass for DeadCodeEliminationPass {
 mod tests {
     use super::*;
     use crate::ir::Module;
+    use crate::optimizer::Pass;
 
     #[test]
     fn test_dce_stub() {
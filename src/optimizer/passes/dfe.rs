@@ -0,0 +1,85 @@
+use crate::analysis::CallGraph;
+use crate::ir::ModuleRef;
+use crate::ir::function::EXPORT_ATTRIBUTE;
+use crate::optimizer::pass_manager::Pass;
+
+/// 死函数消除 Pass
+///
+/// 从根函数（通过 `with_root` 配置、模块入口函数、带有 `export` 属性或 `external`/`weak`
+/// 链接属性的函数）出发沿调用图标记可达函数，删除其余函数。
+/// 模块中找不到任何根时不做修改，以免误删整个程序。
+pub struct DeadFunctionEliminationPass {
+    roots: Vec<String>,
+}
+
+impl DeadFunctionEliminationPass {
+    pub fn new() -> Self {
+        DeadFunctionEliminationPass { roots: Vec::new() }
+    }
+
+    /// 链式接口：添加一个根函数
+    pub fn with_root(mut self, name: &str) -> Self {
+        self.roots.push(name.to_string());
+        self
+    }
+}
+
+impl Default for DeadFunctionEliminationPass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pass for DeadFunctionEliminationPass {
+    fn name(&self) -> &'static str {
+        "optimizer::DeadFunctionEliminationPass"
+    }
+
+    fn description(&self) -> &'static str {
+        "删除从根函数不可达的函数"
+    }
+
+    fn invalidates(&self) -> Vec<&'static str> {
+        vec!["analysis::CallGraph"]
+    }
+
+    fn run(&self, module: &ModuleRef) -> usize {
+        let functions = module.borrow().get_functions();
+        let mut roots: Vec<String> = functions
+            .iter()
+            .filter(|f| {
+                let f = f.borrow();
+                f.has_attribute(EXPORT_ATTRIBUTE) || f.get_linkage().is_visible()
+            })
+            .map(|f| f.borrow().get_name().to_string())
+            .collect();
+        roots.extend(
+            module
+                .borrow()
+                .get_entry_function()
+                .map(|f| f.borrow().get_name().to_string()),
+        );
+        roots.extend(
+            self.roots
+                .iter()
+                .filter(|r| module.borrow().get_function(r).is_some())
+                .cloned(),
+        );
+        if roots.is_empty() {
+            return 0;
+        }
+
+        let reachable = CallGraph::compute(module).reachable_from(roots.iter().map(String::as_str));
+        let dead: Vec<String> = functions
+            .iter()
+            .map(|f| f.borrow().get_name().to_string())
+            .filter(|name| !reachable.contains(name))
+            .collect();
+
+        let mut module = module.borrow_mut();
+        for name in &dead {
+            module.remove_function(name);
+        }
+        dead.len()
+    }
+}
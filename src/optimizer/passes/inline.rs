@@ -0,0 +1,406 @@
+use crate::analysis::CallGraph;
+use crate::ir::function::{Function, FunctionRef};
+use crate::ir::instruction::{Instruction, InstructionModifier, InstructionRef, Opcode};
+use crate::ir::operand::{Operand, OperandKind};
+use crate::ir::types::Type;
+use crate::ir::value::{Value, ValueRef};
+use crate::ir::{BasicBlock, BasicBlockRef, ModuleRef};
+use crate::optimizer::pass_manager::{OPTNONE_ATTRIBUTE, Pass};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+/// 函数内联 Pass
+///
+/// 将指令代价之和（见 `Instruction::cost`）不超过阈值的被调函数内联到调用点。被调函数的值与基本块以 `.iN` 后缀重新命名，
+/// 形参映射到调用实参。单基本块被调函数直接展开到调用点；多基本块被调函数在调用点拆分
+/// 调用者基本块，`ret` 改为跳转到续接块，多个返回值在续接块中以 `phi` 合并。
+/// 递归调用（被调函数能够到达调用者）与 `optnone` 被调函数不会被内联。
+pub struct InlinePass {
+    threshold: usize,
+}
+
+/// 单个调用点
+struct CallSite {
+    block: BasicBlockRef,
+    index: usize,
+    callee: FunctionRef,
+}
+
+/// 递归判断所需的调用图，每次 `run` 只计算一次，可达集合按被调函数缓存
+///
+/// 内联只会把被调函数的边复制到调用者，不会产生新的可达关系，
+/// 因此开始时的调用图对递归判断是保守的。
+struct RecursionInfo {
+    call_graph: CallGraph,
+    cyclic: HashMap<String, bool>,
+    reachable: HashMap<String, HashSet<String>>,
+}
+
+impl RecursionInfo {
+    fn new(module: &ModuleRef) -> Self {
+        RecursionInfo {
+            call_graph: CallGraph::compute(module),
+            cyclic: HashMap::new(),
+            reachable: HashMap::new(),
+        }
+    }
+
+    /// 被调函数能回到调用者或自身时视为递归
+    fn is_recursive(&mut self, callee: &str, caller: &str) -> bool {
+        let call_graph = &self.call_graph;
+        let cyclic = *self.cyclic.entry(callee.to_string()).or_insert_with(|| {
+            call_graph
+                .reachable_from(call_graph.get_callees(callee).iter().map(String::as_str))
+                .contains(callee)
+        });
+        cyclic
+            || self
+                .reachable
+                .entry(callee.to_string())
+                .or_insert_with(|| call_graph.reachable_from([callee]))
+                .contains(caller)
+    }
+}
+
+impl InlinePass {
+    pub fn new() -> Self {
+        InlinePass { threshold: 16 }
+    }
+
+    /// 链式接口：设置被调函数的最大代价
+    pub fn with_threshold(mut self, threshold: usize) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// 函数体的总代价
+    fn function_cost(func: &Function) -> usize {
+        func.instructions()
+            .map(|instr| instr.borrow().cost() as usize)
+            .sum()
+    }
+
+    /// 查找调用者中第一个可内联的调用点
+    fn find_call_site(
+        &self,
+        module: &ModuleRef,
+        caller: &FunctionRef,
+        recursion: &mut RecursionInfo,
+    ) -> Option<CallSite> {
+        let caller_name = caller.borrow().get_name().to_string();
+
+        for bb in caller.borrow().get_basic_blocks() {
+            for (index, instr) in bb.borrow().get_instructions().iter().enumerate() {
+                let instr = instr.borrow();
+                if instr.get_opcode() != Opcode::Call || instr.get_operand_count() == 0 {
+                    continue;
+                }
+                let callee_name = instr.get_operand(0).borrow().get_name().to_string();
+                let Some(callee) = callee_name
+                    .strip_prefix('@')
+                    .and_then(|name| module.borrow().get_function(name))
+                else {
+                    continue;
+                };
+
+                let callee_ref = callee.borrow();
+                let recursive = recursion.is_recursive(callee_ref.get_name(), &caller_name);
+                // 有结果参数的被调函数以 `ret` 返回多个值，内联后无法替换元组结果；
+                // optnone 被调函数的函数体须保持未优化，不展开到调用点
+                let inlinable = !recursive
+                    && !callee_ref.has_attribute(OPTNONE_ATTRIBUTE)
+                    && !callee_ref.get_basic_blocks().is_empty()
+                    && callee_ref.results().is_empty()
+                    && Self::function_cost(&callee_ref) <= self.threshold
+                    && callee_ref.get_arguments().len() + 1 == instr.get_operand_count();
+                drop(callee_ref);
+                if inlinable {
+                    return Some(CallSite {
+                        block: bb.clone(),
+                        index,
+                        callee,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// 选择一个在调用者中未被使用的后缀编号
+    fn fresh_suffix(caller: &Function) -> String {
+        let mut used = HashSet::new();
+        for bb in caller.get_basic_blocks() {
+            let bb = bb.borrow();
+            used.insert(bb.get_name().to_string());
+            for instr in bb.get_instructions() {
+                used.extend(instr.borrow().get_name());
+            }
+        }
+        (0..)
+            .map(|n| format!(".i{}", n))
+            .find(|suffix| !used.iter().any(|name| name.ends_with(suffix.as_str())))
+            .unwrap()
+    }
+
+    /// 克隆被调函数的一条指令，值与跳转目标通过 `map` 重映射
+    ///
+    /// 结果值已由 `CloneMap::new` 预先创建，因此前向引用（如循环中的 `phi`）也能重映射。
+    fn clone_instruction(instr: &Instruction, map: &CloneMap) -> InstructionRef {
+        let is_phi = instr.get_opcode() == Opcode::Phi;
+        let operands = instr
+            .get_operand_refs()
+            .iter()
+            .enumerate()
+            .map(|(idx, operand)| {
+                let operand = operand.borrow();
+                match operand.get_kind() {
+                    // phi 的来源基本块以 `%label` 值引用，位于奇数下标
+                    OperandKind::Value(value) if is_phi && idx % 2 == 1 => {
+                        let name = value.borrow().get_name().to_string();
+                        match name.strip_prefix('%').and_then(|name| map.blocks.get(name)) {
+                            Some(bb) => Operand::create_value(Self::label(bb.borrow().get_name())),
+                            None => Operand::create_value(value.clone()),
+                        }
+                    }
+                    OperandKind::Value(value) => Operand::create_value(map.value(value)),
+                    OperandKind::BasicBlock(_) => {
+                        match operand
+                            .get_block_name()
+                            .and_then(|name| map.blocks.get(&name))
+                        {
+                            Some(bb) => Operand::create_basic_block(bb.clone()),
+                            None => Rc::new(RefCell::new(operand.clone())),
+                        }
+                    }
+                    _ => Rc::new(RefCell::new(operand.clone())),
+                }
+            })
+            .collect();
+
+        let mut cloned = Instruction::with_operands(
+            instr.get_opcode(),
+            instr.get_result().map(|result| map.value(&result)),
+            operands,
+            instr.get_modifier(),
+        );
+        for attr in instr.get_attributes() {
+            cloned.add_attribute(attr.clone());
+        }
+        cloned.set_memory_space(instr.memory_space());
+        cloned.set_mnemonic(instr.get_mnemonic());
+        Rc::new(RefCell::new(cloned))
+    }
+
+    fn label(name: &str) -> ValueRef {
+        Rc::new(RefCell::new(Value::new(
+            Type::get_void_type(),
+            format!("%{}", name),
+        )))
+    }
+
+    /// 跳转到 `target` 的 `br`
+    fn branch_to(target: &BasicBlockRef) -> InstructionRef {
+        Rc::new(RefCell::new(Instruction::with_operands(
+            Opcode::Br,
+            None,
+            vec![Operand::create_basic_block(target.clone())],
+            InstructionModifier::None,
+        )))
+    }
+
+    /// 在调用点内联被调函数
+    fn inline_call_site(caller: &FunctionRef, site: CallSite) {
+        let suffix = Self::fresh_suffix(&caller.borrow());
+        let call = site.block.borrow().get_instructions()[site.index].clone();
+        let call_result = call.borrow().get_result();
+        let callee = site.callee.borrow();
+        let mut map = CloneMap::new(&callee, &call.borrow(), &suffix);
+
+        if callee.get_basic_blocks().len() == 1 {
+            // 单基本块：直接展开到调用点
+            let mut returned = None;
+            let entry = callee.get_basic_blocks()[0].borrow();
+            for (insert_at, instr) in (site.index..).zip(entry.get_instructions()) {
+                let instr = instr.borrow();
+                if instr.get_opcode() == Opcode::Ret {
+                    returned = instr.get_operands().first().map(|op| map.value(op));
+                    break;
+                }
+                let cloned = Self::clone_instruction(&instr, &map);
+                site.block
+                    .borrow_mut()
+                    .insert_instruction(insert_at, cloned, site.block.clone());
+            }
+            site.block.borrow_mut().remove_instruction(&call);
+            if let (Some(result), Some(returned)) = (call_result, returned) {
+                caller.borrow().replace_all_uses_with(&result, &returned);
+            }
+            return;
+        }
+
+        // 多基本块：在 call 处拆分调用者基本块，call 及其后的指令移入续接块，
+        // 原后继中 phi 的来源基本块随之改为续接块
+        let block_name = site.block.borrow().get_name().to_string();
+        let cont = BasicBlock::split_at(
+            &site.block,
+            site.index,
+            format!("{}.cont{}", block_name, suffix),
+        );
+        cont.borrow_mut().remove_instruction(&call);
+        let br = site.block.borrow().get_terminator();
+        if let Some(br) = br {
+            site.block.borrow_mut().remove_instruction(&br);
+        }
+
+        // 先创建全部基本块，使前向跳转也能重映射
+        let new_blocks: Vec<BasicBlockRef> = callee
+            .get_basic_blocks()
+            .iter()
+            .map(|bb| {
+                let name = bb.borrow().get_name().to_string();
+                let new_bb = Rc::new(RefCell::new(BasicBlock::new(
+                    format!("{}.{}{}", callee.get_name(), name, suffix),
+                    Some(caller.clone()),
+                )));
+                map.blocks.insert(name, new_bb.clone());
+                new_bb
+            })
+            .collect();
+
+        let mut returns: Vec<(ValueRef, String)> = Vec::new();
+        for (bb, new_bb) in callee.get_basic_blocks().iter().zip(&new_blocks) {
+            for instr in bb.borrow().get_instructions() {
+                let instr = instr.borrow();
+                let cloned = if instr.get_opcode() == Opcode::Ret {
+                    if let Some(op) = instr.get_operands().first() {
+                        let name = new_bb.borrow().get_name().to_string();
+                        returns.push((map.value(op), name));
+                    }
+                    Self::branch_to(&cont)
+                } else {
+                    Self::clone_instruction(&instr, &map)
+                };
+                new_bb.borrow_mut().add_instruction(cloned, new_bb.clone());
+            }
+        }
+
+        // 调用者基本块跳转到被调函数入口
+        site.block
+            .borrow_mut()
+            .add_instruction(Self::branch_to(&new_blocks[0]), site.block.clone());
+
+        // 合并返回值
+        if let Some(result) = call_result {
+            match returns.as_slice() {
+                [] => {}
+                [(value, _)] => {
+                    caller.borrow().replace_all_uses_with(&result, value);
+                }
+                _ => {
+                    // phi 操作数为 (值, 来源基本块) 对
+                    let operands = returns
+                        .iter()
+                        .flat_map(|(value, block)| [value.clone(), Self::label(block)])
+                        .collect();
+                    let phi = Instruction::new(
+                        Opcode::Phi,
+                        Some(result),
+                        operands,
+                        InstructionModifier::None,
+                    );
+                    cont.borrow_mut().insert_instruction(
+                        0,
+                        Rc::new(RefCell::new(phi)),
+                        cont.clone(),
+                    );
+                }
+            }
+        }
+
+        drop(callee);
+        let mut caller = caller.borrow_mut();
+        let position = caller
+            .get_basic_blocks()
+            .iter()
+            .position(|bb| Rc::ptr_eq(bb, &site.block))
+            .unwrap();
+        for (offset, bb) in new_blocks.into_iter().enumerate() {
+            caller.insert_basic_block(position + 1 + offset, bb);
+        }
+    }
+}
+
+/// 被调函数到调用点的重映射：值与基本块均按被调函数中的名称查找
+struct CloneMap {
+    values: HashMap<String, ValueRef>,
+    blocks: HashMap<String, BasicBlockRef>,
+}
+
+impl CloneMap {
+    /// 形参映射到调用实参，所有指令结果预先以 `suffix` 重新命名
+    fn new(callee: &Function, call: &Instruction, suffix: &str) -> Self {
+        let mut values = HashMap::new();
+        for (idx, arg) in callee.get_arguments().iter().enumerate() {
+            values.insert(
+                arg.borrow().get_name().to_string(),
+                call.get_operand(idx + 1),
+            );
+        }
+        for instr in callee.instructions() {
+            if let Some(result) = instr.borrow().get_result() {
+                let result = result.borrow();
+                let value = Value::new(
+                    result.get_type(),
+                    format!("{}{}", result.get_name(), suffix),
+                );
+                values.insert(result.get_name().to_string(), Rc::new(RefCell::new(value)));
+            }
+        }
+        CloneMap {
+            values,
+            blocks: HashMap::new(),
+        }
+    }
+
+    /// 被调函数中的值在调用点对应的值；常量与全局符号保持不变
+    fn value(&self, value: &ValueRef) -> ValueRef {
+        let name = value.borrow().get_name().to_string();
+        self.values
+            .get(&name)
+            .cloned()
+            .unwrap_or_else(|| value.clone())
+    }
+}
+
+impl Default for InlinePass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pass for InlinePass {
+    fn name(&self) -> &'static str {
+        "optimizer::InlinePass"
+    }
+
+    fn description(&self) -> &'static str {
+        "将小函数内联到调用点"
+    }
+
+    fn run(&self, module: &ModuleRef) -> usize {
+        let mut inlined = 0;
+        let mut recursion = RecursionInfo::new(module);
+        for caller in module.borrow().get_functions() {
+            // optnone 函数保持原样，不向其中内联
+            if caller.borrow().has_attribute(OPTNONE_ATTRIBUTE) {
+                continue;
+            }
+            while let Some(site) = self.find_call_site(module, &caller, &mut recursion) {
+                Self::inline_call_site(&caller, site);
+                inlined += 1;
+            }
+        }
+        inlined
+    }
+}
@@ -0,0 +1,182 @@
+use crate::analysis::FunctionCFG;
+use crate::diagnostics::DiagnosticBag;
+use crate::ir::BasicBlockRef;
+use crate::ir::function::FunctionRef;
+use crate::optimizer::pass_manager::FunctionPass;
+use std::collections::{HashMap, HashSet};
+
+/// 循环不变量外提 Pass（简化实现）
+///
+/// 通过 CFG 上的回边识别自然循环，将操作数全部定义在循环外、
+/// 且无副作用的指令移动到循环的前置块 (preheader) 末尾。
+/// 只处理存在唯一前置块的循环；循环内含有屏障指令 (setcsr/yield) 时不做任何外提。
+/// 可能陷入的指令（除法、取余）只从循环头外提：循环头在进入循环时必然执行，
+/// 其它基本块中的这类指令可能位于条件分支内，外提后会在原本不执行的路径上陷入。
+pub struct LoopInvariantCodeMotionPass;
+
+impl LoopInvariantCodeMotionPass {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// 查找所有自然循环，返回 (循环头, 循环体基本块集合)
+    fn find_loops(cfg: &FunctionCFG) -> Vec<(String, HashSet<String>)> {
+        let Some(entry) = cfg.get_entry() else {
+            return Vec::new();
+        };
+
+        // 深度优先遍历，指向栈中基本块的边即为回边
+        let mut back_edges = Vec::new();
+        let mut visited = HashSet::new();
+        let mut on_stack = HashSet::new();
+        let mut stack = vec![(entry.to_string(), 0usize)];
+        visited.insert(entry.to_string());
+        on_stack.insert(entry.to_string());
+        while let Some((block, idx)) = stack.pop() {
+            let succs = cfg.get_successors(&block);
+            if idx < succs.len() {
+                let succ = succs[idx].clone();
+                stack.push((block.clone(), idx + 1));
+                if on_stack.contains(&succ) {
+                    back_edges.push((block, succ));
+                } else if visited.insert(succ.clone()) {
+                    on_stack.insert(succ.clone());
+                    stack.push((succ, 0));
+                }
+            } else {
+                on_stack.remove(&block);
+            }
+        }
+
+        // 对每条回边 latch -> header，反向收集能到达 latch 而不经过 header 的基本块
+        let mut loops: Vec<(String, HashSet<String>)> = Vec::new();
+        for (latch, header) in back_edges {
+            let mut body = HashSet::from([header.clone()]);
+            let mut work = vec![latch];
+            while let Some(block) = work.pop() {
+                if body.insert(block.clone()) {
+                    work.extend(cfg.get_predecessors(&block).iter().cloned());
+                }
+            }
+            match loops.iter_mut().find(|(h, _)| *h == header) {
+                Some((_, existing)) => existing.extend(body),
+                None => loops.push((header, body)),
+            }
+        }
+        loops
+    }
+
+    /// 对单个函数执行外提，返回移动的指令数
+    fn process_function(&self, func: &FunctionRef) -> usize {
+        let cfg = FunctionCFG::compute(&func.borrow());
+        let blocks: HashMap<String, BasicBlockRef> = func
+            .borrow()
+            .get_basic_blocks()
+            .iter()
+            .map(|bb| (bb.borrow().get_name().to_string(), bb.clone()))
+            .collect();
+
+        let mut hoisted = 0;
+        for (header, body) in Self::find_loops(&cfg) {
+            // 唯一的循环外前驱，且它只跳转到循环头
+            let outside: Vec<_> = cfg
+                .get_predecessors(&header)
+                .iter()
+                .filter(|p| !body.contains(*p))
+                .collect();
+            let [preheader] = outside.as_slice() else {
+                continue;
+            };
+            if cfg.get_successors(preheader).len() != 1 {
+                continue;
+            }
+
+            // 按函数中的顺序遍历循环体
+            let loop_blocks: Vec<BasicBlockRef> = cfg
+                .get_blocks()
+                .iter()
+                .filter(|b| body.contains(*b))
+                .map(|b| blocks[b].clone())
+                .collect();
+
+            let has_barrier = loop_blocks.iter().any(|bb| {
+                bb.borrow()
+                    .get_instructions()
+                    .iter()
+                    .any(|i| i.borrow().get_opcode().is_barrier())
+            });
+            if has_barrier {
+                continue;
+            }
+
+            // 循环内定义的值
+            let mut loop_defs: HashSet<String> = loop_blocks
+                .iter()
+                .flat_map(|bb| bb.borrow().get_instructions().to_vec())
+                .filter_map(|i| i.borrow().get_name())
+                .collect();
+
+            let preheader_bb = blocks[*preheader].clone();
+            for bb in &loop_blocks {
+                let is_header = bb.borrow().get_name() == header;
+                let candidates: Vec<_> = bb.borrow().get_instructions().to_vec();
+                for instr in candidates {
+                    let invariant = {
+                        let ib = instr.borrow();
+                        ib.has_result()
+                            && ib.is_pure()
+                            && (is_header || !ib.get_opcode().may_trap())
+                            && ib
+                                .get_operands()
+                                .iter()
+                                .all(|op| !loop_defs.contains(op.borrow().get_name()))
+                    };
+                    if !invariant {
+                        continue;
+                    }
+
+                    bb.borrow_mut().remove_instruction(&instr);
+                    let insert_at = preheader_bb
+                        .borrow()
+                        .get_instructions()
+                        .len()
+                        .saturating_sub(1);
+                    preheader_bb.borrow_mut().insert_instruction(
+                        insert_at,
+                        instr.clone(),
+                        preheader_bb.clone(),
+                    );
+                    if let Some(name) = instr.borrow().get_name() {
+                        loop_defs.remove(&name);
+                    }
+                    hoisted += 1;
+                }
+            }
+        }
+        hoisted
+    }
+}
+
+impl Default for LoopInvariantCodeMotionPass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FunctionPass for LoopInvariantCodeMotionPass {
+    fn name(&self) -> &'static str {
+        "optimizer::LoopInvariantCodeMotionPass"
+    }
+
+    fn description(&self) -> &'static str {
+        "将循环不变量移动到循环前置块"
+    }
+
+    fn preserves(&self) -> Vec<&'static str> {
+        vec!["analysis::CFG"]
+    }
+
+    fn run_on_function(&self, func: &FunctionRef, _diagnostics: &mut DiagnosticBag) -> usize {
+        self.process_function(func)
+    }
+}
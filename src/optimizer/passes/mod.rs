@@ -2,9 +2,25 @@ pub mod ssa_renumber;
 pub mod dce;
 pub mod const_fold;
 pub mod cse;
+pub mod copy_prop;
+pub mod dfe;
+pub mod licm;
+pub mod inline;
+pub mod simplify_cfg;
+pub mod sccp;
+pub mod break_critical_edges;
+pub mod schedule;
 
 // 重新导出已实现的 Pass
 pub use ssa_renumber::SSARenumberPass;
 pub use dce::DeadCodeEliminationPass;
 pub use const_fold::ConstantFoldingPass;
 pub use cse::CommonSubexpressionEliminationPass;
+pub use copy_prop::CopyPropagationPass;
+pub use dfe::DeadFunctionEliminationPass;
+pub use licm::LoopInvariantCodeMotionPass;
+pub use inline::InlinePass;
+pub use simplify_cfg::SimplifyCFGPass;
+pub use sccp::SCCPPass;
+pub use break_critical_edges::BreakCriticalEdgesPass;
+pub use schedule::SchedulePass;
@@ -0,0 +1,203 @@
+use crate::analysis::{ConstantRange, FunctionCFG, LatticeValue};
+use crate::diagnostics::DiagnosticBag;
+use crate::ir::function::{Function, FunctionRef};
+use crate::ir::instruction::Opcode;
+use crate::ir::value::{Constant, Value};
+use crate::ir::BasicBlockRef;
+use crate::optimizer::pass_manager::FunctionPass;
+use crate::optimizer::passes::SimplifyCFGPass;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+/// 稀疏条件常量传播 Pass
+///
+/// 在同一个不动点中同时求解 SSA 值的格值（Top / Constant / Bottom）与基本块的可达性：
+/// 只有可执行的基本块参与求值，`phi` 只合并来自可执行边的值，
+/// 条件为常量的 `condbr` 只把实际跳转的边标记为可执行。
+///
+/// 求解后把常量结果替换为常量、把对常量的使用替换为立即数，
+/// 再将条件已知的 `condbr` 折叠为 `br` 并删除不可达基本块。
+pub struct SCCPPass;
+
+/// 函数的求解结果
+#[derive(Default)]
+struct Solution {
+    /// 结果名称 -> 格值
+    values: HashMap<String, LatticeValue>,
+    /// 可执行的基本块
+    executable: HashSet<String>,
+    /// 可执行的控制流边 (前驱, 后继)
+    edges: HashSet<(String, String)>,
+}
+
+impl SCCPPass {
+    pub fn new() -> Self {
+        SCCPPass
+    }
+
+    /// 迭代求解格值与可执行边，直到不再变化
+    fn solve(func: &Function) -> Solution {
+        let mut solution = Solution::default();
+        let cfg = FunctionCFG::compute(func);
+        let Some(entry) = cfg.get_entry() else {
+            return solution;
+        };
+        solution.executable.insert(entry.to_string());
+
+        let blocks: HashMap<String, &BasicBlockRef> = func
+            .get_basic_blocks()
+            .iter()
+            .map(|bb| (bb.borrow().get_name().to_string(), bb))
+            .collect();
+        let order = cfg.reverse_postorder();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for name in &order {
+                if !solution.executable.contains(name) {
+                    continue;
+                }
+                let Some(bb) = blocks.get(name) else {
+                    continue;
+                };
+                for instr in bb.borrow().get_instructions() {
+                    let instr = instr.borrow();
+                    if instr.get_opcode().is_terminator() {
+                        for succ in Self::feasible_targets(&instr, &solution.values) {
+                            changed |= solution.edges.insert((name.clone(), succ.clone()));
+                            changed |= solution.executable.insert(succ);
+                        }
+                        continue;
+                    }
+                    let Some(result) = instr.get_name() else {
+                        continue;
+                    };
+                    let value = if instr.get_opcode() == Opcode::Phi {
+                        Self::phi_value(&instr, name, &solution)
+                    } else {
+                        ConstantRange::transfer(&instr, &solution.values)
+                    };
+                    if solution.values.get(&result) != Some(&value) {
+                        solution.values.insert(result, value);
+                        changed = true;
+                    }
+                }
+            }
+        }
+        solution
+    }
+
+    /// 终结指令在当前格值下可能跳转到的后继
+    fn feasible_targets(
+        instr: &crate::ir::instruction::Instruction,
+        values: &HashMap<String, LatticeValue>,
+    ) -> Vec<String> {
+        let targets = instr.branch_targets();
+        if instr.get_opcode() != Opcode::CondBr || targets.len() < 2 {
+            return targets;
+        }
+        match ConstantRange::operand(&instr.get_operand(0).borrow(), values) {
+            LatticeValue::Top => Vec::new(),
+            LatticeValue::Constant(0) => vec![targets[1].clone()],
+            LatticeValue::Constant(_) => vec![targets[0].clone()],
+            LatticeValue::Bottom => targets,
+        }
+    }
+
+    /// phi 的格值：只合并来自可执行边的 (值, 来源基本块) 对
+    fn phi_value(
+        instr: &crate::ir::instruction::Instruction,
+        block: &str,
+        solution: &Solution,
+    ) -> LatticeValue {
+        instr
+            .get_operands()
+            .chunks(2)
+            .filter(|pair| {
+                pair.len() == 2 && {
+                    let label = pair[1].borrow();
+                    let pred = label
+                        .get_name()
+                        .strip_prefix('%')
+                        .unwrap_or(label.get_name());
+                    solution
+                        .edges
+                        .contains(&(pred.to_string(), block.to_string()))
+                }
+            })
+            .fold(LatticeValue::Top, |acc, pair| {
+                acc.meet(ConstantRange::operand(&pair[0].borrow(), &solution.values))
+            })
+    }
+
+    /// 按求解结果改写函数，返回改写的指令与操作数数量
+    fn rewrite(func: &FunctionRef, solution: &Solution) -> usize {
+        let mut changes = 0;
+        for bb in func.borrow().get_basic_blocks() {
+            if !solution.executable.contains(bb.borrow().get_name()) {
+                continue;
+            }
+            for instr in bb.borrow().get_instructions() {
+                let mut instr = instr.borrow_mut();
+                // 常量结果直接替换为常量
+                if let Some(name) = instr.get_name()
+                    && let Some(LatticeValue::Constant(value)) = solution.values.get(&name)
+                    && instr
+                        .get_result()
+                        .is_some_and(|r| !r.borrow().is_constant())
+                {
+                    instr.replace_with_constant(Constant::Int(*value));
+                    changes += 1;
+                    continue;
+                }
+                // 对常量的使用替换为立即数
+                for idx in 0..instr.get_operand_count() {
+                    let operand = instr.get_operand(idx);
+                    let replacement = {
+                        let operand = operand.borrow();
+                        match solution.values.get(operand.get_name()) {
+                            Some(LatticeValue::Constant(value)) if !operand.is_constant() => {
+                                Some(Value::new_constant(*value, operand.get_type()))
+                            }
+                            _ => None,
+                        }
+                    };
+                    if let Some(replacement) = replacement {
+                        instr.set_operand(idx, Rc::new(RefCell::new(replacement)));
+                        changes += 1;
+                    }
+                }
+            }
+        }
+        changes
+    }
+
+    fn process_function(&self, func: &FunctionRef) -> usize {
+        let solution = Self::solve(&func.borrow());
+        let mut changes = Self::rewrite(func, &solution);
+        changes += SimplifyCFGPass::fold_constant_branches(func);
+        changes += SimplifyCFGPass::remove_unreachable_blocks(func);
+        changes
+    }
+}
+
+impl Default for SCCPPass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FunctionPass for SCCPPass {
+    fn name(&self) -> &'static str {
+        "optimizer::SCCPPass"
+    }
+
+    fn description(&self) -> &'static str {
+        "稀疏条件常量传播：联合求解常量与可达性，折叠常量分支并删除死路径"
+    }
+
+    fn run_on_function(&self, func: &FunctionRef, _diagnostics: &mut DiagnosticBag) -> usize {
+        self.process_function(func)
+    }
+}
@@ -0,0 +1,158 @@
+use crate::diagnostics::DiagnosticBag;
+use crate::ir::basic_block::BasicBlockRef;
+use crate::ir::function::FunctionRef;
+use crate::ir::instruction::{Instruction, InstructionRef, Opcode};
+use crate::optimizer::pass_manager::FunctionPass;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+/// 基本块内的列表调度 Pass
+///
+/// 以定义-使用关系与内存顺序建立依赖图，`Opcode::cost` 作为指令延迟，
+/// 按关键路径长度优先调度就绪指令，使长延迟指令尽早发出。
+/// 块首的 phi 与块尾的终结指令保持原位；屏障指令与其前后所有指令保持相对顺序。
+pub struct SchedulePass;
+
+impl SchedulePass {
+    pub fn new() -> Self {
+        SchedulePass
+    }
+
+    /// 指令是否读写内存（含可能访问内存的调用）
+    fn touches_memory(instr: &Instruction) -> bool {
+        matches!(
+            instr.get_opcode(),
+            Opcode::Load | Opcode::Store | Opcode::Alloc | Opcode::Free | Opcode::Call
+        )
+    }
+
+    /// 后一条指令 `later` 是否必须排在 `earlier` 之后
+    fn depends_on(later: &Instruction, earlier: &Instruction) -> bool {
+        if later.get_opcode().is_barrier() || earlier.get_opcode().is_barrier() {
+            return true;
+        }
+        // 定义-使用
+        if let Some(def) = earlier.get_result()
+            && later
+                .get_operands()
+                .iter()
+                .any(|op| Rc::ptr_eq(op, &def) || op.borrow().get_name() == def.borrow().get_name())
+        {
+            return true;
+        }
+        // 内存顺序：两条访存指令中只要有一条写内存（非 load）就不能交换
+        if Self::touches_memory(later)
+            && Self::touches_memory(earlier)
+            && !(later.get_opcode() == Opcode::Load && earlier.get_opcode() == Opcode::Load)
+        {
+            return true;
+        }
+        // 其余副作用（如 volatile load）之间保持原有顺序
+        later.has_side_effects() && earlier.has_side_effects()
+    }
+
+    /// 调度一个基本块，返回位置发生变化的指令数量
+    fn schedule_block(bb: &BasicBlockRef) -> usize {
+        let instrs: Vec<InstructionRef> = bb.borrow().get_instructions().to_vec();
+        let phi_count = instrs
+            .iter()
+            .take_while(|instr| instr.borrow().get_opcode() == Opcode::Phi)
+            .count();
+        let body_end = match instrs.last() {
+            Some(last) if last.borrow().get_opcode().is_terminator() => instrs.len() - 1,
+            _ => instrs.len(),
+        };
+        if body_end <= phi_count + 1 {
+            return 0;
+        }
+        let body = &instrs[phi_count..body_end];
+        let n = body.len();
+
+        // preds[i]：i 依赖的指令下标
+        let preds: Vec<Vec<usize>> = (0..n)
+            .map(|i| {
+                (0..i)
+                    .filter(|&j| Self::depends_on(&body[i].borrow(), &body[j].borrow()))
+                    .collect()
+            })
+            .collect();
+        let mut succs: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for (i, ps) in preds.iter().enumerate() {
+            for &j in ps {
+                succs[j].push(i);
+            }
+        }
+
+        // 优先级：从该指令到依赖图末端的最长延迟路径
+        let mut priority = vec![0u32; n];
+        for i in (0..n).rev() {
+            let tail = succs[i].iter().map(|&s| priority[s]).max().unwrap_or(0);
+            priority[i] = body[i].borrow().get_opcode().cost() + tail;
+        }
+
+        let mut remaining: Vec<usize> = preds.iter().map(Vec::len).collect();
+        let mut scheduled = HashSet::new();
+        let mut order = Vec::with_capacity(n);
+        while order.len() < n {
+            // 就绪指令中优先级最高者，相同时保持原有顺序
+            let next = (0..n)
+                .filter(|i| !scheduled.contains(i) && remaining[*i] == 0)
+                .max_by_key(|&i| (priority[i], std::cmp::Reverse(i)))
+                .expect("依赖图不应有环");
+            scheduled.insert(next);
+            order.push(next);
+            for &s in &succs[next] {
+                remaining[s] -= 1;
+            }
+        }
+
+        let moved = order
+            .iter()
+            .enumerate()
+            .filter(|&(pos, &i)| pos != i)
+            .count();
+        if moved == 0 {
+            return 0;
+        }
+        let new_order: Vec<InstructionRef> = instrs[..phi_count]
+            .iter()
+            .chain(order.iter().map(|&i| &body[i]))
+            .chain(&instrs[body_end..])
+            .cloned()
+            .collect();
+        let mut block = bb.borrow_mut();
+        block.clear_instructions();
+        for instr in new_order {
+            block.add_instruction(instr, bb.clone());
+        }
+        moved
+    }
+}
+
+impl Default for SchedulePass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FunctionPass for SchedulePass {
+    fn name(&self) -> &'static str {
+        "optimizer::SchedulePass"
+    }
+
+    fn description(&self) -> &'static str {
+        "在基本块内按延迟重排独立指令"
+    }
+
+    fn preserves(&self) -> Vec<&'static str> {
+        vec!["analysis::CFG"]
+    }
+
+    fn run_on_function(&self, func: &FunctionRef, _diagnostics: &mut DiagnosticBag) -> usize {
+        let mut moved = 0;
+        for bb in func.borrow().get_basic_blocks() {
+            moved += Self::schedule_block(bb);
+        }
+        moved
+    }
+}
@@ -0,0 +1,234 @@
+use crate::analysis::FunctionCFG;
+use crate::diagnostics::DiagnosticBag;
+use crate::ir::function::FunctionRef;
+use crate::ir::instruction::{Instruction, InstructionModifier, Opcode};
+use crate::ir::types::Type;
+use crate::ir::value::Value;
+use crate::ir::BasicBlockRef;
+use crate::optimizer::pass_manager::FunctionPass;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+/// 控制流图化简 Pass
+///
+/// 依次执行：
+/// 1. 条件为整型常量的 `condbr` 改为跳转到实际目标的 `br`；
+/// 2. 删除从入口不可达的基本块；
+/// 3. 合并直线控制流：基本块以无条件 `br` 跳转到唯一前驱为自身的后继时，
+///    将后继的指令拼接到该基本块末尾并删除后继。后继为入口块、自循环或以 `phi` 开头时不合并。
+///
+/// 被删除的控制流边对应的 `phi` 来源会一并移除。
+pub struct SimplifyCFGPass;
+
+impl SimplifyCFGPass {
+    pub fn new() -> Self {
+        SimplifyCFGPass
+    }
+
+    fn label(name: &str) -> crate::ir::ValueRef {
+        Rc::new(RefCell::new(Value::new(
+            Type::get_void_type(),
+            format!("%{}", name),
+        )))
+    }
+
+    fn same_block(label: &str, name: &str) -> bool {
+        label.strip_prefix('%').unwrap_or(label) == name
+    }
+
+    /// 从 `block` 的 phi 中移除来自 `pred` 的 (值, 来源基本块) 对
+    fn remove_phi_incoming(block: &BasicBlockRef, pred: &str) {
+        for instr in block.borrow().get_instructions() {
+            let mut instr = instr.borrow_mut();
+            if instr.get_opcode() != Opcode::Phi {
+                continue;
+            }
+            let mut idx = 1;
+            while idx < instr.get_operand_count() {
+                let label = instr.get_operand(idx).borrow().get_name().to_string();
+                if Self::same_block(&label, pred) {
+                    instr.remove_operand(idx);
+                    instr.remove_operand(idx - 1);
+                } else {
+                    idx += 2;
+                }
+            }
+        }
+    }
+
+    /// 将条件为常量的 condbr 折叠为 br，返回折叠数量
+    pub(crate) fn fold_constant_branches(func: &FunctionRef) -> usize {
+        let mut folded = 0;
+        let blocks = func.borrow().get_basic_blocks().to_vec();
+        for bb in &blocks {
+            let Some(terminator) = bb.borrow().get_terminator() else {
+                continue;
+            };
+            let (taken, not_taken) = {
+                let term = terminator.borrow();
+                if term.get_opcode() != Opcode::CondBr || term.get_operand_count() < 3 {
+                    continue;
+                }
+                let Some(cond) = term.get_operand(0).borrow().as_i64() else {
+                    continue;
+                };
+                let targets = term.branch_targets();
+                if cond != 0 {
+                    (targets[0].clone(), targets[1].clone())
+                } else {
+                    (targets[1].clone(), targets[0].clone())
+                }
+            };
+
+            let br = Instruction::new(
+                Opcode::Br,
+                None,
+                vec![Self::label(&taken)],
+                InstructionModifier::None,
+            );
+            let mut block = bb.borrow_mut();
+            block.remove_instruction(&terminator);
+            block.add_instruction(Rc::new(RefCell::new(br)), bb.clone());
+            let name = block.get_name().to_string();
+            drop(block);
+
+            if taken != not_taken
+                && let Some(dead_edge) = blocks.iter().find(|b| b.borrow().get_name() == not_taken)
+            {
+                Self::remove_phi_incoming(dead_edge, &name);
+            }
+            folded += 1;
+        }
+        folded
+    }
+
+    /// 删除从入口不可达的基本块，返回删除数量
+    pub(crate) fn remove_unreachable_blocks(func: &FunctionRef) -> usize {
+        let cfg = FunctionCFG::compute(&func.borrow());
+        let reachable: HashSet<String> = cfg.reverse_postorder().into_iter().collect();
+        let dead: Vec<BasicBlockRef> = func
+            .borrow()
+            .get_basic_blocks()
+            .iter()
+            .filter(|bb| !reachable.contains(bb.borrow().get_name()))
+            .cloned()
+            .collect();
+
+        for bb in &dead {
+            let name = bb.borrow().get_name().to_string();
+            for succ in cfg.get_successors(&name) {
+                if let Some(succ) = func
+                    .borrow()
+                    .get_basic_blocks()
+                    .iter()
+                    .find(|b| b.borrow().get_name() == succ)
+                {
+                    Self::remove_phi_incoming(succ, &name);
+                }
+            }
+            func.borrow_mut().remove_basic_block(bb);
+        }
+        dead.len()
+    }
+
+    /// 查找一对可合并的基本块 (前驱, 后继)
+    fn find_mergeable(func: &FunctionRef) -> Option<(BasicBlockRef, BasicBlockRef)> {
+        let func = func.borrow();
+        let cfg = FunctionCFG::compute(&func);
+        let entry = cfg.get_entry()?;
+
+        for bb in func.get_basic_blocks() {
+            let pred = bb.borrow();
+            let Some(terminator) = pred.get_terminator() else {
+                continue;
+            };
+            if terminator.borrow().get_opcode() != Opcode::Br {
+                continue;
+            }
+            let Some(target) = terminator.borrow().branch_targets().pop() else {
+                continue;
+            };
+            if target == entry || target == pred.get_name() {
+                continue;
+            }
+            if cfg.get_predecessors(&target) != [pred.get_name()] {
+                continue;
+            }
+            let Some(succ) = func
+                .get_basic_blocks()
+                .iter()
+                .find(|b| b.borrow().get_name() == target)
+            else {
+                continue;
+            };
+            let starts_with_phi = succ
+                .borrow()
+                .get_instructions()
+                .first()
+                .is_some_and(|i| i.borrow().get_opcode() == Opcode::Phi);
+            if !starts_with_phi {
+                return Some((bb.clone(), succ.clone()));
+            }
+        }
+        None
+    }
+
+    /// 将 `succ` 合并到 `pred` 末尾
+    fn merge(func: &FunctionRef, pred: &BasicBlockRef, succ: &BasicBlockRef) {
+        let terminator = pred.borrow().get_terminator().unwrap();
+        pred.borrow_mut().remove_instruction(&terminator);
+
+        let instructions = succ.borrow().get_instructions().to_vec();
+        succ.borrow_mut().clear_instructions();
+        for instr in instructions {
+            pred.borrow_mut().add_instruction(instr, pred.clone());
+        }
+        func.borrow_mut().remove_basic_block(succ);
+
+        // 后继的后继中以 succ 为来源的 phi 改为以 pred 为来源
+        let succ_name = succ.borrow().get_name().to_string();
+        let pred_label = Self::label(pred.borrow().get_name());
+        for bb in func.borrow().get_basic_blocks() {
+            for instr in bb.borrow().get_instructions() {
+                let mut instr = instr.borrow_mut();
+                if instr.get_opcode() != Opcode::Phi {
+                    continue;
+                }
+                // phi 操作数为 (值, 来源基本块) 对
+                for idx in (1..instr.get_operand_count()).step_by(2) {
+                    let label = instr.get_operand(idx).borrow().get_name().to_string();
+                    if Self::same_block(&label, &succ_name) {
+                        instr.set_operand(idx, pred_label.clone());
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Default for SimplifyCFGPass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FunctionPass for SimplifyCFGPass {
+    fn name(&self) -> &'static str {
+        "optimizer::SimplifyCFGPass"
+    }
+
+    fn description(&self) -> &'static str {
+        "折叠常量条件跳转、删除不可达基本块并合并直线控制流"
+    }
+
+    fn run_on_function(&self, func: &FunctionRef, _diagnostics: &mut DiagnosticBag) -> usize {
+        let mut changes = Self::fold_constant_branches(func);
+        changes += Self::remove_unreachable_blocks(func);
+        while let Some((pred, succ)) = Self::find_mergeable(func) {
+            Self::merge(func, &pred, &succ);
+            changes += 1;
+        }
+        changes
+    }
+}
@@ -1,7 +1,16 @@
-use crate::optimizer::Pass;
+use crate::analysis::FunctionCFG;
+use crate::diagnostics::DiagnosticBag;
+use crate::ir::basic_block::BasicBlockRef;
+use crate::ir::instruction::Instruction;
+use crate::ir::value::ValueRef;
+use crate::optimizer::FunctionPass;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+/// 自定义命名函数：参数为指令及其在函数内的序号
+pub type NamingFn = Box<dyn Fn(&Instruction, usize) -> String>;
 
 /// SSA 命名策略
-#[derive(Clone, Copy)]
 pub enum NamingStrategy {
     /// %0, %1, %2 ...
     Sequential,
@@ -9,17 +18,30 @@ pub enum NamingStrategy {
     TypeBased,
     /// 每个基本块内部重新从 0 计数
     BlockBased,
+    /// 由用户函数生成名称；重名时追加 `.N` 后缀以保证唯一
+    Custom(NamingFn),
+}
+
+/// 基本块遍历顺序
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum BlockOrder {
+    /// 函数中的存储顺序
+    Storage,
+    /// 控制流图的逆后序，编号反映执行顺序；不可达基本块按存储顺序排在最后
+    ReversePostorder,
 }
 
 /// SSA 重命名 Pass
 pub struct SSARenumberPass {
     strategy: NamingStrategy,
+    order: BlockOrder,
 }
 
 impl SSARenumberPass {
     pub fn new() -> Self {
         SSARenumberPass {
             strategy: NamingStrategy::Sequential,
+            order: BlockOrder::Storage,
         }
     }
 
@@ -29,52 +51,169 @@ impl SSARenumberPass {
         self
     }
 
-    /// 核心逻辑：遍历函数并为有返回值的指令重新命名
-    fn process_function(&self, func: &crate::ir::function::FunctionRef) {
-        match self.strategy {
-            NamingStrategy::Sequential | NamingStrategy::TypeBased => {
+    /// 链式接口：设置基本块遍历顺序
+    pub fn with_block_order(mut self, order: BlockOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// 按遍历顺序返回函数的基本块
+    fn ordered_blocks(&self, func: &crate::ir::function::FunctionRef) -> Vec<BasicBlockRef> {
+        let func = func.borrow();
+        let blocks = func.get_basic_blocks().to_vec();
+        if self.order == BlockOrder::Storage {
+            return blocks;
+        }
+
+        let rpo = FunctionCFG::compute(&func).reverse_postorder();
+        let position = |bb: &BasicBlockRef| {
+            let bb = bb.borrow();
+            rpo.iter().position(|name| name == bb.get_name()).unwrap_or(rpo.len())
+        };
+        let mut ordered = blocks;
+        // 稳定排序：不可达基本块保持原有相对顺序
+        ordered.sort_by_key(position);
+        ordered
+    }
+
+    /// 核心逻辑：遍历函数并为有返回值的指令重新命名，返回名称发生变化的指令数
+    fn process_function(&self, func: &crate::ir::function::FunctionRef) -> usize {
+        let defs = Self::collect_definitions(func);
+        let mut renamed = 0;
+        match &self.strategy {
+            NamingStrategy::Sequential | NamingStrategy::TypeBased | NamingStrategy::Custom(_) => {
                 let mut counter: usize = 0;
-                for bb in func.borrow().get_basic_blocks() {
+                let mut used = HashSet::new();
+                for bb in &self.ordered_blocks(func) {
                     for instr in bb.borrow().get_instructions() {
                         if instr.borrow().has_result() {
-                            let new_name = match self.strategy {
+                            let new_name = match &self.strategy {
                                 NamingStrategy::Sequential => format!("%{}", counter),
                                 NamingStrategy::TypeBased => {
                                     let ty_str = instr.borrow().get_type().borrow().to_string();
                                     format!("{}_{}", ty_str, counter)
                                 }
+                                NamingStrategy::Custom(naming) => {
+                                    Self::unique_name(naming(&instr.borrow(), counter), &mut used)
+                                }
                                 _ => unreachable!(),
                             };
                             counter += 1;
-                            instr.borrow_mut().set_name(new_name);
+                            if Self::rename(instr, new_name) {
+                                renamed += 1;
+                            }
                         }
                     }
                 }
             }
             NamingStrategy::BlockBased => {
-                for bb in func.borrow().get_basic_blocks() {
+                for bb in &self.ordered_blocks(func) {
                     let mut counter: usize = 0;
                     for instr in bb.borrow().get_instructions() {
                         if instr.borrow().has_result() {
                             let new_name = format!("%{}_{}", bb.borrow().get_name(), counter);
                             counter += 1;
-                            instr.borrow_mut().set_name(new_name);
+                            if Self::rename(instr, new_name) {
+                                renamed += 1;
+                            }
                         }
                     }
                 }
             }
         }
+        Self::update_operands(func, &defs);
+        renamed
+    }
+
+    /// 收集重命名前的 旧名称 -> 定义值 映射
+    fn collect_definitions(func: &crate::ir::function::FunctionRef) -> HashMap<String, ValueRef> {
+        let mut defs = HashMap::new();
+        for bb in func.borrow().get_basic_blocks() {
+            for instr in bb.borrow().get_instructions() {
+                if let Some(result) = instr.borrow().get_result() {
+                    let name = result.borrow().get_name().to_string();
+                    if !name.is_empty() {
+                        defs.insert(name, result.clone());
+                    }
+                }
+            }
+        }
+        defs
+    }
+
+    /// 将按旧名称引用结果的操作数改为指向定义值本身，使其随重命名更新
+    ///
+    /// 与定义共享同一 `ValueRef` 的操作数已随重命名更新，无需处理。
+    fn update_operands(func: &crate::ir::function::FunctionRef, defs: &HashMap<String, ValueRef>) {
+        for bb in func.borrow().get_basic_blocks() {
+            for instr in bb.borrow().get_instructions() {
+                let mut instr = instr.borrow_mut();
+                for idx in 0..instr.get_operand_count() {
+                    let operand = instr.get_operand(idx);
+                    if defs.values().any(|def| Rc::ptr_eq(def, &operand)) {
+                        continue;
+                    }
+                    let def = defs.get(operand.borrow().get_name()).cloned();
+                    if let Some(def) = def {
+                        instr.set_operand(idx, def);
+                    }
+                }
+            }
+        }
+    }
+
+    /// 名称已被使用时追加 `.N` 后缀，返回唯一名称
+    fn unique_name(name: String, used: &mut HashSet<String>) -> String {
+        let name = if used.contains(&name) {
+            (1..)
+                .map(|n| format!("{}.{}", name, n))
+                .find(|candidate| !used.contains(candidate))
+                .unwrap()
+        } else {
+            name
+        };
+        used.insert(name.clone());
+        name
+    }
+
+    /// 重命名指令结果，返回新名称是否与旧名称不同
+    fn rename(instr: &crate::ir::instruction::InstructionRef, new_name: String) -> bool {
+        if instr.borrow().get_name().as_deref() == Some(new_name.as_str()) {
+            return false;
+        }
+        instr.borrow_mut().set_name(new_name);
+        true
     }
 }
 
 // 类型别名兼容旧测试
 pub type SSARenumber = SSARenumberPass;
 
-impl Pass for SSARenumberPass {
+impl Default for SSARenumberPass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FunctionPass for SSARenumberPass {
     fn name(&self) -> &'static str {
         "optimizer::SSARenumberPass"
     }
 
+    fn instance_id(&self) -> String {
+        // 默认配置沿用 Pass 名称，其它策略与遍历顺序附加后缀
+        let mut id = match self.strategy {
+            NamingStrategy::Sequential => FunctionPass::name(self).to_string(),
+            NamingStrategy::TypeBased => format!("{}::TypeBased", FunctionPass::name(self)),
+            NamingStrategy::BlockBased => format!("{}::BlockBased", FunctionPass::name(self)),
+            NamingStrategy::Custom(_) => format!("{}::Custom", FunctionPass::name(self)),
+        };
+        if self.order == BlockOrder::ReversePostorder {
+            id.push_str("::RPO");
+        }
+        id
+    }
+
     fn description(&self) -> &'static str {
         "为 SSA 指令重新分配唯一名称"
     }
@@ -83,19 +222,22 @@ impl Pass for SSARenumberPass {
         Vec::new()
     }
 
-    fn run(&self, module: &crate::ir::ModuleRef) {
-        for func in module.borrow().get_functions() {
-            self.process_function(&func);
-        }
+    fn run_on_function(
+        &self,
+        func: &crate::ir::function::FunctionRef,
+        _diagnostics: &mut DiagnosticBag,
+    ) -> usize {
+        self.process_function(func)
     }
 }
 
 #[cfg(all(test, feature = "advanced_pass_tests"))]
 mod tests {
     use super::*;
-    use crate::ir::{module::Module, types::{Type, TypeKind}, instruction::{Instruction, Opcode}, basic_block::BasicBlock, function::Function};
+    use crate::ir::{module::Module, types::{Type, TypeKind}, instruction::{Instruction, InstructionModifier, Opcode}, basic_block::BasicBlock, function::Function};
     use std::rc::Rc;
     use std::cell::RefCell;
+    use crate::optimizer::Pass;
 
     #[test]
     fn test_basic_sequential() {
@@ -0,0 +1,99 @@
+// 标准优化流水线
+//
+// 按优化级别（-O0 / -O1 / -O2）给出预设的 Pass 组合。
+
+use crate::diagnostics::DiagnosticBag;
+use crate::ir::ModuleRef;
+use crate::optimizer::Pass;
+use crate::optimizer::pass_manager::{PassError, PassManager};
+use crate::optimizer::passes::*;
+
+/// `-O2` 不动点迭代的最大次数
+pub const O2_MAX_ITERATIONS: usize = 8;
+
+/// 优化级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum OptLevel {
+    /// 不做优化
+    #[default]
+    O0,
+    /// 常量折叠 + 死代码消除
+    O1,
+    /// 在 O1 基础上加入公共子表达式消除、复制传播与控制流简化，并迭代到不动点
+    O2,
+}
+
+impl OptLevel {
+    /// 解析命令行形式的优化级别（`-O0`、`-O1`、`-O2`）
+    pub fn from_flag(flag: &str) -> Option<Self> {
+        match flag {
+            "-O0" => Some(OptLevel::O0),
+            "-O1" => Some(OptLevel::O1),
+            "-O2" => Some(OptLevel::O2),
+            _ => None,
+        }
+    }
+
+    /// 是否需要反复运行流水线直到不动点
+    pub fn runs_to_fixpoint(self) -> bool {
+        self == OptLevel::O2
+    }
+}
+
+/// 构造给定优化级别的标准 Pass 列表（按执行顺序）
+pub fn standard(level: OptLevel) -> Vec<Box<dyn Pass>> {
+    let mut passes: Vec<Box<dyn Pass>> = Vec::new();
+    if level >= OptLevel::O1 {
+        passes.push(Box::new(ConstantFoldingPass::new()));
+    }
+    if level >= OptLevel::O2 {
+        passes.push(Box::new(CommonSubexpressionEliminationPass::new()));
+        passes.push(Box::new(CopyPropagationPass::new()));
+    }
+    if level >= OptLevel::O1 {
+        passes.push(Box::new(DeadCodeEliminationPass::new()));
+    }
+    if level >= OptLevel::O2 {
+        passes.push(Box::new(SimplifyCFGPass::new()));
+    }
+    passes
+}
+
+/// 注册给定优化级别的标准 Pass 并加入 pipeline
+pub fn standard_pass_manager(level: OptLevel) -> PassManager {
+    let mut pm = PassManager::new();
+    for pass in standard(level) {
+        let id = pass.instance_id();
+        pm.register_boxed_pass(pass);
+        pm.add_to_pipeline(&id);
+    }
+    pm
+}
+
+/// 以给定优化级别优化模块；`-O2` 会迭代到不动点
+///
+/// Pass 产生的诊断以警告日志输出。
+pub fn run_standard(module: &ModuleRef, level: OptLevel) -> Result<(), PassError> {
+    let mut diagnostics = DiagnosticBag::new();
+    let result = run_standard_with_diagnostics(module, level, &mut diagnostics);
+    for diagnostic in diagnostics.iter() {
+        log::warn!("{}", diagnostic);
+    }
+    result
+}
+
+/// 与 `run_standard` 相同，但将 Pass 产生的诊断推入 `diagnostics`
+pub fn run_standard_with_diagnostics(
+    module: &ModuleRef,
+    level: OptLevel,
+    diagnostics: &mut DiagnosticBag,
+) -> Result<(), PassError> {
+    let mut pm = standard_pass_manager(level);
+    let result = if level.runs_to_fixpoint() {
+        pm.run_until_fixpoint(module, O2_MAX_ITERATIONS).map(|_| ())
+    } else {
+        pm.run(module)
+    };
+    diagnostics.extend(pm.take_diagnostics());
+    result
+}
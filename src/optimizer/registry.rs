@@ -0,0 +1,89 @@
+// Pass 注册表
+//
+// 将命令行使用的短名称映射到 Pass 构造函数，并据此构建 PassManager。
+
+use crate::optimizer::Pass;
+use crate::optimizer::pass_manager::PassManager;
+use crate::optimizer::passes::*;
+
+/// Pass 构造函数
+type PassConstructor = fn() -> Box<dyn Pass>;
+
+/// 短名称 -> Pass 构造函数
+const PASSES: &[(&str, PassConstructor)] = &[
+    ("ssa-renumber", || Box::new(SSARenumberPass::new())),
+    ("dce", || Box::new(DeadCodeEliminationPass::new())),
+    ("const-fold", || Box::new(ConstantFoldingPass::new())),
+    (
+        "cse",
+        || Box::new(CommonSubexpressionEliminationPass::new()),
+    ),
+    ("copy-prop", || Box::new(CopyPropagationPass::new())),
+    ("dfe", || Box::new(DeadFunctionEliminationPass::new())),
+    ("licm", || Box::new(LoopInvariantCodeMotionPass::new())),
+    ("inline", || Box::new(InlinePass::new())),
+    ("simplify-cfg", || Box::new(SimplifyCFGPass::new())),
+    ("sccp", || Box::new(SCCPPass::new())),
+    ("break-crit-edges", || {
+        Box::new(BreakCriticalEdgesPass::new())
+    }),
+    ("schedule", || Box::new(SchedulePass::new())),
+    ("dce-fast", || Box::new(DeadCodeEliminationPass::fast())),
+];
+
+/// 所有可用 Pass 的短名称
+pub fn available_passes() -> Vec<&'static str> {
+    PASSES.iter().map(|(name, _)| *name).collect()
+}
+
+/// 按短名称构造 Pass
+pub fn create_pass(name: &str) -> Option<Box<dyn Pass>> {
+    PASSES
+        .iter()
+        .find(|(short, _)| *short == name)
+        .map(|(_, ctor)| ctor())
+}
+
+/// 按 `Pass::name()` 构造 Pass，用于补全依赖
+fn create_pass_by_full_name(name: &str) -> Option<Box<dyn Pass>> {
+    PASSES
+        .iter()
+        .map(|(_, ctor)| ctor())
+        .find(|p| p.name() == name)
+}
+
+/// 按短名称列表构建 PassManager
+///
+/// 请求的 Pass 按给定顺序加入 pipeline，其（递归）声明的依赖会先被注册并加入，
+/// 实际执行顺序由 PassManager 的拓扑排序决定。未知名称返回包含可用 Pass 列表的错误。
+pub fn build_pass_manager(names: &[&str]) -> Result<PassManager, String> {
+    let mut pm = PassManager::new();
+    let mut added: Vec<String> = Vec::new();
+    for name in names {
+        let pass = create_pass(name).ok_or_else(|| {
+            format!(
+                "未知的 Pass: '{}'，可用的 Pass: {}",
+                name,
+                available_passes().join(", ")
+            )
+        })?;
+        add_with_dependencies(&mut pm, &mut added, pass);
+    }
+    Ok(pm)
+}
+
+/// 先加入 Pass 的依赖，再注册该 Pass 并加入 pipeline；已加入的 Pass 跳过
+fn add_with_dependencies(pm: &mut PassManager, added: &mut Vec<String>, pass: Box<dyn Pass>) {
+    let id = pass.instance_id();
+    if added.contains(&id) {
+        return;
+    }
+    added.push(id.clone());
+    for dep in pass.dependencies() {
+        if let Some(dep_pass) = create_pass_by_full_name(dep) {
+            add_with_dependencies(pm, added, dep_pass);
+        }
+    }
+    pm.register_boxed_pass(pass);
+    pm.add_to_pipeline(&id);
+}
@@ -1,21 +1,114 @@
 // Venus 编译器前端入口点
 fn main() {
-    use vil::frontend::parse_vil_file;
+    use vil::diagnostics::DiagnosticBag;
+    use vil::frontend::parse_vil_with_diagnostics;
     let args: Vec<String> = std::env::args().collect();
 
-    if args.len() != 2 {
-        eprintln!("用法: {} <vil文件路径>", args[0]);
-        std::process::exit(1);
+    // 解析参数：[-O0|-O1|-O2] [--passes=a,b,c] [--verify] <vil文件路径>
+    let mut passes: Option<Vec<String>> = None;
+    let mut verify = false;
+    let mut level = vil::optimizer::OptLevel::O0;
+    let mut filepath: Option<&String> = None;
+    for arg in &args[1..] {
+        if let Some(list) = arg.strip_prefix("--passes=") {
+            passes = Some(
+                list.split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(String::from)
+                    .collect(),
+            );
+        } else if arg == "--verify" {
+            verify = true;
+        } else if let Some(l) = vil::optimizer::OptLevel::from_flag(arg) {
+            level = l;
+        } else if filepath.is_none() {
+            filepath = Some(arg);
+        } else {
+            filepath = None;
+            break;
+        }
     }
+    let Some(filepath) = filepath else {
+        eprintln!(
+            "用法: {} [-O0|-O1|-O2] [--passes=pass1,pass2,...] [--verify] <vil文件路径>",
+            args[0]
+        );
+        eprintln!(
+            "可用的 Pass: {}",
+            vil::optimizer::available_passes().join(", ")
+        );
+        std::process::exit(1);
+    };
 
-    let filepath = &args[1];
-    match parse_vil_file(filepath) {
-        Ok(module) => {
-            println!("{}", module.borrow());
+    // 在读取文件之前构建 pipeline，尽早报告未知的 Pass
+    let mut pass_manager = match &passes {
+        Some(names) => {
+            let names: Vec<&str> = names.iter().map(String::as_str).collect();
+            match vil::optimizer::build_pass_manager(&names) {
+                Ok(pm) => Some(pm),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        None => None,
+    };
+
+    let bytes = match std::fs::read(filepath) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            eprintln!("无法读取文件 '{}': {}", filepath, e);
+            std::process::exit(1);
         }
+    };
+    let source = match vil::frontend::Lexer::decode_source(&bytes, filepath) {
+        Ok(source) => source,
         Err(e) => {
-            eprintln!("解析错误: {}", e);
+            eprintln!("{}", e);
             std::process::exit(1);
         }
+    };
+    // 前端与优化 Pass 的诊断统一收集，最后按源代码位置排序输出
+    let mut diagnostics = DiagnosticBag::new();
+    let module = parse_vil_with_diagnostics(source, filepath, &mut diagnostics);
+    // --verify 时先验证解析结果，未通过验证的模块不再优化
+    if verify && let Some(module) = &module {
+        vil::analysis::verifier::Verifier::verify_module_with_diagnostics(
+            &module.borrow(),
+            &mut diagnostics,
+        );
+    }
+    if let Some(module) = &module
+        && !diagnostics.has_errors()
+    {
+        // 显式给出 --passes 时忽略优化级别
+        let result = match pass_manager.as_mut() {
+            Some(pm) => {
+                let result = pm.run(module);
+                diagnostics.extend(pm.take_diagnostics());
+                result
+            }
+            None => vil::optimizer::pipeline::run_standard_with_diagnostics(
+                module,
+                level,
+                &mut diagnostics,
+            ),
+        };
+        if let Err(e) = result {
+            diagnostics.error(&format!("优化错误: {}", e), None);
+        }
+    }
+    for diagnostic in diagnostics.sorted() {
+        // 只有位于当前文件中的诊断才附带源代码行（导入文件中的诊断只输出位置）
+        match &diagnostic.location {
+            Some(loc) if loc.filename == *filepath => eprintln!("{}", diagnostic.render(source)),
+            _ => eprintln!("{}", diagnostic),
+        }
+    }
+    match module {
+        Some(module) if !diagnostics.has_errors() => println!("{}", module.borrow()),
+        _ => std::process::exit(1),
     }
 }
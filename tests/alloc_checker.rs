@@ -0,0 +1,76 @@
+use vil::analysis::{AllocChecker, AllocDiagnosticKind};
+use vil::frontend::parse_vil;
+
+#[test]
+fn test_leak_on_one_branch() {
+    let source = r#".module m
+.function leak(.param %c <pred 1>) {
+entry:
+    %p = alloc <i32 x 16> [vspm]
+    condbr %c, %then, %else
+then:
+    free %p
+    br %exit
+else:
+    br %exit
+exit:
+    ret
+}
+"#;
+    let module = parse_vil(source, "leak.vil").expect("应成功解析模块");
+    let diagnostics = AllocChecker::check(&module);
+
+    assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+    assert_eq!(diagnostics[0].kind, AllocDiagnosticKind::Leak);
+    assert_eq!(diagnostics[0].value, "%p");
+    assert_eq!(diagnostics[0].block, "exit");
+}
+
+#[test]
+fn test_double_free_and_free_of_non_alloc() {
+    let source = r#".module m
+.function df(.param %q i32* sram) {
+entry:
+    %p = alloc i32 [sram]
+    free %p
+    free %p
+    free %q
+    ret
+}
+"#;
+    let module = parse_vil(source, "double_free.vil").expect("应成功解析模块");
+    let kinds: Vec<_> = AllocChecker::check(&module)
+        .into_iter()
+        .map(|d| (d.kind, d.value))
+        .collect();
+
+    assert_eq!(
+        kinds,
+        vec![
+            (AllocDiagnosticKind::DoubleFree, "%p".to_string()),
+            (AllocDiagnosticKind::FreeOfNonAlloc, "%q".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_balanced_alloc_is_clean() {
+    let source = r#".module m
+.function ok() {
+    %p = alloc <i16 x 8> [vspm]
+    free %p
+    ret
+}
+"#;
+    let module = parse_vil(source, "ok.vil").expect("应成功解析模块");
+    let func = module.borrow().get_function("ok").unwrap();
+    let alloc = func.borrow().get_basic_blocks()[0]
+        .borrow()
+        .get_instructions()[0]
+        .clone();
+    assert_eq!(
+        alloc.borrow().get_type().borrow().to_string(),
+        "<i16 x 8>* vspm"
+    );
+    assert!(AllocChecker::check(&module).is_empty());
+}
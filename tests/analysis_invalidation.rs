@@ -0,0 +1,126 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use vil::analysis::{CFGAnalysis, ModuleCFG};
+use vil::ir::module::Module;
+use vil::ir::value::Value;
+use vil::ir::{BasicBlock, Function, Instruction, InstructionModifier, ModuleRef, Opcode, Type};
+use vil::optimizer::pass_manager::{Pass, PassManager};
+
+/// 构建 entry --br--> exit 的两块函数
+fn build_module() -> ModuleRef {
+    let func = Rc::new(RefCell::new(Function::new(
+        "f".to_string(),
+        Type::get_void_type(),
+        vec![],
+    )));
+    let entry = Rc::new(RefCell::new(BasicBlock::new("entry".to_string(), Some(func.clone()))));
+    let exit = Rc::new(RefCell::new(BasicBlock::new("exit".to_string(), Some(func.clone()))));
+
+    let target = Rc::new(RefCell::new(Value::new(Type::get_void_type(), "%exit".to_string())));
+    let br = Instruction::new(Opcode::Br, None, vec![target], InstructionModifier::None);
+    entry.borrow_mut().add_instruction(Rc::new(RefCell::new(br)), entry.clone());
+    let ret = Instruction::new(Opcode::Ret, None, vec![], InstructionModifier::None);
+    exit.borrow_mut().add_instruction(Rc::new(RefCell::new(ret)), exit.clone());
+
+    func.borrow_mut().add_basic_block(entry);
+    func.borrow_mut().add_basic_block(exit);
+
+    let module = Rc::new(RefCell::new(Module::new("test_module".to_string())));
+    module.borrow_mut().add_function(func);
+    module
+}
+
+/// 删除所有 br 指令，并声明 CFG 失效
+struct RemoveBranchesPass;
+
+impl Pass for RemoveBranchesPass {
+    fn name(&self) -> &'static str {
+        "test::RemoveBranchesPass"
+    }
+
+    fn invalidates(&self) -> Vec<&'static str> {
+        vec!["analysis::CFG"]
+    }
+
+    fn run(&self, module: &ModuleRef) -> usize {
+        let mut removed = 0;
+        for func in module.borrow().get_functions() {
+            for bb in func.borrow().get_basic_blocks() {
+                let branches: Vec<_> = bb
+                    .borrow()
+                    .get_instructions()
+                    .iter()
+                    .filter(|i| i.borrow().get_opcode() == Opcode::Br)
+                    .cloned()
+                    .collect();
+                for instr in branches {
+                    bb.borrow_mut().remove_instruction(&instr);
+                    removed += 1;
+                }
+            }
+        }
+        removed
+    }
+}
+
+/// 不修改模块的 Pass
+struct NoopPass;
+
+impl Pass for NoopPass {
+    fn name(&self) -> &'static str {
+        "test::NoopPass"
+    }
+
+    fn run(&self, _module: &ModuleRef) -> usize {
+        0
+    }
+}
+
+#[test]
+fn test_mutating_pass_invalidates_cfg() {
+    let module = build_module();
+    let mut pm = PassManager::new();
+    pm.register_analysis(CFGAnalysis);
+    pm.register_pass(RemoveBranchesPass);
+    pm.add_to_pipeline("test::RemoveBranchesPass");
+
+    let before = pm
+        .get_analysis_as::<ModuleCFG>("analysis::CFG", &module)
+        .expect("CFG 分析应可用");
+    assert_eq!(before.get_function("f").unwrap().get_successors("entry"), ["exit"]);
+    assert!(pm.is_analysis_cached("analysis::CFG"));
+
+    pm.run(&module).expect("PassManager 执行失败");
+    assert!(!pm.is_analysis_cached("analysis::CFG"));
+
+    // 重新计算后反映出被删除的边
+    let after = pm
+        .get_analysis_as::<ModuleCFG>("analysis::CFG", &module)
+        .expect("CFG 分析应可用");
+    assert!(!Rc::ptr_eq(&before, &after));
+    assert!(after.get_function("f").unwrap().get_successors("entry").is_empty());
+    assert!(after.get_function("f").unwrap().get_predecessors("exit").is_empty());
+}
+
+#[test]
+fn test_unchanged_module_keeps_cache() {
+    let module = build_module();
+    let mut pm = PassManager::new();
+    pm.register_analysis(CFGAnalysis);
+    pm.register_pass(NoopPass);
+    pm.add_to_pipeline("test::NoopPass");
+
+    let before = pm.get_analysis("analysis::CFG", &module).unwrap();
+    pm.run(&module).expect("PassManager 执行失败");
+    assert!(pm.is_analysis_cached("analysis::CFG"));
+    let after = pm.get_analysis("analysis::CFG", &module).unwrap();
+    assert!(Rc::ptr_eq(&before, &after));
+}
+
+#[test]
+fn test_unregistered_analysis() {
+    let module = build_module();
+    let mut pm = PassManager::new();
+    assert!(pm.get_analysis("analysis::Missing", &module).is_err());
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "arena")]
+
+use std::rc::Rc;
+use vil::frontend::parse_vil;
+use vil::ir::arena::{ArenaModule, ArenaOperand};
+
+#[test]
+fn test_arena_round_trip() {
+    let source = r#".module m
+.memory buf [vspm] <i32 x 16>
+.function helper(.param %v i32) -> i32 {
+entry:
+    %w = mul %v, 3
+    ret %w
+}
+.function main(.param %c <pred 1>, .param %a i32) -> i32 {
+entry:
+    %p = alloc i32 [vspm]
+    store %a, %p
+    %x = load %p
+    condbr %c, %then, %merge
+then:
+    %t = load @buf
+    %y = call @helper(%x)
+    br %merge
+merge:
+    %r = phi %y, %then, %x, %entry
+    ret %r
+}
+"#;
+    let module = parse_vil(source, "arena.vil").expect("应成功解析模块");
+    let arena = ArenaModule::from_module(&module.borrow());
+
+    assert_eq!(arena.functions.len(), 2);
+    assert_eq!(arena.blocks.len(), 4);
+    assert_eq!(arena.instructions.len(), 11);
+
+    // 同一个值在 arena 中只占一个槽位
+    let main = &arena.functions[1];
+    let entry = &arena.blocks[main.blocks[0]];
+    let load = &arena.instructions[entry.instructions[2]];
+    let store = &arena.instructions[entry.instructions[1]];
+    let alloc = &arena.instructions[entry.instructions[0]];
+    assert!(matches!(load.operands[0], ArenaOperand::Value(id) if Some(id) == alloc.result));
+    assert!(matches!(store.operands[1], ArenaOperand::Value(id) if Some(id) == alloc.result));
+
+    let restored = arena.to_module();
+    assert!(module.borrow().structurally_eq(&restored.borrow()));
+    assert_eq!(
+        module.borrow().content_hash(),
+        restored.borrow().content_hash()
+    );
+
+    // 转换回 Rc 表示后，结果值与其使用处共享同一个 ValueRef
+    let func = restored.borrow().get_function("main").unwrap();
+    let entry = func.borrow().get_entry_block().unwrap();
+    let instrs = entry.borrow().get_instructions().to_vec();
+    let pointer = instrs[0].borrow().get_result().unwrap();
+    assert!(Rc::ptr_eq(&pointer, &instrs[2].borrow().get_operand(0)));
+    for instr in &instrs {
+        assert!(Rc::ptr_eq(&instr.borrow().get_parent_bb().unwrap(), &entry));
+    }
+}
@@ -0,0 +1,65 @@
+use vil::analysis::FunctionCFG;
+use vil::frontend::parse_vil;
+use vil::ir::{BasicBlock, Opcode};
+
+#[test]
+fn test_split_four_instruction_block() {
+    let source = r#".module m
+.function f(.param %a i32) -> i32 {
+entry:
+    %x = add %a, 1
+    %y = mul %x, 2
+    %z = sub %y, %a
+    br %exit
+exit:
+    %r = phi %z, %entry
+    ret %r
+}
+"#;
+    let module = parse_vil(source, "split.vil").expect("应成功解析模块");
+    let func = module.borrow().get_function("f").unwrap();
+    let entry = func.borrow().get_entry_block().unwrap();
+
+    let tail = BasicBlock::split_at(&entry, 2, "entry.split".to_string());
+
+    // 前半部分保留前两条指令，并以跳转到新基本块结束
+    let opcodes = |bb: &vil::ir::BasicBlockRef| -> Vec<Opcode> {
+        bb.borrow()
+            .get_instructions()
+            .iter()
+            .map(|instr| instr.borrow().get_opcode())
+            .collect()
+    };
+    assert_eq!(opcodes(&entry), vec![Opcode::Add, Opcode::Mul, Opcode::Br]);
+    assert_eq!(opcodes(&tail), vec![Opcode::Sub, Opcode::Br]);
+    let br = entry.borrow().get_terminator().unwrap();
+    assert_eq!(
+        br.borrow().get_operand(0).borrow().get_name(),
+        "%entry.split"
+    );
+    for instr in tail.borrow().get_instructions() {
+        assert!(std::rc::Rc::ptr_eq(
+            &instr.borrow().get_parent_bb().unwrap(),
+            &tail
+        ));
+    }
+
+    // 新基本块紧跟在原基本块之后，CFG 边与 phi 来源随之更新
+    let names: Vec<String> = func
+        .borrow()
+        .get_basic_blocks()
+        .iter()
+        .map(|bb| bb.borrow().get_name().to_string())
+        .collect();
+    assert_eq!(names, vec!["entry", "entry.split", "exit"]);
+    let cfg = FunctionCFG::compute(&func.borrow());
+    assert_eq!(cfg.get_successors("entry"), ["entry.split".to_string()]);
+    assert_eq!(cfg.get_predecessors("exit"), ["entry.split".to_string()]);
+
+    let exit = func.borrow().get_basic_blocks()[2].clone();
+    let phi = exit.borrow().get_instructions()[0].clone();
+    assert_eq!(
+        phi.borrow().get_operand(1).borrow().get_name(),
+        "%entry.split"
+    );
+}
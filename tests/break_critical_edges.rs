@@ -0,0 +1,56 @@
+use vil::analysis::FunctionCFG;
+use vil::frontend::parse_vil;
+use vil::optimizer::Pass;
+use vil::optimizer::passes::BreakCriticalEdgesPass;
+
+#[test]
+fn test_critical_edge_gets_intermediary_block() {
+    let source = r#".module m
+.function f(.param %c <pred 1>, .param %a i32) -> i32 {
+entry:
+    condbr %c, %then, %merge
+then:
+    %x = add %a, 1
+    br %merge
+merge:
+    %r = phi %x, %then, %a, %entry
+    ret %r
+}
+"#;
+    let module = parse_vil(source, "crit.vil").expect("应成功解析模块");
+    assert_eq!(BreakCriticalEdgesPass::new().run(&module), 1);
+
+    let func = module.borrow().get_function("f").unwrap();
+    let names: Vec<String> = func
+        .borrow()
+        .get_basic_blocks()
+        .iter()
+        .map(|bb| bb.borrow().get_name().to_string())
+        .collect();
+    assert_eq!(names, vec!["entry", "entry.merge.crit", "then", "merge"]);
+
+    // entry -> merge 经由新基本块，then -> merge 不是关键边，保持不变
+    let cfg = FunctionCFG::compute(&func.borrow());
+    assert_eq!(
+        cfg.get_successors("entry"),
+        ["then".to_string(), "entry.merge.crit".to_string()]
+    );
+    assert_eq!(
+        cfg.get_successors("entry.merge.crit"),
+        ["merge".to_string()]
+    );
+    let mut preds = cfg.get_predecessors("merge").to_vec();
+    preds.sort();
+    assert_eq!(preds, vec!["entry.merge.crit", "then"]);
+
+    // phi 的来源随之更新
+    let merge = func.borrow().get_basic_blocks()[3].clone();
+    let phi = merge.borrow().get_instructions()[0].clone();
+    assert_eq!(
+        phi.borrow().get_operand(3).borrow().get_name(),
+        "%entry.merge.crit"
+    );
+
+    // 再次运行不再有关键边
+    assert_eq!(BreakCriticalEdgesPass::new().run(&module), 0);
+}
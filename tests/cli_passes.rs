@@ -0,0 +1,53 @@
+use std::process::Command;
+
+const SAMPLE: &str = r#".module m
+.function f(.param %a i32) -> i32 {
+entry:
+    %x = add 2, 3
+    %y = mul %x, 4
+    %dead = sub %a, 1
+    %r = add %a, %y
+    ret %r
+}
+"#;
+
+fn write_sample(name: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("vcc_{}_{}.vil", name, std::process::id()));
+    std::fs::write(&path, SAMPLE).expect("应能写入临时文件");
+    path
+}
+
+#[test]
+fn test_vcc_runs_requested_passes() {
+    let path = write_sample("const_fold_dce");
+    let output = Command::new(env!("CARGO_BIN_EXE_vcc"))
+        .arg("--passes=const-fold,dce")
+        .arg(&path)
+        .output()
+        .expect("应能运行 vcc");
+    std::fs::remove_file(&path).ok();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    // 常量表达式被折叠进使用处，折叠后的定义与无用指令被删除
+    assert!(stdout.contains("%r = add %a, 20"), "输出: {}", stdout);
+    assert!(!stdout.contains("mul"), "输出: {}", stdout);
+    assert!(!stdout.contains("dead"), "输出: {}", stdout);
+    assert!(!stdout.contains("x ="), "输出: {}", stdout);
+}
+
+#[test]
+fn test_vcc_rejects_unknown_pass() {
+    let path = write_sample("unknown_pass");
+    let output = Command::new(env!("CARGO_BIN_EXE_vcc"))
+        .arg("--passes=const-fold,no-such-pass")
+        .arg(&path)
+        .output()
+        .expect("应能运行 vcc");
+    std::fs::remove_file(&path).ok();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("no-such-pass"));
+    assert!(stderr.contains("可用的 Pass: ssa-renumber, dce, const-fold, cse"));
+}
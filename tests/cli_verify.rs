@@ -0,0 +1,43 @@
+use std::process::Command;
+
+// 结构上不合法：entry 未以终结指令结尾
+const INVALID: &str = r#".module m
+.function f(.param %a i32) -> i32 {
+entry:
+    %x = add %a, 1
+}
+"#;
+
+fn run_vcc(name: &str, args: &[&str]) -> std::process::Output {
+    let path = std::env::temp_dir().join(format!("vcc_{}_{}.vil", name, std::process::id()));
+    std::fs::write(&path, INVALID).expect("应能写入临时文件");
+    let output = Command::new(env!("CARGO_BIN_EXE_vcc"))
+        .args(args)
+        .arg(&path)
+        .output()
+        .expect("应能运行 vcc");
+    std::fs::remove_file(&path).ok();
+    output
+}
+
+#[test]
+fn test_vcc_verify_reports_invalid_module() {
+    let output = run_vcc("verify_invalid", &["--verify"]);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    // 诊断指明函数与基本块
+    assert!(
+        stderr.contains("错误: f::entry: 基本块未以终结指令结尾"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_vcc_without_verify_stays_lenient() {
+    let output = run_vcc("verify_lenient", &[]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains(".function f"), "{}", stdout);
+}
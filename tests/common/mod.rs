@@ -0,0 +1,75 @@
+// 集成测试共用的辅助函数
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use vil::diagnostics::DiagnosticBag;
+use vil::ir::types::{Type, TypeKind};
+use vil::ir::{BasicBlock, Function, Instruction, InstructionModifier, Module, Opcode, Value};
+use vil::optimizer::Pass;
+use vil::optimizer::passes::ConstantFoldingPass;
+
+/// 含一个循环的函数 `f`：`%base` 在入口定义，在循环之后的 exit 中使用
+pub const LOOP_SOURCE: &str = r#".module m
+.function f(.param %n i32) -> i32 {
+entry:
+    %base = add %n, 10
+    br %loop
+loop:
+    %i = phi %n, %entry, %next, %loop
+    %next = sub %i, 1
+    %c = cmpgt %next, 0
+    condbr %c, %loop, %exit
+exit:
+    %r = add %base, %next
+    ret %r
+}
+"#;
+
+/// 构建只含一条 `%r = <opcode> <operands>` 的模块，用 `pass` 折叠后返回 %r 的常量值
+///
+/// `operands` 为 (常量值, 类型)，`result` 为 %r 的类型；Pass 产生的诊断推入 `diagnostics`。
+pub fn fold_instruction(
+    pass: &ConstantFoldingPass,
+    opcode: Opcode,
+    operands: &[(i64, TypeKind)],
+    result: TypeKind,
+    diagnostics: &mut DiagnosticBag,
+) -> Option<i64> {
+    let func = Rc::new(RefCell::new(Function::new(
+        "f".to_string(),
+        Type::get_void_type(),
+        Vec::new(),
+    )));
+    let bb = Rc::new(RefCell::new(BasicBlock::new(
+        "entry".to_string(),
+        Some(func.clone()),
+    )));
+    let result = Rc::new(RefCell::new(Value::new(
+        Type::get_int_type(result),
+        "%r".to_string(),
+    )));
+    let operands = operands
+        .iter()
+        .map(|(value, kind)| {
+            Rc::new(RefCell::new(Value::new_constant(
+                *value,
+                Type::get_int_type(kind.clone()),
+            )))
+        })
+        .collect();
+    let instr = Instruction::new(
+        opcode,
+        Some(result.clone()),
+        operands,
+        InstructionModifier::None,
+    );
+    bb.borrow_mut()
+        .add_instruction(Rc::new(RefCell::new(instr)), bb.clone());
+    func.borrow_mut().add_basic_block(bb);
+
+    let mut module = Module::new("m".to_string());
+    module.add_function(func);
+    pass.run_with_diagnostics(&Rc::new(RefCell::new(module)), diagnostics);
+    result.borrow().as_i64()
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use common::fold_instruction;
+use vil::diagnostics::DiagnosticBag;
+use vil::frontend::parse_vil;
+use vil::ir::types::TypeKind;
+use vil::ir::{ModuleRef, Opcode};
+use vil::optimizer::Pass;
+use vil::optimizer::passes::ConstantFoldingPass;
+
+/// 返回函数 f 中名为 `name` 的指令结果的常量值
+fn constant_of(module: &ModuleRef, name: &str) -> Option<i64> {
+    let func = module.borrow().get_function("f").unwrap();
+    let entry = func.borrow().get_entry_block().unwrap();
+    let entry = entry.borrow();
+    let instr = entry
+        .get_instructions()
+        .iter()
+        .find(|i| i.borrow().get_name().as_deref() == Some(name))
+        .cloned()
+        .unwrap();
+    let result = instr.borrow().get_result().unwrap();
+    result.borrow().as_i64()
+}
+
+#[test]
+fn test_fold_bitcast_reinterprets_bits() {
+    let source = r#".module m
+.function f() {
+    %a = sub 0, 1
+    %u = bitcast %a : u32
+    %b = bitcast %a : b32
+    %s = bitcast %u : i32
+    ret
+}
+"#;
+    let module = parse_vil(source, "bitcast.vil").expect("应成功解析模块");
+    assert_eq!(ConstantFoldingPass::new().run(&module), 4);
+
+    assert_eq!(constant_of(&module, "%a"), Some(-1));
+    assert_eq!(constant_of(&module, "%u"), Some(0xFFFF_FFFF));
+    assert_eq!(constant_of(&module, "%s"), Some(-1));
+    assert!(constant_of(&module, "%b").is_some());
+}
+
+/// 折叠 `%r = <opcode> <constant> : <target>`，返回 %r 的常量值
+fn fold_cast(opcode: Opcode, source: TypeKind, value: i64, target: TypeKind) -> Option<i64> {
+    let pass = ConstantFoldingPass::new();
+    fold_instruction(&pass, opcode, &[(value, source)], target, &mut DiagnosticBag::new())
+}
+
+#[test]
+fn test_fold_int_conversions() {
+    use TypeKind::*;
+
+    assert_eq!(fold_cast(Opcode::SExt, Int8, -1, Int32), Some(-1));
+    assert_eq!(fold_cast(Opcode::ZExt, Int8, 255, Int32), Some(255));
+    // i8 的 -1 与 255 是同一位模式
+    assert_eq!(fold_cast(Opcode::ZExt, Int8, -1, Int32), Some(255));
+    assert_eq!(fold_cast(Opcode::SExt, Uint8, 255, Int16), Some(-1));
+    assert_eq!(
+        fold_cast(Opcode::Trunc, Int32, 0x1_2345, Int16),
+        Some(0x2345)
+    );
+    assert_eq!(fold_cast(Opcode::Trunc, Int32, 0xFFFF, Int16), Some(-1));
+    assert_eq!(fold_cast(Opcode::Trunc, Int32, 0x1FF, Uint8), Some(0xFF));
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use common::fold_instruction;
+use vil::diagnostics::{DiagnosticBag, Severity};
+use vil::frontend::parse_vil;
+use vil::ir::Opcode;
+use vil::ir::types::TypeKind;
+use vil::optimizer::Pass;
+use vil::optimizer::passes::ConstantFoldingPass;
+
+/// 用给定的 Pass 折叠 `%r = <opcode> lhs, rhs`（操作数与结果同为 `kind` 类型）
+fn fold_with(
+    pass: &ConstantFoldingPass,
+    opcode: Opcode,
+    kind: TypeKind,
+    lhs: i64,
+    rhs: i64,
+    diagnostics: &mut DiagnosticBag,
+) -> Option<i64> {
+    let operands = [(lhs, kind.clone()), (rhs, kind.clone())];
+    fold_instruction(pass, opcode, &operands, kind, diagnostics)
+}
+
+/// 用默认的 Pass 折叠 `%r = <opcode> lhs, rhs`
+fn fold(opcode: Opcode, kind: TypeKind, lhs: i64, rhs: i64) -> Option<i64> {
+    let pass = ConstantFoldingPass::new();
+    fold_with(&pass, opcode, kind, lhs, rhs, &mut DiagnosticBag::new())
+}
+
+#[test]
+fn test_fold_wraps_to_type_width() {
+    assert_eq!(fold(Opcode::Add, TypeKind::Uint8, 200, 100), Some(44));
+    assert_eq!(fold(Opcode::Add, TypeKind::Int8, 100, 100), Some(-56));
+    assert_eq!(fold(Opcode::Add, TypeKind::Int32, 100, 100), Some(200));
+}
+
+#[test]
+fn test_unsigned_division_uses_unsigned_operands() {
+    // -2 作为 u8 为 254
+    assert_eq!(fold(Opcode::DivU, TypeKind::Uint8, -2, 2), Some(127));
+    assert_eq!(fold(Opcode::Div, TypeKind::Int8, -2, 2), Some(-1));
+    assert_eq!(fold(Opcode::RemU, TypeKind::Uint32, -1, 10), Some(5));
+}
+
+#[test]
+fn test_strict_mode_reports_overflow() {
+    // 默认模式静默回绕
+    let normal = ConstantFoldingPass::new();
+    let mut diagnostics = DiagnosticBag::new();
+    let folded = fold_with(&normal, Opcode::Add, TypeKind::Int8, 127, 1, &mut diagnostics);
+    assert_eq!(folded, Some(-128));
+    assert!(diagnostics.is_empty());
+
+    // 严格模式不折叠，并报告警告
+    let strict = ConstantFoldingPass::new_strict();
+    let mut diagnostics = DiagnosticBag::new();
+    let mut fold_strict = |opcode, kind, lhs, rhs| {
+        fold_with(&strict, opcode, kind, lhs, rhs, &mut diagnostics)
+    };
+    assert_eq!(fold_strict(Opcode::Add, TypeKind::Int8, 127, 1), None);
+    assert_eq!(fold_strict(Opcode::Sub, TypeKind::Uint8, 0, 1), None);
+    assert_eq!(fold_strict(Opcode::Add, TypeKind::Int8, 100, 27), Some(127));
+    let warnings: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(diagnostics.count(Severity::Warning), 2, "{:?}", warnings);
+    assert!(warnings[0].contains("i8"), "{}", warnings[0]);
+    assert!(warnings[1].contains("u8"), "{}", warnings[1]);
+    assert_eq!(strict.warnings().len(), 2);
+    assert!(normal.warnings().is_empty());
+}
+
+#[test]
+fn test_strict_overflow_reported_once() {
+    // %a 的折叠使函数再迭代一轮，溢出的 %o 仍只报告一次
+    let source = r#".module m
+.function f() -> i32 {
+    %o = mul 2147483647, 2
+    %a = add 1, 2
+    ret %a
+}
+"#;
+    let module = parse_vil(source, "overflow.vil").expect("应成功解析模块");
+    let mut diagnostics = DiagnosticBag::new();
+    let folded = ConstantFoldingPass::new_strict().run_with_diagnostics(&module, &mut diagnostics);
+    assert_eq!(folded, 1);
+    assert_eq!(diagnostics.count(Severity::Warning), 1, "{:?}", diagnostics);
+
+    // 不带诊断的 `Pass::run` 同样可通过 `warnings` 读取警告
+    let module = parse_vil(source, "overflow.vil").expect("应成功解析模块");
+    let strict = ConstantFoldingPass::new_strict();
+    strict.run(&module);
+    let warnings = strict.warnings();
+    assert_eq!(warnings.len(), 1, "{:?}", warnings);
+    assert!(warnings[0].contains("%o"), "{}", warnings[0]);
+}
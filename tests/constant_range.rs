@@ -0,0 +1,75 @@
+use vil::analysis::{ConstantRange, LatticeValue};
+use vil::frontend::parse_vil;
+use vil::ir::ModuleRef;
+
+fn result_of(module: &ModuleRef, name: &str) -> vil::ir::InstructionRef {
+    let func = module.borrow().get_function("f").unwrap();
+    let func = func.borrow();
+    func.get_basic_blocks()
+        .iter()
+        .flat_map(|bb| bb.borrow().get_instructions().to_vec())
+        .find(|i| i.borrow().get_name().as_deref() == Some(name))
+        .expect("应找到指令")
+}
+
+#[test]
+fn test_constant_in_all_predecessors_is_constant_at_merge() {
+    let source = r#".module m
+.function f(.param %c <pred 1>, .param %a i32) {
+entry:
+    condbr %c, %then, %else
+then:
+    %x = add 1, 2
+    br %merge
+else:
+    %y = mov 3
+    br %merge
+merge:
+    %r = phi %x, %then, %y, %else
+    %s = mul %r, 2
+    %t = add %r, %a
+    ret
+}
+"#;
+    let module = parse_vil(source, "constant_range.vil").expect("应成功解析模块");
+    let ranges = ConstantRange::compute(&module);
+
+    assert_eq!(
+        ranges.known_value(&result_of(&module, "%x").borrow()),
+        Some(3)
+    );
+    assert_eq!(
+        ranges.known_value(&result_of(&module, "%r").borrow()),
+        Some(3)
+    );
+    assert_eq!(
+        ranges.known_value(&result_of(&module, "%s").borrow()),
+        Some(6)
+    );
+    // 依赖参数的结果不是常量
+    assert_eq!(ranges.known_value(&result_of(&module, "%t").borrow()), None);
+    assert_eq!(ranges.get_value("f", "%t"), LatticeValue::Bottom);
+}
+
+#[test]
+fn test_different_constants_meet_to_bottom() {
+    let source = r#".module m
+.function f(.param %c <pred 1>) {
+entry:
+    condbr %c, %then, %else
+then:
+    %x = mov 1
+    br %merge
+else:
+    %y = mov 2
+    br %merge
+merge:
+    %r = phi %x, %then, %y, %else
+    ret
+}
+"#;
+    let module = parse_vil(source, "constant_range.vil").expect("应成功解析模块");
+    let ranges = ConstantRange::compute(&module);
+    assert_eq!(ranges.known_value(&result_of(&module, "%r").borrow()), None);
+    assert_eq!(ranges.get_value("f", "%r"), LatticeValue::Bottom);
+}
@@ -0,0 +1,32 @@
+use vil::frontend::parse_vil;
+use vil::optimizer::Pass;
+use vil::optimizer::passes::CopyPropagationPass;
+
+#[test]
+fn test_copy_chains_are_propagated() {
+    let source = r#".module m
+.function f(.param %a i32, .param %b i32) -> i32 {
+entry:
+    %c = mov %a
+    %d = mov %c
+    %x = add %d, %b
+    br %exit
+exit:
+    %y = mul %c, %x
+    ret %y
+}
+"#;
+    let module = parse_vil(source, "copy.vil").expect("应成功解析模块");
+    assert_eq!(CopyPropagationPass::new().run(&module), 2);
+    assert_eq!(CopyPropagationPass::new().run(&module), 0);
+
+    let func = module.borrow().get_function("f").unwrap();
+    let text: Vec<String> = func
+        .borrow()
+        .instructions()
+        .map(|instr| instr.borrow().to_string())
+        .collect();
+    assert!(!text.iter().any(|line| line.contains("mov")), "{:?}", text);
+    assert!(text.contains(&"%x = add %a, %b".to_string()), "{:?}", text);
+    assert!(text.contains(&"%y = mul %a, %x".to_string()), "{:?}", text);
+}
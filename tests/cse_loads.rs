@@ -0,0 +1,55 @@
+use vil::frontend::parse_vil;
+use vil::ir::{ModuleRef, Opcode};
+use vil::optimizer::Pass;
+use vil::optimizer::passes::CommonSubexpressionEliminationPass;
+
+fn count_loads(module: &ModuleRef) -> usize {
+    let func = module.borrow().get_function("f").unwrap();
+    let entry = func.borrow().get_entry_block().unwrap();
+    entry
+        .borrow()
+        .get_instructions()
+        .iter()
+        .filter(|i| i.borrow().get_opcode() == Opcode::Load)
+        .count()
+}
+
+#[test]
+fn test_loads_merged_across_disjoint_store() {
+    let source = r#".module m
+.function f(.param %v i32) {
+    %p = alloc i32 [sram]
+    %q = alloc i32 [vspm]
+    %a = load %p
+    store %v, %q
+    %b = load %p
+    ret
+}
+"#;
+    let module = parse_vil(source, "cse.vil").expect("应成功解析模块");
+    let eliminated = CommonSubexpressionEliminationPass::new().run(&module);
+
+    // %q 位于不同内存空间，不会改写 %p 指向的内存
+    assert_eq!(eliminated, 1);
+    assert_eq!(count_loads(&module), 1);
+}
+
+#[test]
+fn test_loads_not_merged_across_aliasing_store() {
+    let source = r#".module m
+.function f(.param %v i32) {
+    %p = alloc i32 [sram]
+    %q = alloc i32 [sram]
+    %a = load %p
+    store %v, %q
+    %b = load %p
+    ret
+}
+"#;
+    let module = parse_vil(source, "cse.vil").expect("应成功解析模块");
+    let eliminated = CommonSubexpressionEliminationPass::new().run(&module);
+
+    // %q 与 %p 同在 sram，可能别名，第二个 load 必须保留
+    assert_eq!(eliminated, 0);
+    assert_eq!(count_loads(&module), 2);
+}
@@ -0,0 +1,49 @@
+use vil::frontend::parse_vil;
+use vil::optimizer::Pass;
+use vil::optimizer::passes::DeadCodeEliminationPass;
+
+const SOURCE: &str = r#".module m
+.function f(.param %a i32, .param %b i32) -> i32 {
+entry:
+    %x = add %a, %b
+    %y = mul %x, 2
+    %z = sub %y, %a
+    %live = add %a, 1
+    %p = alloc i32 [sram]
+    store %live, %p
+    br %exit
+exit:
+    %dead = xor %live, %b
+    ret %live
+}
+"#;
+
+#[test]
+fn test_fast_dce_matches_full_dce() {
+    let full = parse_vil(SOURCE, "dce.vil").expect("应成功解析模块");
+    let fast = parse_vil(SOURCE, "dce.vil").expect("应成功解析模块");
+
+    // 逆序扫描一遍即可删除整条死链 %z -> %y -> %x，以及其它基本块中的 %dead
+    assert_eq!(DeadCodeEliminationPass::new().run(&full), 4);
+    assert_eq!(DeadCodeEliminationPass::fast().run(&fast), 4);
+    assert_eq!(full.borrow().to_string(), fast.borrow().to_string());
+    assert_eq!(DeadCodeEliminationPass::fast().run(&fast), 0);
+}
+
+#[test]
+fn test_is_trivially_dead() {
+    let module = parse_vil(SOURCE, "dce.vil").expect("应成功解析模块");
+    let func = module.borrow().get_function("f").unwrap();
+    let dead: Vec<String> = func
+        .borrow()
+        .instructions()
+        .filter(|instr| instr.borrow().is_trivially_dead())
+        .filter_map(|instr| instr.borrow().get_name())
+        .collect();
+    // %x、%y 仍被使用；alloc 与 store 不是纯指令
+    assert_eq!(dead, ["%z", "%dead"]);
+    assert_ne!(
+        DeadCodeEliminationPass::fast().instance_id(),
+        DeadCodeEliminationPass::new().instance_id()
+    );
+}
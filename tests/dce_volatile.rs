@@ -0,0 +1,32 @@
+use vil::frontend::parse_vil;
+use vil::ir::Opcode;
+use vil::optimizer::Pass;
+use vil::optimizer::passes::DeadCodeEliminationPass;
+
+#[test]
+fn test_parsed_volatile_load_is_kept() {
+    let source = r#".module m
+.function f() {
+    %p = alloc i32 [sram]
+    %a = load %p
+    %b = load %p volatile
+    ret
+}
+"#;
+    let module = parse_vil(source, "dce.vil").expect("应成功解析模块");
+    let removed = DeadCodeEliminationPass::new().run(&module);
+
+    // 普通 load 的结果未被使用而被删除，volatile load 视为有副作用而保留
+    assert_eq!(removed, 1);
+    let func = module.borrow().get_function("f").unwrap();
+    let entry = func.borrow().get_entry_block().unwrap();
+    let loads: Vec<_> = entry
+        .borrow()
+        .get_instructions()
+        .iter()
+        .filter(|i| i.borrow().get_opcode() == Opcode::Load)
+        .cloned()
+        .collect();
+    assert_eq!(loads.len(), 1);
+    assert!(loads[0].borrow().has_attribute("volatile"));
+}
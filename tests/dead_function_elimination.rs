@@ -0,0 +1,94 @@
+use vil::frontend::parse_vil;
+use vil::ir::ModuleRef;
+use vil::optimizer::Pass;
+use vil::optimizer::passes::DeadFunctionEliminationPass;
+
+fn build_module() -> ModuleRef {
+    let source = r#".module m
+.function helper(.param %x i32) {
+    ret
+}
+.function unused() {
+    ret
+}
+.function main(.param %a i32) {
+    %r = call @helper(%a)
+    ret
+}
+"#;
+    parse_vil(source, "dfe.vil").expect("应成功解析模块")
+}
+
+fn function_names(module: &ModuleRef) -> Vec<String> {
+    let mut names: Vec<String> = module
+        .borrow()
+        .get_functions()
+        .iter()
+        .map(|f| f.borrow().get_name().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_unreferenced_function_is_removed() {
+    let module = build_module();
+    let removed = DeadFunctionEliminationPass::new()
+        .with_root("main")
+        .run(&module);
+
+    assert_eq!(removed, 1);
+    assert_eq!(function_names(&module), ["helper", "main"]);
+}
+
+#[test]
+fn test_export_attribute_is_root() {
+    let module = build_module();
+    let unused = module.borrow().get_function("unused").unwrap();
+    unused.borrow_mut().add_attribute("export".to_string());
+
+    let removed = DeadFunctionEliminationPass::new()
+        .with_root("main")
+        .run(&module);
+    assert_eq!(removed, 0);
+    assert_eq!(function_names(&module), ["helper", "main", "unused"]);
+}
+
+#[test]
+fn test_no_roots_keeps_module() {
+    let module = build_module();
+    assert_eq!(DeadFunctionEliminationPass::new().run(&module), 0);
+    assert_eq!(function_names(&module).len(), 3);
+}
+
+#[test]
+fn test_entry_function_is_root() {
+    let module = build_module();
+    assert!(module.borrow_mut().set_entry_function("main"));
+    assert!(!module.borrow_mut().set_entry_function("missing"));
+
+    assert_eq!(DeadFunctionEliminationPass::new().run(&module), 1);
+    assert_eq!(function_names(&module), ["helper", "main"]);
+}
+
+#[test]
+fn test_external_linkage_is_root() {
+    let source = r#".module m
+external .function api() {
+    ret
+}
+.function unused() {
+    ret
+}
+.function main() {
+    ret
+}
+"#;
+    let module = parse_vil(source, "dfe.vil").expect("应成功解析模块");
+    let removed = DeadFunctionEliminationPass::new()
+        .with_root("main")
+        .run(&module);
+    // 未被引用的 external 函数仍被保留
+    assert_eq!(removed, 1);
+    assert_eq!(function_names(&module), ["api", "main"]);
+}
@@ -0,0 +1,84 @@
+use vil::analysis::verifier::Verifier;
+use vil::diagnostics::{DiagnosticBag, Severity};
+use vil::frontend::parse_vil_with_diagnostics;
+
+#[test]
+fn test_parse_warning_and_verifier_error_collected_together() {
+    let dir = std::env::temp_dir().join(format!("vil_diagnostics_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("lib.vil"),
+        r#".module lib
+.function one() -> i32 {
+entry:
+    ret 1
+}
+"#,
+    )
+    .unwrap();
+    let main_path = dir.join("main.vil");
+    let main_file = main_path.to_string_lossy().into_owned();
+    // 重复导入产生解析警告；i32 函数中不带值的 ret 由验证器报告为错误
+    let source = r#".module main
+.import "lib.vil"
+.import "lib.vil"
+.function f() -> i32 {
+entry:
+    ret
+}
+"#;
+    std::fs::write(&main_path, source).unwrap();
+
+    let mut diagnostics = DiagnosticBag::new();
+    let module = parse_vil_with_diagnostics(source, &main_file, &mut diagnostics);
+    let module = module.expect("重复导入不应导致解析失败");
+    let errors = Verifier::verify_module_with_diagnostics(&module.borrow(), &mut diagnostics);
+    std::fs::remove_dir_all(&dir).ok();
+
+    assert_eq!(errors, 1);
+    assert_eq!(diagnostics.count(Severity::Warning), 1);
+    assert_eq!(diagnostics.count(Severity::Error), 1);
+    assert!(diagnostics.has_errors());
+
+    // 有位置的解析警告排在没有位置的验证错误之前
+    let sorted = diagnostics.sorted();
+    assert_eq!(sorted[0].severity, Severity::Warning);
+    assert!(sorted[0].message.contains("重复导入"), "{}", sorted[0]);
+    let location = sorted[0].location.as_ref().unwrap();
+    assert_eq!((location.line, location.column), (3, 1));
+    assert_eq!(sorted[1].severity, Severity::Error);
+    assert!(sorted[1].message.contains("缺少返回值"), "{}", sorted[1]);
+    assert!(sorted[1].location.is_none());
+}
+
+#[test]
+fn test_parse_error_becomes_diagnostic() {
+    let source = ".module m\n.bogus\n";
+    let mut diagnostics = DiagnosticBag::new();
+    assert!(parse_vil_with_diagnostics(source, "bad.vil", &mut diagnostics).is_none());
+
+    let errors: Vec<_> = diagnostics.iter().collect();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].severity, Severity::Error);
+    assert_eq!(errors[0].location.as_ref().unwrap().line, 2);
+    assert!(errors[0].render(source).contains("2 | .bogus"));
+}
+
+#[test]
+fn test_sorted_orders_by_file_line_and_column() {
+    use vil::frontend::error::SourceLocation;
+
+    let mut diagnostics = DiagnosticBag::new();
+    diagnostics.note("无位置", None);
+    diagnostics.warning("b:1", Some(SourceLocation::new("b.vil", 1, 1, 0)));
+    diagnostics.error("a:5", Some(SourceLocation::new("a.vil", 5, 1, 40)));
+    diagnostics.warning("a:2:7", Some(SourceLocation::new("a.vil", 2, 7, 16)));
+    diagnostics.warning("a:2:3", Some(SourceLocation::new("a.vil", 2, 3, 12)));
+
+    let messages: Vec<&str> = diagnostics
+        .sorted()
+        .iter()
+        .map(|d| d.message.as_str())
+        .collect();
+    assert_eq!(messages, ["a:2:3", "a:2:7", "a:5", "b:1", "无位置"]);
+}
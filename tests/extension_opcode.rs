@@ -0,0 +1,80 @@
+use vil::frontend::{Lexer, Parser, parse_vil, roundtrip_ok};
+use vil::ir::Opcode;
+use vil::optimizer::Pass;
+use vil::optimizer::passes::{ConstantFoldingPass, DeadCodeEliminationPass};
+
+fn first_instruction(module: &vil::ir::ModuleRef) -> vil::ir::instruction::InstructionRef {
+    let func = module.borrow().get_function("f").unwrap();
+    let entry = func.borrow().get_entry_block().unwrap();
+    entry.borrow().get_instructions()[0].clone()
+}
+
+#[test]
+fn test_parse_and_print_extension_opcode() {
+    let source = r#".module m
+.function f(.param %a i32, .param %b i32) -> i32 {
+    %r = ext.42 %a, %b
+    ret %r
+}
+"#;
+    let module = parse_vil(source, "ext.vil").expect("应成功解析扩展指令");
+    let instr = first_instruction(&module);
+    assert_eq!(instr.borrow().get_opcode(), Opcode::Extension(42));
+    assert_eq!(instr.borrow().get_operands().len(), 2);
+
+    let printed = module.borrow().to_string();
+    assert!(printed.contains("= ext.42 "), "输出: {}", printed);
+    // 打印结果可以重新解析
+    roundtrip_ok(source).unwrap();
+
+    let err = parse_vil(&source.replace("ext.42", "ext.70000"), "ext.vil").unwrap_err();
+    assert!(err.to_string().contains("无效的扩展指令编号"), "{}", err);
+}
+
+#[test]
+fn test_registered_extension_mnemonic() {
+    let source = r#".module m
+.function f(.param %a i32) -> i32 {
+    %r = vclmul.s %a, 3
+    %s = ext.7 %r, 1
+    ret %s
+}
+"#;
+    let mut parser = Parser::new(Lexer::new(source, "ext.vil"));
+    assert!(parser.register_extension(7, "vclmul"));
+    // 不能与内建操作码或其它扩展指令重名
+    assert!(!parser.register_extension(8, "add"));
+    assert!(!parser.register_extension(8, "vclmul"));
+    assert!(!parser.register_extension(7, "vclmul2"));
+    assert!(parser.register_extension(7, "vclmul"));
+
+    let module = parser.parse_module().expect("登记的助记符应可解析");
+    let instr = first_instruction(&module);
+    assert_eq!(instr.borrow().get_opcode(), Opcode::Extension(7));
+    assert_eq!(instr.borrow().mnemonic(), "vclmul");
+    assert_eq!(Opcode::Extension(7).to_string(), "ext.7");
+    let printed = module.borrow().to_string();
+    assert!(printed.contains("%r = vclmul.s"), "{}", printed);
+    assert!(printed.contains("%s = vclmul"), "{}", printed);
+
+    // 登记只作用于该解析器
+    let err = parse_vil(source, "ext.vil").unwrap_err();
+    assert!(err.to_string().contains("vclmul"), "{}", err);
+}
+
+#[test]
+fn test_extension_is_not_removed_or_folded() {
+    let source = r#".module m
+.function f() {
+    %r = ext.3 1, 2
+    ret
+}
+"#;
+    let module = parse_vil(source, "ext.vil").expect("应成功解析扩展指令");
+    assert_eq!(ConstantFoldingPass::new().run(&module), 0);
+    // 结果未被使用，但扩展指令被视为有副作用而保留
+    assert_eq!(DeadCodeEliminationPass::new().run(&module), 0);
+    let instr = first_instruction(&module);
+    assert_eq!(instr.borrow().get_opcode(), Opcode::Extension(3));
+    assert!(instr.borrow().has_side_effects());
+}
@@ -0,0 +1,85 @@
+use std::path::PathBuf;
+use vil::frontend::parse_vil_file;
+
+/// 在临时目录下创建一组源文件，返回目录路径
+fn write_files(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("vil_import_{}_{}", name, std::process::id()));
+    for (path, source) in files {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn test_import_function_used_by_importer() {
+    let dir = write_files(
+        "use",
+        &[
+            (
+                "lib/math.vil",
+                r#".module math
+.memory table [sram] <i32 x 4>
+.function inc(.param %x i32) -> i32 {
+entry:
+    %y = add %x, 1
+    ret %y
+}
+"#,
+            ),
+            (
+                "main.vil",
+                r#".module main
+.import "lib\\math.vil"
+.import "lib/math.vil"
+.function f(.param %a i32) -> i32 {
+entry:
+    %r = call @inc(%a)
+    ret %r
+}
+"#,
+            ),
+        ],
+    );
+    let module = parse_vil_file(dir.join("main.vil").to_str().unwrap());
+    std::fs::remove_dir_all(&dir).ok();
+
+    let module = module.expect("应成功解析带导入的模块");
+    let module = module.borrow();
+    assert_eq!(module.get_name(), "main");
+    assert_eq!(module.get_functions().len(), 2);
+    assert!(module.get_function("inc").is_some());
+    assert!(module.get_global_memory_space("table").is_some());
+}
+
+#[test]
+fn test_import_missing_file() {
+    let dir = write_files(
+        "missing",
+        &[("main.vil", ".module main\n\n.import \"nope.vil\"\n")],
+    );
+    let err = parse_vil_file(dir.join("main.vil").to_str().unwrap()).unwrap_err();
+    std::fs::remove_dir_all(&dir).ok();
+
+    assert!(err.to_string().starts_with("IO错误"), "{}", err);
+    let location = err.location().unwrap();
+    assert!(location.filename.ends_with("main.vil"));
+    assert_eq!((location.line, location.column), (3, 1));
+}
+
+#[test]
+fn test_import_cycle() {
+    let dir = write_files(
+        "cycle",
+        &[
+            ("a.vil", ".module a\n.import \"b.vil\"\n"),
+            ("b.vil", ".module b\n.import \"a.vil\"\n"),
+        ],
+    );
+    let err = parse_vil_file(dir.join("a.vil").to_str().unwrap()).unwrap_err();
+    std::fs::remove_dir_all(&dir).ok();
+
+    assert!(err.to_string().contains("检测到循环导入"), "{}", err);
+    assert!(err.location().unwrap().filename.ends_with("b.vil"));
+}
@@ -0,0 +1,226 @@
+use vil::frontend::parse_vil;
+use vil::ir::{InstructionRef, ModuleRef, Opcode, PrinterOptions, print_module};
+use vil::optimizer::Pass;
+use vil::optimizer::passes::InlinePass;
+
+fn opcodes(module: &ModuleRef, name: &str) -> Vec<Opcode> {
+    let func = module.borrow().get_function(name).unwrap();
+    func.borrow()
+        .get_basic_blocks()
+        .iter()
+        .flat_map(|bb| bb.borrow().get_instructions().to_vec())
+        .map(|i| i.borrow().get_opcode())
+        .collect()
+}
+
+#[test]
+fn test_inline_single_block_helper() {
+    let source = r#".module m
+.function add2(.param %x i32, .param %y i32) -> i32 {
+    %s = add %x, %y
+    ret %s
+}
+.function main(.param %a i32, .param %b i32) -> i32 {
+    %r = call @add2(%a, %b)
+    %t = mul %r, %a
+    ret %t
+}
+"#;
+    let module = parse_vil(source, "inline.vil").expect("应成功解析模块");
+    let inlined = InlinePass::new().run(&module);
+
+    assert_eq!(inlined, 1);
+    assert_eq!(
+        opcodes(&module, "main"),
+        [Opcode::Add, Opcode::Mul, Opcode::Ret]
+    );
+
+    // mul 的第一个操作数应改为内联后的 add 结果
+    let main = module.borrow().get_function("main").unwrap();
+    let entry = main.borrow().get_basic_blocks()[0].clone();
+    let instrs = entry.borrow().get_instructions().to_vec();
+    let add = instrs[0].borrow().get_result().unwrap();
+    let mul_lhs = instrs[1].borrow().get_operand(0);
+    assert_eq!(add.borrow().get_name(), "%s.i0");
+    assert_eq!(mul_lhs.borrow().get_name(), "%s.i0");
+    assert_eq!(instrs[0].borrow().get_operand(0).borrow().get_name(), "%a");
+}
+
+#[test]
+fn test_recursive_call_is_not_inlined() {
+    let source = r#".module m
+.function fact(.param %n i32) -> i32 {
+    %r = call @fact(%n)
+    ret %r
+}
+.function main(.param %a i32) -> i32 {
+    %r = call @fact(%a)
+    ret %r
+}
+"#;
+    let module = parse_vil(source, "inline.vil").expect("应成功解析模块");
+    let inlined = InlinePass::new().run(&module);
+
+    assert_eq!(inlined, 0);
+    assert_eq!(opcodes(&module, "main"), [Opcode::Call, Opcode::Ret]);
+}
+
+#[test]
+fn test_optnone_callee_is_not_inlined() {
+    let source = r#".module m
+.function keep(.param %x i32) -> i32 optnone {
+    %s = add %x, 1
+    ret %s
+}
+.function main(.param %a i32) -> i32 {
+    %r = call @keep(%a)
+    ret %r
+}
+"#;
+    let module = parse_vil(source, "inline.vil").expect("应成功解析模块");
+    let inlined = InlinePass::new().run(&module);
+
+    assert_eq!(inlined, 0);
+    assert_eq!(opcodes(&module, "main"), [Opcode::Call, Opcode::Ret]);
+}
+
+#[test]
+fn test_threshold_limits_inlining() {
+    let source = r#".module m
+.function add2(.param %x i32, .param %y i32) -> i32 {
+    %s = add %x, %y
+    ret %s
+}
+.function main(.param %a i32, .param %b i32) -> i32 {
+    %r = call @add2(%a, %b)
+    ret %r
+}
+"#;
+    let module = parse_vil(source, "inline.vil").expect("应成功解析模块");
+    let inlined = InlinePass::new().with_threshold(1).run(&module);
+
+    assert_eq!(inlined, 0);
+    assert_eq!(opcodes(&module, "main"), [Opcode::Call, Opcode::Ret]);
+}
+
+#[test]
+fn test_inline_multiple_returns_uses_phi() {
+    let source = r#".module m
+.function pick(.param %c <pred 1>, .param %x i32, .param %y i32) -> i32 {
+    condbr %c, %yes, %no
+yes:
+    ret %x
+no:
+    ret %y
+}
+.function main(.param %c <pred 1>, .param %a i32, .param %b i32) -> i32 {
+    %r = call @pick(%c, %a, %b)
+    %t = add %r, %a
+    ret %t
+}
+"#;
+    let module = parse_vil(source, "inline.vil").expect("应成功解析模块");
+    let inlined = InlinePass::new().run(&module);
+
+    assert_eq!(inlined, 1);
+    let main = module.borrow().get_function("main").unwrap();
+    let blocks: Vec<String> = main
+        .borrow()
+        .get_basic_blocks()
+        .iter()
+        .map(|bb| bb.borrow().get_name().to_string())
+        .collect();
+    assert_eq!(
+        blocks,
+        [
+            "entry",
+            "pick.entry.i0",
+            "pick.yes.i0",
+            "pick.no.i0",
+            "entry.cont.i0"
+        ]
+    );
+    assert!(!opcodes(&module, "main").contains(&Opcode::Call));
+
+    // 续接块以 phi 合并两个返回值
+    let cont = main.borrow().get_basic_blocks()[4].clone();
+    let phi = cont.borrow().get_instructions()[0].clone();
+    assert_eq!(phi.borrow().get_opcode(), Opcode::Phi);
+    assert_eq!(phi.borrow().get_name().as_deref(), Some("%r"));
+    assert_eq!(phi.borrow().get_operand_count(), 4);
+}
+
+#[test]
+fn test_inline_callee_with_loop() {
+    let source = r#".module m
+.function sum_to(.param %n i32) -> i32 {
+entry:
+    br %loop
+loop:
+    %i = phi 0, %entry, %next, %loop
+    %acc = phi 0, %entry, %acc2, %loop
+    %next = add %i, 1
+    %acc2 = add %acc, %next
+    %done = cmpeq %next, %n
+    condbr %done, %exit, %loop
+exit:
+    ret %acc2
+}
+.function main(.param %c <pred 1>, .param %a i32) -> i32 {
+entry:
+    condbr %c, %body, %join
+body:
+    %s = call @sum_to(%a)
+    br %join
+join:
+    %r = phi %s, %body, %a, %entry
+    ret %r
+}
+"#;
+    let module = parse_vil(source, "inline.vil").expect("应成功解析模块");
+    let inlined = InlinePass::new().run(&module);
+    assert_eq!(inlined, 1);
+    assert!(!opcodes(&module, "main").contains(&Opcode::Call));
+
+    let main = module.borrow().get_function("main").unwrap();
+    let block = |name: &str| {
+        main.borrow()
+            .get_basic_blocks()
+            .iter()
+            .find(|bb| bb.borrow().get_name() == name)
+            .cloned()
+            .unwrap_or_else(|| panic!("缺少基本块 {}", name))
+    };
+    let operand_names = |instr: &InstructionRef| -> Vec<String> {
+        instr
+            .borrow()
+            .get_operands()
+            .iter()
+            .map(|op| op.borrow().get_name().to_string())
+            .collect()
+    };
+
+    // 循环头 phi 中对 %next 的前向引用指向内联后的值，来源基本块也已重命名
+    let header = block("sum_to.loop.i0");
+    let phi = header.borrow().get_instructions()[0].clone();
+    assert_eq!(
+        operand_names(&phi),
+        ["0", "%sum_to.entry.i0", "%next.i0", "%sum_to.loop.i0"]
+    );
+
+    // 调用点之后的后继中，phi 的来源基本块改为续接块
+    let join = block("join");
+    let phi = join.borrow().get_instructions()[0].clone();
+    assert_eq!(
+        operand_names(&phi),
+        ["%acc2.i0", "%body.cont.i0", "%a", "%entry"]
+    );
+
+    // 打印结果能重新解析：没有残留对被调函数中值的引用
+    let opts = PrinterOptions {
+        show_types: false,
+        ..PrinterOptions::default()
+    };
+    let printed = print_module(&module.borrow(), &opts);
+    parse_vil(&printed, "inlined.vil").unwrap_or_else(|e| panic!("{}\n{}", e, printed));
+}
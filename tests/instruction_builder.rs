@@ -0,0 +1,70 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use vil::ir::value::{Value, ValueRef};
+use vil::ir::{BuildError, Instruction, InstructionModifier, Opcode, Type, TypeKind, TypeRef};
+
+fn value(type_: TypeRef, name: &str) -> ValueRef {
+    Rc::new(RefCell::new(Value::new(type_, name.to_string())))
+}
+
+#[test]
+fn test_try_binary_infers_result_type() {
+    let i32_type = Type::get_int_type(TypeKind::Int32);
+    let vec_type = Type::get_vector_type(i32_type.clone(), 4);
+
+    // 向量与标量的公共类型为向量
+    let add = Instruction::try_binary(
+        Opcode::Add,
+        value(vec_type.clone(), "%v"),
+        value(i32_type.clone(), "%s"),
+        InstructionModifier::Vector,
+    )
+    .expect("向量与其元素类型的标量兼容");
+    assert_eq!(add.get_operand_count(), 2);
+    assert_eq!(*add.get_type().borrow(), *vec_type.borrow());
+
+    // 比较产生等长的谓词
+    let cmp = Instruction::try_binary(
+        Opcode::CmpLt,
+        value(vec_type.clone(), "%a"),
+        value(vec_type, "%b"),
+        InstructionModifier::Vector,
+    )
+    .unwrap();
+    assert_eq!(
+        *cmp.get_type().borrow(),
+        *Type::get_predicate_type(4).borrow()
+    );
+}
+
+#[test]
+fn test_try_binary_rejects_mismatched_types() {
+    let i32_type = Type::get_int_type(TypeKind::Int32);
+    let i16_type = Type::get_int_type(TypeKind::Int16);
+
+    let err = Instruction::try_binary(
+        Opcode::Mul,
+        value(i32_type.clone(), "%a"),
+        value(i16_type, "%b"),
+        InstructionModifier::Scalar,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        BuildError::TypeMismatch {
+            lhs: "i32".to_string(),
+            rhs: "i16".to_string(),
+        }
+    );
+    assert_eq!(err.to_string(), "操作数类型 i32 与 i16 不兼容");
+
+    let err = Instruction::try_binary(
+        Opcode::Load,
+        value(i32_type.clone(), "%a"),
+        value(i32_type, "%b"),
+        InstructionModifier::None,
+    )
+    .unwrap_err();
+    assert_eq!(err, BuildError::NotBinary(Opcode::Load));
+}
@@ -0,0 +1,55 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use vil::ir::value::Value;
+use vil::ir::{Instruction, InstructionModifier, Opcode, Type, TypeKind, TypeRef};
+
+fn instruction(opcode: Opcode, type_: TypeRef) -> Instruction {
+    let value = |name: &str| Rc::new(RefCell::new(Value::new(type_.clone(), name.to_string())));
+    Instruction::new(
+        opcode,
+        Some(value("%r")),
+        vec![value("%a"), value("%b")],
+        InstructionModifier::None,
+    )
+}
+
+#[test]
+fn test_opcode_cost_ordering() {
+    assert_eq!(Opcode::Add.cost(), 1);
+    assert_eq!(Opcode::Mul.cost(), 3);
+    assert_eq!(Opcode::Div.cost(), 10);
+    assert_eq!(Opcode::Load.cost(), 4);
+    assert_eq!(Opcode::Store.cost(), 4);
+    assert_eq!(Opcode::Call.cost(), 20);
+    assert!(Opcode::Div.cost() > Opcode::Mul.cost());
+    assert!(Opcode::Mul.cost() > Opcode::Add.cost());
+}
+
+#[test]
+fn test_scalar_vs_vector_cost() {
+    let i32_ty = Type::get_int_type(TypeKind::Int32);
+    let vec_ty = Type::get_vector_type(i32_ty.clone(), 8);
+
+    assert_eq!(instruction(Opcode::Add, i32_ty.clone()).cost(), 1);
+    assert_eq!(instruction(Opcode::Add, vec_ty.clone()).cost(), 8);
+    assert_eq!(instruction(Opcode::Div, i32_ty).cost(), 10);
+    assert_eq!(instruction(Opcode::Div, vec_ty.clone()).cost(), 80);
+
+    // 无结果的 store 按被存储值的宽度计算
+    let value = Rc::new(RefCell::new(Value::new(vec_ty, "%v".to_string())));
+    let pointer = Rc::new(RefCell::new(Value::new(
+        Type::get_pointer_type(
+            Type::get_int_type(TypeKind::Int32),
+            vil::ir::MemorySpace::VSPM,
+        ),
+        "%p".to_string(),
+    )));
+    let store = Instruction::new(
+        Opcode::Store,
+        None,
+        vec![value, pointer],
+        InstructionModifier::None,
+    );
+    assert_eq!(store.cost(), 32);
+}
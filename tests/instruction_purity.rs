@@ -0,0 +1,65 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use vil::ir::value::Value;
+use vil::ir::{Instruction, InstructionModifier, Opcode, Type, TypeKind};
+
+fn instruction(opcode: Opcode) -> Instruction {
+    let int_ty = Type::get_int_type(TypeKind::Int32);
+    let value = |name: &str| Rc::new(RefCell::new(Value::new(int_ty.clone(), name.to_string())));
+    Instruction::new(
+        opcode,
+        Some(value("%r")),
+        vec![value("%a"), value("%b")],
+        InstructionModifier::None,
+    )
+}
+
+#[test]
+fn test_pure_opcodes() {
+    for opcode in [
+        Opcode::Add,
+        Opcode::Sub,
+        Opcode::Mul,
+        Opcode::And,
+        Opcode::Xor,
+        Opcode::Sll,
+        Opcode::CmpEq,
+        Opcode::CmpLt,
+        Opcode::Mov,
+    ] {
+        let instr = instruction(opcode);
+        assert!(instr.is_pure(), "{} 应为纯指令", opcode);
+        assert!(!instr.has_side_effects(), "{} 不应有副作用", opcode);
+    }
+}
+
+#[test]
+fn test_impure_opcodes() {
+    // 有副作用
+    for opcode in [
+        Opcode::Store,
+        Opcode::Call,
+        Opcode::Ret,
+        Opcode::Br,
+        Opcode::CondBr,
+        Opcode::SetCsr,
+        Opcode::Yield,
+    ] {
+        let instr = instruction(opcode);
+        assert!(instr.has_side_effects(), "{} 应有副作用", opcode);
+        assert!(!instr.is_pure(), "{} 不应为纯指令", opcode);
+    }
+
+    // 无副作用但结果不只取决于操作数
+    for opcode in [Opcode::Load, Opcode::Alloc, Opcode::Phi] {
+        let instr = instruction(opcode);
+        assert!(!instr.has_side_effects(), "{} 不应有副作用", opcode);
+        assert!(!instr.is_pure(), "{} 不应为纯指令", opcode);
+    }
+
+    let mut volatile_load = instruction(Opcode::Load);
+    volatile_load.add_attribute("volatile".to_string());
+    assert!(volatile_load.has_side_effects());
+    assert!(!volatile_load.is_pure());
+}
@@ -0,0 +1,72 @@
+use std::rc::Rc;
+use vil::frontend::parse_vil;
+
+#[test]
+fn test_module_is_fully_dropped() {
+    let source = r#".module m
+.function f(.param %c <pred 1>, .param %a i32) -> i32 {
+entry:
+    %x = add %a, 1
+    condbr %c, %then, %exit
+then:
+    %y = mul %x, 2
+    br %exit
+exit:
+    %r = phi %y, %then, %x, %entry
+    ret %r
+}
+"#;
+    let module = parse_vil(source, "drop.vil").expect("应成功解析模块");
+    let func = module.borrow().get_function("f").unwrap();
+    let entry = func.borrow().get_entry_block().unwrap();
+    let instr = entry.borrow().get_instructions()[0].clone();
+
+    // 父指针是弱引用，不计入强引用计数
+    assert_eq!(Rc::strong_count(&func), 2);
+    assert!(Rc::ptr_eq(&instr.borrow().get_parent_bb().unwrap(), &entry));
+    assert!(Rc::ptr_eq(&entry.borrow().get_parent().unwrap(), &func));
+
+    let weak_module = Rc::downgrade(&module);
+    let weak_func = Rc::downgrade(&func);
+    let weak_entry = Rc::downgrade(&entry);
+    let weak_instr = Rc::downgrade(&instr);
+    drop((module, func, entry, instr));
+
+    assert!(weak_module.upgrade().is_none());
+    assert!(weak_func.upgrade().is_none());
+    assert!(weak_entry.upgrade().is_none());
+    assert!(weak_instr.upgrade().is_none());
+}
+
+#[test]
+fn test_loop_module_is_fully_dropped() {
+    // 回边 `br %loop` 与 phi 的来源基本块都指回循环头，基本块操作数不能形成强引用环
+    let source = r#".module m
+.function f(.param %n i32) -> i32 {
+entry:
+    br %loop
+loop:
+    %i = phi 0, %entry, %next, %loop
+    %next = add %i, 1
+    %done = cmpeq %next, %n
+    condbr %done, %exit, %loop
+exit:
+    ret %next
+}
+"#;
+    let module = parse_vil(source, "loop.vil").expect("应成功解析模块");
+    let func = module.borrow().get_function("f").unwrap();
+    let blocks: Vec<_> = func
+        .borrow()
+        .get_basic_blocks()
+        .iter()
+        .map(Rc::downgrade)
+        .collect();
+    let weak_module = Rc::downgrade(&module);
+    let weak_func = Rc::downgrade(&func);
+    drop((module, func));
+
+    assert!(weak_module.upgrade().is_none());
+    assert!(weak_func.upgrade().is_none());
+    assert!(blocks.iter().all(|bb| bb.upgrade().is_none()));
+}
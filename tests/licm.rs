@@ -0,0 +1,83 @@
+use vil::frontend::parse_vil;
+use vil::ir::{ModuleRef, Opcode};
+use vil::optimizer::Pass;
+use vil::optimizer::passes::LoopInvariantCodeMotionPass;
+
+/// 构建 entry -> loop (自循环) -> exit，循环体中间可插入额外指令
+fn build_loop(extra: &str) -> ModuleRef {
+    let source = format!(
+        r#".module m
+.function f(.param %a i32, .param %b i32, .param %c <pred 1>) {{
+entry:
+    br %loop
+loop:
+    %x = add %a, %b
+    {}
+    condbr %c, %loop, %exit
+exit:
+    ret
+}}
+"#,
+        extra
+    );
+    parse_vil(&source, "licm.vil").expect("应成功解析模块")
+}
+
+fn block_opcodes(module: &ModuleRef, block: usize) -> Vec<Opcode> {
+    let func = module.borrow().get_function("f").unwrap();
+    let func = func.borrow();
+    func.get_basic_blocks()[block]
+        .borrow()
+        .get_instructions()
+        .iter()
+        .map(|i| i.borrow().get_opcode())
+        .collect()
+}
+
+#[test]
+fn test_invariant_is_hoisted() {
+    let module = build_loop("");
+    assert_eq!(LoopInvariantCodeMotionPass::new().run(&module), 1);
+    assert_eq!(block_opcodes(&module, 0), vec![Opcode::Add, Opcode::Br]);
+    assert_eq!(block_opcodes(&module, 1), vec![Opcode::CondBr]);
+}
+
+#[test]
+fn test_yield_blocks_hoisting() {
+    let module = build_loop("yield");
+    assert_eq!(LoopInvariantCodeMotionPass::new().run(&module), 0);
+    assert_eq!(block_opcodes(&module, 0), vec![Opcode::Br]);
+    assert_eq!(
+        block_opcodes(&module, 1),
+        vec![Opcode::Add, Opcode::Yield, Opcode::CondBr]
+    );
+}
+
+#[test]
+fn test_trapping_instruction_stays_in_conditional_block() {
+    // body 只在 %c 为真时执行，其中的 rem 外提后会在原本不执行的路径上陷入；
+    // 循环头中的 div 在进入循环时必然执行，可以外提
+    let source = r#".module m
+.function f(.param %a i32, .param %b i32, .param %c <pred 1>) {
+entry:
+    br %loop
+loop:
+    %q = div %a, %b
+    condbr %c, %body, %exit
+body:
+    %r = rem %a, %b
+    %x = add %a, %b
+    br %loop
+exit:
+    ret
+}
+"#;
+    let module = parse_vil(source, "licm.vil").expect("应成功解析模块");
+    assert_eq!(LoopInvariantCodeMotionPass::new().run(&module), 2);
+    assert_eq!(
+        block_opcodes(&module, 0),
+        vec![Opcode::Div, Opcode::Add, Opcode::Br]
+    );
+    assert_eq!(block_opcodes(&module, 1), vec![Opcode::CondBr]);
+    assert_eq!(block_opcodes(&module, 2), vec![Opcode::Rem, Opcode::Br]);
+}
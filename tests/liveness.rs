@@ -0,0 +1,32 @@
+mod common;
+
+use vil::analysis::Liveness;
+use vil::frontend::parse_vil;
+
+#[test]
+fn test_value_live_across_loop() {
+    let module = parse_vil(common::LOOP_SOURCE, "loop.vil").expect("应成功解析模块");
+    let func = module.borrow().get_function("f").unwrap();
+    let func = func.borrow();
+    let liveness = Liveness::compute(&func);
+
+    // %base 在入口定义、循环后使用，因此贯穿整个循环
+    assert!(liveness.get_live_out("entry").unwrap().contains("%base"));
+    assert!(liveness.get_live_in("loop").unwrap().contains("%base"));
+    assert!(liveness.get_live_out("loop").unwrap().contains("%base"));
+    let loop_block = func.get_basic_blocks()[1].clone();
+    for instr in loop_block.borrow().get_instructions() {
+        assert!(liveness.is_live_at("%base", &instr.borrow()));
+    }
+
+    // phi 的来源值只在对应前驱的出口处活跃
+    assert!(liveness.get_live_out("entry").unwrap().contains("%n"));
+    assert!(!liveness.get_live_in("loop").unwrap().contains("%n"));
+    assert!(liveness.get_live_out("loop").unwrap().contains("%next"));
+
+    // 最后一次使用之后不再活跃
+    let exit = func.get_basic_blocks()[2].clone();
+    let ret = exit.borrow().get_instructions()[1].clone();
+    assert!(!liveness.is_live_at("%base", &ret.borrow()));
+    assert!(liveness.is_live_at("%r", &ret.borrow()));
+}
@@ -0,0 +1,66 @@
+use vil::frontend::parse_vil;
+use vil::ir::Opcode;
+use vil::optimizer::pipeline::{standard, standard_pass_manager};
+use vil::optimizer::{OptLevel, run_optimizer};
+
+fn pass_names(level: OptLevel) -> Vec<&'static str> {
+    standard(level).iter().map(|p| p.name()).collect()
+}
+
+#[test]
+fn test_standard_pipelines() {
+    assert!(pass_names(OptLevel::O0).is_empty());
+    assert_eq!(
+        pass_names(OptLevel::O1),
+        vec![
+            "optimizer::ConstantFoldingPass",
+            "optimizer::DeadCodeEliminationPass",
+        ]
+    );
+    assert_eq!(
+        pass_names(OptLevel::O2),
+        vec![
+            "optimizer::ConstantFoldingPass",
+            "optimizer::CommonSubexpressionEliminationPass",
+            "optimizer::CopyPropagationPass",
+            "optimizer::DeadCodeEliminationPass",
+            "optimizer::SimplifyCFGPass",
+        ]
+    );
+    assert_eq!(OptLevel::from_flag("-O2"), Some(OptLevel::O2));
+    assert_eq!(OptLevel::from_flag("-O3"), None);
+}
+
+#[test]
+fn test_o2_registers_and_runs() {
+    let pm = standard_pass_manager(OptLevel::O2);
+    let order = pm.validate_pipeline().expect("O2 pipeline 依赖应可解析");
+    assert_eq!(order.len(), 5);
+
+    let source = r#".module m
+.function f(.param %a i32) -> i32 {
+entry:
+    %x = add 2, 3
+    %y = mul %x, 4
+    %p = add %a, %y
+    %q = add %a, %y
+    %dead = sub %a, 1
+    %r = add %p, %q
+    br %exit
+exit:
+    ret %r
+}
+"#;
+    let module = parse_vil(source, "o2.vil").expect("应成功解析模块");
+    run_optimizer(&module, OptLevel::O2);
+
+    let func = module.borrow().get_function("f").unwrap();
+    let opcodes: Vec<Opcode> = func
+        .borrow()
+        .instructions()
+        .map(|instr| instr.borrow().get_opcode())
+        .collect();
+    assert!(!opcodes.contains(&Opcode::Mul));
+    assert!(!opcodes.contains(&Opcode::Sub));
+    assert_eq!(opcodes.last(), Some(&Opcode::Ret));
+}
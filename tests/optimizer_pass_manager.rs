@@ -15,8 +15,9 @@ impl Pass for DummyPass {
         "test::DummyPass"
     }
 
-    fn run(&self, _module: &ModuleRef) {
+    fn run(&self, _module: &ModuleRef) -> usize {
         COUNTER.fetch_add(1, Ordering::SeqCst);
+        0
     }
 }
 
@@ -36,3 +37,50 @@ fn test_pass_manager_exec_order() {
     // 断言 DummyPass 的 run 被调用一次
     assert_eq!(COUNTER.load(Ordering::SeqCst), 1);
 }
+
+static REPLACED: AtomicUsize = AtomicUsize::new(0);
+
+struct OriginalPass;
+
+impl Pass for OriginalPass {
+    fn name(&self) -> &'static str {
+        "test::SwappablePass"
+    }
+
+    fn run(&self, _module: &ModuleRef) -> usize {
+        panic!("已被替换的 Pass 不应运行");
+    }
+}
+
+struct ReplacementPass;
+
+impl Pass for ReplacementPass {
+    fn name(&self) -> &'static str {
+        "test::SwappablePass"
+    }
+
+    fn run(&self, _module: &ModuleRef) -> usize {
+        REPLACED.fetch_add(1, Ordering::SeqCst);
+        0
+    }
+}
+
+#[test]
+fn test_replace_and_remove_pass() {
+    let mut pm = PassManager::new();
+    pm.register_pass(OriginalPass);
+    pm.replace_pass(ReplacementPass);
+    pm.add_to_pipeline("test::SwappablePass");
+
+    let module = Rc::new(RefCell::new(Module::new("swap".to_string())));
+    pm.run(&module).expect("PassManager 执行失败");
+    assert_eq!(REPLACED.load(Ordering::SeqCst), 1);
+
+    // 注销后 pipeline 中也不再包含该 Pass
+    assert!(pm.remove_pass("test::SwappablePass"));
+    assert!(!pm.remove_pass("test::SwappablePass"));
+    assert!(pm.get_pipeline().is_empty());
+    assert!(pm.get_registered_passes().is_empty());
+    pm.run(&module).expect("PassManager 执行失败");
+    assert_eq!(REPLACED.load(Ordering::SeqCst), 1);
+}
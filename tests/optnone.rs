@@ -0,0 +1,105 @@
+use vil::frontend::{parse_vil, roundtrip_ok};
+use vil::ir::printer::print_function;
+use vil::ir::{Opcode, PrinterOptions};
+use vil::optimizer::passes::{
+    BreakCriticalEdgesPass, ConstantFoldingPass, InlinePass, LoopInvariantCodeMotionPass, SCCPPass,
+    SSARenumberPass,
+};
+use vil::optimizer::{OptLevel, Pass, run_optimizer};
+
+fn first_opcode(module: &vil::ir::ModuleRef, name: &str) -> Opcode {
+    let func = module.borrow().get_function(name).unwrap();
+    let entry = func.borrow().get_entry_block().unwrap();
+    entry.borrow().get_instructions()[0].borrow().get_opcode()
+}
+
+#[test]
+fn test_optnone_function_is_skipped() {
+    let source = r#".module m
+.function keep() -> i32 optnone {
+entry:
+    %a = add 1, 2
+    ret %a
+}
+.function fold() -> i32 {
+entry:
+    %a = add 1, 2
+    ret %a
+}
+"#;
+    let module = parse_vil(source, "optnone.vil").expect("应成功解析带属性的函数");
+    let keep = module.borrow().get_function("keep").unwrap();
+    assert!(keep.borrow().has_attribute("optnone"));
+    assert!(module.borrow().to_string().contains("-> i32 optnone {"));
+    roundtrip_ok(source).unwrap();
+
+    assert_eq!(ConstantFoldingPass::new().run(&module), 1);
+    // optnone 函数保持原样，普通函数中的加法被折叠
+    assert_eq!(first_opcode(&module, "keep"), Opcode::Add);
+    assert_ne!(first_opcode(&module, "fold"), Opcode::Add);
+}
+
+#[test]
+fn test_optnone_survives_full_pipeline() {
+    let body = r#"entry:
+    %a = add 1, 2
+    %b = add %x, %a
+    %c = add %x, %a
+    %dead = sub %x, 1
+    %t = cmpeq 1, 1
+    condbr %t, %loop, %exit
+loop:
+    %i = phi 0, %entry, %n, %loop
+    %k = mul %x, 3
+    %n = add %i, %k
+    %done = cmpge %n, 10
+    condbr %done, %exit, %loop
+exit:
+    %r = call @helper(%b)
+    ret %r
+}
+"#;
+    let source = format!(
+        ".module m\n.function helper(.param %v i32) -> i32 {{\n    ret %v\n}}\n\
+         .function keep(.param %x i32) -> i32 optnone {{\n{}",
+        body
+    );
+    let module = parse_vil(&source, "optnone.vil").expect("应成功解析模块");
+    let keep = module.borrow().get_function("keep").unwrap();
+    let before = print_function(&keep.borrow(), &PrinterOptions::default());
+
+    run_optimizer(&module, OptLevel::O2);
+    let passes: Vec<Box<dyn Pass>> = vec![
+        Box::new(LoopInvariantCodeMotionPass::new()),
+        Box::new(SCCPPass::new()),
+        Box::new(BreakCriticalEdgesPass::new()),
+        Box::new(SSARenumberPass::new()),
+        Box::new(InlinePass::new()),
+    ];
+    for pass in passes {
+        pass.run(&module);
+    }
+    // optnone 函数中的折叠、公共子表达式、死代码、常量分支、循环不变量与调用都保持原样
+    assert_eq!(
+        print_function(&keep.borrow(), &PrinterOptions::default()),
+        before
+    );
+}
+
+#[test]
+fn test_unknown_function_attribute_is_rejected() {
+    let source = ".module m\n.function f() optnon {\n    ret\n}\n";
+    let err = parse_vil(source, "optnone.vil").unwrap_err();
+    assert!(
+        err.to_string().contains("未知的函数属性: 'optnon'"),
+        "{}",
+        err
+    );
+    assert!(
+        parse_vil(
+            ".module m\n.function f() export optnone {\n    ret\n}\n",
+            "optnone.vil"
+        )
+        .is_ok()
+    );
+}
@@ -28,9 +28,10 @@ impl Pass for PassA {
         "test::PassA"
     }
 
-    fn run(&self, _module: &ModuleRef) {
+    fn run(&self, _module: &ModuleRef) -> usize {
         let order = EXECUTION_ORDER.fetch_add(1, Ordering::SeqCst);
         PASS_A_ORDER.store(order, Ordering::SeqCst);
+        0
     }
 }
 
@@ -46,9 +47,10 @@ impl Pass for PassB {
         vec!["test::PassA"]
     }
 
-    fn run(&self, _module: &ModuleRef) {
+    fn run(&self, _module: &ModuleRef) -> usize {
         let order = EXECUTION_ORDER.fetch_add(1, Ordering::SeqCst);
         PASS_B_ORDER.store(order, Ordering::SeqCst);
+        0
     }
 }
 
@@ -64,9 +66,10 @@ impl Pass for PassC {
         vec!["test::PassB"]
     }
 
-    fn run(&self, _module: &ModuleRef) {
+    fn run(&self, _module: &ModuleRef) -> usize {
         let order = EXECUTION_ORDER.fetch_add(1, Ordering::SeqCst);
         PASS_C_ORDER.store(order, Ordering::SeqCst);
+        0
     }
 }
 
@@ -116,7 +119,7 @@ fn test_circular_dependency_detection() {
         fn dependencies(&self) -> Vec<&'static str> {
             vec!["test::CircularPassB"]
         }
-        fn run(&self, _: &ModuleRef) {}
+        fn run(&self, _: &ModuleRef) -> usize { 0 }
     }
 
     struct CircularPassB;
@@ -127,7 +130,7 @@ fn test_circular_dependency_detection() {
         fn dependencies(&self) -> Vec<&'static str> {
             vec!["test::CircularPassA"]
         }
-        fn run(&self, _: &ModuleRef) {}
+        fn run(&self, _: &ModuleRef) -> usize { 0 }
     }
 
     // 创建 PassManager
@@ -169,7 +172,7 @@ fn test_missing_dependency_detection() {
         fn dependencies(&self) -> Vec<&'static str> {
             vec!["test::NonExistentPass"]
         }
-        fn run(&self, _: &ModuleRef) {}
+        fn run(&self, _: &ModuleRef) -> usize { 0 }
     }
 
     // 创建 PassManager
@@ -194,4 +197,158 @@ fn test_missing_dependency_detection() {
     } else {
         panic!("预期 MissingDependency 错误");
     }
-} 
\ No newline at end of file
+}
+
+// 测试依赖图的 DOT 输出
+#[test]
+fn test_pipeline_dot() {
+    let mut pm = PassManager::new();
+    pm.register_pass(PassA);
+    pm.register_pass(PassB);
+    pm.register_pass(PassC);
+    pm.add_to_pipeline("test::PassC");
+    pm.add_to_pipeline("test::PassB");
+    pm.add_to_pipeline("test::PassA");
+
+    let dot = pm.pipeline_dot().expect("生成 DOT 失败");
+    assert!(dot.starts_with("digraph pipeline {"));
+    assert!(dot.contains("\"test::PassA\";"));
+    // 每个 Pass 都有指向其声明依赖的边
+    assert!(dot.contains("\"test::PassB\" -> \"test::PassA\";"));
+    assert!(dot.contains("\"test::PassC\" -> \"test::PassB\";"));
+    assert!(!dot.contains("\"test::PassA\" -> "));
+    assert!(!dot.contains("循环"));
+}
+
+// 测试存在循环依赖时 DOT 仍可输出
+#[test]
+fn test_pipeline_dot_with_cycle() {
+    struct CycleX;
+    impl Pass for CycleX {
+        fn name(&self) -> &'static str {
+            "test::CycleX"
+        }
+        fn dependencies(&self) -> Vec<&'static str> {
+            vec!["test::CycleY"]
+        }
+        fn run(&self, _: &ModuleRef) -> usize { 0 }
+    }
+
+    struct CycleY;
+    impl Pass for CycleY {
+        fn name(&self) -> &'static str {
+            "test::CycleY"
+        }
+        fn dependencies(&self) -> Vec<&'static str> {
+            vec!["test::CycleX"]
+        }
+        fn run(&self, _: &ModuleRef) -> usize { 0 }
+    }
+
+    let mut pm = PassManager::new();
+    pm.register_pass(CycleX);
+    pm.register_pass(CycleY);
+    pm.add_to_pipeline("test::CycleX");
+    pm.add_to_pipeline("test::CycleY");
+
+    let dot = pm.pipeline_dot().expect("循环依赖不应导致 DOT 生成失败");
+    assert!(dot.contains("检测到 Pass 依赖循环"));
+    assert!(dot.contains("\"test::CycleX\" [color=red];"));
+    assert!(dot.contains("\"test::CycleX\" -> \"test::CycleY\";"));
+    assert!(dot.contains("\"test::CycleY\" -> \"test::CycleX\";"));
+}
+
+// 测试只校验 pipeline 而不执行
+#[test]
+fn test_validate_pipeline_without_running() {
+    let mut pm = PassManager::new();
+    pm.register_pass(PassA);
+    pm.register_pass(PassB);
+    pm.register_pass(PassC);
+    pm.add_to_pipeline("test::PassC");
+    pm.add_to_pipeline("test::PassB");
+    pm.add_to_pipeline("test::PassA");
+
+    let order = pm.validate_pipeline().expect("pipeline 应有效");
+    assert_eq!(order, vec!["test::PassA", "test::PassB", "test::PassC"]);
+}
+
+// 测试校验时报告循环依赖
+#[test]
+fn test_validate_pipeline_reports_cycle() {
+    struct LoopA;
+    impl Pass for LoopA {
+        fn name(&self) -> &'static str {
+            "test::LoopA"
+        }
+        fn dependencies(&self) -> Vec<&'static str> {
+            vec!["test::LoopB"]
+        }
+        fn run(&self, _: &ModuleRef) -> usize {
+            panic!("校验时不应运行 Pass")
+        }
+    }
+
+    struct LoopB;
+    impl Pass for LoopB {
+        fn name(&self) -> &'static str {
+            "test::LoopB"
+        }
+        fn dependencies(&self) -> Vec<&'static str> {
+            vec!["test::LoopA"]
+        }
+        fn run(&self, _: &ModuleRef) -> usize {
+            panic!("校验时不应运行 Pass")
+        }
+    }
+
+    let mut pm = PassManager::new();
+    pm.register_pass(LoopA);
+    pm.register_pass(LoopB);
+    pm.add_to_pipeline("test::LoopA");
+    pm.add_to_pipeline("test::LoopB");
+
+    match pm.validate_pipeline() {
+        Err(PassError::CircularDependency(cycle)) => assert!(cycle.len() >= 2),
+        other => panic!("预期 CircularDependency 错误，实际为 {:?}", other),
+    }
+}
+
+// 测试单独运行一个 Pass 时先运行其依赖，且不影响原有 pipeline
+#[test]
+fn test_run_single_with_dependencies() {
+    thread_local! {
+        static LOG: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+    }
+
+    struct Logged(&'static str, Vec<&'static str>);
+    impl Pass for Logged {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+        fn dependencies(&self) -> Vec<&'static str> {
+            self.1.clone()
+        }
+        fn run(&self, _: &ModuleRef) -> usize {
+            LOG.with(|log| log.borrow_mut().push(self.0));
+            0
+        }
+    }
+
+    let mut pm = PassManager::new();
+    pm.register_pass(Logged("test::Base", vec![]));
+    pm.register_pass(Logged("test::Middle", vec!["test::Base"]));
+    pm.register_pass(Logged("test::Top", vec!["test::Middle"]));
+    pm.register_pass(Logged("test::Other", vec![]));
+    pm.add_to_pipeline("test::Other");
+
+    let module = Rc::new(RefCell::new(Module::new("test_module".to_string())));
+    pm.run_single("test::Middle", &module).expect("单独运行 Pass 失败");
+    LOG.with(|log| assert_eq!(*log.borrow(), ["test::Base", "test::Middle"]));
+    assert_eq!(pm.get_pipeline(), ["test::Other"]);
+
+    assert!(matches!(
+        pm.run_single("test::Missing", &module),
+        Err(PassError::NotRegistered(name)) if name == "test::Missing"
+    ));
+}
@@ -0,0 +1,102 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use vil::ir::module::Module;
+use vil::ir::ModuleRef;
+use vil::optimizer::pass_manager::{Pass, PassManager};
+
+// 两个互相"暴露优化机会"的计数器：A 只能领先 B 一步，B 只能追到 A
+thread_local! {
+    static COUNT_A: Cell<usize> = const { Cell::new(0) };
+    static COUNT_B: Cell<usize> = const { Cell::new(0) };
+}
+
+const LIMIT: usize = 3;
+
+// Pass A: 在 B 追上之后才能继续前进
+struct ProducerPass;
+
+impl Pass for ProducerPass {
+    fn name(&self) -> &'static str {
+        "test::ProducerPass"
+    }
+
+    fn run(&self, _module: &ModuleRef) -> usize {
+        let a = COUNT_A.with(|c| c.get());
+        let b = COUNT_B.with(|c| c.get());
+        if a < LIMIT && a == b {
+            COUNT_A.with(|c| c.set(a + 1));
+            1
+        } else {
+            0
+        }
+    }
+}
+
+// Pass B: 追赶 A
+struct ConsumerPass;
+
+impl Pass for ConsumerPass {
+    fn name(&self) -> &'static str {
+        "test::ConsumerPass"
+    }
+
+    fn dependencies(&self) -> Vec<&'static str> {
+        vec!["test::ProducerPass"]
+    }
+
+    fn run(&self, _module: &ModuleRef) -> usize {
+        let a = COUNT_A.with(|c| c.get());
+        let b = COUNT_B.with(|c| c.get());
+        if b < a {
+            COUNT_B.with(|c| c.set(b + 1));
+            1
+        } else {
+            0
+        }
+    }
+}
+
+fn build_pass_manager() -> PassManager {
+    COUNT_A.with(|c| c.set(0));
+    COUNT_B.with(|c| c.set(0));
+
+    let mut pm = PassManager::new();
+    pm.register_pass(ProducerPass);
+    pm.register_pass(ConsumerPass);
+    pm.add_to_pipeline("test::ConsumerPass");
+    pm.add_to_pipeline("test::ProducerPass");
+    pm
+}
+
+#[test]
+fn test_run_until_fixpoint() {
+    let mut pm = build_pass_manager();
+    let module = Rc::new(RefCell::new(Module::new("test_module".to_string())));
+
+    // 单次运行只能推进一步
+    pm.run(&module).expect("PassManager 执行失败");
+    assert_eq!(COUNT_A.with(|c| c.get()), 1);
+    assert_eq!(COUNT_B.with(|c| c.get()), 1);
+
+    // 迭代到不动点：前 LIMIT-1 次有修改，最后一次确认无修改
+    let iterations = pm
+        .run_until_fixpoint(&module, 100)
+        .expect("PassManager 执行失败");
+    assert_eq!(iterations, LIMIT);
+    assert_eq!(COUNT_A.with(|c| c.get()), LIMIT);
+    assert_eq!(COUNT_B.with(|c| c.get()), LIMIT);
+}
+
+#[test]
+fn test_run_until_fixpoint_respects_max_iters() {
+    let mut pm = build_pass_manager();
+    let module = Rc::new(RefCell::new(Module::new("test_module".to_string())));
+
+    let iterations = pm
+        .run_until_fixpoint(&module, 2)
+        .expect("PassManager 执行失败");
+    assert_eq!(iterations, 2);
+    assert_eq!(COUNT_A.with(|c| c.get()), 2);
+    assert_eq!(COUNT_B.with(|c| c.get()), 2);
+}
@@ -0,0 +1,66 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use vil::ir::ModuleRef;
+use vil::ir::module::Module;
+use vil::optimizer::pass_manager::{Pass, PassManager};
+
+// 故意耗时的 Pass
+struct SlowPass;
+
+impl Pass for SlowPass {
+    fn name(&self) -> &'static str {
+        "test::SlowPass"
+    }
+
+    fn run(&self, _module: &ModuleRef) -> usize {
+        std::thread::sleep(Duration::from_millis(20));
+        0
+    }
+}
+
+struct FastPass;
+
+impl Pass for FastPass {
+    fn name(&self) -> &'static str {
+        "test::FastPass"
+    }
+
+    fn dependencies(&self) -> Vec<&'static str> {
+        vec!["test::SlowPass"]
+    }
+
+    fn run(&self, _module: &ModuleRef) -> usize {
+        0
+    }
+}
+
+#[test]
+fn test_later_passes_skipped_when_budget_exhausted() {
+    let module = Rc::new(RefCell::new(Module::new("m".to_string())));
+    let mut pm = PassManager::new();
+    pm.register_pass(SlowPass);
+    pm.register_pass(FastPass);
+    pm.add_to_pipeline("test::SlowPass");
+    pm.add_to_pipeline("test::FastPass");
+    pm.enable_statistics()
+        .set_time_budget(Duration::from_millis(1));
+
+    pm.run(&module).expect("应成功运行");
+
+    let stats = pm.get_statistics();
+    assert_eq!(stats.len(), 2);
+    // 正在运行的 Pass 不会被中断
+    assert_eq!(stats[0].name, "test::SlowPass");
+    assert!(!stats[0].skipped);
+    assert!(stats[0].duration >= Duration::from_millis(20));
+    assert_eq!(stats[1].name, "test::FastPass");
+    assert!(stats[1].skipped);
+    assert_eq!(stats[1].skip_reason.as_deref(), Some("时间预算耗尽"));
+
+    // 每次运行重新计时：预算充足时两个 Pass 都会执行
+    pm.set_time_budget(Duration::from_secs(60));
+    pm.run(&module).expect("应成功运行");
+    assert!(pm.get_statistics().iter().all(|stats| !stats.skipped));
+}
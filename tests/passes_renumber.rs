@@ -115,4 +115,174 @@ fn test_ssa_renumber() {
     assert_eq!(instructions[0].borrow().get_name(), Some("%0".to_string()));
     assert_eq!(instructions[1].borrow().get_name(), Some("%1".to_string()));
     assert_eq!(instructions[2].borrow().get_name(), Some("%2".to_string()));
-} 
\ No newline at end of file
+}
+
+#[test]
+fn test_ssa_renumber_custom_strategy() {
+    use vil::ir::Instruction;
+    use vil::optimizer::pass_manager::Pass;
+    use vil::optimizer::passes::ssa_renumber::NamingStrategy;
+
+    let names = |module: &Rc<RefCell<Module>>| -> Vec<Option<String>> {
+        let function = module.borrow().get_functions()[0].clone();
+        let bb = function.borrow().get_basic_blocks()[0].clone();
+        bb.borrow().get_instructions().iter().map(|i| i.borrow().get_name()).collect()
+    };
+
+    // v<序号>_<基本块名>
+    let module = build_test_module();
+    let naming = |instr: &Instruction, n: usize| {
+        let bb = instr.get_parent_bb().expect("指令应属于基本块");
+        format!("%v{}_{}", n, bb.borrow().get_name())
+    };
+    SSARenumber::new()
+        .with_strategy(NamingStrategy::Custom(Box::new(naming)))
+        .run(&module);
+    assert_eq!(names(&module), vec![
+        Some("%v0_entry".to_string()),
+        Some("%v1_entry".to_string()),
+        Some("%v2_entry".to_string()),
+    ]);
+
+    // 重名时追加后缀保证唯一
+    let module = build_test_module();
+    SSARenumber::new()
+        .with_strategy(NamingStrategy::Custom(Box::new(|_, _| "%x".to_string())))
+        .run(&module);
+    assert_eq!(names(&module), vec![
+        Some("%x".to_string()),
+        Some("%x.1".to_string()),
+        Some("%x.2".to_string()),
+    ]);
+}
+
+#[test]
+fn test_ssa_renumber_updates_operand_references() {
+    use vil::ir::value::Value;
+    use vil::optimizer::pass_manager::Pass;
+
+    let int_type = Type::get_int_type(TypeKind::Int32);
+    let new_value = |name: &str| Rc::new(RefCell::new(Value::new(int_type.clone(), name.to_string())));
+
+    let module = Rc::new(RefCell::new(Module::new("m".to_string())));
+    let function = Rc::new(RefCell::new(Function::new("f".to_string(), Type::get_void_type(), vec![])));
+    let bb = Rc::new(RefCell::new(BasicBlock::new("entry".to_string(), Some(function.clone()))));
+
+    // %a = add %p, %q ; %b = mul %a, %a，其中 %b 的操作数是按名称引用的独立 Value
+    let a = Rc::new(RefCell::new(Instruction::new(
+        Opcode::Add,
+        Some(new_value("%a")),
+        vec![new_value("%p"), new_value("%q")],
+        InstructionModifier::None,
+    )));
+    let b = Rc::new(RefCell::new(Instruction::new(
+        Opcode::Mul,
+        Some(new_value("%b")),
+        vec![new_value("%a"), new_value("%a")],
+        InstructionModifier::None,
+    )));
+    bb.borrow_mut().add_instruction(a.clone(), bb.clone());
+    bb.borrow_mut().add_instruction(b.clone(), bb.clone());
+    function.borrow_mut().add_basic_block(bb);
+    module.borrow_mut().add_function(function);
+
+    SSARenumber::new().run(&module);
+
+    assert_eq!(a.borrow().get_name(), Some("%0".to_string()));
+    let a_result = a.borrow().get_result().unwrap();
+    for operand in b.borrow().get_operands() {
+        assert!(Rc::ptr_eq(&operand, &a_result));
+        assert_eq!(operand.borrow().get_name(), "%0");
+    }
+    // 未在函数内定义的名称保持不变
+    assert_eq!(a.borrow().get_operand(0).borrow().get_name(), "%p");
+}
+
+#[test]
+fn test_ssa_renumber_reverse_postorder() {
+    use vil::ir::value::Value;
+    use vil::optimizer::pass_manager::Pass;
+    use vil::optimizer::passes::ssa_renumber::BlockOrder;
+
+    let int_type = Type::get_int_type(TypeKind::Int32);
+    let new_value = |name: &str| Rc::new(RefCell::new(Value::new(int_type.clone(), name.to_string())));
+    let label = |name: &str| Rc::new(RefCell::new(Value::new(Type::get_void_type(), name.to_string())));
+
+    let module = Rc::new(RefCell::new(Module::new("m".to_string())));
+    let function = Rc::new(RefCell::new(Function::new("f".to_string(), Type::get_void_type(), vec![])));
+    let mut results = std::collections::HashMap::new();
+
+    // 菱形 CFG，合并块在存储顺序中位于两个前驱之前
+    let blocks = [
+        ("entry", "%c", Opcode::CondBr, vec![new_value("%c"), label("%then"), label("%else")]),
+        ("merge", "%m", Opcode::Ret, vec![]),
+        ("then", "%t", Opcode::Br, vec![label("%merge")]),
+        ("else", "%e", Opcode::Br, vec![label("%merge")]),
+    ];
+    for (name, value, terminator, operands) in blocks {
+        let bb = Rc::new(RefCell::new(BasicBlock::new(name.to_string(), Some(function.clone()))));
+        let instr = Rc::new(RefCell::new(Instruction::new(
+            Opcode::Add,
+            Some(new_value(value)),
+            vec![new_value("%x"), new_value("%y")],
+            InstructionModifier::None,
+        )));
+        let term = Instruction::new(terminator, None, operands, InstructionModifier::None);
+        bb.borrow_mut().add_instruction(instr.clone(), bb.clone());
+        bb.borrow_mut().add_instruction(Rc::new(RefCell::new(term)), bb.clone());
+        function.borrow_mut().add_basic_block(bb);
+        results.insert(name, instr);
+    }
+    module.borrow_mut().add_function(function);
+
+    SSARenumber::new()
+        .with_block_order(BlockOrder::ReversePostorder)
+        .run(&module);
+
+    let number = |block: &str| -> usize {
+        let name = results[block].borrow().get_name().unwrap();
+        name.trim_start_matches('%').parse().unwrap()
+    };
+    assert_eq!(number("entry"), 0);
+    assert!(number("merge") > number("then"));
+    assert!(number("merge") > number("else"));
+}
+
+#[test]
+fn test_instruction_ids_stable_across_renaming() {
+    use vil::optimizer::passes::ssa_renumber::NamingStrategy;
+
+    let module = build_test_module();
+    let ids = |module: &Rc<RefCell<Module>>| -> Vec<(u64, Option<String>)> {
+        let func = module.borrow().get_functions()[0].clone();
+        let func = func.borrow();
+        func.get_basic_blocks()
+            .iter()
+            .flat_map(|bb| bb.borrow().get_instructions().to_vec())
+            .map(|i| (i.borrow().get_id(), i.borrow().get_name()))
+            .collect()
+    };
+
+    let before = ids(&module);
+    let unique: HashSet<u64> = before.iter().map(|(id, _)| *id).collect();
+    assert_eq!(unique.len(), before.len());
+
+    let mut pm = PassManager::new();
+    pm.register_pass(SSARenumber::new().with_strategy(NamingStrategy::BlockBased));
+    pm.add_to_pipeline("optimizer::SSARenumberPass::BlockBased");
+    pm.run(&module).expect("PassManager 执行失败");
+
+    // 名称改变，ID 保持不变
+    let after = ids(&module);
+    assert_ne!(before, after);
+    assert_eq!(
+        before.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+        after.iter().map(|(id, _)| *id).collect::<Vec<_>>()
+    );
+
+    // 克隆得到新的 ID
+    let func = module.borrow().get_functions()[0].clone();
+    let first = func.borrow().get_basic_blocks()[0].borrow().get_instructions()[0].clone();
+    let copy = first.borrow().clone();
+    assert!(!unique.contains(&copy.get_id()));
+}
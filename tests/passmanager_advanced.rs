@@ -86,7 +86,7 @@ fn test_conditional_execution() {
         fn name(&self) -> &'static str { "test::ConditionalPassAlwaysRun" }
         fn description(&self) -> &'static str { "测试条件执行的 Pass (总是运行)" }
         fn should_run(&self, _module: &vil::ir::ModuleRef) -> bool { true }
-        fn run(&self, _module: &vil::ir::ModuleRef) { /* 空实现 */ }
+        fn run(&self, _module: &vil::ir::ModuleRef) -> usize { 0 }
     }
 
     struct ConditionalPassNeverRun;
@@ -94,7 +94,7 @@ fn test_conditional_execution() {
         fn name(&self) -> &'static str { "test::ConditionalPassNeverRun" }
         fn description(&self) -> &'static str { "测试条件执行的 Pass (永不运行)" }
         fn should_run(&self, _module: &vil::ir::ModuleRef) -> bool { false }
-        fn run(&self, _module: &vil::ir::ModuleRef) { /* 空实现 */ }
+        fn run(&self, _module: &vil::ir::ModuleRef) -> usize { 0 }
     }
 
     let mut pm = PassManager::new();
@@ -146,4 +146,83 @@ fn test_ssa_renumber_strategies() {
     
     // 运行优化
     pm.run(&module).expect("优化过程中出错");
-} 
\ No newline at end of file
+} 
+
+#[test]
+fn test_dce_reports_removed_count() {
+    use vil::ir::{BasicBlock, Function, Instruction, InstructionModifier, Opcode, Type, TypeKind};
+    use vil::ir::value::Value;
+
+    let int_ty = Type::get_int_type(TypeKind::Int32);
+    let new_value = |name: &str| Rc::new(RefCell::new(Value::new(int_ty.clone(), name.to_string())));
+
+    let function = Rc::new(RefCell::new(Function::new(
+        "f".to_string(),
+        Type::get_void_type(),
+        vec![],
+    )));
+    let bb = Rc::new(RefCell::new(BasicBlock::new("entry".to_string(), Some(function.clone()))));
+
+    // %a 与 %b 无人使用（死代码），%c 被 store 使用（活跃）
+    let a = new_value("%a");
+    let b = new_value("%b");
+    let c = new_value("%c");
+    let instrs = vec![
+        Instruction::new(Opcode::Add, Some(a.clone()), vec![new_value("1"), new_value("2")], InstructionModifier::None),
+        Instruction::new(Opcode::Mul, Some(b), vec![a, new_value("3")], InstructionModifier::None),
+        Instruction::new(Opcode::Sub, Some(c.clone()), vec![new_value("4"), new_value("5")], InstructionModifier::None),
+        Instruction::new(Opcode::Store, None, vec![c, new_value("%ptr")], InstructionModifier::None),
+    ];
+    for instr in instrs {
+        bb.borrow_mut().add_instruction(Rc::new(RefCell::new(instr)), bb.clone());
+    }
+    function.borrow_mut().add_basic_block(bb.clone());
+
+    let module = new_test_module();
+    module.borrow_mut().add_function(function);
+
+    let mut pm = PassManager::new();
+    pm.enable_statistics();
+    pm.register_pass(DeadCodeEliminationPass::new());
+    pm.add_to_pipeline("optimizer::DeadCodeEliminationPass");
+    pm.run(&module).expect("优化过程中出错");
+
+    let stats = pm.get_statistics();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].changes, 2);
+    assert_eq!(bb.borrow().get_instructions().len(), 2);
+    assert!(stats[0].to_string().contains("修改 2 处"));
+}
+
+#[test]
+fn test_configured_pass_instances_coexist() {
+    use vil::optimizer::pass_manager::Pass;
+    use vil::optimizer::passes::ssa_renumber::NamingStrategy;
+
+    let module = new_test_module();
+    let sequential = SSARenumberPass::new();
+    let type_based = SSARenumberPass::new().with_strategy(NamingStrategy::TypeBased);
+    let type_based_id = type_based.instance_id();
+    assert_ne!(sequential.instance_id(), type_based_id);
+
+    let mut pm = PassManager::new();
+    pm.enable_statistics();
+    pm.register_pass(sequential);
+    pm.register_pass(type_based);
+
+    let mut registered = pm.get_registered_passes();
+    registered.sort();
+    assert_eq!(registered, vec![
+        "optimizer::SSARenumberPass".to_string(),
+        type_based_id.clone(),
+    ]);
+
+    pm.add_to_pipeline("optimizer::SSARenumberPass");
+    pm.add_to_pipeline(&type_based_id);
+    pm.run(&module).expect("优化过程中出错");
+
+    let stats = pm.get_statistics();
+    assert_eq!(stats.len(), 2);
+    assert_eq!(stats[0].name, "optimizer::SSARenumberPass");
+    assert_eq!(stats[1].name, type_based_id);
+}
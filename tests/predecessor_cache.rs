@@ -0,0 +1,93 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use vil::analysis::FunctionCFG;
+use vil::frontend::parse_vil;
+use vil::ir::{BasicBlock, Type, Value};
+
+#[test]
+fn test_cached_predecessors_match_cfg() {
+    let source = r#".module m
+.function f(.param %c <pred 1>) -> i32 {
+entry:
+    condbr %c, %left, %right
+left:
+    br %join
+right:
+    condbr %c, %join, %join
+join:
+    %r = phi 1, %left, 2, %right
+    ret %r
+}
+"#;
+    let module = parse_vil(source, "preds.vil").expect("应成功解析模块");
+    let func = module.borrow().get_function("f").unwrap();
+    let blocks = func.borrow().get_basic_blocks().to_vec();
+
+    let check = || {
+        let cfg = FunctionCFG::compute(&func.borrow());
+        for bb in &blocks {
+            let name = bb.borrow().get_name().to_string();
+            let cached: Vec<String> = BasicBlock::get_predecessors(bb)
+                .iter()
+                .map(|pred| pred.borrow().get_name().to_string())
+                .collect();
+            assert_eq!(cached, cfg.get_predecessors(&name), "基本块 {}", name);
+        }
+    };
+    check();
+    assert_eq!(BasicBlock::predecessor_count(&blocks[3]), 2);
+
+    // 修改终结指令后缓存随之更新：left 改为跳回 entry
+    let br = blocks[1].borrow().get_terminator().unwrap();
+    let target = Value::new(Type::get_void_type(), "%entry".to_string());
+    br.borrow_mut()
+        .set_operand(0, Rc::new(RefCell::new(target)));
+    check();
+    assert_eq!(BasicBlock::predecessor_count(&blocks[3]), 1);
+    assert_eq!(BasicBlock::predecessor_count(&blocks[0]), 1);
+
+    // 移除基本块后前驱随之更新
+    let left = blocks[1].clone();
+    func.borrow_mut().remove_basic_block(&left);
+    let remaining = func.borrow().get_basic_blocks().to_vec();
+    assert_eq!(BasicBlock::predecessor_count(&remaining[0]), 0);
+
+    // 手动失效后重新计算仍得到相同结果
+    func.borrow().invalidate_predecessors();
+    assert_eq!(BasicBlock::predecessor_count(&remaining[2]), 1);
+}
+
+#[test]
+fn test_dead_predecessors_are_filtered() {
+    let source = r#".module m
+.function f(.param %c <pred 1>) -> i32 {
+entry:
+    condbr %c, %left, %right
+left:
+    br %join
+right:
+    br %join
+join:
+    %r = phi 1, %left, 2, %right
+    ret %r
+}
+"#;
+    let module = parse_vil(source, "preds.vil").expect("应成功解析模块");
+    let func = module.borrow().get_function("f").unwrap();
+    let mut blocks = func.borrow().get_basic_blocks().to_vec();
+    let join = blocks.pop().unwrap();
+    assert_eq!(BasicBlock::predecessor_count(&join), 2);
+
+    // 移出函数的基本块保留最后一次计算的缓存；释放其中的前驱后不再返回它
+    func.borrow_mut().remove_basic_block(&join);
+    func.borrow_mut().remove_basic_block(&blocks[1]);
+    let left = Rc::downgrade(&blocks[1]);
+    drop(blocks);
+    assert!(left.upgrade().is_none(), "left 应已被释放");
+    let preds: Vec<String> = BasicBlock::get_predecessors(&join)
+        .iter()
+        .map(|pred| pred.borrow().get_name().to_string())
+        .collect();
+    assert_eq!(preds, vec!["right".to_string()]);
+}
+
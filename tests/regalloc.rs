@@ -0,0 +1,78 @@
+mod common;
+
+use vil::backend::regalloc::{LinearScan, Location, ValueId};
+use vil::frontend::parse_vil;
+
+#[test]
+fn test_spill_when_pressure_exceeds_registers() {
+    let source = r#".module m
+.function f(.param %a i32) -> i32 {
+entry:
+    %b = add %a, 1
+    %c = add %a, 2
+    %d = add %a, 3
+    %e = add %b, %c
+    %g = add %e, %d
+    %h = add %g, %a
+    ret %h
+}
+"#;
+    let module = parse_vil(source, "pressure.vil").expect("应成功解析模块");
+    let func = module.borrow().get_function("f").unwrap();
+    let func = func.borrow();
+
+    let intervals = LinearScan::live_intervals(&func);
+    let interval = |name: &str| {
+        let i = intervals.iter().find(|i| i.name == name).unwrap();
+        (i.start, i.end)
+    };
+    assert_eq!(interval("%a"), (0, 6));
+    assert_eq!(interval("%b"), (1, 4));
+    assert_eq!(interval("%h"), (6, 7));
+
+    // %a、%b、%c、%d 同时活跃，两个寄存器不足以容纳
+    let locations = LinearScan::new(2).allocate(&func);
+    assert_eq!(locations.len(), intervals.len());
+    // 位置以参数序号与定义指令的 ID 为键
+    assert!(locations.contains_key(&ValueId::Argument(0)));
+    let first = func.get_basic_blocks()[0].borrow().get_instructions()[0].clone();
+    assert!(locations.contains_key(&ValueId::Instruction(first.borrow().get_id())));
+    assert!(
+        locations
+            .values()
+            .any(|loc| matches!(loc, Location::Stack(_)))
+    );
+
+    // 同一时刻活跃的值不会共用寄存器
+    for x in &intervals {
+        for y in &intervals {
+            let overlap = x.value != y.value && x.start <= y.end && y.start <= x.end;
+            if overlap
+                && let (Location::Register(rx), Location::Register(ry)) =
+                    (locations[&x.value], locations[&y.value])
+            {
+                assert_ne!(rx, ry, "{} 与 {} 共用寄存器", x.name, y.name);
+            }
+        }
+    }
+
+    // 寄存器充足时无需溢出
+    let locations = LinearScan::new(8).allocate(&func);
+    assert!(
+        locations
+            .values()
+            .all(|loc| matches!(loc, Location::Register(_)))
+    );
+}
+
+#[test]
+fn test_interval_extends_across_loop() {
+    let module = parse_vil(common::LOOP_SOURCE, "loop.vil").expect("应成功解析模块");
+    let func = module.borrow().get_function("f").unwrap();
+    let intervals = LinearScan::live_intervals(&func.borrow());
+    let base = intervals.iter().find(|i| i.name == "%base").unwrap();
+    // %base 跨越整个循环（位置 3..=6）直到 exit 中的使用
+    assert_eq!((base.start, base.end), (1, 7));
+    let next = intervals.iter().find(|i| i.name == "%next").unwrap();
+    assert!(next.end >= 6);
+}
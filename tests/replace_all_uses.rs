@@ -0,0 +1,39 @@
+use std::rc::Rc;
+use vil::frontend::parse_vil;
+use vil::ir::Instruction;
+
+#[test]
+fn test_replace_value_used_in_three_instructions() {
+    let source = r#".module m
+.function f(.param %x i32, .param %y i32) -> i32 {
+entry:
+    %a = add %x, 1
+    %b = mul %a, 2
+    %c = sub %a, %y
+    br %exit
+exit:
+    %d = add %a, %c
+    ret %d
+}
+"#;
+    let module = parse_vil(source, "rauw.vil").expect("应成功解析模块");
+    let func = module.borrow().get_function("f").unwrap();
+    let instrs: Vec<_> = func.borrow().instructions().collect();
+    let def = instrs[0].clone();
+    let replacement = instrs[2].borrow().get_operand(1); // %y
+
+    assert_eq!(Instruction::replace_all_uses_with(&def, &replacement), 3);
+
+    // mul、sub 与后继基本块中的 add 都改为引用替换值
+    for idx in [1, 2, 4] {
+        let user = instrs[idx].borrow();
+        assert!(Rc::ptr_eq(&user.get_operand(0), &replacement));
+    }
+    // 定义本身保持不变，由调用者删除
+    assert_eq!(def.borrow().get_name().as_deref(), Some("%a"));
+    assert!(
+        func.borrow()
+            .instructions()
+            .any(|instr| Rc::ptr_eq(&instr, &def))
+    );
+}
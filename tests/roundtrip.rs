@@ -0,0 +1,148 @@
+use vil::analysis::verifier::Verifier;
+use vil::frontend::{parse_vil, roundtrip_ok};
+use vil::ir::{PrinterOptions, print_module};
+
+#[test]
+fn test_roundtrip_memory_spaces() {
+    let source = r#".module my_module
+.type %Vec4 = <i32 x 4>
+.memory vspm_buffer [vspm] <i16 x 1024>
+.memory sram_buffer [sram] i32 512
+.memory vec_buffer [vspm] %Vec4
+.memory aligned_buffer [sram] i8 256 .align 32
+"#;
+    roundtrip_ok(source).unwrap();
+}
+
+#[test]
+fn test_roundtrip_memory_and_calls() {
+    let source = r#".module m
+.entry @main
+.function main(.param %v i32) -> i32 {
+    %p = alloc i32 [sram]
+    store %v, %p align 16
+    %a = load %p volatile
+    %r = call @main(%a)
+    %s = add %r, 1
+    %b = bitcast %s : b32
+    ret %s
+}
+"#;
+    roundtrip_ok(source).unwrap();
+}
+
+#[test]
+fn test_roundtrip_control_flow() {
+    let source = r#".module m
+.function pick(.param %c <pred 1>, .param %x <i32 x 8>, .param %y <i32 x 8>) {
+    condbr %c, %yes, %no
+yes:
+    %s = add.v %x, %y
+    br %done
+no:
+    %m = cmplt %x, %y
+    br %done
+done:
+    yield
+    ret
+}
+"#;
+    roundtrip_ok(source).unwrap();
+}
+
+#[test]
+fn test_printed_format_is_pinned() {
+    let source = r#".module m
+.memory buf [sram] i32 16
+.function f(.param %v i32) -> i32 {
+    %p = alloc i32 [sram]
+    %x = load %p volatile
+    ret %x
+}
+"#;
+    let module = parse_vil(source, "pinned.vil").unwrap();
+    let opts = PrinterOptions {
+        show_types: false,
+        ..PrinterOptions::default()
+    };
+    let expected = r#".module m
+
+.memory buf [sram] i32 16
+
+.function f(.param %v i32) -> i32 {
+entry:
+  %p = alloc i32 [sram]
+  %x = load %p volatile
+  ret %x
+}
+
+"#;
+    assert_eq!(print_module(&module.borrow(), &opts), expected);
+}
+
+#[test]
+fn test_instruction_display_reparses() {
+    let header = ".module m\n.function f(.param %c <pred 1>, .param %v i32) -> i32 {\n";
+    let source = format!(
+        "{}{}",
+        header,
+        r#"entry:
+    %p = alloc i32 [sram]
+    store %v, %p
+    %a = load %p
+    %s = add %a, 1
+    %r = call @f(%c, %s)
+    condbr %c, %left, %right
+left:
+    br %join
+right:
+    br %join
+join:
+    %m = phi %s, %left, 2, %right
+    ret %m
+}
+"#
+    );
+    let module = parse_vil(&source, "display.vil").unwrap();
+
+    // 以 Instruction 的 Display 重新拼出函数体
+    let mut printed = header.to_string();
+    let func = module.borrow().get_function("f").unwrap();
+    for bb in func.borrow().get_basic_blocks() {
+        printed.push_str(&format!("{}:\n", bb.borrow().get_name()));
+        for instr in bb.borrow().get_instructions() {
+            let line = instr.borrow().to_string();
+            assert!(!line.contains(":i32"), "操作数不应带类型: {}", line);
+            printed.push_str(&format!("    {}\n", line));
+        }
+    }
+    printed.push_str("}\n");
+
+    let reparsed = parse_vil(&printed, "display.vil").expect("打印出的指令应能重新解析");
+    let opts = PrinterOptions::default();
+    assert_eq!(
+        print_module(&reparsed.borrow(), &opts),
+        print_module(&module.borrow(), &opts)
+    );
+}
+
+#[test]
+fn test_roundtrip_sample_programs() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/samples");
+    let mut paths: Vec<_> = std::fs::read_dir(&dir)
+        .expect("应能读取示例程序目录")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "vil"))
+        .collect();
+    paths.sort();
+    assert!(paths.len() >= 3, "示例程序不足: {:?}", paths);
+    for path in paths {
+        let source = std::fs::read_to_string(&path).unwrap();
+        if let Err(e) = roundtrip_ok(&source) {
+            panic!("{}: {}", path.display(), e);
+        }
+        let module = parse_vil(&source, &path.to_string_lossy()).unwrap();
+        let errors = Verifier::verify_module(&module.borrow());
+        assert!(errors.is_empty(), "{}: {:?}", path.display(), errors);
+    }
+}
@@ -0,0 +1,82 @@
+use vil::frontend::parse_vil;
+use vil::ir::{ModuleRef, Opcode};
+use vil::optimizer::Pass;
+use vil::optimizer::passes::SCCPPass;
+
+fn block_names(module: &ModuleRef) -> Vec<String> {
+    let func = module.borrow().get_function("f").unwrap();
+    let func = func.borrow();
+    func.get_basic_blocks()
+        .iter()
+        .map(|bb| bb.borrow().get_name().to_string())
+        .collect()
+}
+
+#[test]
+fn test_constant_condition_eliminates_dead_path() {
+    let source = r#".module m
+.function f(.param %a i32) {
+entry:
+    %x = mov 4
+    %c = sub %x, 4
+    condbr %c, %then, %else
+then:
+    %y = add %a, 1
+    br %merge
+else:
+    %z = mul %x, 2
+    br %merge
+merge:
+    %r = phi %y, %then, %z, %else
+    %s = add %r, %a
+    ret
+}
+"#;
+    let module = parse_vil(source, "sccp.vil").expect("应成功解析模块");
+    assert!(SCCPPass::new().run(&module) > 0);
+
+    // then 分支不可达，被删除
+    assert_eq!(block_names(&module), vec!["entry", "else", "merge"]);
+
+    let func = module.borrow().get_function("f").unwrap();
+    let func = func.borrow();
+    let blocks = func.get_basic_blocks();
+    let terminator = blocks[0].borrow().get_terminator().unwrap();
+    assert_eq!(terminator.borrow().get_opcode(), Opcode::Br);
+
+    // phi 只剩来自 else 的常量 8，对其的使用被替换为立即数
+    let merge = blocks[2].borrow();
+    let phi = merge.get_instructions()[0].borrow();
+    assert_eq!(phi.get_result().unwrap().borrow().as_i64(), Some(8));
+    let add = merge.get_instructions()[1].borrow();
+    assert_eq!(add.get_operand(0).borrow().as_i64(), Some(8));
+}
+
+#[test]
+fn test_unknown_condition_keeps_both_paths() {
+    let source = r#".module m
+.function f(.param %c <pred 1>) {
+entry:
+    condbr %c, %then, %else
+then:
+    %x = mov 1
+    br %merge
+else:
+    %y = mov 2
+    br %merge
+merge:
+    %r = phi %x, %then, %y, %else
+    ret
+}
+"#;
+    let module = parse_vil(source, "sccp.vil").expect("应成功解析模块");
+    SCCPPass::new().run(&module);
+    assert_eq!(block_names(&module), vec!["entry", "then", "else", "merge"]);
+
+    let func = module.borrow().get_function("f").unwrap();
+    let func = func.borrow();
+    let merge = func.get_basic_blocks()[3].borrow();
+    let phi = merge.get_instructions()[0].borrow();
+    assert_eq!(phi.get_opcode(), Opcode::Phi);
+    assert_eq!(phi.get_operand_count(), 4);
+}
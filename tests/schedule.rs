@@ -0,0 +1,76 @@
+use vil::frontend::parse_vil;
+use vil::ir::Opcode;
+use vil::optimizer::Pass;
+use vil::optimizer::passes::SchedulePass;
+
+fn opcodes(module: &vil::ir::ModuleRef) -> Vec<Opcode> {
+    let func = module.borrow().get_function("f").unwrap();
+    let entry = func.borrow().get_entry_block().unwrap();
+    entry
+        .borrow()
+        .get_instructions()
+        .iter()
+        .map(|instr| instr.borrow().get_opcode())
+        .collect()
+}
+
+#[test]
+fn test_div_moved_earlier() {
+    let source = r#".module m
+.function f(.param %x i32, .param %y i32) -> i32 {
+entry:
+    %a = add %x, 1
+    %b = add %x, 2
+    %d = div %y, 3
+    %c = add %a, %b
+    %r = add %c, %d
+    ret %r
+}
+"#;
+    let module = parse_vil(source, "sched.vil").expect("应成功解析模块");
+    assert!(SchedulePass::new().run(&module) > 0);
+    assert_eq!(
+        opcodes(&module),
+        vec![
+            Opcode::Div,
+            Opcode::Add,
+            Opcode::Add,
+            Opcode::Add,
+            Opcode::Add,
+            Opcode::Ret
+        ]
+    );
+    // 已调度的基本块再次运行不再变化
+    assert_eq!(SchedulePass::new().run(&module), 0);
+}
+
+#[test]
+fn test_memory_order_and_barriers_preserved() {
+    let source = r#".module m
+.function f(.param %p i32* sram, .param %x i32) -> i32 {
+entry:
+    %a = add %x, 1
+    store %a, %p
+    yield
+    %v = load %p
+    %d = div %x, 3
+    %r = add %v, %d
+    ret %r
+}
+"#;
+    let module = parse_vil(source, "mem.vil").expect("应成功解析模块");
+    SchedulePass::new().run(&module);
+    // div 不能越过 yield 屏障，load 不能越过 store
+    assert_eq!(
+        opcodes(&module),
+        vec![
+            Opcode::Add,
+            Opcode::Store,
+            Opcode::Yield,
+            Opcode::Div,
+            Opcode::Load,
+            Opcode::Add,
+            Opcode::Ret
+        ]
+    );
+}
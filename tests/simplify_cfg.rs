@@ -0,0 +1,114 @@
+use vil::frontend::parse_vil;
+use vil::ir::{ModuleRef, Opcode};
+use vil::optimizer::Pass;
+use vil::optimizer::passes::SimplifyCFGPass;
+
+fn blocks(module: &ModuleRef) -> Vec<(String, Vec<Opcode>)> {
+    let func = module.borrow().get_function("f").unwrap();
+    let func = func.borrow();
+    func.get_basic_blocks()
+        .iter()
+        .map(|bb| {
+            let bb = bb.borrow();
+            let opcodes = bb
+                .get_instructions()
+                .iter()
+                .map(|i| i.borrow().get_opcode())
+                .collect();
+            (bb.get_name().to_string(), opcodes)
+        })
+        .collect()
+}
+
+#[test]
+fn test_merge_straight_line_chain() {
+    let source = r#".module m
+.function f(.param %a i32, .param %b i32) {
+entry:
+    %x = add %a, %b
+    br %next
+next:
+    %y = mul %x, %x
+    br %last
+last:
+    ret
+}
+"#;
+    let module = parse_vil(source, "simplify.vil").expect("应成功解析模块");
+    assert_eq!(SimplifyCFGPass::new().run(&module), 2);
+    assert_eq!(
+        blocks(&module),
+        vec![(
+            "entry".to_string(),
+            vec![Opcode::Add, Opcode::Mul, Opcode::Ret]
+        )]
+    );
+}
+
+#[test]
+fn test_successor_with_other_predecessors_is_kept() {
+    let source = r#".module m
+.function f(.param %c <pred 1>) {
+entry:
+    condbr %c, %left, %join
+left:
+    br %join
+join:
+    ret
+}
+"#;
+    let module = parse_vil(source, "simplify.vil").expect("应成功解析模块");
+    assert_eq!(SimplifyCFGPass::new().run(&module), 0);
+    assert_eq!(blocks(&module).len(), 3);
+}
+
+#[test]
+fn test_fold_constant_condbr() {
+    let source = r#".module m
+.function f(.param %a i32) {
+entry:
+    condbr 1, %then, %else
+then:
+    %x = add %a, %a
+    ret
+else:
+    %y = mul %a, %a
+    ret
+}
+"#;
+    let module = parse_vil(source, "simplify.vil").expect("应成功解析模块");
+    // 折叠 condbr、删除 else、合并 then
+    assert_eq!(SimplifyCFGPass::new().run(&module), 3);
+    assert_eq!(
+        blocks(&module),
+        vec![("entry".to_string(), vec![Opcode::Add, Opcode::Ret])]
+    );
+}
+
+#[test]
+fn test_fold_constant_condbr_to_br() {
+    let source = r#".module m
+.function f(.param %c <pred 1>) {
+entry:
+    condbr 1, %then, %else
+then:
+    condbr %c, %then, %exit
+else:
+    br %exit
+exit:
+    ret
+}
+"#;
+    let module = parse_vil(source, "simplify.vil").expect("应成功解析模块");
+    // then 有自环前驱，不能并入 entry
+    assert_eq!(SimplifyCFGPass::new().run(&module), 2);
+
+    let names: Vec<String> = blocks(&module).into_iter().map(|(name, _)| name).collect();
+    assert_eq!(names, ["entry", "then", "exit"]);
+
+    let func = module.borrow().get_function("f").unwrap();
+    let entry = func.borrow().get_entry_block().unwrap();
+    let br = entry.borrow().get_terminator().unwrap();
+    assert_eq!(br.borrow().get_opcode(), Opcode::Br);
+    assert_eq!(br.borrow().get_operand(0).borrow().get_name(), "%then");
+}
@@ -0,0 +1,70 @@
+use vil::backend::venus;
+use vil::diagnostics::DiagnosticBag;
+use vil::frontend::{Lexer, LexerOptions, Parser, parse_vil};
+
+#[test]
+fn test_mulh_and_redsum_golden() {
+    let source = r#".module m
+.memory buf [vspm] <i32 x 8>
+.function kernel(.param %x <i32 x 8>, .param %y <i32 x 8>) -> i32 {
+entry:
+    %z = mulh.v %x, %y
+    store.v %z, @buf
+    %s = redsum.v %z
+    ret %s
+}
+"#;
+    let module = parse_vil(source, "kernel.vil").expect("应成功解析模块");
+    let expected = "    .section .vspm
+    .align 4
+buf:
+    .space 32
+    .text
+    .globl kernel
+kernel:
+.Lkernel.entry:
+    mulh.v %z, %x, %y
+    store.v.vspm %z, (buf)
+    redsum.v %s, %z
+    ret %s
+";
+    assert_eq!(venus::emit(&module), expected);
+}
+
+#[test]
+fn test_opcode_without_lowering_emits_err() {
+    let source = r#".module m
+.function f(.param %c <pred 1>, .param %a i32) -> i32 {
+entry:
+    condbr %c, %then, %exit
+then:
+    br %exit
+exit:
+    %r = phi %a, %then, %a, %entry
+    ret %r
+}
+"#;
+    let module = parse_vil(source, "phi.vil").expect("应成功解析模块");
+    let asm = venus::emit(&module);
+    assert!(asm.contains("    bnez %c, .Lf.then\n    j .Lf.exit\n"));
+    assert!(asm.contains("    .err \"phi: 无对应的 Venus 指令\"\n"));
+
+    // 宽松模式下的未知助记符同样输出 `.err`，不会被当作真实指令
+    let source =
+        ".module m\n.function g(.param %a i32) -> i32 {\n    %r = frob %a\n    ret %r\n}\n";
+    let options = LexerOptions {
+        lenient_opcodes: true,
+        ..Default::default()
+    };
+    let mut parser = Parser::new(Lexer::new_with_options(source, "unknown.vil", options));
+    let module = parser
+        .parse_module_with_diagnostics(&mut DiagnosticBag::new())
+        .expect("应成功解析模块");
+    let asm = venus::emit(&module);
+    assert!(
+        asm.contains("    .err \"frob: 无对应的 Venus 指令\"\n"),
+        "{}",
+        asm
+    );
+    assert!(!asm.contains("frob %r"), "{}", asm);
+}
@@ -0,0 +1,92 @@
+use vil::backend::wasm::emit_wat;
+use vil::frontend::parse_vil;
+
+#[test]
+fn test_scalar_add_golden() {
+    let source = r#".module m
+.function sum(.param %a i32, .param %b i32) -> i32 {
+entry:
+    %x = add %a, %b
+    ret %x
+}
+"#;
+    let module = parse_vil(source, "add.vil").expect("应成功解析模块");
+    let expected = r#"(module
+  (func $sum (export "sum") (param $a i32) (param $b i32) (result i32)
+    (local $x i32)
+    local.get $a
+    local.get $b
+    i32.add
+    local.set $x
+    local.get $x
+    return
+  )
+)
+"#;
+    assert_eq!(emit_wat(&module), expected);
+}
+
+#[test]
+fn test_narrow_signed_srl_golden() {
+    // i8 的 -1 逻辑右移 1 位应得到 127，需先截取到 8 位再移位
+    let source = r#".module m
+.function half(.param %a i8) -> i8 {
+entry:
+    %r = srl %a, 1
+    ret %r
+}
+"#;
+    let module = parse_vil(source, "srl.vil").expect("应成功解析模块");
+    let expected = r#"(module
+  (func $half (export "half") (param $a i32) (result i32)
+    (local $r i32)
+    local.get $a
+    i32.const 255
+    i32.and
+    i32.const 1
+    i32.shr_u
+    i32.extend8_s
+    local.set $r
+    local.get $r
+    return
+  )
+)
+"#;
+    assert_eq!(emit_wat(&module), expected);
+}
+
+#[test]
+fn test_branches_phis_and_narrow_types() {
+    let source = r#".module m
+.function pick(.param %c <pred 1>, .param %a i8) -> i8 {
+entry:
+    condbr %c, %then, %merge
+then:
+    %x = add %a, 100
+    br %merge
+merge:
+    %r = phi %x, %then, %a, %entry
+    ret %r
+}
+"#;
+    let module = parse_vil(source, "pick.vil").expect("应成功解析模块");
+    let wat = emit_wat(&module);
+    assert!(wat.contains("br_table $__bb0 $__bb1 $__bb2 $__bb2"));
+    assert!(wat.contains("i32.add\n        i32.extend8_s\n        local.set $x"));
+    assert!(wat.contains("local.get $x\n        local.set $r\n        i32.const 2"));
+}
+
+#[test]
+fn test_large_memory_layout() {
+    // 32 位 × 2 亿个元素的位数超出 u32
+    let source = r#".module m
+.memory big [sram] i32 200000000
+.function f() {
+entry:
+    ret
+}
+"#;
+    let module = parse_vil(source, "big.vil").expect("应成功解析模块");
+    let wat = emit_wat(&module);
+    assert!(wat.contains("(memory (export \"memory\") 12208)"), "{}", wat);
+}